@@ -1 +1,2 @@
-pub mod server_token;
\ No newline at end of file
+pub mod server_token;
+pub mod token_verifier;
\ No newline at end of file
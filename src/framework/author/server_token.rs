@@ -1,14 +1,202 @@
-use md5;
+use crate::framework::config::config::Author;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use tracing::warn;
+
+type HmacSha256 = Hmac<Sha256>;
 
 pub const PROJECT_NAME: &str = "pantyhose_server";
 
-pub fn generate_token(author_key: &str) -> String {
-    let input = format!("{}{}{}", author_key, PROJECT_NAME, author_key);
-    let digest = md5::compute(input);
-    format!("{:x}", digest)
+/// 节点认证随机数长度（字节）
+pub const NODE_NONCE_LEN: usize = 32;
+
+/// 连接方证明的签名前缀，与监听方证明区分，避免把一方的证明当作另一方的使用
+const CLIENT_PROOF_LABEL: &str = "node-auth/client";
+/// 监听方证明的签名前缀
+const SERVER_PROOF_LABEL: &str = "node-auth/server";
+
+/// 生成节点认证随机数
+pub fn generate_nonce() -> Vec<u8> {
+    let mut nonce = vec![0u8; NODE_NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    nonce
+}
+
+/// 集群节点认证（挑战-应答）
+///
+/// 连接方发送自己的随机数，监听方返回自己的随机数和对连接方随机数的证明；
+/// 连接方校验后在注册/连接请求中返回对监听方随机数的证明。
+/// 证明为 HMAC-SHA256(密钥, 前缀 || 随机数 || 服务器ID)，随机数只使用一次，密钥不在网络上传输。
+///
+/// 轮换密钥时 `secondary_key` 与 `key` 同时有效：签名始终使用 `key`，校验接受任意一个
+#[derive(Clone, Default)]
+pub struct NodeAuthenticator {
+    /// 有效密钥，第一个用于签名
+    keys: Vec<Vec<u8>>,
+}
+
+impl NodeAuthenticator {
+    /// 根据author配置创建
+    pub fn new(config: &Author) -> Self {
+        let mut keys = vec![config.key.as_bytes().to_vec()];
+        if let Some(ref secondary_key) = config.secondary_key {
+            if !secondary_key.is_empty() && secondary_key != &config.key {
+                warn!("Cluster secondary author key is configured, remove it once key rotation is finished");
+                keys.push(secondary_key.as_bytes().to_vec());
+            }
+        }
+        Self { keys }
+    }
+
+    /// 是否配置了用于签名的密钥
+    pub fn has_key(&self) -> bool {
+        !self.signing_key().is_empty()
+    }
+
+    /// 连接方证明：对监听方随机数和连接方服务器ID签名
+    pub fn client_proof(&self, server_nonce: &[u8], server_id: u32) -> Vec<u8> {
+        Self::sign(self.signing_key(), CLIENT_PROOF_LABEL, server_nonce, server_id)
+    }
+
+    /// 校验连接方证明
+    pub fn verify_client_proof(&self, server_nonce: &[u8], server_id: u32, proof: &[u8]) -> bool {
+        self.verify(CLIENT_PROOF_LABEL, server_nonce, server_id, proof)
+    }
+
+    /// 监听方证明：对连接方随机数和监听方服务器ID签名
+    pub fn server_proof(&self, client_nonce: &[u8], server_id: u32) -> Vec<u8> {
+        Self::sign(self.signing_key(), SERVER_PROOF_LABEL, client_nonce, server_id)
+    }
+
+    /// 校验监听方证明
+    pub fn verify_server_proof(&self, client_nonce: &[u8], server_id: u32, proof: &[u8]) -> bool {
+        self.verify(SERVER_PROOF_LABEL, client_nonce, server_id, proof)
+    }
+
+    fn signing_key(&self) -> &[u8] {
+        self.keys.first().map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn mac(key: &[u8], label: &str, nonce: &[u8], server_id: u32) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(PROJECT_NAME.as_bytes());
+        mac.update(b"/");
+        mac.update(label.as_bytes());
+        mac.update(nonce);
+        mac.update(&server_id.to_be_bytes());
+        mac
+    }
+
+    fn sign(key: &[u8], label: &str, nonce: &[u8], server_id: u32) -> Vec<u8> {
+        Self::mac(key, label, nonce, server_id).finalize().into_bytes().to_vec()
+    }
+
+    /// 依次用每个有效密钥校验，比较为常数时间
+    fn verify(&self, label: &str, nonce: &[u8], server_id: u32, proof: &[u8]) -> bool {
+        if nonce.len() != NODE_NONCE_LEN {
+            return false;
+        }
+        self.keys.iter().any(|key| Self::mac(key, label, nonce, server_id).verify_slice(proof).is_ok())
+    }
 }
 
-pub fn server_token_authentication(token: &str, author_key: &str) -> bool {
-    let generated_token = generate_token(author_key);
-    generated_token == token
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authenticator(key: &str, secondary_key: Option<&str>) -> NodeAuthenticator {
+        NodeAuthenticator::new(&Author {
+            key: key.to_string(),
+            secondary_key: secondary_key.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn proof_round_trip() {
+        let connector = authenticator("cluster-key", None);
+        let listener = authenticator("cluster-key", None);
+        assert!(connector.has_key());
+
+        let client_nonce = generate_nonce();
+        let server_nonce = generate_nonce();
+        assert_eq!(client_nonce.len(), NODE_NONCE_LEN);
+        assert_ne!(client_nonce, server_nonce);
+
+        let server_proof = listener.server_proof(&client_nonce, 1);
+        assert!(connector.verify_server_proof(&client_nonce, 1, &server_proof));
+        let client_proof = connector.client_proof(&server_nonce, 2);
+        assert!(listener.verify_client_proof(&server_nonce, 2, &client_proof));
+    }
+
+    #[test]
+    fn proof_is_bound_to_label() {
+        let authenticator = authenticator("cluster-key", None);
+        let nonce = generate_nonce();
+
+        let client_proof = authenticator.client_proof(&nonce, 1);
+        assert!(!authenticator.verify_server_proof(&nonce, 1, &client_proof));
+        let server_proof = authenticator.server_proof(&nonce, 1);
+        assert!(!authenticator.verify_client_proof(&nonce, 1, &server_proof));
+    }
+
+    #[test]
+    fn wrong_server_id_is_rejected() {
+        let authenticator = authenticator("cluster-key", None);
+        let nonce = generate_nonce();
+
+        let client_proof = authenticator.client_proof(&nonce, 1);
+        assert!(!authenticator.verify_client_proof(&nonce, 2, &client_proof));
+        let server_proof = authenticator.server_proof(&nonce, 1);
+        assert!(!authenticator.verify_server_proof(&nonce, 2, &server_proof));
+    }
+
+    #[test]
+    fn wrong_nonce_or_key_is_rejected() {
+        let node = authenticator("cluster-key", None);
+        let nonce = generate_nonce();
+        let proof = node.client_proof(&nonce, 1);
+        assert!(!node.verify_client_proof(&generate_nonce(), 1, &proof));
+        assert!(!node.verify_client_proof(&nonce, 1, &proof[..proof.len() - 1]));
+        assert!(!authenticator("other-key", None).verify_client_proof(&nonce, 1, &proof));
+    }
+
+    #[test]
+    fn wrong_nonce_length_is_rejected() {
+        let authenticator = authenticator("cluster-key", None);
+        for length in [0, NODE_NONCE_LEN - 1, NODE_NONCE_LEN + 1] {
+            let nonce = vec![7u8; length];
+            let proof = authenticator.client_proof(&nonce, 1);
+            assert!(!authenticator.verify_client_proof(&nonce, 1, &proof), "nonce length {}", length);
+            let proof = authenticator.server_proof(&nonce, 1);
+            assert!(!authenticator.verify_server_proof(&nonce, 1, &proof), "nonce length {}", length);
+        }
+    }
+
+    #[test]
+    fn secondary_key_is_accepted_during_rotation() {
+        let old_node = authenticator("old-key", None);
+        let new_node = authenticator("new-key", Some("old-key"));
+        let nonce = generate_nonce();
+
+        // 轮换期间新节点接受旧密钥签名的证明
+        let proof = old_node.client_proof(&nonce, 1);
+        assert!(new_node.verify_client_proof(&nonce, 1, &proof));
+        let proof = old_node.server_proof(&nonce, 1);
+        assert!(new_node.verify_server_proof(&nonce, 1, &proof));
+
+        // 签名始终使用主密钥，旧节点不接受
+        let proof = new_node.client_proof(&nonce, 2);
+        assert!(!old_node.verify_client_proof(&nonce, 2, &proof));
+        assert!(authenticator("new-key", None).verify_client_proof(&nonce, 2, &proof));
+    }
+
+    #[test]
+    fn empty_key() {
+        assert!(!authenticator("", None).has_key());
+        assert!(!NodeAuthenticator::default().has_key());
+        // 与主密钥相同或为空的备用密钥被忽略
+        assert_eq!(authenticator("key", Some("key")).keys.len(), 1);
+        assert_eq!(authenticator("key", Some("")).keys.len(), 1);
+    }
+}
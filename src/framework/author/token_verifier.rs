@@ -0,0 +1,500 @@
+use crate::framework::config::config::LoginConfig;
+use crate::framework::data::stable_hash;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{error, info, warn};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 令牌签名算法，写在令牌头部的alg字段
+pub const HMAC_TOKEN_ALGORITHM: &str = "HS256";
+
+/// 重放缓存超过该数量时清理已过期的随机数
+const NONCE_CACHE_PURGE_THRESHOLD: usize = 1024;
+
+/// 内置的令牌校验器类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenVerifierKind {
+    /// HMAC-SHA256签名令牌（JWT HS256格式）
+    Hmac,
+    /// 测试模式，不校验签名
+    Test,
+}
+
+impl TokenVerifierKind {
+    /// 从配置字符串转换（hmac / test）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "hmac" => Some(TokenVerifierKind::Hmac),
+            "test" => Some(TokenVerifierKind::Test),
+            _ => None,
+        }
+    }
+}
+
+/// 令牌校验失败原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenError {
+    /// 格式错误（分段、base64或JSON无法解析）
+    Malformed(String),
+    /// 签名算法不是HS256
+    UnsupportedAlgorithm(String),
+    /// 签名不匹配
+    BadSignature,
+    /// 缺少必需的声明
+    MissingClaim(&'static str),
+    /// 已过期
+    Expired,
+    /// 尚未生效
+    NotYetValid,
+    /// 签发者不匹配
+    InvalidIssuer,
+    /// 受众不匹配
+    InvalidAudience,
+    /// 令牌已经使用过
+    Replayed,
+}
+
+/// 校验通过的令牌内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenClaims {
+    /// 用户ID（sub）
+    pub user_id: u64,
+    /// 签发者（iss）
+    pub issuer: Option<String>,
+    /// 过期时间（exp，Unix秒），测试模式为0
+    pub expires_at: u64,
+    /// 随机数（jti），用于防重放
+    pub nonce: Option<String>,
+}
+
+/// 令牌校验器，在登录任务的工作线程中调用
+///
+/// 实现可以替换内置的校验器，通过LoginMessageHandler::set_token_verifier设置
+pub trait TokenVerifier: Send + Sync {
+    /// 校验令牌，成功时返回令牌中的用户信息
+    fn verify(&self, token: &str) -> Result<TokenClaims, TokenError>;
+}
+
+/// 根据登录配置创建令牌校验器
+///
+/// # 返回值
+/// 配置无效（未知的校验器类型、HMAC缺少密钥、未显式允许test）时返回None
+pub fn create_token_verifier(config: &LoginConfig) -> Option<Arc<dyn TokenVerifier>> {
+    match TokenVerifierKind::parse(&config.verifier) {
+        Some(TokenVerifierKind::Hmac) => {
+            if config.secret.is_empty() {
+                error!("Login token verifier is hmac but no secret is configured");
+                return None;
+            }
+            if config.issuer.is_none() || config.audience.is_none() {
+                warn!("Login token issuer or audience is not configured and will not be checked");
+            }
+            Some(Arc::new(HmacTokenVerifier::new(config)))
+        }
+        Some(TokenVerifierKind::Test) => {
+            if !config.allow_test {
+                error!("Login token verifier is test but allow_test is not set, refusing to accept unverified tokens");
+                return None;
+            }
+            warn!("Login token verifier is in TEST mode, tokens are NOT verified");
+            Some(Arc::new(TestTokenVerifier))
+        }
+        None => {
+            error!("Unknown login token verifier: {}", config.verifier);
+            None
+        }
+    }
+}
+
+/// 当前Unix时间（秒）
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// 令牌头部
+#[derive(Deserialize)]
+struct TokenHeader {
+    alg: String,
+}
+
+/// 数值或字符串形式的声明值
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(u64),
+    String(String),
+}
+
+/// 单个或多个受众
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Audience {
+    fn contains(&self, audience: &str) -> bool {
+        match self {
+            Audience::One(value) => value == audience,
+            Audience::Many(values) => values.iter().any(|value| value == audience),
+        }
+    }
+}
+
+/// 令牌载荷
+#[derive(Deserialize)]
+struct TokenPayload {
+    sub: Option<NumberOrString>,
+    iss: Option<String>,
+    aud: Option<Audience>,
+    exp: Option<u64>,
+    nbf: Option<u64>,
+    jti: Option<String>,
+}
+
+/// 已使用的随机数缓存，记录到令牌过期为止
+struct NonceCache {
+    /// 随机数 -> 过期时间（Unix秒）
+    nonces: HashMap<String, u64>,
+    /// 下次清理的数量阈值
+    purge_threshold: usize,
+}
+
+impl NonceCache {
+    fn new() -> Self {
+        Self {
+            nonces: HashMap::new(),
+            purge_threshold: NONCE_CACHE_PURGE_THRESHOLD,
+        }
+    }
+
+    /// 记录随机数，已存在且未过期时返回false
+    fn insert(&mut self, nonce: &str, expires_at: u64, now: u64) -> bool {
+        if let Some(&existing) = self.nonces.get(nonce) {
+            if existing >= now {
+                return false;
+            }
+        }
+        self.nonces.insert(nonce.to_string(), expires_at);
+
+        if self.nonces.len() >= self.purge_threshold {
+            self.nonces.retain(|_, expires_at| *expires_at >= now);
+            self.purge_threshold = (self.nonces.len() * 2).max(NONCE_CACHE_PURGE_THRESHOLD);
+        }
+        true
+    }
+}
+
+/// HMAC-SHA256签名令牌校验器
+///
+/// 令牌为JWT HS256格式：base64url(头部).base64url(载荷).base64url(签名)，
+/// 载荷中sub为用户ID，exp为过期时间，iss/aud按配置校验，jti为防重放的随机数
+pub struct HmacTokenVerifier {
+    /// 签名密钥
+    secret: Vec<u8>,
+    /// 要求的签发者，None表示不校验
+    issuer: Option<String>,
+    /// 要求的受众，None表示不校验
+    audience: Option<String>,
+    /// 允许的时钟偏差（秒）
+    leeway_secs: u64,
+    /// 是否拒绝重复使用的令牌（要求jti）
+    replay_check: bool,
+    /// 已使用的随机数
+    nonce_cache: Mutex<NonceCache>,
+}
+
+impl HmacTokenVerifier {
+    /// 根据登录配置创建校验器
+    pub fn new(config: &LoginConfig) -> Self {
+        Self {
+            secret: config.secret.as_bytes().to_vec(),
+            issuer: config.issuer.clone().filter(|issuer| !issuer.is_empty()),
+            audience: config.audience.clone().filter(|audience| !audience.is_empty()),
+            leeway_secs: config.leeway_secs,
+            replay_check: config.replay_check,
+            nonce_cache: Mutex::new(NonceCache::new()),
+        }
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length")
+    }
+
+    /// 按指定的当前时间（Unix秒）校验令牌
+    fn verify_at(&self, token: &str, now: u64) -> Result<TokenClaims, TokenError> {
+        let mut segments = token.split('.');
+        let (header, payload, signature) = match (segments.next(), segments.next(), segments.next(), segments.next()) {
+            (Some(header), Some(payload), Some(signature), None) => (header, payload, signature),
+            _ => return Err(TokenError::Malformed("expected 3 segments".to_string())),
+        };
+
+        // 先校验签名，未通过签名的内容不做进一步解析
+        let header_json = Self::decode_segment(header, "header")?;
+        let header_value: TokenHeader = serde_json::from_slice(&header_json)
+            .map_err(|e| TokenError::Malformed(format!("invalid header: {}", e)))?;
+        if header_value.alg != HMAC_TOKEN_ALGORITHM {
+            return Err(TokenError::UnsupportedAlgorithm(header_value.alg));
+        }
+        let signature = Self::decode_segment(signature, "signature")?;
+        let mut mac = self.mac();
+        mac.update(header.as_bytes());
+        mac.update(b".");
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature).map_err(|_| TokenError::BadSignature)?;
+
+        let payload_json = Self::decode_segment(payload, "payload")?;
+        let claims: TokenPayload = serde_json::from_slice(&payload_json)
+            .map_err(|e| TokenError::Malformed(format!("invalid payload: {}", e)))?;
+
+        let expires_at = claims.exp.ok_or(TokenError::MissingClaim("exp"))?;
+        if expires_at.saturating_add(self.leeway_secs) < now {
+            return Err(TokenError::Expired);
+        }
+        if let Some(not_before) = claims.nbf {
+            if not_before > now.saturating_add(self.leeway_secs) {
+                return Err(TokenError::NotYetValid);
+            }
+        }
+        if let Some(ref issuer) = self.issuer {
+            if claims.iss.as_ref() != Some(issuer) {
+                return Err(TokenError::InvalidIssuer);
+            }
+        }
+        if let Some(ref audience) = self.audience {
+            if !claims.aud.as_ref().is_some_and(|aud| aud.contains(audience)) {
+                return Err(TokenError::InvalidAudience);
+            }
+        }
+        let user_id = match claims.sub {
+            Some(NumberOrString::Number(user_id)) => user_id,
+            Some(NumberOrString::String(ref user_id)) => user_id.parse::<u64>()
+                .map_err(|_| TokenError::Malformed(format!("sub is not a user id: {}", user_id)))?,
+            None => return Err(TokenError::MissingClaim("sub")),
+        };
+
+        // 所有检查通过后才记录随机数，避免无效令牌占用缓存
+        if self.replay_check {
+            let nonce = claims.jti.as_deref().ok_or(TokenError::MissingClaim("jti"))?;
+            let mut nonce_cache = self.nonce_cache.lock().unwrap();
+            if !nonce_cache.insert(nonce, expires_at.saturating_add(self.leeway_secs), now) {
+                return Err(TokenError::Replayed);
+            }
+        }
+
+        Ok(TokenClaims {
+            user_id,
+            issuer: claims.iss,
+            expires_at,
+            nonce: claims.jti,
+        })
+    }
+
+    /// 解码base64url分段
+    fn decode_segment(segment: &str, name: &str) -> Result<Vec<u8>, TokenError> {
+        URL_SAFE_NO_PAD.decode(segment)
+            .map_err(|e| TokenError::Malformed(format!("invalid {} encoding: {}", name, e)))
+    }
+}
+
+impl TokenVerifier for HmacTokenVerifier {
+    fn verify(&self, token: &str) -> Result<TokenClaims, TokenError> {
+        self.verify_at(token, now_secs())
+    }
+}
+
+/// 测试模式校验器，不校验签名，不能用于对外服务
+///
+/// 数字令牌直接作为用户ID，其他非空令牌按哈希得到固定的用户ID
+pub struct TestTokenVerifier;
+
+impl TokenVerifier for TestTokenVerifier {
+    fn verify(&self, token: &str) -> Result<TokenClaims, TokenError> {
+        if token.is_empty() {
+            return Err(TokenError::Malformed("empty token".to_string()));
+        }
+        let user_id = token.parse::<u64>()
+            .unwrap_or_else(|_| 10000 + stable_hash(token.as_bytes()) % 100_000_000);
+        info!("Test token verifier accepted token as user {}", user_id);
+
+        Ok(TokenClaims {
+            user_id,
+            issuer: None,
+            expires_at: 0,
+            nonce: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "test-secret";
+    const NOW: u64 = 1_700_000_000;
+
+    fn verifier() -> HmacTokenVerifier {
+        HmacTokenVerifier::new(&LoginConfig {
+            secret: SECRET.to_string(),
+            issuer: Some("pantyhose-auth".to_string()),
+            audience: Some("pantyhose".to_string()),
+            leeway_secs: 30,
+            ..LoginConfig::default()
+        })
+    }
+
+    fn sign_with(secret: &str, header_json: &str, payload_json: &str) -> String {
+        let signing_input = format!("{}.{}", URL_SAFE_NO_PAD.encode(header_json), URL_SAFE_NO_PAD.encode(payload_json));
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(signing_input.as_bytes());
+        format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+    }
+
+    fn sign(payload_json: &str) -> String {
+        sign_with(SECRET, r#"{"alg":"HS256","typ":"JWT"}"#, payload_json)
+    }
+
+    fn payload(exp: u64, extra: &str) -> String {
+        format!(r#"{{"sub":10001,"iss":"pantyhose-auth","aud":"pantyhose","exp":{},"jti":"{}"{}}}"#, exp, exp, extra)
+    }
+
+    #[test]
+    fn valid_token() {
+        let claims = verifier().verify(&sign(&payload(u64::MAX / 2, ""))).unwrap();
+        assert_eq!(claims.user_id, 10001);
+        assert_eq!(claims.issuer.as_deref(), Some("pantyhose-auth"));
+        assert_eq!(claims.expires_at, u64::MAX / 2);
+
+        let token = sign(r#"{"sub":"10002","iss":"pantyhose-auth","aud":"pantyhose","exp":4000000000,"jti":"a"}"#);
+        assert_eq!(verifier().verify(&token).unwrap().user_id, 10002);
+    }
+
+    #[test]
+    fn bad_signature() {
+        let token = sign_with("other-secret", r#"{"alg":"HS256","typ":"JWT"}"#, &payload(4_000_000_000, ""));
+        assert_eq!(verifier().verify(&token), Err(TokenError::BadSignature));
+
+        // 签名有效但载荷被替换
+        let token = sign(&payload(4_000_000_000, ""));
+        let mut segments: Vec<&str> = token.split('.').collect();
+        let tampered = URL_SAFE_NO_PAD.encode(r#"{"sub":1,"iss":"pantyhose-auth","aud":"pantyhose","exp":4000000000,"jti":"b"}"#);
+        segments[1] = &tampered;
+        assert_eq!(verifier().verify(&segments.join(".")), Err(TokenError::BadSignature));
+    }
+
+    #[test]
+    fn malformed_token() {
+        assert!(matches!(verifier().verify("abc"), Err(TokenError::Malformed(_))));
+        assert!(matches!(verifier().verify("a.b.c.d"), Err(TokenError::Malformed(_))));
+        assert!(matches!(verifier().verify("!.b.c"), Err(TokenError::Malformed(_))));
+    }
+
+    #[test]
+    fn unsupported_algorithm() {
+        let token = sign_with(SECRET, r#"{"alg":"none","typ":"JWT"}"#, &payload(4_000_000_000, ""));
+        assert_eq!(verifier().verify(&token), Err(TokenError::UnsupportedAlgorithm("none".to_string())));
+        let token = sign_with(SECRET, r#"{"alg":"HS512","typ":"JWT"}"#, &payload(4_000_000_000, ""));
+        assert_eq!(verifier().verify(&token), Err(TokenError::UnsupportedAlgorithm("HS512".to_string())));
+    }
+
+    #[test]
+    fn expired_and_leeway() {
+        let verifier = verifier();
+        assert_eq!(verifier.verify(&sign(&payload(1, ""))), Err(TokenError::Expired));
+        assert_eq!(verifier.verify_at(&sign(&payload(NOW - 31, "")), NOW), Err(TokenError::Expired));
+        // 过期时间在时钟偏差内仍然有效
+        assert!(verifier.verify_at(&sign(&payload(NOW - 30, "")), NOW).is_ok());
+        assert!(verifier.verify_at(&sign(&payload(NOW - 1, "")), NOW).is_ok());
+    }
+
+    #[test]
+    fn not_yet_valid() {
+        let verifier = verifier();
+        let token = sign(&payload(NOW + 3600, &format!(r#","nbf":{}"#, NOW + 31)));
+        assert_eq!(verifier.verify_at(&token, NOW), Err(TokenError::NotYetValid));
+        let token = sign(&payload(NOW + 3601, &format!(r#","nbf":{}"#, NOW + 30)));
+        assert!(verifier.verify_at(&token, NOW).is_ok());
+    }
+
+    #[test]
+    fn issuer_and_audience_mismatch() {
+        let verifier = verifier();
+        let token = sign(r#"{"sub":10001,"iss":"other","aud":"pantyhose","exp":4000000000,"jti":"c"}"#);
+        assert_eq!(verifier.verify(&token), Err(TokenError::InvalidIssuer));
+        let token = sign(r#"{"sub":10001,"aud":"pantyhose","exp":4000000000,"jti":"d"}"#);
+        assert_eq!(verifier.verify(&token), Err(TokenError::InvalidIssuer));
+        let token = sign(r#"{"sub":10001,"iss":"pantyhose-auth","aud":"other","exp":4000000000,"jti":"e"}"#);
+        assert_eq!(verifier.verify(&token), Err(TokenError::InvalidAudience));
+        let token = sign(r#"{"sub":10001,"iss":"pantyhose-auth","exp":4000000000,"jti":"f"}"#);
+        assert_eq!(verifier.verify(&token), Err(TokenError::InvalidAudience));
+    }
+
+    #[test]
+    fn audience_array() {
+        let verifier = verifier();
+        let token = sign(r#"{"sub":10001,"iss":"pantyhose-auth","aud":["web","pantyhose"],"exp":4000000000,"jti":"g"}"#);
+        assert!(verifier.verify(&token).is_ok());
+        let token = sign(r#"{"sub":10001,"iss":"pantyhose-auth","aud":["web","admin"],"exp":4000000000,"jti":"h"}"#);
+        assert_eq!(verifier.verify(&token), Err(TokenError::InvalidAudience));
+    }
+
+    #[test]
+    fn missing_claims() {
+        let verifier = verifier();
+        let token = sign(r#"{"iss":"pantyhose-auth","aud":"pantyhose","exp":4000000000,"jti":"i"}"#);
+        assert_eq!(verifier.verify(&token), Err(TokenError::MissingClaim("sub")));
+        let token = sign(r#"{"sub":10001,"iss":"pantyhose-auth","aud":"pantyhose","jti":"j"}"#);
+        assert_eq!(verifier.verify(&token), Err(TokenError::MissingClaim("exp")));
+        let token = sign(r#"{"sub":10001,"iss":"pantyhose-auth","aud":"pantyhose","exp":4000000000}"#);
+        assert_eq!(verifier.verify(&token), Err(TokenError::MissingClaim("jti")));
+    }
+
+    #[test]
+    fn replayed_nonce() {
+        let verifier = verifier();
+        let token = sign(&payload(4_000_000_000, ""));
+        assert!(verifier.verify(&token).is_ok());
+        assert_eq!(verifier.verify(&token), Err(TokenError::Replayed));
+
+        // 关闭重放检查时同一个令牌可以重复使用，也不要求jti
+        let verifier = HmacTokenVerifier::new(&LoginConfig {
+            secret: SECRET.to_string(),
+            replay_check: false,
+            ..LoginConfig::default()
+        });
+        assert!(verifier.verify(&token).is_ok());
+        assert!(verifier.verify(&token).is_ok());
+        assert!(verifier.verify(&sign(r#"{"sub":10001,"exp":4000000000}"#)).is_ok());
+    }
+
+    #[test]
+    fn test_verifier() {
+        assert_eq!(TestTokenVerifier.verify("10001").unwrap().user_id, 10001);
+        assert_eq!(TestTokenVerifier.verify("alice").unwrap().user_id, TestTokenVerifier.verify("alice").unwrap().user_id);
+        assert!(TestTokenVerifier.verify("").is_err());
+    }
+
+    #[test]
+    fn test_verifier_requires_allow_test() {
+        let config = LoginConfig { verifier: "test".to_string(), ..LoginConfig::default() };
+        assert!(create_token_verifier(&config).is_none());
+
+        let config = LoginConfig { verifier: "test".to_string(), allow_test: true, ..LoginConfig::default() };
+        assert!(create_token_verifier(&config).is_some());
+    }
+
+    #[test]
+    fn hmac_verifier_requires_secret() {
+        assert!(create_token_verifier(&LoginConfig::default()).is_none());
+        let config = LoginConfig { secret: SECRET.to_string(), ..LoginConfig::default() };
+        assert!(create_token_verifier(&config).is_some());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::framework::network::net_addr;
+use std::time::{Duration, Instant};
+
+/// 主动发起的集群连接状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusterLinkState {
+    /// 正在连接或握手
+    Connecting,
+    /// 已连接并完成握手
+    Connected,
+    /// 连接断开或失败，等待重连
+    WaitingRetry,
+}
+
+/// 当前节点主动发起的集群连接（连接master或连接其他节点）
+///
+/// 连接断开或连接失败后按指数退避重连，直到节点被移出集群
+#[derive(Debug, Clone)]
+pub struct ClusterLink {
+    /// 目标服务器ID
+    pub server_id: u32,
+    /// 目标后端主机地址
+    pub back_host: String,
+    /// 目标后端TCP端口
+    pub back_tcp_port: u16,
+    /// 是否为连接master的链路
+    pub is_master: bool,
+    /// 当前使用的后端会话ID，等待重连时为None
+    pub session_id: Option<u64>,
+    /// 连接状态
+    pub state: ClusterLinkState,
+    /// 连续重连次数，连接成功后清零
+    pub retry_count: u32,
+    /// 下一次重连的时间点
+    pub next_retry_time: Option<Instant>,
+    /// 最近一次的重连等待时间
+    pub last_retry_delay: Duration,
+}
+
+impl ClusterLink {
+    /// 创建新的集群连接
+    pub fn new(server_id: u32, back_host: String, back_tcp_port: u16, is_master: bool) -> Self {
+        Self {
+            server_id,
+            back_host,
+            back_tcp_port,
+            is_master,
+            session_id: None,
+            state: ClusterLinkState::Connecting,
+            retry_count: 0,
+            next_retry_time: None,
+            last_retry_delay: Duration::ZERO,
+        }
+    }
+
+    /// 计算下一次重连的等待时间：min_delay * 2^retry_count，不超过max_delay
+    pub fn backoff_delay(&self, min_delay: Duration, max_delay: Duration) -> Duration {
+        min_delay.saturating_mul(2u32.saturating_pow(self.retry_count)).min(max_delay)
+    }
+
+    /// 是否到了重连时间
+    pub fn is_retry_due(&self, now: Instant) -> bool {
+        self.state == ClusterLinkState::WaitingRetry
+            && self.next_retry_time.is_some_and(|time| time <= now)
+    }
+
+    /// 获取连接信息字符串
+    pub fn get_info(&self) -> String {
+        format!("Link[server_id={}, master={}, addr={}, state={:?}, retry_count={}]",
+                self.server_id, self.is_master, net_addr::format_addr(&self.back_host, self.back_tcp_port),
+                self.state, self.retry_count)
+    }
+}
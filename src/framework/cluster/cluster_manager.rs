@@ -3,19 +3,98 @@ use crate::framework::network::{
     NetworkEngineEventManager, NetworkEventData, NetworkEventType, NetworkEventHandler,
     network_event_queue::ServerType
 };
-use crate::framework::config::config::ServerConfig;
-use crate::framework::session::SessionTrait;
-use crate::framework::author::server_token::generate_token;
-use tracing::{info, error, debug};
+use crate::framework::config::config::{ClusterConfig, ServerConfig};
+use crate::framework::session::{BackSessionState, SessionTrait};
+use crate::framework::author::server_token::{NodeAuthenticator, generate_nonce, NODE_NONCE_LEN};
+use super::ServerManager;
+use super::cluster_link::{ClusterLink, ClusterLinkState};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{info, error, debug, warn};
+
+/// 节点被移出集群的原因，对应NodeUnregisterBNotify.reason
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeRemoveReason {
+    /// 连续多个心跳间隔没有收到心跳
+    HeartbeatTimeout = 1,
+    /// 节点主动离开集群（NodeUnregisterBRequest）
+    Leave = 2,
+    /// 同一服务器ID的新节点注册，替换已失联的旧节点
+    Replaced = 3,
+}
+
+impl NodeRemoveReason {
+    /// 从协议中的数值转换
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(NodeRemoveReason::HeartbeatTimeout),
+            2 => Some(NodeRemoveReason::Leave),
+            3 => Some(NodeRemoveReason::Replaced),
+            _ => None,
+        }
+    }
+}
+
+/// 节点注册错误码，对应NodeRegisterBResponse.error_code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeRegisterErrorCode {
+    /// 注册成功
+    Success = 0,
+    /// 服务器ID已被在线节点使用，请求方应退出
+    DuplicateServerId = 1,
+}
+
+impl NodeRegisterErrorCode {
+    /// 从协议中的数值转换
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(NodeRegisterErrorCode::Success),
+            1 => Some(NodeRegisterErrorCode::DuplicateServerId),
+            _ => None,
+        }
+    }
+}
+
+/// 当前节点负载的计算函数，每次发送心跳前调用
+pub type LoadProvider = Box<dyn FnMut() -> u32 + Send>;
+
+/// 当前节点在集群中的身份，由服务器从配置中收集后传给init
+pub struct ClusterNodeOptions {
+    /// 当前服务器所在的分组名，master分组不连接master
+    pub server_group_name: String,
+    /// master服务器配置
+    pub master_config: Option<ServerConfig>,
+    /// 当前服务器配置
+    pub server_config: Option<ServerConfig>,
+    /// 节点认证
+    pub node_authenticator: NodeAuthenticator,
+}
+
+/// 旧会话超过多少个心跳间隔没有收到心跳，即认为已失联，可被同一服务器ID的新节点替换
+const STALE_HEARTBEAT_INTERVALS: u32 = 2;
 
 pub struct ClusterManager {
     network_engine: Option<*mut NetworkEngine>,
     back_session_manager: Option<*mut crate::framework::session::BackSessionManager>,
+    server_manager: Option<*mut ServerManager>,
     master_config: Option<ServerConfig>,
     server_group_name: String,
     server_config: Option<ServerConfig>,  // 服务器配置
-    author_key: String,  // 作者密钥
-    client_token: String,  // 客户端令牌
+    node_authenticator: NodeAuthenticator,  // 节点认证
+    heartbeat_interval: Duration,  // 心跳发送间隔，为0时不发送心跳
+    heartbeat_miss_threshold: u32,  // 允许连续丢失的心跳间隔数
+    last_heartbeat_time: Instant,  // 上一次发送心跳的时间
+    next_heartbeat_id: u32,  // 下一个心跳消息ID
+    links: HashMap<u32, ClusterLink>,  // 主动发起的连接 <server_id, 连接状态>
+    reconnect_min_delay: Duration,  // 重连初始等待时间
+    reconnect_max_delay: Duration,  // 重连最大等待时间
+    handshake_timeout: Duration,  // 节点认证握手超时
+    pending_leaves: Vec<(u64, u32)>,  // 已申请离开集群、等待移除的 <会话ID, 服务器ID>（master使用）
+    is_leaving: bool,  // 当前节点是否正在离开集群
+    leave_timeout: Duration,  // 退出时等待master确认离开的最长时间
+    local_load: u32,  // 当前节点负载，随心跳上报给其他节点
+    load_provider: Option<LoadProvider>,  // 每次发送心跳前刷新local_load
+    shutdown_requested: bool,  // 集群要求当前节点退出（如服务器ID冲突）
 }
 
 // 安全性：ClusterManager只在单线程环境中使用
@@ -27,11 +106,25 @@ impl ClusterManager {
         Self {
             network_engine: None,
             back_session_manager: None,
+            server_manager: None,
             master_config: None,
             server_group_name: String::new(),
             server_config: None,
-            author_key: String::new(),
-            client_token: String::new(),
+            node_authenticator: NodeAuthenticator::default(),
+            heartbeat_interval: Duration::ZERO,
+            heartbeat_miss_threshold: 0,
+            last_heartbeat_time: Instant::now(),
+            next_heartbeat_id: 1,
+            links: HashMap::new(),
+            reconnect_min_delay: Duration::ZERO,
+            reconnect_max_delay: Duration::ZERO,
+            handshake_timeout: Duration::ZERO,
+            pending_leaves: Vec::new(),
+            is_leaving: false,
+            leave_timeout: Duration::ZERO,
+            local_load: 0,
+            load_provider: None,
+            shutdown_requested: false,
         }
     }
 
@@ -39,25 +132,40 @@ impl ClusterManager {
     // 初始化方法：设置配置并注册事件处理器
     pub fn init(
         &mut self, 
-        server_group_name: String,
         network_engine: &mut NetworkEngine,
         back_session_manager: &mut crate::framework::session::BackSessionManager,
+        server_manager: &mut ServerManager,
         event_manager: &mut NetworkEngineEventManager,
-        master_config: Option<ServerConfig>,
-        server_config: Option<ServerConfig>,
-        author_key: String,
+        node_options: ClusterNodeOptions,
+        cluster_config: &ClusterConfig,
     ) -> bool {
+        let ClusterNodeOptions { server_group_name, master_config, server_config, node_authenticator } = node_options;
         
         // Store pointers for later use
         self.network_engine = Some(network_engine as *mut NetworkEngine);
         self.back_session_manager = Some(back_session_manager as *mut crate::framework::session::BackSessionManager);
+        self.server_manager = Some(server_manager as *mut ServerManager);
         self.master_config = master_config.clone();
         self.server_group_name = server_group_name.clone();
         self.server_config = server_config;
-        self.author_key = author_key;
+        self.node_authenticator = node_authenticator;
+        
+        // 心跳配置
+        self.heartbeat_interval = Duration::from_millis(cluster_config.heartbeat_interval_ms);
+        self.heartbeat_miss_threshold = cluster_config.heartbeat_miss_threshold.max(1);
+        self.last_heartbeat_time = Instant::now();
+        info!("Cluster heartbeat interval {}ms, miss threshold {}", 
+              cluster_config.heartbeat_interval_ms, self.heartbeat_miss_threshold);
         
-        // 生成client_token
-        self.client_token = generate_token(&self.author_key);
+        // 重连配置
+        self.reconnect_min_delay = Duration::from_millis(cluster_config.reconnect_min_delay_ms.max(1));
+        self.reconnect_max_delay = Duration::from_millis(cluster_config.reconnect_max_delay_ms).max(self.reconnect_min_delay);
+        info!("Cluster reconnect delay {}ms ~ {}ms", 
+              self.reconnect_min_delay.as_millis(), self.reconnect_max_delay.as_millis());
+        
+        // 握手超时配置
+        self.handshake_timeout = Duration::from_millis(cluster_config.handshake_timeout_ms.max(1));
+        self.leave_timeout = Duration::from_millis(cluster_config.leave_timeout_ms);
         
         // 如果有master配置，说明当前不是master，需要连接到master
         if server_group_name != "master" {
@@ -78,11 +186,527 @@ impl ClusterManager {
             // Clear all pointers
             self.network_engine = None;
             self.back_session_manager = None;
+            self.server_manager = None;
             self.master_config = None;
+            self.load_provider = None;
+            self.links.clear();
+            self.pending_leaves.clear();
             info!("ClusterManager disposed and unregistered from NetworkEngine");
         }
     }
 
+    // ========== heartbeat methods ==========
+    /// 主循环中调用：移除主动离开的节点，关闭握手超时的会话，处理到期的重连，按间隔发送心跳，并移除心跳超时的节点
+    pub fn update(&mut self) {
+        self.process_pending_leaves();
+        self.check_handshake_timeouts();
+        self.process_reconnects();
+
+        if self.heartbeat_interval.is_zero() {
+            return;
+        }
+
+        if self.last_heartbeat_time.elapsed() >= self.heartbeat_interval {
+            self.last_heartbeat_time = Instant::now();
+            self.send_heartbeats();
+        }
+
+        self.check_heartbeat_timeouts();
+    }
+
+    /// 设置当前节点负载（如在线玩家数），随下一次心跳上报给其他节点，供按负载路由使用
+    pub fn set_local_load(&mut self, load: u32) {
+        self.local_load = load;
+    }
+
+    /// 获取当前节点负载
+    pub fn get_local_load(&self) -> u32 {
+        self.local_load
+    }
+
+    /// 设置负载计算函数，每次发送心跳前调用并通过set_local_load更新负载，替换之前的函数
+    ///
+    /// Server默认使用当前节点的前端会话数量，后端服务器可以改为房间人数等业务负载
+    pub fn set_load_provider(&mut self, load_provider: LoadProvider) {
+        self.load_provider = Some(load_provider);
+    }
+
+    /// 调用负载计算函数刷新当前节点负载
+    fn refresh_local_load(&mut self) {
+        if let Some(load_provider) = self.load_provider.as_mut() {
+            let load = load_provider();
+            self.set_local_load(load);
+        }
+    }
+
+    /// 向所有已授权的后端会话发送心跳请求
+    fn send_heartbeats(&mut self) {
+        use crate::proto::messages::protobuf::message::cluster::NodeHeartbeatBRequest;
+
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &mut *ptr },
+            None => return,
+        };
+
+        self.refresh_local_load();
+        let heartbeat_id = self.next_heartbeat_id;
+        self.next_heartbeat_id = self.next_heartbeat_id.wrapping_add(1);
+        let heartbeat = NodeHeartbeatBRequest {
+            msg_unique_id: heartbeat_id,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            load: self.local_load,
+        };
+
+        let session_ids: Vec<u64> = back_session_mgr.get_all_sessions()
+            .iter()
+            .filter(|session| session.is_authenticated())
+            .map(|session| session.get_session_id())
+            .collect();
+
+        for session_id in session_ids {
+            if let Some(session) = back_session_mgr.get_any_session_mut(session_id) {
+                if !session.send_message(heartbeat.clone()) {
+                    warn!("Failed to send heartbeat to server {} (session {})", session.get_server_id(), session_id);
+                }
+            }
+        }
+    }
+
+    /// 关闭超时未完成握手的会话，主动发起的连接进入重连流程
+    fn check_handshake_timeouts(&mut self) {
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &mut *ptr },
+            None => return,
+        };
+
+        for (session_id, _server_id) in back_session_mgr.close_expired_handshakes(self.handshake_timeout) {
+            self.handle_link_lost(session_id);
+        }
+    }
+
+    /// 检查所有已授权会话的心跳，超过阈值的节点会被移除
+    fn check_heartbeat_timeouts(&mut self) {
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &mut *ptr },
+            None => return,
+        };
+
+        let timeout = self.heartbeat_interval * self.heartbeat_miss_threshold;
+        let timeout_sessions: Vec<(u64, u32)> = back_session_mgr.get_all_sessions()
+            .iter()
+            .filter(|session| session.is_authenticated() && session.get_last_heartbeat_time().elapsed() > timeout)
+            .map(|session| (session.get_session_id(), session.get_server_id()))
+            .collect();
+
+        for (session_id, server_id) in timeout_sessions {
+            error!("Server {} (session {}) missed heartbeats for more than {}ms, evicting", 
+                   server_id, session_id, timeout.as_millis());
+            self.evict_node(session_id, NodeRemoveReason::HeartbeatTimeout);
+        }
+    }
+
+    /// 将节点从集群中移除：关闭后端会话、从ServerManager删除，master还会通知其他节点
+    /// 
+    /// 注意：不能在该会话自身的消息处理函数中调用
+    pub fn evict_node(&mut self, session_id: u64, reason: NodeRemoveReason) {
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &mut *ptr },
+            None => return,
+        };
+
+        let server_id = match back_session_mgr.get_any_session(session_id) {
+            Some(session) => session.get_server_id(),
+            None => {
+                debug!("Evict node: session {} already removed", session_id);
+                return;
+            }
+        };
+
+        if back_session_mgr.get_session(session_id).is_some() {
+            back_session_mgr.remove_session(session_id);
+        } else {
+            back_session_mgr.remove_unauthorized_session(session_id);
+        }
+
+        self.remove_server_info(server_id);
+        info!("Evicted server {} (session {}) from cluster, reason: {:?}", server_id, session_id, reason);
+
+        // 如果是当前节点主动发起的连接，进入重连流程
+        self.handle_link_lost(session_id);
+
+        if self.is_master() {
+            self.broadcast_node_unregister(server_id, reason);
+        }
+    }
+
+    /// 处理master发来的节点移除通知：从ServerManager删除并关闭与该节点的会话
+    pub fn remove_node(&mut self, server_id: u32, reason: NodeRemoveReason) {
+        if self.master_config.as_ref().is_some_and(|config| config.id == server_id) {
+            warn!("Ignoring removal of master server {}", server_id);
+            return;
+        }
+
+        self.remove_server_info(server_id);
+
+        // 节点已被移出集群，不再重连
+        if self.links.remove(&server_id).is_some() {
+            info!("Stopped reconnecting to removed server {}", server_id);
+        }
+
+        if let Some(ptr) = self.back_session_manager {
+            let back_session_mgr = unsafe { &mut *ptr };
+            if let Some(session_id) = back_session_mgr.find_session_by_server_id(server_id).map(|session| session.get_session_id()) {
+                back_session_mgr.remove_session(session_id);
+                info!("Closed back session {} to removed server {}", session_id, server_id);
+            }
+        }
+
+        info!("Server {} removed from cluster, reason: {:?}", server_id, reason);
+    }
+
+    /// 从ServerManager中删除服务器信息（master自身不在ServerManager中）
+    fn remove_server_info(&mut self, server_id: u32) {
+        if self.master_config.as_ref().is_some_and(|config| config.id == server_id) {
+            return;
+        }
+
+        if let Some(ptr) = self.server_manager {
+            let server_manager = unsafe { &mut *ptr };
+            if server_manager.remove_server(server_id).is_none() {
+                debug!("Server {} not found in ServerManager", server_id);
+            }
+        }
+    }
+
+    /// master向其他所有已授权节点广播节点移除通知
+    fn broadcast_node_unregister(&mut self, server_id: u32, reason: NodeRemoveReason) {
+        use crate::proto::messages::protobuf::message::cluster::NodeUnregisterBNotify;
+
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &mut *ptr },
+            None => return,
+        };
+
+        let notify = NodeUnregisterBNotify {
+            server_id,
+            reason: reason as u32,
+        };
+
+        let mut notified_count = 0;
+        back_session_mgr.for_each_session(|session| {
+            if session.is_authenticated() && session.get_server_id() != server_id {
+                if session.send_message(notify.clone()) {
+                    notified_count += 1;
+                } else {
+                    error!("Failed to send NodeUnregisterBNotify to server {}", session.get_server_id());
+                }
+            }
+        });
+
+        info!("Broadcast removal of server {} to {} nodes", server_id, notified_count);
+    }
+
+    /// 当前节点是否为master
+    pub fn is_master(&self) -> bool {
+        self.server_group_name == "master"
+    }
+
+    /// 集群是否要求当前节点退出，Server主循环检查后停止运行
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.shutdown_requested
+    }
+
+    // ========== duplicate node methods ==========
+    /// 旧会话是否仍然在线：连接未断开，且最近收到过心跳
+    fn is_session_alive(&self, session: &crate::framework::session::BackSession) -> bool {
+        if !session.is_connected() {
+            return false;
+        }
+        self.heartbeat_interval.is_zero()
+            || session.get_last_heartbeat_time().elapsed() <= self.heartbeat_interval * STALE_HEARTBEAT_INTERVALS
+    }
+
+    /// 处理同一服务器ID的重复节点，在授权新会话之前调用
+    /// 
+    /// master决定节点能否加入集群：旧会话仍在线时拒绝新节点，旧会话已失联时移除旧节点；
+    /// 其他节点只会收到master已接受的节点的连接，直接用新连接替换旧连接
+    /// 
+    /// # 返回值
+    /// 新会话可以继续注册/连接时返回true
+    pub fn resolve_duplicate_node(&mut self, server_id: u32, session_id: u64) -> bool {
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &mut *ptr },
+            None => return true,
+        };
+
+        let (old_session_id, alive) = match back_session_mgr.find_session_by_server_id(server_id) {
+            Some(session) if session.get_session_id() != session_id => (session.get_session_id(), self.is_session_alive(session)),
+            _ => return true,
+        };
+
+        if alive && self.is_master() {
+            error!("Rejecting server {} on session {}: the server id is already online on session {}", 
+                   server_id, session_id, old_session_id);
+            return false;
+        }
+
+        warn!("Server {} connected again on session {}, replacing {} session {}", 
+              server_id, session_id, if alive { "previous" } else { "stale" }, old_session_id);
+        self.evict_node(old_session_id, NodeRemoveReason::Replaced);
+        true
+    }
+
+    /// master拒绝了当前节点的注册：停止重连并请求退出，避免两个进程使用同一个服务器ID
+    pub fn handle_node_register_rejected(&mut self, session_id: u64, error_code: u32) {
+        let master_id = self.master_config.as_ref().map_or(0, |config| config.id);
+        if !self.is_awaiting_handshake_response(session_id, master_id) {
+            error!("Security: Unexpected NodeRegisterBResponse on session {}, closing session", session_id);
+            if let Some(ptr) = self.back_session_manager {
+                unsafe { (*ptr).remove_bad_token_session(session_id, master_id) };
+            }
+            self.handle_link_lost(session_id);
+            return;
+        }
+
+        match NodeRegisterErrorCode::from_u32(error_code) {
+            Some(NodeRegisterErrorCode::DuplicateServerId) => {
+                error!("Master rejected registration: server id {} is already used by a running node, shutting down", 
+                       self.server_config.as_ref().map_or(0, |config| config.id));
+            }
+            _ => error!("Master rejected registration with error code {}, shutting down", error_code),
+        }
+
+        self.links.clear();
+        if let Some(ptr) = self.back_session_manager {
+            unsafe { (*ptr).remove_unauthorized_session(session_id) };
+        }
+        self.shutdown_requested = true;
+    }
+
+    // ========== leave methods ==========
+    /// 通知master当前节点主动离开集群，并停止所有重连（Server退出主循环后调用）
+    /// 
+    /// master收到后会关闭与当前节点的会话并广播NodeUnregisterBNotify，其他节点随即移除当前节点；
+    /// 如果通知没有送达，master仍会通过心跳超时移除当前节点
+    /// 
+    /// # 返回值
+    /// 已向master发送离开请求、需要等待确认时返回true
+    pub fn leave_cluster(&mut self) -> bool {
+        use crate::proto::messages::protobuf::message::cluster::NodeUnregisterBRequest;
+
+        if self.is_leaving {
+            return false;
+        }
+        self.is_leaving = true;
+
+        // 不再重连任何节点
+        self.links.clear();
+
+        if self.is_master() {
+            return false;
+        }
+
+        let (server_id, master_id) = match (&self.server_config, &self.master_config) {
+            (Some(server_config), Some(master_config)) => (server_config.id, master_config.id),
+            _ => return false,
+        };
+
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &mut *ptr },
+            None => return false,
+        };
+
+        let request = NodeUnregisterBRequest {
+            msg_unique_id: chrono::Utc::now().timestamp_millis() as u32,
+            server_id,
+        };
+
+        match back_session_mgr.find_session_by_server_id_mut(master_id) {
+            Some(session) if session.is_authenticated() => {
+                if session.send_message(request) {
+                    info!("Sent NodeUnregisterBRequest to master server {}, leaving cluster", master_id);
+                    true
+                } else {
+                    error!("Failed to send NodeUnregisterBRequest to master server {}", master_id);
+                    false
+                }
+            }
+            _ => {
+                warn!("Not connected to master server {}, leaving cluster without notice", master_id);
+                false
+            }
+        }
+    }
+
+    /// 是否仍在等待master确认离开集群
+    /// 
+    /// master处理NodeUnregisterBRequest后关闭与当前节点的会话，会话移除即视为确认
+    pub fn is_leave_pending(&self) -> bool {
+        if !self.is_leaving {
+            return false;
+        }
+        let (Some(master_config), Some(ptr)) = (&self.master_config, self.back_session_manager) else {
+            return false;
+        };
+        unsafe { (*ptr).find_session_by_server_id(master_config.id) }
+            .is_some_and(|session| session.is_connected())
+    }
+
+    /// 退出时等待master确认离开的最长时间
+    pub fn get_leave_timeout(&self) -> Duration {
+        self.leave_timeout
+    }
+
+    /// master记录申请离开集群的会话，在下一次update中移除
+    /// 
+    /// 不能在该会话自身的消息处理函数中直接移除会话，因此延迟处理
+    pub fn schedule_node_leave(&mut self, session_id: u64, server_id: u32) {
+        if !self.pending_leaves.iter().any(|&(pending_session_id, _)| pending_session_id == session_id) {
+            self.pending_leaves.push((session_id, server_id));
+        }
+    }
+
+    /// 移除所有已申请离开集群的节点，并广播NodeUnregisterBNotify
+    fn process_pending_leaves(&mut self) {
+        if self.pending_leaves.is_empty() {
+            return;
+        }
+
+        let pending_leaves = std::mem::take(&mut self.pending_leaves);
+        for (session_id, server_id) in pending_leaves {
+            let session_exists = self.back_session_manager
+                .is_some_and(|ptr| unsafe { (*ptr).get_any_session(session_id).is_some() });
+            if session_exists {
+                self.evict_node(session_id, NodeRemoveReason::Leave);
+            } else {
+                // 离开的节点已经断开连接，会话已被移除，仍需删除服务器信息并通知其他节点
+                self.remove_server_info(server_id);
+                info!("Server {} (session {}) left cluster", server_id, session_id);
+                if self.is_master() {
+                    self.broadcast_node_unregister(server_id, NodeRemoveReason::Leave);
+                }
+            }
+        }
+    }
+
+    // ========== reconnect methods ==========
+    /// 连接集群中的其他节点（来自NodeRegisterBResponse或NodeRegisterBNotify）
+    /// 
+    /// 已存在的连接不会重复创建，连接断开后会自动按退避策略重连
+    pub fn connect_to_peer(&mut self, server_id: u32, back_host: &str, back_tcp_port: u16) {
+        if let Some(link) = self.links.get_mut(&server_id) {
+            if link.state == ClusterLinkState::WaitingRetry {
+                // 等待重连期间地址可能已更新，下次重连使用新地址
+                link.back_host = back_host.to_string();
+                link.back_tcp_port = back_tcp_port;
+            }
+            debug!("Link to server {} already exists, skip connecting: {}", server_id, link.get_info());
+            return;
+        }
+
+        self.links.insert(server_id, ClusterLink::new(server_id, back_host.to_string(), back_tcp_port, false));
+        self.open_link(server_id);
+    }
+
+    /// 获取主动发起的连接状态
+    pub fn get_link(&self, server_id: u32) -> Option<&ClusterLink> {
+        self.links.get(&server_id)
+    }
+
+    /// 获取所有主动发起的连接状态
+    pub fn get_links(&self) -> Vec<&ClusterLink> {
+        self.links.values().collect()
+    }
+
+    /// 获取主动发起的连接当前会话的握手状态，等待重连时为None
+    pub fn get_link_session_state(&self, server_id: u32) -> Option<BackSessionState> {
+        let session_id = self.links.get(&server_id)?.session_id?;
+        let back_session_mgr = unsafe { &*self.back_session_manager? };
+        back_session_mgr.get_session_state(session_id)
+    }
+
+    /// 为连接创建新的客户端会话
+    fn open_link(&mut self, server_id: u32) {
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &mut *ptr },
+            None => {
+                error!("BackSessionManager pointer is null");
+                return;
+            }
+        };
+
+        let (min_delay, max_delay) = (self.reconnect_min_delay, self.reconnect_max_delay);
+        if let Some(link) = self.links.get_mut(&server_id) {
+            match back_session_mgr.create_client_session(server_id, &link.back_host, link.back_tcp_port) {
+                Some(session_id) => {
+                    link.session_id = Some(session_id);
+                    link.state = ClusterLinkState::Connecting;
+                    link.next_retry_time = None;
+                    debug!("Opening {} with session {}", link.get_info(), session_id);
+                }
+                None => {
+                    // 地址暂时无法解析（如DNS故障），按连接失败处理
+                    let delay = link.backoff_delay(min_delay, max_delay);
+                    link.session_id = None;
+                    link.state = ClusterLinkState::WaitingRetry;
+                    link.retry_count += 1;
+                    link.next_retry_time = Some(Instant::now() + delay);
+                    link.last_retry_delay = delay;
+                    warn!("Cannot open {}, retrying in {}ms", link.get_info(), delay.as_millis());
+                }
+            }
+        }
+    }
+
+    /// 连接建立成功，重置重连计数
+    fn handle_link_connected(&mut self, session_id: u64) {
+        if let Some(link) = self.links.values_mut().find(|link| link.session_id == Some(session_id)) {
+            if link.retry_count > 0 {
+                info!("Reconnected to server {} at {}:{} after {} retries", 
+                      link.server_id, link.back_host, link.back_tcp_port, link.retry_count);
+            }
+            link.state = ClusterLinkState::Connected;
+            link.retry_count = 0;
+            link.last_retry_delay = Duration::ZERO;
+        }
+    }
+
+    /// 连接失败或断开，按指数退避安排重连
+    fn handle_link_lost(&mut self, session_id: u64) {
+        let (min_delay, max_delay) = (self.reconnect_min_delay, self.reconnect_max_delay);
+        if let Some(link) = self.links.values_mut().find(|link| link.session_id == Some(session_id)) {
+            let delay = link.backoff_delay(min_delay, max_delay);
+            link.session_id = None;
+            link.state = ClusterLinkState::WaitingRetry;
+            link.retry_count += 1;
+            link.next_retry_time = Some(Instant::now() + delay);
+            link.last_retry_delay = delay;
+            warn!("Link to server {} (session {}) lost, reconnecting in {}ms (attempt {})", 
+                  link.server_id, session_id, delay.as_millis(), link.retry_count);
+        }
+    }
+
+    /// 对到期的连接发起重连
+    fn process_reconnects(&mut self) {
+        if self.links.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let due_server_ids: Vec<u32> = self.links.values()
+            .filter(|link| link.is_retry_due(now))
+            .map(|link| link.server_id)
+            .collect();
+
+        for server_id in due_server_ids {
+            if let Some(link) = self.links.get(&server_id) {
+                info!("Reconnecting to server {} at {}:{} (attempt {})", 
+                      server_id, link.back_host, link.back_tcp_port, link.retry_count);
+            }
+            self.open_link(server_id);
+        }
+    }
+
     // ========== other methods ==========
     // 连接到主服务器
     fn handle_client_connect_success(&mut self, event: &NetworkEventData) {
@@ -98,23 +722,137 @@ impl ClusterManager {
                     // 检查是否是连接到master服务器的session
                     if let Some(ref master_config) = self.master_config {
                         if session.get_server_id() == master_config.id {
-                            info!("Connected to master server, sending node registration");
-                            
-                            // 发送节点注册消息到master
-                            self.send_node_register(session_id);
+                            info!("Connected to master server, sending node challenge");
                         } else {
-                            info!("Connected to other node server {}, sending node connect request", session.get_server_id());
-                            
-                            // 发送节点连接消息（其他节点不需要延迟）
-                            self.send_node_connect(session_id);
+                            info!("Connected to other node server {}, sending node challenge", session.get_server_id());
                         }
+                        
+                        // 先完成挑战-应答认证，收到NodeChallengeBResponse后再发送注册/连接请求
+                        self.send_node_challenge(session_id);
                     }
                 }
             }
         }
     }
     
-    fn send_node_register(&mut self, session_id: u64) {
+    /// 发送节点认证挑战，随机数保存在会话中用于校验对端的证明
+    fn send_node_challenge(&mut self, session_id: u64) {
+        use crate::proto::messages::protobuf::message::cluster::NodeChallengeBRequest;
+        
+        let server_id = match self.server_config {
+            Some(ref config) => config.id,
+            None => {
+                error!("Cannot send node challenge: server_config is None");
+                return;
+            }
+        };
+        
+        if let Some(back_session_mgr) = self.back_session_manager {
+            unsafe {
+                let mgr = &mut *back_session_mgr;
+                if let Some(session) = mgr.get_unauthorized_session_mut(session_id) {
+                    let nonce = generate_nonce();
+                    session.set_auth_nonce(nonce.clone());
+                    if !session.send_message(NodeChallengeBRequest { server_id, nonce }) {
+                        error!("Failed to send node challenge on session {}", session_id);
+                    }
+                }
+            }
+        }
+    }
+    
+    /// 处理监听方返回的认证挑战响应
+    /// 
+    /// 校验监听方的证明后，用自己的证明发送注册（master）或连接（其他节点）请求；
+    /// 校验失败时关闭会话，连接随后按重连策略重试
+    pub fn handle_node_challenge_response(&mut self, session_id: u64, server_id: u32, server_nonce: &[u8], proof: &[u8]) {
+        let back_session_mgr = match self.back_session_manager {
+            Some(mgr) => unsafe { &mut *mgr },
+            None => {
+                error!("BackSessionManager not available for node challenge response");
+                return;
+            }
+        };
+        
+        let (client_nonce, peer_server_id) = match back_session_mgr.get_unauthorized_session_mut(session_id) {
+            Some(session) => (session.take_auth_nonce(), session.get_server_id()),
+            None => {
+                warn!("Ignoring NodeChallengeBResponse on session {} that is not waiting for authentication", session_id);
+                return;
+            }
+        };
+        
+        // 只接受本端发起挑战、且来自预期服务器的证明
+        if client_nonce.is_empty()
+            || server_id != peer_server_id
+            || server_nonce.len() != NODE_NONCE_LEN
+            || !self.node_authenticator.verify_server_proof(&client_nonce, peer_server_id, proof) {
+            error!("Security: Server {} failed node authentication on session {}, closing session", peer_server_id, session_id);
+            back_session_mgr.remove_bad_token_session(session_id, peer_server_id);
+            self.handle_link_lost(session_id);
+            return;
+        }
+        
+        let local_server_id = match self.server_config {
+            Some(ref config) => config.id,
+            None => {
+                error!("Cannot answer node challenge: server_config is None");
+                return;
+            }
+        };
+        let auth_proof = self.node_authenticator.client_proof(server_nonce, local_server_id);
+        debug!("Server {} authenticated on session {}", peer_server_id, session_id);
+        
+        let is_master = self.master_config.as_ref().is_some_and(|config| config.id == peer_server_id);
+        if is_master {
+            self.send_node_register(session_id, auth_proof);
+        } else {
+            self.send_node_connect(session_id, auth_proof);
+        }
+    }
+    
+    /// 会话是否已通过挑战校验、正在等待目标服务器的注册/连接响应
+    fn is_awaiting_handshake_response(&self, session_id: u64, server_id: u32) -> bool {
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &*ptr },
+            None => return false,
+        };
+
+        // 挑战校验通过后会话中的随机数已被取出
+        back_session_mgr.get_unauthorized_session(session_id).is_some_and(|session| {
+            session.get_state() == BackSessionState::Handshaking
+                && !session.has_auth_nonce()
+                && session.get_server_id() == server_id
+        })
+    }
+
+    /// 收到注册/连接响应后完成握手：将会话移入已授权列表，连接进入Connected状态
+    /// 
+    /// 只接受已通过挑战校验、且来自该连接目标服务器的响应，否则关闭会话并重连
+    pub fn complete_handshake(&mut self, session_id: u64, server_id: u32, server_type: String) -> bool {
+        let back_session_mgr = match self.back_session_manager {
+            Some(ptr) => unsafe { &mut *ptr },
+            None => return false,
+        };
+
+        if !self.is_awaiting_handshake_response(session_id, server_id) {
+            error!("Security: Unexpected handshake response from server {} on session {} (state {}), closing session", 
+                   server_id, session_id, 
+                   back_session_mgr.get_session_state(session_id).map_or("unknown", |state| state.as_str()));
+            back_session_mgr.remove_bad_token_session(session_id, server_id);
+            self.handle_link_lost(session_id);
+            return false;
+        }
+
+        if !back_session_mgr.authorize_session(session_id, server_id, server_type.clone()) {
+            return false;
+        }
+        self.handle_link_connected(session_id);
+        info!("Handshake with server {} ({}) completed, session {} authorized", server_id, server_type, session_id);
+        true
+    }
+    
+    fn send_node_register(&mut self, session_id: u64, auth_proof: Vec<u8>) {
         use crate::proto::messages::protobuf::message::cluster::{NodeRegisterBRequest, ServerConfig as ClusterServerConfig};
         use crate::proto::messages::{MessageId, MessageIdSerialize};
         
@@ -131,7 +869,7 @@ impl ClusterManager {
             // 创建节点注册请求（msg_unique_id字段现在用作请求ID，不是消息类型ID）
             let register_request = NodeRegisterBRequest {
                 msg_unique_id: chrono::Utc::now().timestamp_millis() as u32,  // 使用时间戳作为请求ID
-                client_token: self.client_token.clone(),
+                auth_proof,
                 server_config: Some(current_server_config),
             };
             
@@ -141,19 +879,9 @@ impl ClusterManager {
                     let mgr = &mut *back_session_mgr;
                     if let Some(session) = mgr.get_unauthorized_session_mut(session_id) {
                         // 发送消息 - send_message会处理编码
+                        // 会话保持握手状态，收到NodeRegisterBResponse后才授权
                         if session.send_message(register_request) {
-                            // 发送成功，将session设置为可信任（已认证）
-                            // 这个session连接的是master，所以使用master的信息
-                            if let Some(ref master_config) = self.master_config {
-                                if mgr.authorize_session(session_id, master_config.id, "master".to_string()) {
-                                    info!("Session {} moved to authorized list after successfully sending NodeRegisterBRequest to master, server_id: {}, server_type: master", 
-                                          session_id, master_config.id);
-                                } else {
-                                    error!("Failed to authorize session {} after sending NodeRegisterBRequest", session_id);
-                                }
-                            } else {
-                                error!("Master config not available for session authorization");
-                            }
+                            info!("Sent NodeRegisterBRequest to master on session {}, waiting for response", session_id);
                         } else {
                             error!("Failed to send node register request");
                         }
@@ -165,7 +893,7 @@ impl ClusterManager {
         }
     }
     
-    fn send_node_connect(&mut self, session_id: u64) {
+    fn send_node_connect(&mut self, session_id: u64, auth_proof: Vec<u8>) {
         use crate::proto::messages::protobuf::message::cluster::{NodeConnectBRequest, ServerConfig as ClusterServerConfig};
         
         // 检查是否有server_config
@@ -178,10 +906,10 @@ impl ClusterManager {
                 back_tcp_port: config.back_tcp_port as u32,
             };
             
-            // 创建节点连接请求，包含当前服务器的配置信息和token
+            // 创建节点连接请求，包含当前服务器的配置信息和认证证明
             let connect_request = NodeConnectBRequest {
                 msg_unique_id: chrono::Utc::now().timestamp_millis() as u32,  // 使用时间戳作为请求ID
-                client_token: self.client_token.clone(),
+                auth_proof,
                 server_config: Some(current_server_config),
             };
             
@@ -191,10 +919,9 @@ impl ClusterManager {
                     let mgr = &mut *back_session_mgr;
                     if let Some(session) = mgr.get_unauthorized_session_mut(session_id) {
                         // 发送消息 - send_message会处理编码
+                        // 会话保持握手状态，收到NodeConnectBResponse后才授权
                         if session.send_message(connect_request) {
-                            // 发送成功，先设置session为已认证状态
-                            session.set_authenticated(true);
-                            info!("Successfully sent NodeConnectBRequest from session {}, set to authenticated", session_id);
+                            info!("Sent NodeConnectBRequest on session {}, waiting for response", session_id);
                         } else {
                             error!("Failed to send node connect request");
                         }
@@ -208,18 +935,16 @@ impl ClusterManager {
     
     fn connect_to_master(&mut self) {
         if let Some(ref config) = self.master_config {
-            unsafe {
-                if let Some(manager) = self.back_session_manager.and_then(|p| p.as_mut()) {
-                    // 创建一个新的客户端会话连接到 master
-                    manager.create_client_session(
-                        config.id, // server_id
-                        &config.back_host,
-                        config.back_tcp_port,
-                    );
-                } else {
-                    error!("BackSessionManager pointer is null");
-                }
+            if self.links.contains_key(&config.id) {
+                debug!("Link to master server {} already exists", config.id);
+                return;
             }
+            
+            // 创建一个新的客户端会话连接到 master，断开后自动重连并重新注册
+            let master_link = ClusterLink::new(config.id, config.back_host.clone(), config.back_tcp_port, true);
+            let master_id = config.id;
+            self.links.insert(master_id, master_link);
+            self.open_link(master_id);
         }
     }
 }
@@ -242,7 +967,34 @@ impl NetworkEventHandler for ClusterManager {
             NetworkEventType::ClientConnectSuccess => {
                 self.handle_client_connect_success(event);
             }
+            NetworkEventType::ClientConnectFailed | NetworkEventType::Disconnect => {
+                self.handle_link_lost(event.session_id);
+            }
             _ => {}
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn load_provider_refreshes_local_load() {
+        let mut cluster_manager = ClusterManager::new();
+        cluster_manager.set_local_load(7);
+        cluster_manager.refresh_local_load();
+        assert_eq!(cluster_manager.get_local_load(), 7);
+
+        let session_count = Arc::new(AtomicU32::new(42));
+        let provider_count = session_count.clone();
+        cluster_manager.set_load_provider(Box::new(move || provider_count.load(Ordering::Relaxed)));
+        cluster_manager.refresh_local_load();
+        assert_eq!(cluster_manager.get_local_load(), 42);
+
+        session_count.store(3, Ordering::Relaxed);
+        cluster_manager.refresh_local_load();
+        assert_eq!(cluster_manager.get_local_load(), 3);
+    }
+}
@@ -1,11 +1,12 @@
 use crate::framework::session::{BackSessionMessageDispatcher, BackSession, SessionTrait};
-use crate::proto::messages::protobuf::message::cluster::{NodeRegisterBRequest, NodeRegisterBResponse, NodeConnectBRequest, NodeConnectBResponse, NodeRegisterBNotify, ServerConfig};
-use crate::proto::messages::protobuf::message::protobuf_message_id::{MSG_ID_NODE_REGISTER_B_REQUEST, MSG_ID_NODE_REGISTER_B_RESPONSE, MSG_ID_NODE_CONNECT_B_REQUEST, MSG_ID_NODE_CONNECT_B_RESPONSE, MSG_ID_NODE_REGISTER_B_NOTIFY};
+use crate::proto::messages::protobuf::message::cluster::{NodeChallengeBRequest, NodeChallengeBResponse, NodeRegisterBRequest, NodeRegisterBResponse, NodeConnectBRequest, NodeConnectBResponse, NodeRegisterBNotify, ServerConfig, NodeHeartbeatBRequest, NodeHeartbeatBResponse, NodeUnregisterBRequest, NodeUnregisterBNotify};
 use crate::framework::cluster::{ClusterManager, ServerManager};
-use crate::framework::author::server_token::server_token_authentication;
+use crate::framework::cluster::cluster_manager::{NodeRemoveReason, NodeRegisterErrorCode};
+use crate::framework::author::server_token::{NodeAuthenticator, generate_nonce, NODE_NONCE_LEN};
 use super::server_info::ServerInfo;
-use tracing::{debug, info, error};
-use std::any::Any;
+use crate::framework::network::net_addr;
+use crate::proto::messages::MessageIdSerialize;
+use tracing::{debug, info, warn, error};
 
 /// 集群消息处理器
 /// 由Server持有，负责处理集群相关的消息
@@ -22,8 +23,8 @@ pub struct ClusterMessageHandler {
     master_server_id: u32,
     /// 当前服务器类型
     server_type: String,
-    /// Author key for token validation
-    author_key: String,
+    /// 节点认证
+    node_authenticator: NodeAuthenticator,
 }
 
 impl ClusterMessageHandler {
@@ -36,7 +37,7 @@ impl ClusterMessageHandler {
             current_server_config: std::ptr::null(),
             master_server_id: 0,
             server_type: String::new(),
-            author_key: String::new(),
+            node_authenticator: NodeAuthenticator::default(),
         }
     }
     
@@ -49,7 +50,7 @@ impl ClusterMessageHandler {
         server_config: &crate::framework::config::config::ServerConfig, 
         server_type: &str,
         master_server_id: Option<u32>,
-        author_key: String) {
+        node_authenticator: NodeAuthenticator) {
         // 保存配置和类型
         self.server_manager = server_manager;
         self.cluster_manager = cluster_manager;
@@ -57,64 +58,66 @@ impl ClusterMessageHandler {
         self.current_server_config = server_config as *const crate::framework::config::config::ServerConfig;
         self.master_server_id = master_server_id.unwrap_or(0);
         self.server_type = server_type.to_string();
-        self.author_key = author_key;
+        self.node_authenticator = node_authenticator;
+        
+        // 所有处理函数共用同一个ClusterMessageHandler地址
+        let cluster_handler_addr = self as *mut ClusterMessageHandler as usize;
+        
+        // 握手消息通过register_handshake注册，其他消息在会话授权前会被拒绝
+        // 所有服务器都监听后端连接：注册认证挑战处理器
+        dispatcher.register_handshake::<NodeChallengeBRequest, _>(move |session, request| {
+            Self::handle_node_challenge_request(session, request, cluster_handler_addr as *mut ClusterMessageHandler);
+        });
         
         if server_type == "master" {
-            // Master服务器：只注册NodeRegisterBRequest处理器
-            
-            // 注册 NodeRegisterBRequest 处理器（master 模式）
-            let cluster_handler_addr = self as *mut ClusterMessageHandler as usize;
-            let handler_request = Box::new(move |session: &mut BackSession, message: &dyn Any| {
-                let cluster_handler_ptr = cluster_handler_addr as *mut ClusterMessageHandler;
-                Self::handle_node_register_request(session, message, cluster_handler_ptr);
+            // Master服务器：只注册NodeRegisterBRequest和NodeUnregisterBRequest处理器
+            dispatcher.register_handshake::<NodeRegisterBRequest, _>(move |session, request| {
+                Self::handle_node_register_request(session, request, cluster_handler_addr as *mut ClusterMessageHandler);
+            });
+            dispatcher.register::<NodeUnregisterBRequest, _>(move |session, request| {
+                Self::handle_node_unregister_request(session, request, cluster_handler_addr as *mut ClusterMessageHandler);
             });
-            dispatcher.register_handler(MSG_ID_NODE_REGISTER_B_REQUEST, handler_request);
         } else {
             // 非master服务器：注册所有其他处理器
-            
-            // 注册 NodeRegisterBResponse 处理器
-            let cluster_handler_addr2 = self as *mut ClusterMessageHandler as usize;
-            let handler_response = Box::new(move |session: &mut BackSession, message: &dyn Any| {
-                let cluster_handler_ptr = cluster_handler_addr2 as *mut ClusterMessageHandler;
-                Self::handle_node_register_response(session, message, cluster_handler_ptr);
+            dispatcher.register_handshake::<NodeChallengeBResponse, _>(move |session, response| {
+                Self::handle_node_challenge_response(session, response, cluster_handler_addr as *mut ClusterMessageHandler);
             });
-            dispatcher.register_handler(MSG_ID_NODE_REGISTER_B_RESPONSE, handler_response);
-            
-            // 注册 NodeConnectBRequest 处理器
-            let cluster_handler_addr3 = self as *mut ClusterMessageHandler as usize;
-            let handler_connect_request = Box::new(move |session: &mut BackSession, message: &dyn Any| {
-                let cluster_handler_ptr = cluster_handler_addr3 as *mut ClusterMessageHandler;
-                Self::handle_node_connect_request(session, message, cluster_handler_ptr);
+            dispatcher.register_handshake::<NodeRegisterBResponse, _>(move |session, response| {
+                Self::handle_node_register_response(session, response, cluster_handler_addr as *mut ClusterMessageHandler);
             });
-            dispatcher.register_handler(MSG_ID_NODE_CONNECT_B_REQUEST, handler_connect_request);
-            
-            // 注册 NodeConnectBResponse 处理器
-            let cluster_handler_addr4 = self as *mut ClusterMessageHandler as usize;
-            let handler_connect_response = Box::new(move |session: &mut BackSession, message: &dyn Any| {
-                let cluster_handler_ptr = cluster_handler_addr4 as *mut ClusterMessageHandler;
-                Self::handle_node_connect_response(session, message, cluster_handler_ptr);
+            dispatcher.register_handshake::<NodeConnectBRequest, _>(move |session, request| {
+                Self::handle_node_connect_request(session, request, cluster_handler_addr as *mut ClusterMessageHandler);
             });
-            dispatcher.register_handler(MSG_ID_NODE_CONNECT_B_RESPONSE, handler_connect_response);
-            
-            // 注册 NodeRegisterBNotify 处理器
-            let cluster_handler_addr5 = self as *mut ClusterMessageHandler as usize;
-            let handler_register_notify = Box::new(move |session: &mut BackSession, message: &dyn Any| {
-                let cluster_handler_ptr = cluster_handler_addr5 as *mut ClusterMessageHandler;
-                Self::handle_node_register_notify(session, message, cluster_handler_ptr);
+            dispatcher.register_handshake::<NodeConnectBResponse, _>(move |session, response| {
+                Self::handle_node_connect_response(session, response, cluster_handler_addr as *mut ClusterMessageHandler);
+            });
+            dispatcher.register::<NodeRegisterBNotify, _>(move |session, notify| {
+                Self::handle_node_register_notify(session, notify, cluster_handler_addr as *mut ClusterMessageHandler);
+            });
+            dispatcher.register::<NodeUnregisterBNotify, _>(move |session, notify| {
+                Self::handle_node_unregister_notify(session, notify, cluster_handler_addr as *mut ClusterMessageHandler);
             });
-            dispatcher.register_handler(MSG_ID_NODE_REGISTER_B_NOTIFY, handler_register_notify);
         }
         
+        // 所有服务器：注册心跳处理器
+        dispatcher.register::<NodeHeartbeatBRequest, _>(Self::handle_node_heartbeat_request);
+        dispatcher.register::<NodeHeartbeatBResponse, _>(Self::handle_node_heartbeat_response);
     }
 
     /// 清理集群消息处理器
     pub fn dispose(&mut self, dispatcher: &mut BackSessionMessageDispatcher) {
         // 注销消息处理器
-        dispatcher.unregister_handler(MSG_ID_NODE_REGISTER_B_REQUEST);
-        dispatcher.unregister_handler(MSG_ID_NODE_REGISTER_B_RESPONSE);
-        dispatcher.unregister_handler(MSG_ID_NODE_CONNECT_B_REQUEST);
-        dispatcher.unregister_handler(MSG_ID_NODE_CONNECT_B_RESPONSE);
-        dispatcher.unregister_handler(MSG_ID_NODE_REGISTER_B_NOTIFY);
+        dispatcher.unregister::<NodeChallengeBRequest>();
+        dispatcher.unregister::<NodeChallengeBResponse>();
+        dispatcher.unregister::<NodeRegisterBRequest>();
+        dispatcher.unregister::<NodeRegisterBResponse>();
+        dispatcher.unregister::<NodeConnectBRequest>();
+        dispatcher.unregister::<NodeConnectBResponse>();
+        dispatcher.unregister::<NodeRegisterBNotify>();
+        dispatcher.unregister::<NodeUnregisterBRequest>();
+        dispatcher.unregister::<NodeUnregisterBNotify>();
+        dispatcher.unregister::<NodeHeartbeatBRequest>();
+        dispatcher.unregister::<NodeHeartbeatBResponse>();
         
         // 清空指针
         self.server_manager = std::ptr::null_mut();
@@ -126,511 +129,765 @@ impl ClusterMessageHandler {
         debug!("ClusterMessageHandler disposed");
     }
 
+    /// 处理节点认证挑战请求（静态函数，用于注册到消息分发器）
+    /// 
+    /// 返回本端随机数和对连接方随机数的证明，本端随机数保存在会话中，
+    /// 用于校验随后的NodeRegisterBRequest/NodeConnectBRequest
+    pub fn handle_node_challenge_request(
+        session: &mut BackSession, 
+        request: &NodeChallengeBRequest,
+        cluster_handler_ptr: *mut ClusterMessageHandler
+    ) {
+        let session_id = session.get_session_id();
+        debug!("Handling NodeChallengeBRequest from session {}, server_id: {}", session_id, request.server_id);
+        
+        // 检查 ClusterMessageHandler 指针是否有效
+        if cluster_handler_ptr.is_null() {
+            error!("ClusterMessageHandler pointer is null");
+            return;
+        }
+        
+        // 安全访问 ClusterMessageHandler
+        let cluster_handler = unsafe { &mut *cluster_handler_ptr };
+        
+        if cluster_handler.back_session_manager.is_null() || cluster_handler.current_server_config.is_null() {
+            error!("BackSessionManager or current server config pointer is null");
+            return;
+        }
+        let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
+        let current_config = unsafe { &*cluster_handler.current_server_config };
+        
+        // 只有等待认证的会话可以发起挑战，每个会话只接受一次
+        if back_session_mgr.get_unauthorized_session(session_id).is_none() {
+            warn!("Ignoring NodeChallengeBRequest on authorized session {}", session_id);
+            return;
+        }
+        if session.has_auth_nonce() || request.nonce.len() != NODE_NONCE_LEN {
+            error!("Security: Invalid NodeChallengeBRequest from server_id: {} on session {}, closing session", 
+                   request.server_id, session_id);
+            back_session_mgr.remove_bad_token_session(session_id, request.server_id);
+            return;
+        }
+        
+        let server_nonce = generate_nonce();
+        session.set_auth_nonce(server_nonce.clone());
+        
+        let response = NodeChallengeBResponse {
+            server_id: current_config.id,
+            nonce: server_nonce,
+            proof: cluster_handler.node_authenticator.server_proof(&request.nonce, current_config.id),
+        };
+        if !session.send_message(response) {
+            error!("Failed to send NodeChallengeBResponse to server_id: {}", request.server_id);
+        }
+    }
+    
+    /// 处理节点认证挑战响应（静态函数，用于注册到消息分发器）
+    pub fn handle_node_challenge_response(
+        session: &mut BackSession, 
+        response: &NodeChallengeBResponse,
+        cluster_handler_ptr: *mut ClusterMessageHandler
+    ) {
+        debug!("Handling NodeChallengeBResponse from session {}", session.get_session_id());
+        
+        // 检查 ClusterMessageHandler 指针是否有效
+        if cluster_handler_ptr.is_null() {
+            error!("ClusterMessageHandler pointer is null");
+            return;
+        }
+        
+        // 安全访问 ClusterMessageHandler
+        let cluster_handler = unsafe { &mut *cluster_handler_ptr };
+        
+        if cluster_handler.cluster_manager.is_null() {
+            error!("ClusterManager pointer is null");
+            return;
+        }
+        let cluster_manager = unsafe { &mut *cluster_handler.cluster_manager };
+        cluster_manager.handle_node_challenge_response(
+            session.get_session_id(), 
+            response.server_id, 
+            &response.nonce, 
+            &response.proof,
+        );
+    }
+    
+    /// 通过会话管理器发送消息
+    /// 
+    /// 会话授权后已移入授权列表，不能再使用分发时传入的会话引用
+    fn send_to_session<T>(&self, session_id: u64, message: T) -> bool
+    where
+        T: MessageIdSerialize + Clone + Send + 'static,
+    {
+        if self.back_session_manager.is_null() {
+            error!("BackSessionManager pointer is null");
+            return false;
+        }
+        let back_session_mgr = unsafe { &mut *self.back_session_manager };
+        back_session_mgr.get_any_session_mut(session_id)
+            .is_some_and(|session| session.send_message(message))
+    }
+
+    /// 校验连接方在注册/连接请求中的证明，会话中的随机数校验后即失效
+    fn verify_node_proof(&self, session: &mut BackSession, server_id: u32, auth_proof: &[u8]) -> bool {
+        let server_nonce = session.take_auth_nonce();
+        !server_nonce.is_empty() && self.node_authenticator.verify_client_proof(&server_nonce, server_id, auth_proof)
+    }
+
+    /// 启用后端双向TLS时，请求中声明的服务器ID必须与对端证书中的身份一致；明文连接不检查
+    fn verify_cert_identity(session: &BackSession, server_id: u32) -> bool {
+        session.get_cert_server_id().is_none_or(|cert_server_id| cert_server_id == server_id)
+    }
+
     /// 处理节点注册请求（静态函数，用于注册到消息分发器）
     pub fn handle_node_register_request(
         session: &mut BackSession, 
-        message: &dyn Any,
+        request: &NodeRegisterBRequest,
         cluster_handler_ptr: *mut ClusterMessageHandler
     ) {
-        debug!("Handling NodeRegisterBRequest from session {}", session.get_session_id());
+        let request_session_id = session.get_session_id();
+        debug!("Handling NodeRegisterBRequest from session {}", request_session_id);
 
-        // 尝试将消息转换为 NodeRegisterBRequest
-        if let Some(request) = message.downcast_ref::<NodeRegisterBRequest>() {
-            // 如果 server_config 是 None，返回错误
-            let server_config = match request.server_config.as_ref() {
-                Some(config) => config,
-                None => {
-                    error!("NodeRegisterBRequest missing server_config");
-                    return;
-                }
-            };
-            info!("Received NodeRegisterBRequest from server_id: {}, server_type: {}", 
-                    server_config.server_id, server_config.server_type);
-            
-            debug!("Processing node registration for server_id: {}", server_config.server_id);
-            
-            // 检查 ClusterMessageHandler 指针是否有效
-            if cluster_handler_ptr.is_null() {
-                error!("ClusterMessageHandler pointer is null");
+        // 如果 server_config 是 None，返回错误
+        let server_config = match request.server_config.as_ref() {
+            Some(config) => config,
+            None => {
+                error!("NodeRegisterBRequest missing server_config");
                 return;
             }
-            
-            // 安全访问 ClusterMessageHandler
-            let cluster_handler = unsafe { &mut *cluster_handler_ptr };
-            
-            // 验证token
-            if !server_token_authentication(&request.client_token, &cluster_handler.author_key) {
-                error!("Invalid token from server_id: {}, closing session", server_config.server_id);
-                
-                // 检查 BackSessionManager 指针是否有效
-                if !cluster_handler.back_session_manager.is_null() {
-                    let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
-                    let session_id = session.get_session_id();
-                    back_session_mgr.remove_bad_token_session(session_id, server_config.server_id);
-                }
+        };
+        // 端口在协议中为u32，超出范围时拒绝，不截断
+        let back_tcp_port = match net_addr::port_from_u32(server_config.back_tcp_port) {
+            Some(port) => port,
+            None => {
+                error!("NodeRegisterBRequest from server_id {} has invalid back_tcp_port {}", server_config.server_id, server_config.back_tcp_port);
                 return;
             }
-            
-            info!("Token validation successful for server_id: {}", server_config.server_id);
-            
-            // 验证通过，将session设置为可信任（已认证）
+        };
+        info!("Received NodeRegisterBRequest from server_id: {}, server_type: {}", 
+                server_config.server_id, server_config.server_type);
+        
+        debug!("Processing node registration for server_id: {}", server_config.server_id);
+        
+        // 检查 ClusterMessageHandler 指针是否有效
+        if cluster_handler_ptr.is_null() {
+            error!("ClusterMessageHandler pointer is null");
+            return;
+        }
+        
+        // 安全访问 ClusterMessageHandler
+        let cluster_handler = unsafe { &mut *cluster_handler_ptr };
+        
+        // 校验证书身份
+        if !Self::verify_cert_identity(session, server_config.server_id) {
+            error!("Security: Server_id {} claimed on session {} does not match certificate identity {:?}, closing session", 
+                   server_config.server_id, request_session_id, session.get_cert_server_id());
             if !cluster_handler.back_session_manager.is_null() {
                 let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
-                let session_id = session.get_session_id();
-                if back_session_mgr.authorize_session(session_id, server_config.server_id, server_config.server_type.clone()) {
-                    info!("Session {} moved to authorized list after successful token validation in NodeRegisterBRequest, server_id: {}, server_type: {}", 
-                          session_id, server_config.server_id, server_config.server_type);
-                } else {
-                    error!("Failed to authorize session {} after token validation in NodeRegisterBRequest", session_id);
-                }
+                back_session_mgr.remove_bad_token_session(request_session_id, server_config.server_id);
             }
+            return;
+        }
+        
+        // 校验挑战-应答证明
+        if !cluster_handler.verify_node_proof(session, server_config.server_id, &request.auth_proof) {
+            error!("Node authentication failed for server_id: {}, closing session", server_config.server_id);
             
-            // 检查 ServerManager 指针是否有效
-            if cluster_handler.server_manager.is_null() {
-                error!("ServerManager pointer is null");
-                return;
+            // 检查 BackSessionManager 指针是否有效
+            if !cluster_handler.back_session_manager.is_null() {
+                let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
+                let session_id = session.get_session_id();
+                back_session_mgr.remove_bad_token_session(session_id, server_config.server_id);
             }
-            
-            // 安全访问 ServerManager
-            let server_manager = unsafe { &mut *cluster_handler.server_manager };
-            let master_server_id = cluster_handler.master_server_id;
-            
-            // 1. 将 server_config 添加到 server_manager
-            let new_server = ServerInfo::new(
-                server_config.server_id,
-                server_config.server_type.clone(),
-                server_config.back_host.clone(),
-                server_config.back_tcp_port,
-            );
-            info!("Adding server to cluster: {}", new_server.get_info());
-            server_manager.add_server(new_server);
-            
-            // 2. 遍历 server_manager，获取 server_id 小于当前请求服务器 ID 的服务器
-            let mut server_list = Vec::new();
-            let request_server_id = server_config.server_id;
-            
-            for server in server_manager.get_all_servers() {
-                let server_id = server.get_server_id();
-                // 只返回 server_id 小于请求服务器的，且排除 master server 自己
-                if server_id < request_server_id && server_id != master_server_id {
-                    server_list.push(ServerConfig {
-                        server_id: server.get_server_id(),
-                        server_type: server.get_server_type().clone(),
-                        back_host: server.get_back_host().clone(),
-                        back_tcp_port: server.get_back_tcp_port(),
-                    });
+            return;
+        }
+        
+        info!("Node authentication successful for server_id: {}", server_config.server_id);
+        
+        // 服务器ID已被在线节点使用时拒绝注册，请求方收到错误码后退出；会话保持未授权，由握手超时关闭
+        if !cluster_handler.cluster_manager.is_null() {
+            let cluster_manager = unsafe { &mut *cluster_handler.cluster_manager };
+            if !cluster_manager.resolve_duplicate_node(server_config.server_id, request_session_id) {
+                let response = NodeRegisterBResponse {
+                    msg_unique_id: request.msg_unique_id,
+                    server_list: Vec::new(),
+                    error_code: NodeRegisterErrorCode::DuplicateServerId as u32,
+                };
+                if !session.send_message(response) {
+                    error!("Failed to send NodeRegisterBResponse rejection to server_id: {}", server_config.server_id);
                 }
+                return;
             }
-            
-            debug!("Found {} servers with ID < {} (excluding master server ID {})", 
-                   server_list.len(), request_server_id, master_server_id);
-            
-            // 3. 创建响应消息
-            let response = NodeRegisterBResponse {
-                msg_unique_id: request.msg_unique_id,
-                server_list,
-            };
-            
-            // 4. 发送响应
-            if session.send_message(response.clone()) {
-                info!("Sent NodeRegisterBResponse to server_id: {} successfully with {} servers", 
-                      server_config.server_id, response.server_list.len());
+        }
+        
+        // 验证通过，将session设置为可信任（已认证）
+        if !cluster_handler.back_session_manager.is_null() {
+            let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
+            let session_id = session.get_session_id();
+            if back_session_mgr.authorize_session(session_id, server_config.server_id, server_config.server_type.clone()) {
+                info!("Session {} moved to authorized list after successful token validation in NodeRegisterBRequest, server_id: {}, server_type: {}", 
+                      session_id, server_config.server_id, server_config.server_type);
             } else {
-                error!("Failed to send NodeRegisterBResponse to server_id: {}", 
-                       server_config.server_id);
+                error!("Failed to authorize session {} after token validation in NodeRegisterBRequest", session_id);
             }
-            
-            // 5. 给 server_id > request_server_id 的服务器发送 NodeRegisterBNotify
-            let notify_message = NodeRegisterBNotify {
-                server_config: Some(ServerConfig {
-                    server_id: server_config.server_id,
-                    server_type: server_config.server_type.clone(),
-                    back_host: server_config.back_host.clone(),
-                    back_tcp_port: server_config.back_tcp_port,
-                }),
-            };
-            
-            // 获取 BackSessionManager 并查找相应的会话
-            if cluster_handler.back_session_manager.is_null() {
-                error!("BackSessionManager pointer is null");
-                return;
+        }
+        
+        // 检查 ServerManager 指针是否有效
+        if cluster_handler.server_manager.is_null() {
+            error!("ServerManager pointer is null");
+            return;
+        }
+        
+        // 安全访问 ServerManager
+        let server_manager = unsafe { &mut *cluster_handler.server_manager };
+        let master_server_id = cluster_handler.master_server_id;
+        
+        // 1. 将 server_config 添加到 server_manager
+        let new_server = ServerInfo::new(
+            server_config.server_id,
+            server_config.server_type.clone(),
+            server_config.back_host.clone(),
+            back_tcp_port,
+        );
+        info!("Adding server to cluster: {}", new_server.get_info());
+        server_manager.add_server(new_server);
+        
+        // 2. 遍历 server_manager，获取 server_id 小于当前请求服务器 ID 的服务器
+        let mut server_list = Vec::new();
+        let request_server_id = server_config.server_id;
+        
+        for server in server_manager.get_all_servers() {
+            let server_id = server.get_server_id();
+            // 只返回 server_id 小于请求服务器的，且排除 master server 自己
+            if server_id < request_server_id && server_id != master_server_id {
+                server_list.push(ServerConfig {
+                    server_id: server.get_server_id(),
+                    server_type: server.get_server_type().clone(),
+                    back_host: server.get_back_host().clone(),
+                    back_tcp_port: u32::from(server.get_back_tcp_port()),
+                });
             }
-            
-            let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
-            
-            // 遍历所有服务器，找到 server_id > request_server_id 的服务器并发送通知
-            for server in server_manager.get_all_servers() {
-                let server_id = server.get_server_id();
-                if server_id > request_server_id && server_id != master_server_id {
-                    // 查找对应的 BackSession
-                    if let Some(target_session) = back_session_mgr.find_session_by_server_id(server_id) {
-                        let target_session_id = target_session.get_session_id();
-                        if let Some(target_session_mut) = back_session_mgr.get_session_mut(target_session_id) {
-                            if target_session_mut.send_message(notify_message.clone()) {
-                                info!("Sent NodeRegisterBNotify to server_id: {} about new server_id: {}", 
-                                      server_id, server_config.server_id);
-                            } else {
-                                error!("Failed to send NodeRegisterBNotify to server_id: {}", server_id);
-                            }
+        }
+        
+        debug!("Found {} servers with ID < {} (excluding master server ID {})", 
+               server_list.len(), request_server_id, master_server_id);
+        
+        // 3. 创建响应消息
+        let response = NodeRegisterBResponse {
+            msg_unique_id: request.msg_unique_id,
+            server_list,
+            error_code: NodeRegisterErrorCode::Success as u32,
+        };
+        
+        // 4. 发送响应（会话已移入授权列表）
+        if cluster_handler.send_to_session(request_session_id, response.clone()) {
+            info!("Sent NodeRegisterBResponse to server_id: {} successfully with {} servers", 
+                  server_config.server_id, response.server_list.len());
+        } else {
+            error!("Failed to send NodeRegisterBResponse to server_id: {}", 
+                   server_config.server_id);
+        }
+        
+        // 5. 给 server_id > request_server_id 的服务器发送 NodeRegisterBNotify
+        let notify_message = NodeRegisterBNotify {
+            server_config: Some(ServerConfig {
+                server_id: server_config.server_id,
+                server_type: server_config.server_type.clone(),
+                back_host: server_config.back_host.clone(),
+                back_tcp_port: server_config.back_tcp_port,
+            }),
+        };
+        
+        // 获取 BackSessionManager 并查找相应的会话
+        if cluster_handler.back_session_manager.is_null() {
+            error!("BackSessionManager pointer is null");
+            return;
+        }
+        
+        let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
+        
+        // 遍历所有服务器，找到 server_id > request_server_id 的服务器并发送通知
+        for server in server_manager.get_all_servers() {
+            let server_id = server.get_server_id();
+            if server_id > request_server_id && server_id != master_server_id {
+                // 查找对应的 BackSession
+                if let Some(target_session) = back_session_mgr.find_session_by_server_id(server_id) {
+                    let target_session_id = target_session.get_session_id();
+                    if let Some(target_session_mut) = back_session_mgr.get_session_mut(target_session_id) {
+                        if target_session_mut.send_message(notify_message.clone()) {
+                            info!("Sent NodeRegisterBNotify to server_id: {} about new server_id: {}", 
+                                  server_id, server_config.server_id);
+                        } else {
+                            error!("Failed to send NodeRegisterBNotify to server_id: {}", server_id);
                         }
-                    } else {
-                        debug!("No BackSession found for server_id: {} (may not be connected yet)", server_id);
                     }
+                } else {
+                    debug!("No BackSession found for server_id: {} (may not be connected yet)", server_id);
                 }
             }
-        } else {
-            error!("Failed to downcast message to NodeRegisterBRequest");
         }
     }
 
     /// 处理节点注册响应（静态函数，用于注册到消息分发器）
     pub fn handle_node_register_response(
         session: &mut BackSession, 
-        message: &dyn Any,
+        response: &NodeRegisterBResponse,
         cluster_handler_ptr: *mut ClusterMessageHandler
     ) {
         debug!("Handling NodeRegisterBResponse from session {}", session.get_session_id());
 
-        // 尝试将消息转换为 NodeRegisterBResponse
-        if let Some(response) = message.downcast_ref::<NodeRegisterBResponse>() {
-            info!("Received NodeRegisterBResponse with {} servers", 
-                    response.server_list.len());
-            
-            debug!("Processing node register response with msg_unique_id: {}", response.msg_unique_id);
-            
-            // 检查 ClusterMessageHandler 指针是否有效
-            if cluster_handler_ptr.is_null() {
-                error!("ClusterMessageHandler pointer is null");
-                return;
-            }
-            
-            // 安全访问 ClusterMessageHandler
-            let cluster_handler = unsafe { &mut *cluster_handler_ptr };
-            
-            // 检查指针是否有效
-            if cluster_handler.cluster_manager.is_null() {
-                error!("ClusterManager pointer is null");
-                return;
-            }
-            
-            if cluster_handler.back_session_manager.is_null() {
-                error!("BackSessionManager pointer is null");
-                return;
-            }
+        info!("Received NodeRegisterBResponse with {} servers", 
+                response.server_list.len());
+        
+        debug!("Processing node register response with msg_unique_id: {}", response.msg_unique_id);
+        
+        // 检查 ClusterMessageHandler 指针是否有效
+        if cluster_handler_ptr.is_null() {
+            error!("ClusterMessageHandler pointer is null");
+            return;
+        }
+        
+        // 安全访问 ClusterMessageHandler
+        let cluster_handler = unsafe { &mut *cluster_handler_ptr };
+        
+        // 检查指针是否有效
+        if cluster_handler.cluster_manager.is_null() {
+            error!("ClusterManager pointer is null");
+            return;
+        }
+        
+        if cluster_handler.back_session_manager.is_null() {
+            error!("BackSessionManager pointer is null");
+            return;
+        }
+        
+        // 安全访问管理器
+        let cluster_manager = unsafe { &mut *cluster_handler.cluster_manager };
+        
+        // master拒绝注册（服务器ID冲突）时当前节点退出
+        if response.error_code != NodeRegisterErrorCode::Success as u32 {
+            cluster_manager.handle_node_register_rejected(session.get_session_id(), response.error_code);
+            return;
+        }
+        
+        // 收到master的响应才完成握手
+        if !cluster_manager.complete_handshake(session.get_session_id(), cluster_handler.master_server_id, "master".to_string()) {
+            return;
+        }
+        
+        // 处理服务器列表，创建客户端连接
+        for server_config in &response.server_list {
+            let Some(back_tcp_port) = net_addr::port_from_u32(server_config.back_tcp_port) else {
+                error!("Skipping server_id {} with invalid back_tcp_port {} in NodeRegisterBResponse", 
+                       server_config.server_id, server_config.back_tcp_port);
+                continue;
+            };
             
-            // 安全访问管理器
-            let _cluster_manager = unsafe { &mut *cluster_handler.cluster_manager };
-            let back_session_manager = unsafe { &mut *cluster_handler.back_session_manager };
+            info!("Received server info - ID: {}, Type: {}, Host: {}:{}", 
+                  server_config.server_id, 
+                  server_config.server_type,
+                  server_config.back_host,
+                  server_config.back_tcp_port);
             
-            // 处理服务器列表，创建客户端连接
-            for server_config in &response.server_list {
-                info!("Received server info - ID: {}, Type: {}, Host: {}:{}", 
-                      server_config.server_id, 
-                      server_config.server_type,
-                      server_config.back_host,
-                      server_config.back_tcp_port);
-                
-                // 创建与其他服务器的客户端连接
-                info!("ClusterMessageHandler: Connecting to CLUSTER NODE (from register response) - server_id={}, back_host={}, back_tcp_port={}", 
-                      server_config.server_id, 
-                      server_config.back_host, 
-                      server_config.back_tcp_port);
-                
-                back_session_manager.create_client_session(
-                    server_config.server_id,
-                    &server_config.back_host,
-                    server_config.back_tcp_port as u16
-                );
-                
-                debug!("Client session created for server {}", server_config.server_id);
-            }
+            // 创建与其他服务器的客户端连接
+            info!("ClusterMessageHandler: Connecting to CLUSTER NODE (from register response) - server_id={}, back_host={}, back_tcp_port={}", 
+                  server_config.server_id, 
+                  server_config.back_host, 
+                  server_config.back_tcp_port);
             
-            info!("Successfully processed NodeRegisterBResponse with {} servers and created {} client connections", 
-                  response.server_list.len(), response.server_list.len());
-        } else {
-            error!("Failed to downcast message to NodeRegisterBResponse");
+            cluster_manager.connect_to_peer(
+                server_config.server_id,
+                &server_config.back_host,
+                back_tcp_port
+            );
         }
+        
+        info!("Successfully processed NodeRegisterBResponse with {} servers and created {} client connections", 
+              response.server_list.len(), response.server_list.len());
     }
 
     /// 处理节点连接请求（静态函数，用于注册到消息分发器）
     pub fn handle_node_connect_request(
         session: &mut BackSession, 
-        message: &dyn Any,
+        request: &NodeConnectBRequest,
         cluster_handler_ptr: *mut ClusterMessageHandler
     ) {
-        debug!("Handling NodeConnectBRequest from session {}", session.get_session_id());
+        let request_session_id = session.get_session_id();
+        debug!("Handling NodeConnectBRequest from session {}", request_session_id);
 
-        // 尝试将消息转换为 NodeConnectBRequest
-        if let Some(request) = message.downcast_ref::<NodeConnectBRequest>() {
-            // 如果 server_config 是 None，返回错误
-            let server_config = match request.server_config.as_ref() {
-                Some(config) => config,
-                None => {
-                    error!("NodeConnectBRequest missing server_config");
-                    return;
-                }
-            };
-            info!("Received NodeConnectBRequest from server_id: {}, server_type: {}", 
-                    server_config.server_id, server_config.server_type);
-            
-            debug!("Processing node connect request with msg_unique_id: {}", request.msg_unique_id);
-            
-            // 检查 ClusterMessageHandler 指针是否有效
-            if cluster_handler_ptr.is_null() {
-                error!("ClusterMessageHandler pointer is null");
+        // 如果 server_config 是 None，返回错误
+        let server_config = match request.server_config.as_ref() {
+            Some(config) => config,
+            None => {
+                error!("NodeConnectBRequest missing server_config");
                 return;
             }
-            
-            // 安全访问 ClusterMessageHandler
-            let cluster_handler = unsafe { &mut *cluster_handler_ptr };
-            
-            // 验证token
-            if !server_token_authentication(&request.client_token, &cluster_handler.author_key) {
-                error!("Invalid token from server_id: {} in NodeConnectBRequest, closing session", server_config.server_id);
-                
-                // 检查 BackSessionManager 指针是否有效
-                if !cluster_handler.back_session_manager.is_null() {
-                    let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
-                    let session_id = session.get_session_id();
-                    back_session_mgr.remove_bad_token_session(session_id, server_config.server_id);
-                }
+        };
+        // 端口在协议中为u32，超出范围时拒绝，不截断
+        let back_tcp_port = match net_addr::port_from_u32(server_config.back_tcp_port) {
+            Some(port) => port,
+            None => {
+                error!("NodeConnectBRequest from server_id {} has invalid back_tcp_port {}", server_config.server_id, server_config.back_tcp_port);
                 return;
             }
-            
-            info!("Token validation successful for server_id: {} in NodeConnectBRequest", server_config.server_id);
-            
-            // 验证通过，将session设置为可信任（已认证）
+        };
+        info!("Received NodeConnectBRequest from server_id: {}, server_type: {}", 
+                server_config.server_id, server_config.server_type);
+        
+        debug!("Processing node connect request with msg_unique_id: {}", request.msg_unique_id);
+        
+        // 检查 ClusterMessageHandler 指针是否有效
+        if cluster_handler_ptr.is_null() {
+            error!("ClusterMessageHandler pointer is null");
+            return;
+        }
+        
+        // 安全访问 ClusterMessageHandler
+        let cluster_handler = unsafe { &mut *cluster_handler_ptr };
+        
+        // 校验证书身份
+        if !Self::verify_cert_identity(session, server_config.server_id) {
+            error!("Security: Server_id {} claimed on session {} does not match certificate identity {:?}, closing session", 
+                   server_config.server_id, request_session_id, session.get_cert_server_id());
             if !cluster_handler.back_session_manager.is_null() {
                 let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
-                let session_id = session.get_session_id();
-                if back_session_mgr.authorize_session(session_id, server_config.server_id, server_config.server_type.clone()) {
-                    info!("Session {} moved to authorized list after successful token validation in NodeConnectBRequest, server_id: {}, server_type: {}", 
-                          session_id, server_config.server_id, server_config.server_type);
-                } else {
-                    error!("Failed to authorize session {} after token validation in NodeConnectBRequest", session_id);
-                }
-            }
-            
-            // 检查 ServerManager 和当前服务器配置指针是否有效
-            if cluster_handler.server_manager.is_null() {
-                error!("ServerManager pointer is null");
-                return;
+                back_session_mgr.remove_bad_token_session(request_session_id, server_config.server_id);
             }
+            return;
+        }
+        
+        // 校验挑战-应答证明
+        if !cluster_handler.verify_node_proof(session, server_config.server_id, &request.auth_proof) {
+            error!("Node authentication failed for server_id: {} in NodeConnectBRequest, closing session", server_config.server_id);
             
-            if cluster_handler.current_server_config.is_null() {
-                error!("Current server config pointer is null");
-                return;
+            // 检查 BackSessionManager 指针是否有效
+            if !cluster_handler.back_session_manager.is_null() {
+                let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
+                let session_id = session.get_session_id();
+                back_session_mgr.remove_bad_token_session(session_id, server_config.server_id);
             }
-            
-            // 安全访问 ServerManager 和当前服务器配置
-            let server_manager = unsafe { &mut *cluster_handler.server_manager };
-            let current_config = unsafe { &*cluster_handler.current_server_config };
-            
-            // 将请求中的服务器配置添加到 server_manager
-            let new_server = ServerInfo::new(
-                server_config.server_id,
-                server_config.server_type.clone(),
-                server_config.back_host.clone(),
-                server_config.back_tcp_port,
-            );
-            info!("Adding requesting server to cluster: {}", new_server.get_info());
-            server_manager.add_server(new_server);
-            
-            // 创建响应消息（使用当前服务器的真实配置信息）
-            let response = NodeConnectBResponse {
-                msg_unique_id: request.msg_unique_id,
-                server_config: Some(ServerConfig {
-                    server_id: current_config.id,
-                    server_type: unsafe { &*cluster_handler_ptr }.server_type.clone(),
-                    back_host: current_config.back_host.clone(),
-                    back_tcp_port: current_config.back_tcp_port as u32,
-                }),
-            };
-            
-            // 发送响应
-            if session.send_message(response.clone()) {
-                info!("Sent NodeConnectBResponse to server_id: {} successfully", 
-                      server_config.server_id);
-                debug!("Response details: msgid={}, response_server_id={}", 
-                       response.msg_unique_id, response.server_config.as_ref().unwrap().server_id);
+            return;
+        }
+        
+        info!("Node authentication successful for server_id: {} in NodeConnectBRequest", server_config.server_id);
+        
+        // 节点已通过master的注册，同一服务器ID的旧连接直接被替换
+        if !cluster_handler.cluster_manager.is_null() {
+            let cluster_manager = unsafe { &mut *cluster_handler.cluster_manager };
+            cluster_manager.resolve_duplicate_node(server_config.server_id, request_session_id);
+        }
+        
+        // 验证通过，将session设置为可信任（已认证）
+        if !cluster_handler.back_session_manager.is_null() {
+            let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
+            let session_id = session.get_session_id();
+            if back_session_mgr.authorize_session(session_id, server_config.server_id, server_config.server_type.clone()) {
+                info!("Session {} moved to authorized list after successful token validation in NodeConnectBRequest, server_id: {}, server_type: {}", 
+                      session_id, server_config.server_id, server_config.server_type);
             } else {
-                error!("Failed to send NodeConnectBResponse to server_id: {}", 
-                       server_config.server_id);
+                error!("Failed to authorize session {} after token validation in NodeConnectBRequest", session_id);
             }
+        }
+        
+        // 检查 ServerManager 和当前服务器配置指针是否有效
+        if cluster_handler.server_manager.is_null() {
+            error!("ServerManager pointer is null");
+            return;
+        }
+        
+        if cluster_handler.current_server_config.is_null() {
+            error!("Current server config pointer is null");
+            return;
+        }
+        
+        // 安全访问 ServerManager 和当前服务器配置
+        let server_manager = unsafe { &mut *cluster_handler.server_manager };
+        let current_config = unsafe { &*cluster_handler.current_server_config };
+        
+        // 将请求中的服务器配置添加到 server_manager
+        let new_server = ServerInfo::new(
+            server_config.server_id,
+            server_config.server_type.clone(),
+            server_config.back_host.clone(),
+            back_tcp_port,
+        );
+        info!("Adding requesting server to cluster: {}", new_server.get_info());
+        server_manager.add_server(new_server);
+        
+        // 创建响应消息（使用当前服务器的真实配置信息）
+        let response = NodeConnectBResponse {
+            msg_unique_id: request.msg_unique_id,
+            server_config: Some(ServerConfig {
+                server_id: current_config.id,
+                server_type: unsafe { &*cluster_handler_ptr }.server_type.clone(),
+                back_host: current_config.back_host.clone(),
+                back_tcp_port: current_config.back_tcp_port as u32,
+            }),
+        };
+        
+        // 发送响应
+        if cluster_handler.send_to_session(request_session_id, response.clone()) {
+            info!("Sent NodeConnectBResponse to server_id: {} successfully", 
+                  server_config.server_id);
+            debug!("Response details: msgid={}, response_server_id={}", 
+                   response.msg_unique_id, response.server_config.as_ref().unwrap().server_id);
         } else {
-            error!("Failed to downcast message to NodeConnectBRequest");
+            error!("Failed to send NodeConnectBResponse to server_id: {}", 
+                   server_config.server_id);
         }
     }
 
     /// 处理节点连接响应（静态函数，用于注册到消息分发器）
     pub fn handle_node_connect_response(
         session: &mut BackSession, 
-        message: &dyn Any,
+        response: &NodeConnectBResponse,
         cluster_handler_ptr: *mut ClusterMessageHandler
     ) {
-        debug!("Handling NodeConnectBResponse from session {}", session.get_session_id());
+        let session_id = session.get_session_id();
+        debug!("Handling NodeConnectBResponse from session {}", session_id);
 
-        // 检查session是否已认证，未认证的session可能是恶意连接
-        if !session.is_authenticated() {
-            error!("Security: Received NodeConnectBResponse from unauthenticated session {}, removing session", session.get_session_id());
-            // 检查 ClusterMessageHandler 指针是否有效
-            if !cluster_handler_ptr.is_null() {
-                let cluster_handler = unsafe { &mut *cluster_handler_ptr };
-                if !cluster_handler.back_session_manager.is_null() {
-                    let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
-                    let session_id = session.get_session_id();
-                    back_session_mgr.remove_bad_token_session(session_id, 0);
-                }
-            }
-            return;
-        }
-
-        // 尝试将消息转换为 NodeConnectBResponse
-        if let Some(response) = message.downcast_ref::<NodeConnectBResponse>() {
-            // 如果 server_config 是 None，返回错误
-            let server_config = match response.server_config.as_ref() {
-                Some(config) => config,
-                None => {
-                    error!("NodeConnectBResponse missing server_config");
-                    return;
-                }
-            };
-            info!("Received NodeConnectBResponse from server_id: {}, server_type: {}", 
-                    server_config.server_id, server_config.server_type);
-            
-            debug!("Processing node connect response with msg_unique_id: {}", response.msg_unique_id);
-            
-            // 检查 ClusterMessageHandler 指针是否有效
-            if cluster_handler_ptr.is_null() {
-                error!("ClusterMessageHandler pointer is null");
+        // 如果 server_config 是 None，返回错误
+        let server_config = match response.server_config.as_ref() {
+            Some(config) => config,
+            None => {
+                error!("NodeConnectBResponse missing server_config");
                 return;
             }
-            
-            // 安全访问 ClusterMessageHandler
-            let cluster_handler = unsafe { &mut *cluster_handler_ptr };
-            
-            // 检查 ServerManager 指针是否有效
-            if cluster_handler.server_manager.is_null() {
-                error!("ServerManager pointer is null");
+        };
+        // 端口在协议中为u32，超出范围时拒绝，不截断
+        let back_tcp_port = match net_addr::port_from_u32(server_config.back_tcp_port) {
+            Some(port) => port,
+            None => {
+                error!("NodeConnectBResponse from server_id {} has invalid back_tcp_port {}", server_config.server_id, server_config.back_tcp_port);
                 return;
             }
-            
-            // 安全访问 ServerManager
-            let server_manager = unsafe { &mut *cluster_handler.server_manager };
-            
-            // 将响应中的服务器配置添加到 server_manager
-            let responding_server = ServerInfo::new(
-                server_config.server_id,
-                server_config.server_type.clone(),
-                server_config.back_host.clone(),
-                server_config.back_tcp_port,
-            );
-            info!("Adding responding server to cluster: {}", responding_server.get_info());
-            server_manager.add_server(responding_server);
-            
-            // 处理连接确认
-            info!("Node connection established with server_id: {}, server_type: {}", 
-                  server_config.server_id, server_config.server_type);
-            
-            // 收到响应确认连接，调用authorize_session更新服务器信息
-            if !cluster_handler.back_session_manager.is_null() {
-                let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
-                let session_id = session.get_session_id();
-                if back_session_mgr.authorize_session(session_id, server_config.server_id, server_config.server_type.clone()) {
-                    info!("Session {} authorized with server info after NodeConnectBResponse, server_id: {}, server_type: {}", 
-                          session_id, server_config.server_id, server_config.server_type);
-                } else {
-                    // 如果authorize_session失败，可能session已经在authorized列表中，直接更新服务器信息
-                    if let Some(session) = back_session_mgr.get_session_mut(session_id) {
-                        session.set_server_id(server_config.server_id);
-                        session.set_server_type(Some(server_config.server_type.clone()));
-                        info!("Updated existing session {} server info after NodeConnectBResponse, server_id: {}, server_type: {}", 
-                              session_id, server_config.server_id, server_config.server_type);
-                    }
-                }
-            }
-            
-            debug!("Successfully processed NodeConnectBResponse from server {}", 
-                   server_config.server_id);
-        } else {
-            error!("Failed to downcast message to NodeConnectBResponse");
+        };
+        info!("Received NodeConnectBResponse from server_id: {}, server_type: {}", 
+                server_config.server_id, server_config.server_type);
+        
+        debug!("Processing node connect response with msg_unique_id: {}", response.msg_unique_id);
+        
+        // 检查 ClusterMessageHandler 指针是否有效
+        if cluster_handler_ptr.is_null() {
+            error!("ClusterMessageHandler pointer is null");
+            return;
         }
+        
+        // 安全访问 ClusterMessageHandler
+        let cluster_handler = unsafe { &mut *cluster_handler_ptr };
+        
+        // 检查 ServerManager 和 ClusterManager 指针是否有效
+        if cluster_handler.server_manager.is_null() || cluster_handler.cluster_manager.is_null() {
+            error!("ServerManager or ClusterManager pointer is null");
+            return;
+        }
+        
+        // 收到对端的响应才完成握手，未通过挑战校验或不是连接目标的响应会关闭会话
+        let cluster_manager = unsafe { &mut *cluster_handler.cluster_manager };
+        if !cluster_manager.complete_handshake(session_id, server_config.server_id, server_config.server_type.clone()) {
+            return;
+        }
+        
+        // 安全访问 ServerManager
+        let server_manager = unsafe { &mut *cluster_handler.server_manager };
+        
+        // 将响应中的服务器配置添加到 server_manager
+        let responding_server = ServerInfo::new(
+            server_config.server_id,
+            server_config.server_type.clone(),
+            server_config.back_host.clone(),
+            back_tcp_port,
+        );
+        info!("Adding responding server to cluster: {}", responding_server.get_info());
+        server_manager.add_server(responding_server);
+        
+        // 处理连接确认
+        info!("Node connection established with server_id: {}, server_type: {}", 
+              server_config.server_id, server_config.server_type);
+        
+        debug!("Successfully processed NodeConnectBResponse from server {}", 
+               server_config.server_id);
     }
 
     /// 处理节点注册通知（静态函数，用于注册到消息分发器）
     pub fn handle_node_register_notify(
         _session: &mut BackSession, 
-        message: &dyn Any,
+        notify: &NodeRegisterBNotify,
         cluster_handler_ptr: *mut ClusterMessageHandler
     ) {
         debug!("Handling NodeRegisterBNotify");
 
-        // 尝试将消息转换为 NodeRegisterBNotify
-        if let Some(notify) = message.downcast_ref::<NodeRegisterBNotify>() {
-            // 如果 server_config 是 None，返回错误
-            let server_config = match notify.server_config.as_ref() {
-                Some(config) => config,
-                None => {
-                    error!("NodeRegisterBNotify missing server_config");
-                    return;
-                }
-            };
-            info!("Received NodeRegisterBNotify from server_id: {}, server_type: {}", 
-                  server_config.server_id, server_config.server_type);
-            
-            // 检查 ClusterMessageHandler 指针是否有效
-            if cluster_handler_ptr.is_null() {
-                error!("ClusterMessageHandler pointer is null");
+        // 如果 server_config 是 None，返回错误
+        let server_config = match notify.server_config.as_ref() {
+            Some(config) => config,
+            None => {
+                error!("NodeRegisterBNotify missing server_config");
                 return;
             }
-            
-            // 安全访问 ClusterMessageHandler
-            let cluster_handler = unsafe { &mut *cluster_handler_ptr };
-            
-            // 检查 ServerManager 和 BackSessionManager 指针是否有效
-            if cluster_handler.server_manager.is_null() {
-                error!("ServerManager pointer is null");
+        };
+        // 端口在协议中为u32，超出范围时拒绝，不截断
+        let back_tcp_port = match net_addr::port_from_u32(server_config.back_tcp_port) {
+            Some(port) => port,
+            None => {
+                error!("NodeRegisterBNotify from server_id {} has invalid back_tcp_port {}", server_config.server_id, server_config.back_tcp_port);
                 return;
             }
-            
-            if cluster_handler.back_session_manager.is_null() {
-                error!("BackSessionManager pointer is null");
+        };
+        info!("Received NodeRegisterBNotify from server_id: {}, server_type: {}", 
+              server_config.server_id, server_config.server_type);
+        
+        // 检查 ClusterMessageHandler 指针是否有效
+        if cluster_handler_ptr.is_null() {
+            error!("ClusterMessageHandler pointer is null");
+            return;
+        }
+        
+        // 安全访问 ClusterMessageHandler
+        let cluster_handler = unsafe { &mut *cluster_handler_ptr };
+        
+        // 检查 ServerManager 和 BackSessionManager 指针是否有效
+        if cluster_handler.server_manager.is_null() {
+            error!("ServerManager pointer is null");
+            return;
+        }
+        
+        if cluster_handler.cluster_manager.is_null() {
+            error!("ClusterManager pointer is null");
+            return;
+        }
+        
+        // 安全访问 ServerManager 和 ClusterManager
+        let server_manager = unsafe { &mut *cluster_handler.server_manager };
+        let cluster_manager = unsafe { &mut *cluster_handler.cluster_manager };
+        
+        // 1. 将通知中的服务器配置添加到 server_manager
+        let new_server = ServerInfo::new(
+            server_config.server_id,
+            server_config.server_type.clone(),
+            server_config.back_host.clone(),
+            back_tcp_port,
+        );
+        info!("Adding notified server to cluster: {}", new_server.get_info());
+        server_manager.add_server(new_server);
+        
+        // 2. 通过 cluster_manager.connect_to_peer 去连接新服务器（断开后自动重连）
+        info!("ClusterMessageHandler: Connecting to CLUSTER NODE (from register notify) - server_id={}, back_host={}, back_tcp_port={}", 
+              server_config.server_id, 
+              server_config.back_host, 
+              server_config.back_tcp_port);
+        
+        cluster_manager.connect_to_peer(
+            server_config.server_id,
+            &server_config.back_host,
+            back_tcp_port
+        );
+        
+        info!("Created client session to connect to notified server_id: {} at {}:{}", 
+              server_config.server_id, 
+              server_config.back_host, 
+              server_config.back_tcp_port);
+        
+    }
+
+    /// 处理节点心跳请求（静态函数，用于注册到消息分发器）
+    pub fn handle_node_heartbeat_request(session: &mut BackSession, request: &NodeHeartbeatBRequest) {
+        // 只有完成认证的会话才参与心跳
+        if !session.is_authenticated() {
+            debug!("Ignoring NodeHeartbeatBRequest from unauthenticated session {}", session.get_session_id());
+            return;
+        }
+
+        session.refresh_heartbeat_time();
+        session.set_reported_load(request.load);
+
+        let response = NodeHeartbeatBResponse {
+            msg_unique_id: request.msg_unique_id,
+            timestamp: request.timestamp,
+        };
+        if !session.send_message(response) {
+            error!("Failed to send NodeHeartbeatBResponse to server {}", session.get_server_id());
+        }
+    }
+
+    /// 处理节点心跳响应（静态函数，用于注册到消息分发器）
+    pub fn handle_node_heartbeat_response(session: &mut BackSession, response: &NodeHeartbeatBResponse) {
+        if !session.is_authenticated() {
+            debug!("Ignoring NodeHeartbeatBResponse from unauthenticated session {}", session.get_session_id());
+            return;
+        }
+
+        session.refresh_heartbeat_time();
+        debug!("Heartbeat from server {}, rtt {}ms", 
+               session.get_server_id(), chrono::Utc::now().timestamp_millis() - response.timestamp);
+    }
+
+    /// 处理节点注销请求（静态函数，用于注册到消息分发器，master 模式）
+    pub fn handle_node_unregister_request(
+        session: &mut BackSession, 
+        request: &NodeUnregisterBRequest,
+        cluster_handler_ptr: *mut ClusterMessageHandler
+    ) {
+        if !session.is_authenticated() {
+            error!("Security: Received NodeUnregisterBRequest from unauthenticated session {}", session.get_session_id());
+            return;
+        }
+
+        // 节点只能注销自己
+        if request.server_id != session.get_server_id() {
+            error!("Security: Server {} tried to unregister server {}", session.get_server_id(), request.server_id);
+            return;
+        }
+        info!("Received NodeUnregisterBRequest: server_id={}", request.server_id);
+
+        if cluster_handler_ptr.is_null() {
+            error!("ClusterMessageHandler pointer is null");
+            return;
+        }
+
+        let cluster_handler = unsafe { &mut *cluster_handler_ptr };
+        if cluster_handler.cluster_manager.is_null() {
+            error!("ClusterManager pointer is null");
+            return;
+        }
+
+        // 当前正在处理该会话的消息，移除会话和广播通知延迟到ClusterManager::update
+        let cluster_manager = unsafe { &mut *cluster_handler.cluster_manager };
+        cluster_manager.schedule_node_leave(session.get_session_id(), request.server_id);
+    }
+
+    /// 处理节点注销通知（静态函数，用于注册到消息分发器）
+    pub fn handle_node_unregister_notify(
+        session: &mut BackSession, 
+        notify: &NodeUnregisterBNotify,
+        cluster_handler_ptr: *mut ClusterMessageHandler
+    ) {
+        // 只接受来自master的通知
+        if !session.is_authenticated() || session.get_server_type().is_none_or(|t| t != "master") {
+            error!("Security: Received NodeUnregisterBNotify from non-master session {}", session.get_session_id());
+            return;
+        }
+
+        let reason = match NodeRemoveReason::from_u32(notify.reason) {
+            Some(reason) => reason,
+            None => {
+                error!("NodeUnregisterBNotify for server {} has unknown reason {}", notify.server_id, notify.reason);
                 return;
             }
-            
-            // 安全访问 ServerManager 和 BackSessionManager
-            let server_manager = unsafe { &mut *cluster_handler.server_manager };
-            let back_session_mgr = unsafe { &mut *cluster_handler.back_session_manager };
-            
-            // 1. 将通知中的服务器配置添加到 server_manager
-            let new_server = ServerInfo::new(
-                server_config.server_id,
-                server_config.server_type.clone(),
-                server_config.back_host.clone(),
-                server_config.back_tcp_port,
-            );
-            info!("Adding notified server to cluster: {}", new_server.get_info());
-            server_manager.add_server(new_server);
-            
-            // 2. 调用 back_session_manager.create_client_session 去连接新服务器
-            info!("ClusterMessageHandler: Connecting to CLUSTER NODE (from register notify) - server_id={}, back_host={}, back_tcp_port={}", 
-                  server_config.server_id, 
-                  server_config.back_host, 
-                  server_config.back_tcp_port);
-            
-            back_session_mgr.create_client_session(
-                server_config.server_id,
-                &server_config.back_host,
-                server_config.back_tcp_port as u16
-            );
-            
-            info!("Created client session to connect to notified server_id: {} at {}:{}", 
-                  server_config.server_id, 
-                  server_config.back_host, 
-                  server_config.back_tcp_port);
-            
-        } else {
-            error!("Failed to downcast message to NodeRegisterBNotify");
+        };
+        info!("Received NodeUnregisterBNotify: server_id={}, reason={:?}", notify.server_id, reason);
+
+        if cluster_handler_ptr.is_null() {
+            error!("ClusterMessageHandler pointer is null");
+            return;
         }
+
+        let cluster_handler = unsafe { &mut *cluster_handler_ptr };
+        if cluster_handler.cluster_manager.is_null() {
+            error!("ClusterManager pointer is null");
+            return;
+        }
+
+        let cluster_manager = unsafe { &mut *cluster_handler.cluster_manager };
+        cluster_manager.remove_node(notify.server_id, reason);
     }
-}
\ No newline at end of file
+}
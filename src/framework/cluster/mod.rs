@@ -1,8 +1,11 @@
 pub mod cluster_manager;
 pub mod cluster_message_handler;
+pub mod cluster_link;
 pub mod server_manager;
 pub mod server_info;
+pub mod user_directory;
 
-pub use cluster_manager::ClusterManager;
+pub use cluster_manager::{ClusterManager, ClusterNodeOptions};
 pub use cluster_message_handler::ClusterMessageHandler;
-pub use server_manager::ServerManager;
\ No newline at end of file
+pub use server_manager::{ServerManager, ServerChangeEvent};
+pub use user_directory::{UserDirectory, UserLocation};
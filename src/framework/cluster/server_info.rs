@@ -1,3 +1,4 @@
+use crate::framework::network::net_addr;
 use tracing::{debug, info};
 
 /// 服务器信息
@@ -10,12 +11,12 @@ pub struct ServerInfo {
     /// 后端主机地址
     pub back_host: String,
     /// 后端TCP端口
-    pub back_tcp_port: u32,
+    pub back_tcp_port: u16,
 }
 
 impl ServerInfo {
     /// 创建新的服务器实例
-    pub fn new(server_id: u32, server_type: String, back_host: String, back_tcp_port: u32) -> Self {
+    pub fn new(server_id: u32, server_type: String, back_host: String, back_tcp_port: u16) -> Self {
         Self {
             server_id,
             server_type,
@@ -40,13 +41,13 @@ impl ServerInfo {
     }
 
     /// 获取后端TCP端口
-    pub fn get_back_tcp_port(&self) -> u32 {
+    pub fn get_back_tcp_port(&self) -> u16 {
         self.back_tcp_port
     }
 
     /// 获取服务器地址字符串
     pub fn get_address(&self) -> String {
-        format!("{}:{}", self.back_host, self.back_tcp_port)
+        net_addr::format_addr(&self.back_host, self.back_tcp_port)
     }
 
     /// 获取服务器信息字符串
@@ -2,12 +2,25 @@ use super::server_info::ServerInfo;
 use std::collections::HashMap;
 use tracing::{debug, info, warn, error};
 
+/// 服务器列表变化事件
+pub enum ServerChangeEvent<'a> {
+    /// 服务器加入
+    Added(&'a ServerInfo),
+    /// 服务器移除
+    Removed(&'a ServerInfo),
+}
+
+/// 服务器列表变化监听器
+pub type ServerChangeListener = Box<dyn FnMut(&ServerChangeEvent) + Send>;
+
 /// 服务器管理器
 pub struct ServerManager {
     /// 服务器列表 (server_id -> ServerInfo)
     servers: HashMap<u32, ServerInfo>,
     /// 按类型分组的服务器 (server_type -> Vec<server_id>)
     servers_by_type: HashMap<String, Vec<u32>>,
+    /// 服务器列表变化监听器
+    listeners: Vec<ServerChangeListener>,
 }
 
 impl ServerManager {
@@ -16,6 +29,7 @@ impl ServerManager {
         Self {
             servers: HashMap::new(),
             servers_by_type: HashMap::new(),
+            listeners: Vec::new(),
         }
     }
     
@@ -36,6 +50,7 @@ impl ServerManager {
         let count = self.servers.len();
         self.servers.clear();
         self.servers_by_type.clear();
+        self.listeners.clear();
         
         if count > 0 {
             info!("ServerManager disposed, cleared {} servers", count);
@@ -44,14 +59,31 @@ impl ServerManager {
         }
     }
 
+    /// 添加服务器变化监听器，服务器加入和移除时回调
+    pub fn add_listener(&mut self, listener: ServerChangeListener) {
+        self.listeners.push(listener);
+    }
+
+    /// 通知所有监听器
+    fn notify_listeners(&mut self, event: ServerChangeEvent) {
+        for listener in self.listeners.iter_mut() {
+            listener(&event);
+        }
+    }
+
     /// 添加服务器
     pub fn add_server(&mut self, server: ServerInfo) -> bool {
         let server_id = server.server_id;
         let server_type = server.server_type.clone();
         
-        // 检查是否已存在
-        if self.servers.contains_key(&server_id) {
-            warn!("Server {} already exists, updating", server_id);
+        // 检查是否已存在，类型不变时只更新信息，不触发变化事件
+        if let Some(existing) = self.servers.get_mut(&server_id) {
+            if existing.server_type == server_type {
+                debug!("Server {} already exists, updating info", server_id);
+                *existing = server;
+                return true;
+            }
+            warn!("Server {} already exists with type {}, replacing", server_id, existing.server_type);
             self.remove_server(server_id);
         }
         
@@ -65,6 +97,7 @@ impl ServerManager {
             .push(server_id);
         
         info!("Added server: {}", server.get_info());
+        self.notify_listeners(ServerChangeEvent::Added(&server));
         true
     }
 
@@ -80,6 +113,7 @@ impl ServerManager {
             }
             
             info!("Removed server: {}", server.get_info());
+            self.notify_listeners(ServerChangeEvent::Removed(&server));
             Some(server)
         } else {
             None
@@ -136,11 +170,14 @@ impl ServerManager {
         self.servers_by_type.keys().cloned().collect()
     }
 
-    /// 清空所有服务器
+    /// 清空所有服务器，每个服务器都会触发移除事件
     pub fn clear(&mut self) {
         let count = self.servers.len();
-        self.servers.clear();
+        let servers: Vec<ServerInfo> = self.servers.drain().map(|(_, server)| server).collect();
         self.servers_by_type.clear();
+        for server in &servers {
+            self.notify_listeners(ServerChangeEvent::Removed(server));
+        }
         if count > 0 {
             info!("Cleared {} servers", count);
         }
@@ -0,0 +1,556 @@
+use super::server_manager::{ServerManager, ServerChangeEvent};
+use crate::framework::config::config::Config;
+use crate::framework::network::{NetworkEventHandler, network_event_queue::{NetworkEventData, NetworkEventType}};
+use crate::framework::rpc::{RpcManager, RpcMessageDispatcher, RpcError, RpcHandlerResult, HashRing};
+use crate::framework::session::{BackSessionManager, BackSessionMessageDispatcher, FrontSessionManager, SessionTrait};
+use crate::proto::messages::protobuf::message::cluster::{
+    UserOnlineBRequest, UserOnlineBResponse, UserOfflineBNotify, UserLocateBRequest, UserLocateBResponse, UserKickBNotify,
+};
+use crate::proto::messages::protobuf::message::session::KickFNotify;
+use std::collections::HashMap;
+use tracing::{debug, error, info, warn};
+
+/// 目录请求的默认超时时间（毫秒）
+pub const DEFAULT_DIRECTORY_TIMEOUT_MS: u64 = 3000;
+
+/// 踢下线原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KickReason {
+    /// 同一用户在其他地方登录
+    DuplicateLogin = 1,
+}
+
+impl KickReason {
+    /// 从协议中的数值转换
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(KickReason::DuplicateLogin),
+            _ => None,
+        }
+    }
+}
+
+/// 用户在集群中的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserLocation {
+    /// 用户所在的前端服务器ID
+    pub server_id: u32,
+    /// 用户的前端会话ID
+    pub front_session_id: u64,
+}
+
+// 包装器结构体，使原始指针满足Send + Sync要求（仅用于满足闭包类型约束）
+// 实际运行时所有调用都在主线程，不会有并发访问
+#[derive(Clone, Copy)]
+struct UserDirectoryPtr(*mut UserDirectory);
+unsafe impl Send for UserDirectoryPtr {}
+unsafe impl Sync for UserDirectoryPtr {}
+
+/// 在线用户目录，记录每个在线用户所在的前端服务器和前端会话
+///
+/// 目录由config.xml中directory配置的服务器类型（默认master）承载，有多台时按用户ID一致性哈希分片。
+/// 每个节点都持有本类：
+/// - 前端服务器在用户登录后调用report_online，前端会话断开时自动上报下线
+/// - 任意节点通过locate查询用户位置
+/// - 目录服务器保存目录，开启kick_duplicate时通知之前登录所在的前端服务器踢掉旧会话，
+///   前端服务器离开集群时清除其上的所有用户
+/// - 与目录服务器的连接重新建立（如目录服务器重启）时，前端服务器重新上报该目录服务器上的用户
+///
+/// ## 重要说明
+/// 本类为单例，被Server持有，所有方法都在主线程调用，不存在线程安全问题。
+pub struct UserDirectory {
+    /// 当前服务器ID
+    server_id: u32,
+    /// 承载目录的服务器类型
+    host_server_type: String,
+    /// 当前服务器是否承载目录
+    is_host: bool,
+    /// 重复登录时是否踢掉之前的会话
+    kick_duplicate: bool,
+    /// 目录服务器的哈希环，按配置固定，避免目录服务器上下线时用户被分到没有记录的服务器
+    host_ring: HashRing,
+    /// 目录 <用户ID, 位置>，仅目录服务器使用
+    entries: HashMap<u64, UserLocation>,
+    /// 当前服务器上已上报的用户 <前端会话ID, 用户ID>
+    local_users: HashMap<u64, u64>,
+    /// RPC管理器指针，用于向目录服务器发送请求
+    rpc_manager: *mut RpcManager,
+    /// 前端会话管理器指针，用于踢下线
+    front_session_manager: *mut FrontSessionManager,
+}
+
+// 安全性：UserDirectory只在单线程环境中使用
+unsafe impl Send for UserDirectory {}
+
+impl UserDirectory {
+    /// 创建新的在线用户目录
+    pub fn new() -> Self {
+        Self {
+            server_id: 0,
+            host_server_type: String::new(),
+            is_host: false,
+            kick_duplicate: true,
+            host_ring: HashRing::default(),
+            entries: HashMap::new(),
+            local_users: HashMap::new(),
+            rpc_manager: std::ptr::null_mut(),
+            front_session_manager: std::ptr::null_mut(),
+        }
+    }
+
+    /// 初始化在线用户目录并注册消息处理器
+    ///
+    /// 注意：本方法在主线程调用
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(
+        &mut self,
+        server_id: u32,
+        server_type: &str,
+        config: &Config,
+        rpc_manager: &mut RpcManager,
+        rpc_message_dispatcher: &mut RpcMessageDispatcher,
+        back_dispatcher: &mut BackSessionMessageDispatcher,
+        front_session_manager: &mut FrontSessionManager,
+        server_manager: &mut ServerManager,
+    ) -> bool {
+        self.server_id = server_id;
+        self.host_server_type = config.directory.server_type.clone();
+        self.is_host = server_type == self.host_server_type;
+        self.kick_duplicate = config.directory.kick_duplicate;
+        self.rpc_manager = rpc_manager as *mut RpcManager;
+        self.front_session_manager = front_session_manager as *mut FrontSessionManager;
+        rpc_manager.set_user_directory(self);
+
+        for group in config.servers.group.iter().filter(|group| group.name == self.host_server_type) {
+            for server in &group.server {
+                self.host_ring.add_node(server.id);
+            }
+        }
+        if self.host_ring.is_empty() {
+            error!("No server of type {} configured to host the user directory", self.host_server_type);
+            return false;
+        }
+
+        let directory_ptr = UserDirectoryPtr(self as *mut UserDirectory);
+
+        // 所有节点都可能承载前端会话，需要处理踢下线通知
+        back_dispatcher.register::<UserKickBNotify, _>(move |_session, notify| {
+            unsafe {
+                let ptr = directory_ptr;
+                let directory = &mut *ptr.0;
+                directory.kick_local(notify.user_id, notify.front_session_id, notify.reason);
+            }
+        });
+
+        if self.is_host {
+            rpc_message_dispatcher.register_request::<UserOnlineBRequest, _, _>(move |_session, _msg_unique_id, _front_session_id, request| {
+                unsafe {
+                    let ptr = directory_ptr;
+                    let directory = &mut *ptr.0;
+                    directory.handle_online_request(request)
+                }
+            });
+            rpc_message_dispatcher.register_request::<UserLocateBRequest, _, _>(move |_session, _msg_unique_id, _front_session_id, request| {
+                unsafe {
+                    let ptr = directory_ptr;
+                    let directory = &*ptr.0;
+                    directory.handle_locate_request(request)
+                }
+            });
+            back_dispatcher.register::<UserOfflineBNotify, _>(move |_session, notify| {
+                unsafe {
+                    let ptr = directory_ptr;
+                    let directory = &mut *ptr.0;
+                    directory.remove_entry(notify.user_id, notify.server_id, notify.front_session_id);
+                }
+            });
+
+            // 前端服务器离开集群时清除其上的所有用户
+            server_manager.add_listener(Box::new(move |event| {
+                if let ServerChangeEvent::Removed(server) = event {
+                    let ptr = directory_ptr;
+                    let directory = unsafe { &mut *ptr.0 };
+                    directory.remove_server_entries(server.server_id);
+                }
+            }));
+
+            info!("User directory hosted on this server (type {}, kick_duplicate={})", self.host_server_type, self.kick_duplicate);
+        }
+
+        true
+    }
+
+    /// 监听后端会话授权，与目录服务器的连接建立时重新上报本服务器上属于该目录服务器的用户
+    ///
+    /// 目录服务器重启后目录为空，需要前端服务器重新上报才能查询到之前登录的用户
+    ///
+    /// 注意：本方法在主线程调用
+    pub fn attach(&mut self, back_session_manager: &mut BackSessionManager) {
+        let directory_ptr = UserDirectoryPtr(self as *mut UserDirectory);
+        back_session_manager.add_authorized_listener(Box::new(move |server_id, server_type| {
+            let ptr = directory_ptr;
+            let directory = unsafe { &mut *ptr.0 };
+            if server_type == directory.host_server_type {
+                directory.resync_host(server_id);
+            }
+        }));
+    }
+
+    /// 向目录服务器重新上报本服务器上属于它的在线用户
+    fn resync_host(&mut self, host_server_id: u32) {
+        let users: Vec<(u64, u64)> = self.local_users.iter()
+            .filter(|(_, user_id)| self.get_host_server_id(**user_id) == Some(host_server_id))
+            .map(|(front_session_id, user_id)| (*front_session_id, *user_id))
+            .collect();
+        if users.is_empty() {
+            return;
+        }
+
+        if self.rpc_manager.is_null() {
+            error!("UserDirectory not initialized");
+            return;
+        }
+        let rpc_manager = unsafe { &mut *self.rpc_manager };
+        info!("Re-reporting {} online users to directory server {}", users.len(), host_server_id);
+        for (front_session_id, user_id) in users {
+            let request = UserOnlineBRequest {
+                user_id,
+                server_id: self.server_id,
+                front_session_id,
+            };
+            let sent = rpc_manager.request_to_server::<UserOnlineBRequest, UserOnlineBResponse, _>(
+                host_server_id,
+                request,
+                DEFAULT_DIRECTORY_TIMEOUT_MS,
+                move |result| {
+                    if let Err(error) = result {
+                        warn!("Failed to re-report user {} to directory server {}: {:?}", user_id, host_server_id, error);
+                    }
+                },
+            );
+            if !sent {
+                warn!("Failed to re-report user {} to directory server {}", user_id, host_server_id);
+            }
+        }
+    }
+
+    /// 用户所属的目录服务器ID
+    pub fn get_host_server_id(&self, user_id: u64) -> Option<u32> {
+        self.host_ring.get_node(user_id)
+    }
+
+    /// 上报用户登录，应在前端会话设置用户ID后调用
+    ///
+    /// 注意：本方法在主线程调用
+    ///
+    /// # 参数
+    /// * `front_session_id` - 用户的前端会话ID
+    /// * `user_id` - 用户ID
+    /// * `callback` - 目录服务器处理后调用，参数为之前登录的位置（开启kick_duplicate时已被踢下线）；
+    ///   请求失败时为Err
+    ///
+    /// # 返回值
+    /// 上报发出返回true，目录服务器不可达返回false且不会调用回调
+    pub fn report_online<F>(&mut self, front_session_id: u64, user_id: u64, callback: F) -> bool
+    where
+        F: FnOnce(Result<Option<UserLocation>, RpcError>) + Send + 'static,
+    {
+        // 同一个会话换了用户时先上报旧用户下线
+        if self.local_users.get(&front_session_id).is_some_and(|old_user_id| *old_user_id != user_id) {
+            self.report_offline(front_session_id);
+        }
+        self.local_users.insert(front_session_id, user_id);
+
+        let request = UserOnlineBRequest {
+            user_id,
+            server_id: self.server_id,
+            front_session_id,
+        };
+
+        let host_server_id = match self.get_host_server_id(user_id) {
+            Some(host_server_id) => host_server_id,
+            None => return false,
+        };
+        if host_server_id == self.server_id {
+            let response = self.handle_online_request(&request);
+            callback(response.map(Self::previous_location).map_err(|e| RpcError::Remote { code: e.code, message: e.message }));
+            return true;
+        }
+
+        if self.rpc_manager.is_null() {
+            error!("UserDirectory not initialized");
+            return false;
+        }
+        let rpc_manager = unsafe { &mut *self.rpc_manager };
+        let sent = rpc_manager.request_to_server::<UserOnlineBRequest, UserOnlineBResponse, _>(
+            host_server_id,
+            request,
+            DEFAULT_DIRECTORY_TIMEOUT_MS,
+            move |result| callback(result.map(Self::previous_location)),
+        );
+        if !sent {
+            error!("Failed to report user {} online to directory server {}", user_id, host_server_id);
+        }
+        sent
+    }
+
+    /// 上报前端会话上的用户下线，前端会话断开时会自动调用
+    ///
+    /// 注意：本方法在主线程调用
+    pub fn report_offline(&mut self, front_session_id: u64) {
+        let user_id = match self.local_users.remove(&front_session_id) {
+            Some(user_id) => user_id,
+            None => return,
+        };
+
+        let host_server_id = match self.get_host_server_id(user_id) {
+            Some(host_server_id) => host_server_id,
+            None => return,
+        };
+        if host_server_id == self.server_id {
+            self.remove_entry(user_id, self.server_id, front_session_id);
+            return;
+        }
+
+        if self.rpc_manager.is_null() {
+            error!("UserDirectory not initialized");
+            return;
+        }
+        let rpc_manager = unsafe { &mut *self.rpc_manager };
+        let notify = UserOfflineBNotify {
+            user_id,
+            server_id: self.server_id,
+            front_session_id,
+        };
+        if !rpc_manager.call_to_server(host_server_id, notify) {
+            warn!("Failed to report user {} offline to directory server {}", user_id, host_server_id);
+        }
+    }
+
+    /// 查询用户在集群中的位置
+    ///
+    /// 注意：本方法在主线程调用
+    ///
+    /// # 参数
+    /// * `user_id` - 用户ID
+    /// * `callback` - 查询完成后调用，用户不在线时为Ok(None)
+    ///
+    /// # 返回值
+    /// 查询发出返回true，目录服务器不可达返回false且不会调用回调
+    pub fn locate<F>(&mut self, user_id: u64, callback: F) -> bool
+    where
+        F: FnOnce(Result<Option<UserLocation>, RpcError>) + Send + 'static,
+    {
+        let host_server_id = match self.get_host_server_id(user_id) {
+            Some(host_server_id) => host_server_id,
+            None => return false,
+        };
+        if host_server_id == self.server_id {
+            callback(Ok(self.entries.get(&user_id).copied()));
+            return true;
+        }
+
+        if self.rpc_manager.is_null() {
+            error!("UserDirectory not initialized");
+            return false;
+        }
+        let rpc_manager = unsafe { &mut *self.rpc_manager };
+        rpc_manager.request_to_server::<UserLocateBRequest, UserLocateBResponse, _>(
+            host_server_id,
+            UserLocateBRequest { user_id },
+            DEFAULT_DIRECTORY_TIMEOUT_MS,
+            move |result| {
+                callback(result.map(|response| {
+                    response.online.then_some(UserLocation {
+                        server_id: response.server_id,
+                        front_session_id: response.front_session_id,
+                    })
+                }))
+            },
+        )
+    }
+
+    /// 从上线响应中取出之前登录的位置
+    fn previous_location(response: UserOnlineBResponse) -> Option<UserLocation> {
+        (response.previous_server_id != 0).then_some(UserLocation {
+            server_id: response.previous_server_id,
+            front_session_id: response.previous_front_session_id,
+        })
+    }
+
+    /// 目录服务器处理用户上线，重复登录时按配置踢掉之前的会话
+    fn handle_online_request(&mut self, request: &UserOnlineBRequest) -> RpcHandlerResult<UserOnlineBResponse> {
+        let location = UserLocation {
+            server_id: request.server_id,
+            front_session_id: request.front_session_id,
+        };
+        let previous = self.entries.insert(request.user_id, location).filter(|previous| *previous != location);
+        debug!("User {} online at server {} front session {}", request.user_id, location.server_id, location.front_session_id);
+
+        let previous = match previous {
+            Some(previous) => previous,
+            None => return Ok(UserOnlineBResponse { kicked: false, previous_server_id: 0, previous_front_session_id: 0 }),
+        };
+
+        info!("User {} logged in again at server {} (previously at server {} front session {})",
+              request.user_id, location.server_id, previous.server_id, previous.front_session_id);
+        if self.kick_duplicate {
+            self.kick(request.user_id, previous, KickReason::DuplicateLogin);
+        }
+
+        Ok(UserOnlineBResponse {
+            kicked: self.kick_duplicate,
+            previous_server_id: previous.server_id,
+            previous_front_session_id: previous.front_session_id,
+        })
+    }
+
+    /// 目录服务器处理位置查询
+    fn handle_locate_request(&self, request: &UserLocateBRequest) -> RpcHandlerResult<UserLocateBResponse> {
+        Ok(match self.entries.get(&request.user_id) {
+            Some(location) => UserLocateBResponse { online: true, server_id: location.server_id, front_session_id: location.front_session_id },
+            None => UserLocateBResponse { online: false, server_id: 0, front_session_id: 0 },
+        })
+    }
+
+    /// 移除用户的目录记录，位置与记录不一致时（已在其他地方重新登录）忽略
+    fn remove_entry(&mut self, user_id: u64, server_id: u32, front_session_id: u64) {
+        let matches = self.entries.get(&user_id)
+            .is_some_and(|location| location.server_id == server_id && location.front_session_id == front_session_id);
+        if matches {
+            self.entries.remove(&user_id);
+            debug!("User {} offline from server {} front session {}", user_id, server_id, front_session_id);
+        }
+    }
+
+    /// 清除指定前端服务器上的所有用户
+    fn remove_server_entries(&mut self, server_id: u32) {
+        let count = self.entries.len();
+        self.entries.retain(|_, location| location.server_id != server_id);
+        let removed = count - self.entries.len();
+        if removed > 0 {
+            info!("Removed {} directory entries of departed server {}", removed, server_id);
+        }
+    }
+
+    /// 通知用户所在的前端服务器踢掉指定会话
+    fn kick(&mut self, user_id: u64, location: UserLocation, reason: KickReason) {
+        if location.server_id == self.server_id {
+            self.kick_local(user_id, location.front_session_id, reason as u32);
+            return;
+        }
+
+        if self.rpc_manager.is_null() {
+            error!("UserDirectory not initialized");
+            return;
+        }
+        let rpc_manager = unsafe { &mut *self.rpc_manager };
+        let notify = UserKickBNotify {
+            user_id,
+            front_session_id: location.front_session_id,
+            reason: reason as u32,
+        };
+        if !rpc_manager.call_to_server(location.server_id, notify) {
+            warn!("Failed to send kick for user {} to server {}", user_id, location.server_id);
+        }
+    }
+
+    /// 踢掉当前服务器上的前端会话，会话的用户ID不一致时忽略
+    fn kick_local(&mut self, user_id: u64, front_session_id: u64, reason: u32) {
+        if self.front_session_manager.is_null() {
+            error!("UserDirectory not initialized");
+            return;
+        }
+        let front_session_manager = unsafe { &mut *self.front_session_manager };
+        let session = match front_session_manager.get_session_mut(front_session_id) {
+            Some(session) if session.get_user_id() == Some(user_id) => session,
+            _ => {
+                debug!("Kick target user {} front session {} not found", user_id, front_session_id);
+                return;
+            }
+        };
+
+        // 目录中的记录已指向新的登录，断开时不再上报下线
+        self.local_users.remove(&front_session_id);
+
+        info!("Kicking user {} from front session {} (reason {:?})", user_id, front_session_id, KickReason::from_u32(reason));
+        session.send_message(KickFNotify { reason });
+        session.close();
+    }
+
+    /// 清理在线用户目录
+    ///
+    /// 注意：本方法在主线程调用
+    pub fn dispose(&mut self) {
+        self.entries.clear();
+        self.local_users.clear();
+        self.rpc_manager = std::ptr::null_mut();
+        self.front_session_manager = std::ptr::null_mut();
+
+        debug!("UserDirectory disposed");
+    }
+}
+
+impl Default for UserDirectory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NetworkEventHandler for UserDirectory {
+    /// 前端会话断开时上报用户下线
+    fn handle_event(&mut self, event: &mut NetworkEventData) {
+        if !event.server_type.is_front() {
+            return;
+        }
+        match event.event_type {
+            NetworkEventType::Disconnect | NetworkEventType::StreamDataNotExpected => {
+                self.report_offline(event.session_id);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::network::network_event_queue::ServerType;
+
+    /// 当前服务器（ID为1）同时承载目录和前端会话
+    fn local_host_directory() -> UserDirectory {
+        let mut directory = UserDirectory::new();
+        directory.server_id = 1;
+        directory.is_host = true;
+        directory.host_ring.add_node(1);
+        directory
+    }
+
+    fn report_online(directory: &mut UserDirectory, front_session_id: u64, user_id: u64) {
+        assert!(directory.report_online(front_session_id, user_id, |result| assert!(result.is_ok())));
+    }
+
+    #[test]
+    fn front_disconnect_reports_offline_for_tcp_and_websocket() {
+        let mut directory = local_host_directory();
+        report_online(&mut directory, 11, 1001);
+        report_online(&mut directory, 12, 1002);
+        assert_eq!(directory.entries.len(), 2);
+
+        directory.handle_event(&mut NetworkEventData::new(NetworkEventType::Disconnect, ServerType::FrontTcp, 11, None));
+        directory.handle_event(&mut NetworkEventData::new(NetworkEventType::Disconnect, ServerType::FrontWebSocket, 12, None));
+
+        assert!(directory.entries.is_empty());
+        assert!(directory.local_users.is_empty());
+    }
+
+    #[test]
+    fn back_disconnect_does_not_report_offline() {
+        let mut directory = local_host_directory();
+        report_online(&mut directory, 11, 1001);
+
+        directory.handle_event(&mut NetworkEventData::new(NetworkEventType::Disconnect, ServerType::BackTcp, 11, None));
+
+        assert_eq!(directory.entries.get(&1001), Some(&UserLocation { server_id: 1, front_session_id: 11 }));
+    }
+}
@@ -5,11 +5,140 @@ use quick_xml::de::from_str;
 use tracing::error;
 
 const DEFAULT_HOST: &str = "127.0.0.1";
+const DEFAULT_HEARTBEAT_INTERVAL_MS: u64 = 5000;
+const DEFAULT_HEARTBEAT_MISS_THRESHOLD: u32 = 3;
+const DEFAULT_RECONNECT_MIN_DELAY_MS: u64 = 1000;
+const DEFAULT_RECONNECT_MAX_DELAY_MS: u64 = 30000;
+const DEFAULT_HANDSHAKE_TIMEOUT_MS: u64 = 10000;
+const DEFAULT_LEAVE_TIMEOUT_MS: u64 = 3000;
+const DEFAULT_SCHEDULE_TIMEZONE: &str = "UTC";
+const DEFAULT_SCHEDULE_CATCH_UP: &str = "skip";
+const DEFAULT_SERVER_WEIGHT: u32 = 1;
+const DEFAULT_ROUTER_STRATEGY: &str = "random";
+const DEFAULT_DIRECTORY_SERVER_TYPE: &str = "master";
+const DEFAULT_LOGIN_TIMEOUT_MS: u64 = 60000;
+const DEFAULT_LOGIN_VERIFIER: &str = "hmac";
+const DEFAULT_LOGIN_LEEWAY_SECS: u64 = 30;
+const DEFAULT_LOGIN_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_TLS_HANDSHAKE_TIMEOUT_MS: u64 = 10000;
+const DEFAULT_TLS_RELOAD_INTERVAL_MS: u64 = 60000;
+const DEFAULT_BACK_TLS_IDENTITY_PREFIX: &str = "node-";
+const DEFAULT_SEND_QUEUE_CAPACITY: usize = 1024;
+const DEFAULT_SEND_QUEUE_MAX_BATCH_BYTES: usize = 64 * 1024;
+const DEFAULT_SEND_QUEUE_FRONT_POLICY: &str = "disconnect";
+const DEFAULT_SEND_QUEUE_BACK_POLICY: &str = "disconnect";
+const DEFAULT_IDLE_TIMEOUT_FRONT_MS: u64 = 300000;
+const DEFAULT_IDLE_TIMEOUT_UNAUTHENTICATED_MS: u64 = 30000;
+const DEFAULT_IDLE_TIMEOUT_CHECK_INTERVAL_MS: u64 = 1000;
 
 fn default_host() -> String {
     DEFAULT_HOST.to_string()
 }
 
+fn default_heartbeat_interval_ms() -> u64 {
+    DEFAULT_HEARTBEAT_INTERVAL_MS
+}
+
+fn default_heartbeat_miss_threshold() -> u32 {
+    DEFAULT_HEARTBEAT_MISS_THRESHOLD
+}
+
+fn default_reconnect_min_delay_ms() -> u64 {
+    DEFAULT_RECONNECT_MIN_DELAY_MS
+}
+
+fn default_reconnect_max_delay_ms() -> u64 {
+    DEFAULT_RECONNECT_MAX_DELAY_MS
+}
+
+fn default_handshake_timeout_ms() -> u64 {
+    DEFAULT_HANDSHAKE_TIMEOUT_MS
+}
+
+fn default_leave_timeout_ms() -> u64 {
+    DEFAULT_LEAVE_TIMEOUT_MS
+}
+
+fn default_schedule_timezone() -> String {
+    DEFAULT_SCHEDULE_TIMEZONE.to_string()
+}
+
+fn default_schedule_catch_up() -> String {
+    DEFAULT_SCHEDULE_CATCH_UP.to_string()
+}
+
+fn default_server_weight() -> u32 {
+    DEFAULT_SERVER_WEIGHT
+}
+
+fn default_router_strategy() -> String {
+    DEFAULT_ROUTER_STRATEGY.to_string()
+}
+
+fn default_directory_server_type() -> String {
+    DEFAULT_DIRECTORY_SERVER_TYPE.to_string()
+}
+
+fn default_login_timeout_ms() -> u64 {
+    DEFAULT_LOGIN_TIMEOUT_MS
+}
+
+fn default_login_verifier() -> String {
+    DEFAULT_LOGIN_VERIFIER.to_string()
+}
+
+fn default_login_leeway_secs() -> u64 {
+    DEFAULT_LOGIN_LEEWAY_SECS
+}
+
+fn default_login_max_attempts() -> u32 {
+    DEFAULT_LOGIN_MAX_ATTEMPTS
+}
+
+fn default_tls_handshake_timeout_ms() -> u64 {
+    DEFAULT_TLS_HANDSHAKE_TIMEOUT_MS
+}
+
+fn default_tls_reload_interval_ms() -> u64 {
+    DEFAULT_TLS_RELOAD_INTERVAL_MS
+}
+
+fn default_back_tls_identity_prefix() -> String {
+    DEFAULT_BACK_TLS_IDENTITY_PREFIX.to_string()
+}
+
+fn default_send_queue_capacity() -> usize {
+    DEFAULT_SEND_QUEUE_CAPACITY
+}
+
+fn default_send_queue_max_batch_bytes() -> usize {
+    DEFAULT_SEND_QUEUE_MAX_BATCH_BYTES
+}
+
+fn default_send_queue_front_policy() -> String {
+    DEFAULT_SEND_QUEUE_FRONT_POLICY.to_string()
+}
+
+fn default_send_queue_back_policy() -> String {
+    DEFAULT_SEND_QUEUE_BACK_POLICY.to_string()
+}
+
+fn default_idle_timeout_front_ms() -> u64 {
+    DEFAULT_IDLE_TIMEOUT_FRONT_MS
+}
+
+fn default_idle_timeout_unauthenticated_ms() -> u64 {
+    DEFAULT_IDLE_TIMEOUT_UNAUTHENTICATED_MS
+}
+
+fn default_idle_timeout_check_interval_ms() -> u64 {
+    DEFAULT_IDLE_TIMEOUT_CHECK_INTERVAL_MS
+}
+
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Config {
     pub run_time: RunTime,
@@ -17,6 +146,24 @@ pub struct Config {
     pub log: Log,
     pub author: Author,
     pub mongodb: Option<MongoDBConfig>,
+    #[serde(default)]
+    pub cluster: ClusterConfig,
+    #[serde(default)]
+    pub schedules: ScheduleConfig,
+    #[serde(default)]
+    pub routers: RouterConfig,
+    #[serde(default)]
+    pub directory: DirectoryConfig,
+    #[serde(default)]
+    pub login: LoginConfig,
+    /// 前端TLS配置，不配置时前端使用明文TCP和ws://
+    pub front_tls: Option<FrontTlsConfig>,
+    /// 后端双向TLS配置，不配置则后端使用明文TCP
+    pub back_tls: Option<BackTlsConfig>,
+    #[serde(default)]
+    pub send_queue: SendQueueConfig,
+    #[serde(default)]
+    pub idle_timeout: IdleTimeoutConfig,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -37,18 +184,42 @@ pub struct ServerGroup {
 pub struct ServerConfig {
     #[serde(rename = "@id")]
     pub id: u32,
+    /// 默认监听地址（IP字面量），0.0.0.0 监听所有IPv4地址，:: 同时监听IPv4和IPv6
     #[serde(default = "default_host", rename = "@host")]
     pub host: String,
+    /// 对客户端公布的前端地址
     #[serde(default = "default_host", rename = "@front_host")]
     pub front_host: String,
+    /// 在集群消息中公布的后端地址，其他节点用它连接本节点
     #[serde(default = "default_host", rename = "@back_host")]
     pub back_host: String,
+    /// 前端监听地址，未配置时使用host
+    #[serde(default, rename = "@front_bind_host")]
+    pub front_bind_host: Option<String>,
+    /// 后端监听地址，未配置时使用host
+    #[serde(default, rename = "@back_bind_host")]
+    pub back_bind_host: Option<String>,
     #[serde(rename = "@back_tcp_port")]
     pub back_tcp_port: u16,
     #[serde(rename = "@front_tcp_port")]
     pub front_tcp_port: Option<u16>,
     #[serde(rename = "@front_ws_port")]
     pub front_ws_port: Option<u16>,
+    /// 按权重路由时的节点权重
+    #[serde(default = "default_server_weight", rename = "@weight")]
+    pub weight: u32,
+}
+
+impl ServerConfig {
+    /// 前端监听地址
+    pub fn get_front_bind_host(&self) -> &str {
+        self.front_bind_host.as_deref().unwrap_or(&self.host)
+    }
+
+    /// 后端监听地址
+    pub fn get_back_bind_host(&self) -> &str {
+        self.back_bind_host.as_deref().unwrap_or(&self.host)
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -67,8 +238,300 @@ pub struct Log {
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Author {
+    /// 集群节点认证密钥，用于签名和校验
+    #[serde(rename = "@key")]
+    pub key: String,
+    /// 轮换期间同时有效的另一个密钥，只用于校验
+    #[serde(default, rename = "@secondary_key")]
+    pub secondary_key: Option<String>,
+}
+
+/// 集群节点间的心跳与重连配置
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClusterConfig {
+    /// 心跳发送间隔（毫秒）
+    #[serde(default = "default_heartbeat_interval_ms", rename = "@heartbeat_interval_ms")]
+    pub heartbeat_interval_ms: u64,
+    /// 连续多少个心跳间隔没有收到心跳即判定节点失联
+    #[serde(default = "default_heartbeat_miss_threshold", rename = "@heartbeat_miss_threshold")]
+    pub heartbeat_miss_threshold: u32,
+    /// 断线重连的初始等待时间（毫秒），之后每次失败翻倍
+    #[serde(default = "default_reconnect_min_delay_ms", rename = "@reconnect_min_delay_ms")]
+    pub reconnect_min_delay_ms: u64,
+    /// 断线重连的最大等待时间（毫秒）
+    #[serde(default = "default_reconnect_max_delay_ms", rename = "@reconnect_max_delay_ms")]
+    pub reconnect_max_delay_ms: u64,
+    /// 节点认证握手超时（毫秒），超时未完成握手的会话会被关闭
+    #[serde(default = "default_handshake_timeout_ms", rename = "@handshake_timeout_ms")]
+    pub handshake_timeout_ms: u64,
+    /// 退出时等待master确认离开集群的最长时间（毫秒）
+    #[serde(default = "default_leave_timeout_ms", rename = "@leave_timeout_ms")]
+    pub leave_timeout_ms: u64,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval_ms: DEFAULT_HEARTBEAT_INTERVAL_MS,
+            heartbeat_miss_threshold: DEFAULT_HEARTBEAT_MISS_THRESHOLD,
+            reconnect_min_delay_ms: DEFAULT_RECONNECT_MIN_DELAY_MS,
+            reconnect_max_delay_ms: DEFAULT_RECONNECT_MAX_DELAY_MS,
+            handshake_timeout_ms: DEFAULT_HANDSHAKE_TIMEOUT_MS,
+            leave_timeout_ms: DEFAULT_LEAVE_TIMEOUT_MS,
+        }
+    }
+}
+
+/// 定时任务配置
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScheduleConfig {
+    /// 任务使用的时区：UTC或+HH:MM
+    #[serde(default = "default_schedule_timezone", rename = "@timezone")]
+    pub timezone: String,
+    #[serde(default, rename = "job")]
+    pub jobs: Vec<ScheduleJobConfig>,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            timezone: DEFAULT_SCHEDULE_TIMEZONE.to_string(),
+            jobs: Vec::new(),
+        }
+    }
+}
+
+/// 单个定时任务配置，cron和at二选一
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScheduleJobConfig {
+    /// 任务名，业务代码通过任务名设置回调
+    #[serde(rename = "@name")]
+    pub name: String,
+    /// cron表达式：分 时 日 月 周
+    #[serde(rename = "@cron")]
+    pub cron: Option<String>,
+    /// 一次性任务的执行时间：YYYY-MM-DD HH:MM[:SS]
+    #[serde(rename = "@at")]
+    pub at: Option<String>,
+    /// 错过执行后的补偿策略：skip或fire_once
+    #[serde(default = "default_schedule_catch_up", rename = "@catch_up")]
+    pub catch_up: String,
+    /// 只在该服务器组加载，不填则所有服务器都加载
+    #[serde(rename = "@group")]
+    pub group: Option<String>,
+}
+
+/// 按服务器类型配置的负载均衡策略
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct RouterConfig {
+    #[serde(default)]
+    pub router: Vec<RouterStrategyConfig>,
+}
+
+/// 单个服务器类型的负载均衡策略
+#[derive(Deserialize, Debug, Clone)]
+pub struct RouterStrategyConfig {
+    /// 目标服务器类型
+    #[serde(rename = "@server_type")]
+    pub server_type: String,
+    /// 策略：random、round_robin、least_pending、weighted、consistent_hash、least_load
+    #[serde(default = "default_router_strategy", rename = "@strategy")]
+    pub strategy: String,
+}
+
+/// 在线用户目录配置
+#[derive(Deserialize, Debug, Clone)]
+pub struct DirectoryConfig {
+    /// 承载目录的服务器类型，有多台时按用户ID一致性哈希分片
+    #[serde(default = "default_directory_server_type", rename = "@server_type")]
+    pub server_type: String,
+    /// 同一用户再次登录时是否踢掉之前的会话（可以在其他前端服务器上）
+    #[serde(default = "default_true", rename = "@kick_duplicate")]
+    pub kick_duplicate: bool,
+}
+
+impl Default for DirectoryConfig {
+    fn default() -> Self {
+        Self {
+            server_type: DEFAULT_DIRECTORY_SERVER_TYPE.to_string(),
+            kick_duplicate: true,
+        }
+    }
+}
+
+/// 前端登录配置
+#[derive(Deserialize, Debug, Clone)]
+pub struct LoginConfig {
+    /// 连接建立后必须在多长时间内完成登录（毫秒），超时后关闭会话
+    #[serde(default = "default_login_timeout_ms", rename = "@timeout_ms")]
+    pub timeout_ms: u64,
+    /// 令牌校验器：hmac（HMAC-SHA256签名令牌）或test（测试模式，不校验）
+    #[serde(default = "default_login_verifier", rename = "@verifier")]
+    pub verifier: String,
+    /// 是否允许使用test校验器，未显式开启时配置test会拒绝启动
+    #[serde(default, rename = "@allow_test")]
+    pub allow_test: bool,
+    /// 每个会话最多允许失败的登录次数，达到后关闭会话（0表示不限制）
+    #[serde(default = "default_login_max_attempts", rename = "@max_attempts")]
+    pub max_attempts: u32,
+    /// HMAC签名密钥，与签发令牌的登录服务共享
+    #[serde(default, rename = "@secret")]
+    pub secret: String,
+    /// 要求的签发者（iss），不配置则不校验
+    #[serde(rename = "@issuer")]
+    pub issuer: Option<String>,
+    /// 要求的受众（aud），不配置则不校验
+    #[serde(rename = "@audience")]
+    pub audience: Option<String>,
+    /// 校验exp/nbf时允许的时钟偏差（秒）
+    #[serde(default = "default_login_leeway_secs", rename = "@leeway_secs")]
+    pub leeway_secs: u64,
+    /// 是否拒绝重复使用的令牌（要求令牌带jti）
+    #[serde(default = "default_true", rename = "@replay_check")]
+    pub replay_check: bool,
+}
+
+impl Default for LoginConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: DEFAULT_LOGIN_TIMEOUT_MS,
+            verifier: DEFAULT_LOGIN_VERIFIER.to_string(),
+            allow_test: false,
+            max_attempts: DEFAULT_LOGIN_MAX_ATTEMPTS,
+            secret: String::new(),
+            issuer: None,
+            audience: None,
+            leeway_secs: DEFAULT_LOGIN_LEEWAY_SECS,
+            replay_check: true,
+        }
+    }
+}
+
+/// 前端TLS配置
+#[derive(Deserialize, Debug, Clone)]
+pub struct FrontTlsConfig {
+    /// PEM格式的证书链路径，第一个为服务器证书
+    #[serde(rename = "@cert")]
+    pub cert: String,
+    /// PEM格式的私钥路径
+    #[serde(rename = "@key")]
+    pub key: String,
+    /// 前端TCP是否使用TLS
+    #[serde(default = "default_true", rename = "@tcp")]
+    pub tcp: bool,
+    /// 前端WebSocket是否使用TLS（wss://）
+    #[serde(default = "default_true", rename = "@ws")]
+    pub ws: bool,
+    /// 前端TCP的ALPN协议，逗号分隔，不配置则不协商
+    #[serde(rename = "@tcp_alpn")]
+    pub tcp_alpn: Option<String>,
+    /// 前端WebSocket的ALPN协议，逗号分隔，如 http/1.1
+    #[serde(rename = "@ws_alpn")]
+    pub ws_alpn: Option<String>,
+    /// TLS握手超时（毫秒）
+    #[serde(default = "default_tls_handshake_timeout_ms", rename = "@handshake_timeout_ms")]
+    pub handshake_timeout_ms: u64,
+    /// 检查证书文件变化的间隔（毫秒），0表示不自动重新加载
+    #[serde(default = "default_tls_reload_interval_ms", rename = "@reload_interval_ms")]
+    pub reload_interval_ms: u64,
+}
+
+/// 后端双向TLS（mTLS）配置
+///
+/// 所有节点使用同一个CA签发的证书，证书主题的CN为 `identity_prefix` + 服务器ID（如 node-3），
+/// 证书需要同时包含serverAuth和clientAuth用途
+#[derive(Deserialize, Debug, Clone)]
+pub struct BackTlsConfig {
+    /// PEM格式的证书链路径，{id} 会替换为当前服务器ID，如 certs/node-{id}.crt
+    #[serde(rename = "@cert")]
+    pub cert: String,
+    /// PEM格式的私钥路径，{id} 会替换为当前服务器ID
     #[serde(rename = "@key")]
     pub key: String,
+    /// 签发节点证书的CA证书（PEM）
+    #[serde(rename = "@ca")]
+    pub ca: String,
+    /// 证书CN中服务器ID的前缀
+    #[serde(default = "default_back_tls_identity_prefix", rename = "@identity_prefix")]
+    pub identity_prefix: String,
+    /// TLS握手超时（毫秒）
+    #[serde(default = "default_tls_handshake_timeout_ms", rename = "@handshake_timeout_ms")]
+    pub handshake_timeout_ms: u64,
+    /// 检查证书文件变化的间隔（毫秒），0表示不自动重新加载
+    #[serde(default = "default_tls_reload_interval_ms", rename = "@reload_interval_ms")]
+    pub reload_interval_ms: u64,
+}
+
+impl BackTlsConfig {
+    /// 当前服务器使用的证书路径
+    pub fn cert_path(&self, server_id: u32) -> String {
+        self.cert.replace("{id}", &server_id.to_string())
+    }
+
+    /// 当前服务器使用的私钥路径
+    pub fn key_path(&self, server_id: u32) -> String {
+        self.key.replace("{id}", &server_id.to_string())
+    }
+}
+
+/// 连接发送队列配置
+///
+/// 每个连接一个写任务，消息按发送顺序写出；队列满时按策略处理发送过慢的对端
+#[derive(Deserialize, Debug, Clone)]
+pub struct SendQueueConfig {
+    /// 每个连接队列最多容纳的消息数
+    #[serde(default = "default_send_queue_capacity", rename = "@capacity")]
+    pub capacity: usize,
+    /// 写任务一次合并写出的最大字节数
+    #[serde(default = "default_send_queue_max_batch_bytes", rename = "@max_batch_bytes")]
+    pub max_batch_bytes: usize,
+    /// 前端连接队列满时的策略：drop或disconnect
+    #[serde(default = "default_send_queue_front_policy", rename = "@front_policy")]
+    pub front_policy: String,
+    /// 后端连接队列满时的策略：drop或disconnect
+    #[serde(default = "default_send_queue_back_policy", rename = "@back_policy")]
+    pub back_policy: String,
+}
+
+impl Default for SendQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_SEND_QUEUE_CAPACITY,
+            max_batch_bytes: DEFAULT_SEND_QUEUE_MAX_BATCH_BYTES,
+            front_policy: DEFAULT_SEND_QUEUE_FRONT_POLICY.to_string(),
+            back_policy: DEFAULT_SEND_QUEUE_BACK_POLICY.to_string(),
+        }
+    }
+}
+
+/// 连接空闲超时配置，超时时间为0表示不检查
+///
+/// 最后一次收到或写出数据后超过限制的连接会被关闭，断开事件的原因为IdleTimeout
+#[derive(Deserialize, Debug, Clone)]
+pub struct IdleTimeoutConfig {
+    /// 已认证的前端会话最长空闲时间（毫秒）
+    #[serde(default = "default_idle_timeout_front_ms", rename = "@front_ms")]
+    pub front_ms: u64,
+    /// 未认证的前端会话最长空闲时间（毫秒），应小于front_ms
+    #[serde(default = "default_idle_timeout_unauthenticated_ms", rename = "@unauthenticated_ms")]
+    pub unauthenticated_ms: u64,
+    /// 已授权的后端会话最长空闲时间（毫秒），默认不检查，节点间由心跳检测
+    #[serde(default, rename = "@back_ms")]
+    pub back_ms: u64,
+    /// 检查间隔（毫秒）
+    #[serde(default = "default_idle_timeout_check_interval_ms", rename = "@check_interval_ms")]
+    pub check_interval_ms: u64,
+}
+
+impl Default for IdleTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            front_ms: DEFAULT_IDLE_TIMEOUT_FRONT_MS,
+            unauthenticated_ms: DEFAULT_IDLE_TIMEOUT_UNAUTHENTICATED_MS,
+            back_ms: 0,
+            check_interval_ms: DEFAULT_IDLE_TIMEOUT_CHECK_INTERVAL_MS,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
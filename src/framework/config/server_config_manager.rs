@@ -1,4 +1,5 @@
 use crate::framework::config::config::{Config, ServerConfig};
+use crate::framework::network::net_addr;
 use std::collections::HashMap;
 use tracing::{debug, error, info, warn};
 
@@ -212,14 +213,14 @@ impl ServerConfigManager {
 
             // Check back_tcp_port (always exists) - use back_host
             port_usage
-                .entry((server.back_host.clone(), server.back_tcp_port))
+                .entry((Self::host_key(&server.back_host), server.back_tcp_port))
                 .or_insert_with(Vec::new)
                 .push((server_id, "back_tcp_port"));
 
             // Check front_tcp_port if exists - use front_host
             if let Some(port) = server.front_tcp_port {
                 port_usage
-                    .entry((server.front_host.clone(), port))
+                    .entry((Self::host_key(&server.front_host), port))
                     .or_insert_with(Vec::new)
                     .push((server_id, "front_tcp_port"));
             }
@@ -227,7 +228,7 @@ impl ServerConfigManager {
             // Check front_ws_port if exists - use front_host
             if let Some(port) = server.front_ws_port {
                 port_usage
-                    .entry((server.front_host.clone(), port))
+                    .entry((Self::host_key(&server.front_host), port))
                     .or_insert_with(Vec::new)
                     .push((server_id, "front_ws_port"));
             }
@@ -249,6 +250,81 @@ impl ServerConfigManager {
         true
     }
 
+    /// Normalize a host for comparison, so that "::1" and "[::1]" are treated as the same address
+    fn host_key(host: &str) -> String {
+        match net_addr::parse_ip(host) {
+            Some(ip) => ip.to_string(),
+            None => host.to_ascii_lowercase(),
+        }
+    }
+
+    /// Check bind and advertise addresses of a server
+    ///
+    /// Bind hosts must be IP literals (IPv4, IPv6, or wildcard 0.0.0.0 / ::).
+    /// Advertise hosts may be IP literals or host names, but not wildcard addresses.
+    fn check_addresses(server: &ServerConfig) -> bool {
+        let has_front = server.front_tcp_port.is_some() || server.front_ws_port.is_some();
+
+        let mut bind_hosts = vec![("back bind host", server.get_back_bind_host())];
+        let mut advertise_hosts = vec![("back_host", server.back_host.as_str())];
+        if has_front {
+            bind_hosts.push(("front bind host", server.get_front_bind_host()));
+            advertise_hosts.push(("front_host", server.front_host.as_str()));
+        }
+
+        for (name, host) in &bind_hosts {
+            if net_addr::parse_ip(host).is_none() {
+                error!("Server {} has invalid {} '{}': must be an IP address", server.id, name, host);
+                return false;
+            }
+        }
+
+        for (name, host) in &advertise_hosts {
+            if !net_addr::is_valid_host(host) {
+                error!("Server {} has invalid {} '{}'", server.id, name, host);
+                return false;
+            }
+            if net_addr::is_unspecified_host(host) {
+                error!("Server {} {} '{}' is a wildcard address and cannot be advertised, set it to a reachable address", server.id, name, host);
+                return false;
+            }
+        }
+
+        // A listener bound to a specific address only accepts connections to that address
+        let back_bind_ip = net_addr::parse_ip(server.get_back_bind_host());
+        let back_ip = net_addr::parse_ip(&server.back_host);
+        if let (Some(bind_ip), Some(advertise_ip)) = (back_bind_ip, back_ip) {
+            if !bind_ip.is_unspecified() && bind_ip != advertise_ip {
+                warn!("Server {} advertises back address {} but listens on {}, peers may fail to connect",
+                      server.id, advertise_ip, bind_ip);
+            }
+        }
+
+        // Listeners of the same server must not bind the same port on overlapping addresses
+        let mut listeners = vec![("back_tcp_port", back_bind_ip, server.back_tcp_port)];
+        let front_bind_ip = net_addr::parse_ip(server.get_front_bind_host());
+        if let Some(port) = server.front_tcp_port {
+            listeners.push(("front_tcp_port", front_bind_ip, port));
+        }
+        if let Some(port) = server.front_ws_port {
+            listeners.push(("front_ws_port", front_bind_ip, port));
+        }
+        for (i, (name, ip, port)) in listeners.iter().enumerate() {
+            for (other_name, other_ip, other_port) in &listeners[i + 1..] {
+                let overlaps = match (ip, other_ip) {
+                    (Some(ip), Some(other_ip)) => ip == other_ip || ip.is_unspecified() || other_ip.is_unspecified(),
+                    _ => true,
+                };
+                if port == other_port && overlaps {
+                    error!("Server {} binds {} and {} to the same port {}", server.id, name, other_name, port);
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
     /// Validate all server configurations
     pub fn validate(&self) -> bool {
         if self.servers_by_id.is_empty() {
@@ -283,6 +359,13 @@ impl ServerConfigManager {
             }
         }
 
+        // Check bind and advertise addresses
+        for server_info in self.servers_by_id.values() {
+            if !Self::check_addresses(&server_info.server_config) {
+                return false;
+            }
+        }
+
         // Check for port conflicts
         if !self.check_port_conflicts() {
             return false;
@@ -307,4 +390,72 @@ impl Default for ServerConfigManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(host: &str, back_host: &str) -> ServerConfig {
+        ServerConfig {
+            id: 1,
+            host: host.to_string(),
+            front_host: "127.0.0.1".to_string(),
+            back_host: back_host.to_string(),
+            front_bind_host: None,
+            back_bind_host: None,
+            back_tcp_port: 9000,
+            front_tcp_port: None,
+            front_ws_port: None,
+            weight: 1,
+        }
+    }
+
+    #[test]
+    fn accepts_ipv6_bind_and_host_name_advertise() {
+        let mut config = server("::", "node-1.cluster.local");
+        config.front_host = "[2001:db8::1]".to_string();
+        config.front_tcp_port = Some(9001);
+        config.front_ws_port = Some(9002);
+        assert!(ServerConfigManager::check_addresses(&config));
+    }
+
+    #[test]
+    fn rejects_host_name_bind_host() {
+        let mut config = server("0.0.0.0", "10.0.0.1");
+        config.back_bind_host = Some("localhost".to_string());
+        assert!(!ServerConfigManager::check_addresses(&config));
+    }
+
+    #[test]
+    fn rejects_invalid_or_wildcard_advertise_host() {
+        assert!(!ServerConfigManager::check_addresses(&server("0.0.0.0", "bad_host")));
+        assert!(!ServerConfigManager::check_addresses(&server("0.0.0.0", "0.0.0.0")));
+
+        // 没有前端端口时不检查front_host
+        let mut config = server("0.0.0.0", "10.0.0.1");
+        config.front_host = "::".to_string();
+        assert!(ServerConfigManager::check_addresses(&config));
+        config.front_tcp_port = Some(9001);
+        assert!(!ServerConfigManager::check_addresses(&config));
+    }
+
+    #[test]
+    fn rejects_same_port_on_overlapping_bind_addresses() {
+        let mut config = server("::", "10.0.0.1");
+        config.front_tcp_port = Some(9000);
+        assert!(!ServerConfigManager::check_addresses(&config));
+
+        // 通配地址与具体地址重叠
+        config.front_bind_host = Some("10.0.0.1".to_string());
+        assert!(!ServerConfigManager::check_addresses(&config));
+
+        // 不同的具体地址可以使用同一端口
+        config.back_bind_host = Some("10.0.0.2".to_string());
+        assert!(ServerConfigManager::check_addresses(&config));
+
+        config.front_tcp_port = Some(9001);
+        config.front_ws_port = Some(9001);
+        assert!(!ServerConfigManager::check_addresses(&config));
+    }
+}
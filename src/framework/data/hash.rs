@@ -0,0 +1,26 @@
+/// 计算稳定的64位哈希值（FNV-1a + splitmix64混合）
+///
+/// 所有节点必须对同一个键得到同样的结果，因此不能使用带随机种子的标准库哈希
+pub fn stable_hash(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_hash_is_deterministic() {
+        assert_eq!(stable_hash(b"10001"), stable_hash(b"10001"));
+        assert_ne!(stable_hash(b"10001"), stable_hash(b"10002"));
+        assert_eq!(stable_hash(b""), stable_hash(&[]));
+    }
+}
@@ -52,6 +52,20 @@ impl<T: Ord> MinHeap<T> {
         self.data.clear();
     }
 
+    /// 删除第一个满足条件的元素
+    pub fn remove_first<F>(&mut self, predicate: F) -> Option<T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let index = self.data.iter().position(predicate)?;
+        let item = self.data.swap_remove(index);
+        if index < self.data.len() {
+            self.heapify_down(index);
+            self.heapify_up(index);
+        }
+        Some(item)
+    }
+
     fn heapify_up(&mut self, mut index: usize) {
         while index > 0 {
             let parent_index = (index - 1) / 2;
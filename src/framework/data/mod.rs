@@ -1,13 +1,16 @@
 //! Data structures and utilities module
 //! 
 //! This module provides various data structures and utilities for the framework,
-//! including dynamic buffers for efficient memory management and heap data structures.
+//! including dynamic buffers for efficient memory management, heap data structures
+//! and a stable hash function shared by all nodes.
 
 pub mod dynamic_buffer;
 pub mod min_heap;
 pub mod max_heap;
+pub mod hash;
 
 // Re-export the main types
 pub use dynamic_buffer::{DynamicBuffer, BufferStats};
 pub use min_heap::MinHeap;
-pub use max_heap::MaxHeap;
\ No newline at end of file
+pub use max_heap::MaxHeap;
+pub use hash::stable_hash;
\ No newline at end of file
@@ -9,6 +9,7 @@ pub mod master;
 pub mod rpc;
 pub mod msg;
 pub mod timer;
+pub mod schedule;
 pub mod author;
 pub mod task;
 pub mod db;
@@ -1,7 +1,8 @@
 use crate::framework::msg::MsgProcessor;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use tokio::io::AsyncRead;
 use tokio::io::AsyncWrite;
 use tracing::{info, error, debug, warn};
@@ -14,6 +15,8 @@ pub struct Connection {
     pub msg_processor: Option<Arc<dyn MsgProcessor>>,
     /// 连接创建时间
     pub created_time: Instant,
+    /// 最后活跃时间，读写任务收发数据时更新
+    pub activity: ActivityTracker,
 }
 
 impl Connection {
@@ -24,10 +27,41 @@ impl Connection {
             session_id,
             msg_processor: None,
             created_time: now,
+            activity: ActivityTracker::new(now),
         }
     }
 }
 
+/// 连接的最后活跃时间
+///
+/// 可以复制到读写任务中，任务收发数据时调用touch，主线程读取最后活跃时间
+#[derive(Debug, Clone)]
+pub struct ActivityTracker {
+    /// 计时起点
+    base: Instant,
+    /// 最后活跃时间距离起点的毫秒数
+    elapsed_ms: Arc<AtomicU64>,
+}
+
+impl ActivityTracker {
+    pub fn new(base: Instant) -> Self {
+        Self {
+            base,
+            elapsed_ms: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// 记录一次收发数据
+    pub fn touch(&self) {
+        self.elapsed_ms.store(self.base.elapsed().as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// 最后活跃时间
+    pub fn last_activity_time(&self) -> Instant {
+        self.base + Duration::from_millis(self.elapsed_ms.load(Ordering::Relaxed))
+    }
+}
+
 /// 连接状态枚举
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionState {
@@ -148,10 +182,9 @@ pub trait ConnectionTrait: Send + Sync {
         self.get_connection().created_time
     }
 
-    /// 获取最后活跃时间
+    /// 获取最后活跃时间（最后一次收到或写出数据的时间）
     fn get_last_activity_time(&self) -> std::time::Instant {
-        // 目前返回创建时间，未来可以扩展为真正的最后活跃时间
-        self.get_connection().created_time
+        self.get_connection().activity.last_activity_time()
     }
 
     /// 设置连接状态
@@ -188,13 +221,12 @@ pub trait ConnectionTrait: Send + Sync {
     /// 检查连接是否超时
     /// 
     /// # 参数
-    /// * `timeout_duration` - 超时时长（秒）
+    /// * `timeout_duration` - 允许的最长空闲时间
     /// 
     /// # 返回值
-    /// 超时返回true，未超时返回false
-    fn is_timeout(&self, timeout_duration: u64) -> bool {
-        let elapsed = self.get_last_activity_time().elapsed();
-        elapsed.as_secs() > timeout_duration
+    /// 超过timeout_duration没有收发数据返回true，未超时返回false
+    fn is_timeout(&self, timeout_duration: Duration) -> bool {
+        self.get_last_activity_time().elapsed() > timeout_duration
     }
 
     /// 处理接收到的原始数据
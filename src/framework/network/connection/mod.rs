@@ -1,7 +1,9 @@
 pub mod connection;
+pub mod send_queue;
 pub mod tcp_connection;
 pub mod websocket_connection;
 
 pub use connection::{Connection, ConnectionTrait, ConnectionState, ConnectionType};
+pub use send_queue::SendQueueOptions;
 pub use tcp_connection::TcpConnection;
 pub use websocket_connection::WebSocketConnection;
\ No newline at end of file
@@ -0,0 +1,270 @@
+use bytes::Bytes;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::task::JoinHandle;
+use tracing::warn;
+use crate::framework::config::config::SendQueueConfig;
+
+/// 发送队列满时的处理策略
+///
+/// 入队在主线程进行，任何策略都不会等待队列出现空位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendOverflowPolicy {
+    /// 丢弃新消息，连接保持
+    Drop,
+    /// 断开发送过慢的连接
+    Disconnect,
+}
+
+impl SendOverflowPolicy {
+    /// 从配置字符串解析策略
+    pub fn parse(policy: &str) -> Option<Self> {
+        match policy {
+            "drop" => Some(SendOverflowPolicy::Drop),
+            "disconnect" => Some(SendOverflowPolicy::Disconnect),
+            _ => None,
+        }
+    }
+
+    /// 策略名称，用于日志
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SendOverflowPolicy::Drop => "drop",
+            SendOverflowPolicy::Disconnect => "disconnect",
+        }
+    }
+}
+
+/// 消息入队失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendQueueError {
+    /// 写任务已结束（连接已关闭或写入失败）
+    Closed,
+    /// 队列已满，按drop策略丢弃了消息
+    Dropped,
+    /// 队列已满，按disconnect策略需要断开连接
+    Overflow,
+}
+
+/// 连接发送队列参数
+#[derive(Debug, Clone)]
+pub struct SendQueueOptions {
+    /// 队列最多容纳的消息数
+    pub capacity: usize,
+    /// 队列满时的处理策略
+    pub policy: SendOverflowPolicy,
+    /// 写任务一次合并写出的最大字节数
+    pub max_batch_bytes: usize,
+}
+
+impl Default for SendQueueOptions {
+    fn default() -> Self {
+        Self::from_config(&SendQueueConfig::default(), SendOverflowPolicy::Disconnect.as_str())
+            .expect("default send queue config is valid")
+    }
+}
+
+impl SendQueueOptions {
+    /// 根据配置创建，policy为front_policy或back_policy的值
+    pub fn from_config(config: &SendQueueConfig, policy: &str) -> Result<Self, String> {
+        let policy = SendOverflowPolicy::parse(policy)
+            .ok_or_else(|| format!("invalid send queue policy '{}', expected drop or disconnect", policy))?;
+        if config.capacity == 0 {
+            return Err("send queue capacity must be greater than 0".to_string());
+        }
+        Ok(Self {
+            capacity: config.capacity,
+            policy,
+            max_batch_bytes: config.max_batch_bytes.max(1),
+        })
+    }
+}
+
+/// 连接的有界发送队列
+///
+/// 每个连接一个写任务，按入队顺序写出消息；队列中的消息是已经序列化好的帧。
+/// 丢弃发送端后写任务会写完剩余的消息再关闭连接
+pub struct SendQueue {
+    session_id: u64,
+    sender: mpsc::Sender<Bytes>,
+    policy: SendOverflowPolicy,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl SendQueue {
+    /// 创建队列，返回的接收端交给写任务
+    pub fn new(session_id: u64, options: &SendQueueOptions) -> (Self, mpsc::Receiver<Bytes>) {
+        let (sender, receiver) = mpsc::channel(options.capacity);
+        let queue = Self {
+            session_id,
+            sender,
+            policy: options.policy,
+            writer: None,
+        };
+        (queue, receiver)
+    }
+
+    /// 保存写任务，断开连接时用于终止
+    pub fn set_writer(&mut self, writer: JoinHandle<()>) {
+        self.writer = Some(writer);
+    }
+
+    /// 队列中等待写出的消息数
+    pub fn depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// 队列容量
+    pub fn capacity(&self) -> usize {
+        self.sender.max_capacity()
+    }
+
+    /// 消息入队，队列满时按策略处理，不会等待
+    pub fn push(&self, frame: Bytes) -> Result<(), SendQueueError> {
+        match self.sender.try_send(frame) {
+            Ok(()) => return Ok(()),
+            Err(TrySendError::Closed(_)) => return Err(SendQueueError::Closed),
+            Err(TrySendError::Full(_)) => {}
+        }
+
+        match self.policy {
+            SendOverflowPolicy::Drop => {
+                warn!("Send queue of connection {} is full ({} messages), dropping message", self.session_id, self.capacity());
+                Err(SendQueueError::Dropped)
+            }
+            SendOverflowPolicy::Disconnect => {
+                warn!("Send queue of connection {} is full ({} messages), disconnecting slow consumer", self.session_id, self.capacity());
+                Err(SendQueueError::Overflow)
+            }
+        }
+    }
+
+    /// 立即终止写任务，丢弃未写出的消息
+    pub fn abort(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            writer.abort();
+        }
+    }
+}
+
+/// 等待下一批消息：至少一条，随后取出已在队列中的消息，直到达到max_batch_bytes
+///
+/// # 返回值
+/// 发送端已全部丢弃且队列为空时返回false
+pub async fn recv_batch(receiver: &mut mpsc::Receiver<Bytes>, batch: &mut Vec<Bytes>, max_batch_bytes: usize) -> bool {
+    let Some(frame) = receiver.recv().await else {
+        return false;
+    };
+    let mut batch_bytes = frame.len();
+    batch.push(frame);
+    while batch_bytes < max_batch_bytes {
+        match receiver.try_recv() {
+            Ok(frame) => {
+                batch_bytes += frame.len();
+                batch.push(frame);
+            }
+            Err(_) => break,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(capacity: usize, policy: SendOverflowPolicy) -> SendQueueOptions {
+        SendQueueOptions { capacity, policy, max_batch_bytes: 64 * 1024 }
+    }
+
+    fn frame(len: usize) -> Bytes {
+        Bytes::from(vec![0u8; len])
+    }
+
+    #[test]
+    fn block_policy_is_rejected() {
+        assert_eq!(SendOverflowPolicy::parse("drop"), Some(SendOverflowPolicy::Drop));
+        assert_eq!(SendOverflowPolicy::parse("disconnect"), Some(SendOverflowPolicy::Disconnect));
+        assert_eq!(SendOverflowPolicy::parse("block"), None);
+        assert!(SendQueueOptions::from_config(&SendQueueConfig::default(), "block").is_err());
+    }
+
+    #[test]
+    fn zero_capacity_is_rejected() {
+        let config = SendQueueConfig { capacity: 0, ..SendQueueConfig::default() };
+        assert!(SendQueueOptions::from_config(&config, "drop").is_err());
+    }
+
+    #[test]
+    fn drop_policy_keeps_queued_messages() {
+        let (queue, mut receiver) = SendQueue::new(1, &options(2, SendOverflowPolicy::Drop));
+        assert_eq!(queue.push(frame(1)), Ok(()));
+        assert_eq!(queue.push(frame(2)), Ok(()));
+        assert_eq!(queue.push(frame(3)), Err(SendQueueError::Dropped));
+        assert_eq!(queue.depth(), 2);
+
+        // 取出一条后又可以入队
+        assert_eq!(receiver.try_recv().unwrap().len(), 1);
+        assert_eq!(queue.push(frame(4)), Ok(()));
+        assert_eq!(receiver.try_recv().unwrap().len(), 2);
+        assert_eq!(receiver.try_recv().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn disconnect_policy_reports_overflow() {
+        let (queue, _receiver) = SendQueue::new(1, &options(1, SendOverflowPolicy::Disconnect));
+        assert_eq!(queue.push(frame(1)), Ok(()));
+        assert_eq!(queue.push(frame(1)), Err(SendQueueError::Overflow));
+        assert_eq!(queue.depth(), 1);
+    }
+
+    #[test]
+    fn push_after_writer_stopped_is_closed() {
+        let (queue, receiver) = SendQueue::new(1, &options(4, SendOverflowPolicy::Disconnect));
+        drop(receiver);
+        assert_eq!(queue.push(frame(1)), Err(SendQueueError::Closed));
+    }
+
+    #[tokio::test]
+    async fn recv_batch_merges_until_max_batch_bytes() {
+        let (queue, mut receiver) = SendQueue::new(1, &options(16, SendOverflowPolicy::Disconnect));
+        for len in [6, 6, 6, 2] {
+            queue.push(frame(len)).unwrap();
+        }
+
+        let mut batch = Vec::new();
+        assert!(recv_batch(&mut receiver, &mut batch, 10).await);
+        assert_eq!(batch.iter().map(Bytes::len).collect::<Vec<_>>(), vec![6, 6]);
+
+        batch.clear();
+        assert!(recv_batch(&mut receiver, &mut batch, 10).await);
+        assert_eq!(batch.iter().map(Bytes::len).collect::<Vec<_>>(), vec![6, 2]);
+    }
+
+    #[tokio::test]
+    async fn recv_batch_takes_oversized_frame_alone() {
+        let (queue, mut receiver) = SendQueue::new(1, &options(16, SendOverflowPolicy::Disconnect));
+        queue.push(frame(100)).unwrap();
+        queue.push(frame(1)).unwrap();
+
+        let mut batch = Vec::new();
+        assert!(recv_batch(&mut receiver, &mut batch, 10).await);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].len(), 100);
+    }
+
+    #[tokio::test]
+    async fn recv_batch_drains_remaining_frames_after_queue_dropped() {
+        let (queue, mut receiver) = SendQueue::new(1, &options(16, SendOverflowPolicy::Disconnect));
+        queue.push(frame(3)).unwrap();
+        queue.push(frame(4)).unwrap();
+        drop(queue);
+
+        let mut batch = Vec::new();
+        assert!(recv_batch(&mut receiver, &mut batch, 1024).await);
+        assert_eq!(batch.len(), 2);
+
+        batch.clear();
+        assert!(!recv_batch(&mut receiver, &mut batch, 1024).await);
+        assert!(batch.is_empty());
+    }
+}
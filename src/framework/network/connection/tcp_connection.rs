@@ -1,20 +1,31 @@
 use super::connection::{Connection, ConnectionTrait, ConnectionState, ConnectionType};
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::sync::Arc;
 use tokio::net::TcpStream;
+use tokio::io::{ReadHalf, WriteHalf};
+use tokio::task::JoinHandle;
+use bytes::BytesMut;
+use crate::framework::network::net_addr;
+use crate::framework::network::net_stream::NetStream;
+use crate::framework::network::tls::TlsClientConnector;
+use super::send_queue::{self, SendQueue, SendQueueError, SendQueueOptions};
 use tokio::sync::Mutex;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tracing::{info, error, debug, warn};
-use crate::framework::network::network_event_queue::{NetworkEventQueue, NetworkEventData, NetworkEventType, ServerType};
+use crate::framework::network::network_event_queue::{DisconnectReason, NetworkEventQueue, NetworkEventData, NetworkEventType, ServerType};
 
 /// TCP连接实现
 pub struct TcpConnection {
     /// 基础连接
     connection: Connection,
     /// TCP流读半部
-    tcp_read_half: Option<Arc<tokio::sync::Mutex<tokio::net::tcp::OwnedReadHalf>>>,
-    /// TCP流写半部
-    tcp_write_half: Option<Arc<tokio::sync::Mutex<tokio::net::tcp::OwnedWriteHalf>>>,
+    tcp_read_half: Option<Arc<tokio::sync::Mutex<ReadHalf<NetStream>>>>,
+    /// 发送队列，写半部由队列的写任务持有
+    send_queue: Option<SendQueue>,
+    /// 发送队列参数
+    send_queue_options: SendQueueOptions,
+    /// 读取任务
+    read_task: Option<JoinHandle<()>>,
     /// 远程地址
     remote_addr: SocketAddr,
     /// 连接状态
@@ -25,6 +36,8 @@ pub struct TcpConnection {
     notify: Arc<tokio::sync::Notify>,
     /// 服务器类型
     server_type: ServerType,
+    /// 主动连接时使用的TLS连接器，None表示明文连接
+    tls_connector: Option<TlsClientConnector>,
 }
 
 impl TcpConnection {
@@ -32,86 +45,188 @@ impl TcpConnection {
     /// 
     /// # 参数
     /// * `session_id` - 会话ID
-    /// * `tcp_stream` - TCP流
+    /// * `tcp_stream` - TCP流（明文或TLS）
     /// * `remote_addr` - 远程地址
     /// * `event_queue` - 事件队列
     /// * `notify` - 通知器
     /// * `server_type` - 服务器类型
+    /// * `send_queue_options` - 发送队列参数
     pub fn new(
         session_id: u64, 
-        tcp_stream: TcpStream, 
+        tcp_stream: NetStream, 
         remote_addr: SocketAddr,
         event_queue: NetworkEventQueue,
         notify: Arc<tokio::sync::Notify>,
         server_type: ServerType,
+        send_queue_options: SendQueueOptions,
     ) -> Self {
-        let (read_half, write_half) = tcp_stream.into_split();
-        Self {
+        let (read_half, write_half) = tokio::io::split(tcp_stream);
+        let mut tcp_connection = Self {
             connection: Connection::new(session_id),
             tcp_read_half: Some(Arc::new(Mutex::new(read_half))),
-            tcp_write_half: Some(Arc::new(Mutex::new(write_half))),
+            send_queue: None,
+            send_queue_options,
+            read_task: None,
             remote_addr,
             connection_state: ConnectionState::Connected,
             event_queue,
             notify,
             server_type,
-        }
+            tls_connector: None,
+        };
+        tcp_connection.send_queue = Some(tcp_connection.spawn_writer(write_half));
+        tcp_connection
     }
     
-    /// 创建用于客户端连接的TCP连接
+    /// 创建用于客户端连接的TCP连接，远程地址在连接成功后设置
     /// 
     /// # 参数
     /// * `session_id` - 会话ID
-    /// * `remote_addr` - 远程地址
     /// * `event_queue` - 事件队列
     /// * `notify` - 通知器
     /// * `server_type` - 服务器类型
+    /// * `send_queue_options` - 发送队列参数
     pub fn new_for_client(
         session_id: u64, 
-        remote_addr: SocketAddr,
         event_queue: NetworkEventQueue,
         notify: Arc<tokio::sync::Notify>,
         server_type: ServerType,
+        send_queue_options: SendQueueOptions,
     ) -> Self {
         Self {
             connection: Connection::new(session_id),
             tcp_read_half: None,
-            tcp_write_half: None,
-            remote_addr,
+            send_queue: None,
+            send_queue_options,
+            read_task: None,
+            remote_addr: SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
             connection_state: ConnectionState::Disconnected,
             event_queue,
             notify,
             server_type,
+            tls_connector: None,
         }
     }
     
+    /// 设置主动连接使用的TLS连接器，需要在connect_to之前调用
+    pub fn set_tls_connector(&mut self, connector: TlsClientConnector) {
+        self.tls_connector = Some(connector);
+    }
+
+    /// 启动写任务：按入队顺序写出消息，把已在队列中的小消息合并成一次write_all
+    ///
+    /// 发送队列被丢弃后写完剩余消息，再关闭写半部让对端收到EOF；写入失败时发送断开事件
+    fn spawn_writer(&self, mut write_half: WriteHalf<NetStream>) -> SendQueue {
+        let session_id = self.connection.session_id;
+        let (mut queue, mut receiver) = SendQueue::new(session_id, &self.send_queue_options);
+        let max_batch_bytes = self.send_queue_options.max_batch_bytes;
+        let activity = self.connection.activity.clone();
+        let remote_addr = self.remote_addr;
+        let event_queue = self.event_queue.clone();
+        let notify = Arc::clone(&self.notify);
+        let server_type = self.server_type;
+
+        let writer = tokio::spawn(async move {
+            let mut batch = Vec::new();
+            let mut buffer = BytesMut::new();
+            while send_queue::recv_batch(&mut receiver, &mut batch, max_batch_bytes).await {
+                let write_result = if batch.len() == 1 {
+                    write_half.write_all(&batch[0]).await
+                } else {
+                    for frame in &batch {
+                        buffer.extend_from_slice(frame);
+                    }
+                    write_half.write_all(&buffer).await
+                };
+                let write_result = match write_result {
+                    Ok(()) => write_half.flush().await,
+                    Err(e) => Err(e),
+                };
+                
+                match write_result {
+                    Ok(()) => {
+                        activity.touch();
+                        debug!("TcpConnection {} sent {} messages successfully", session_id, batch.len());
+                    }
+                    Err(e) => {
+                        // 读任务可能一直等不到数据，由写任务发送断开事件，会话管理器收到后移除会话
+                        error!("TcpConnection {} failed to send data: {}", session_id, e);
+                        let event = NetworkEventData::new_disconnect(
+                            server_type,
+                            session_id,
+                            Some(remote_addr),
+                            DisconnectReason::Error,
+                        );
+                        event_queue.push(event).await;
+                        notify.notify_one();
+                        return;
+                    }
+                }
+                batch.clear();
+                buffer.clear();
+            }
+            
+            match write_half.shutdown().await {
+                Ok(_) => {
+                    debug!("TCP connection {} write half shutdown successfully", session_id);
+                }
+                Err(e) => {
+                    debug!("Failed to shutdown write half for connection {}: {}", session_id, e);
+                }
+            }
+        });
+        queue.set_writer(writer);
+        queue
+    }
+
+    /// 发送队列中等待写出的消息数
+    pub fn get_send_queue_depth(&self) -> usize {
+        self.send_queue.as_ref().map_or(0, |queue| queue.depth())
+    }
+
+    /// 主动断开连接：终止读任务，并发送带原因的断开事件，会话管理器收到后移除会话
+    ///
+    /// 发送过慢时丢弃未写出的消息，其他原因写完队列中剩余的消息再关闭写半部
+    pub fn disconnect(&mut self, reason: DisconnectReason) {
+        if self.connection_state == ConnectionState::Disconnected {
+            return;
+        }
+        info!("TcpConnection {} disconnecting, reason: {}", self.connection.session_id, reason.as_str());
+        self.connection_state = ConnectionState::Disconnected;
+        if let Some(mut queue) = self.send_queue.take() {
+            if reason == DisconnectReason::SlowConsumer {
+                queue.abort();
+            }
+        }
+        if let Some(read_task) = self.read_task.take() {
+            read_task.abort();
+        }
+        self.tcp_read_half = None;
+
+        let event = NetworkEventData::new_disconnect(
+            self.server_type,
+            self.connection.session_id,
+            Some(self.remote_addr),
+            reason,
+        );
+        let event_queue = self.event_queue.clone();
+        let notify = Arc::clone(&self.notify);
+        tokio::spawn(async move {
+            event_queue.push(event).await;
+            notify.notify_one();
+        });
+    }
+
     /// 优雅关闭TCP连接
-    /// 这个方法会先关闭写半部，让read task自然结束
-    /// 同步方法，内部使用tokio::spawn处理异步操作
+    /// 丢弃发送队列，写任务写完剩余消息后关闭写半部，让read task自然结束
     pub fn shutdown(&mut self) {
         debug!("Shutting down TCP connection {}", self.connection.session_id);
         
         // 先将状态设为断开，防止新的send_message调用
         self.connection_state = ConnectionState::Disconnected;
         
-        // 关闭写半部，这会导致对端收到EOF
-        if let Some(write_half) = self.tcp_write_half.take() {
-            let session_id = self.connection.session_id;
-            
-            // 在后台异步任务中执行shutdown
-            tokio::spawn(async move {
-                // 使用lock而不是try_lock，等待正在进行的发送操作完成
-                match write_half.lock().await.shutdown().await {
-                    Ok(_) => {
-                        debug!("TCP connection {} write half shutdown successfully", session_id);
-                    }
-                    Err(e) => {
-                        debug!("Failed to shutdown write half for connection {}: {}", session_id, e);
-                    }
-                }
-                // write_half被drop，释放资源
-            });
-        }
+        // 丢弃发送端，写任务写完后关闭写半部，这会导致对端收到EOF
+        self.send_queue = None;
         
         // 清理read_half
         // read task会在收到EOF后自然结束
@@ -121,7 +236,7 @@ impl TcpConnection {
     }
 
     /// 启动读取任务
-    pub fn start_read_task(&self) {
+    pub fn start_read_task(&mut self) {
         if let Some(tcp_read_half) = &self.tcp_read_half {
             let session_id = self.connection.session_id;
             let tcp_read_half_clone = Arc::clone(tcp_read_half);
@@ -130,8 +245,9 @@ impl TcpConnection {
             let notify = Arc::clone(&self.notify);
             let server_type = self.server_type;
             let msg_processor = self.connection.msg_processor.clone();
+            let activity = self.connection.activity.clone();
             
-            tokio::spawn(async move {
+            self.read_task = Some(tokio::spawn(async move {
                 let mut temp_buffer = vec![0u8; 4096];
                 let mut receive_buffer = crate::framework::data::dynamic_buffer::DynamicBuffer::new(8192, 1024);
                 
@@ -146,11 +262,11 @@ impl TcpConnection {
                         Ok(0) => {
                             // 连接关闭
                             debug!("TCP connection {} closed by peer", session_id);
-                            let event = NetworkEventData::new(
-                                NetworkEventType::Disconnect,
+                            let event = NetworkEventData::new_disconnect(
                                 server_type,
                                 session_id,
                                 Some(remote_addr),
+                                DisconnectReason::Closed,
                             );
                             event_queue.push(event).await;
                             notify.notify_one();
@@ -158,15 +274,16 @@ impl TcpConnection {
                         }
                         Ok(n) => {
                             // 收到数据，写入receive_buffer
+                            activity.touch();
                             debug!("TCP connection {} received {} bytes", session_id, n);
                             receive_buffer.write_slice(&temp_buffer[..n]);
                             
                             // Process messages in the buffer
                             while receive_buffer.readable_bytes() >= 4 {
-                                // Peek at message header
+                                // Peek at message header (offset relative to read_index)
                                 if let (Some(msg_id), Some(msg_len)) = (
-                                    receive_buffer.peek_u16(receive_buffer.read_index()),
-                                    receive_buffer.peek_u16(receive_buffer.read_index() + 2)
+                                    receive_buffer.peek_u16(0),
+                                    receive_buffer.peek_u16(2)
                                 ) {
                                     let msg_len = msg_len as usize;
                                     
@@ -229,11 +346,11 @@ impl TcpConnection {
                         }
                         Err(e) => {
                             error!("TCP connection {} read error: {}", session_id, e);
-                            let event = NetworkEventData::new(
-                                NetworkEventType::Disconnect,
+                            let event = NetworkEventData::new_disconnect(
                                 server_type,
                                 session_id,
                                 Some(remote_addr),
+                                DisconnectReason::Error,
                             );
                             event_queue.push(event).await;
                             notify.notify_one();
@@ -241,21 +358,23 @@ impl TcpConnection {
                         }
                     }
                 }
-            });
+            }));
         } else {
             error!("Cannot start read task: TCP stream is None");
         }
     }
 
-    /// 连接到指定地址
+    /// 连接到指定地址，主机名在连接任务中解析
     /// 
     /// # 参数
-    /// * `addr` - 目标地址
+    /// * `host` - 目标主机名或IP字面量
+    /// * `port` - 目标端口
     /// * `event_queue` - 事件队列，用于发送连接成功事件
     /// * `notify` - 通知器，用于通知事件处理
     pub fn connect_to(
         &mut self, 
-        addr: SocketAddr,
+        host: String,
+        port: u16,
         event_queue: crate::framework::network::NetworkEventQueue,
         notify: Option<Arc<tokio::sync::Notify>>,
     ) {
@@ -267,22 +386,36 @@ impl TcpConnection {
         }
 
         self.connection_state = ConnectionState::Connecting;
-        self.remote_addr = addr;
-        debug!("TcpConnection {} connecting to {}", self.connection.session_id, addr);
+        let target = net_addr::format_addr(&host, port);
+        debug!("TcpConnection {} connecting to {}", self.connection.session_id, target);
 
         let session_id = self.connection.session_id;
+        let tls_connector = self.tls_connector.clone();
         
         tokio::spawn(async move {
-            match TcpStream::connect(addr).await {
+            // 主机名在连接任务中解析，不阻塞主循环
+            let addr = net_addr::lookup_addr(&host, port).await;
+            let result = match addr {
+                Ok(addr) => match TcpStream::connect(addr).await {
+                    Ok(stream) => match tls_connector {
+                        Some(connector) => connector.connect(stream).await,
+                        None => Ok(NetStream::from(stream)),
+                    },
+                    Err(e) => Err(e.to_string()),
+                },
+                Err(ref e) => Err(e.clone()),
+            };
+            let addr = addr.ok();
+            match result {
                 Ok(stream) => {
-                    debug!("TcpConnection {} successfully connected to {}", session_id, addr);
+                    debug!("TcpConnection {} successfully connected to {}", session_id, target);
                     
-                    // 发送连接成功事件
+                    // 发送连接成功事件，携带解析出的地址
                     let event = NetworkEventData::new_with_stream(
                         NetworkEventType::ClientConnectSuccess,
                         ServerType::BackTcp,
                         session_id,
-                        Some(addr),
+                        addr,
                         stream,
                     );
                     
@@ -294,18 +427,33 @@ impl TcpConnection {
                     }
                 }
                 Err(e) => {
-                    error!("TcpConnection {} failed to connect to {}: {}", session_id, addr, e);
+                    error!("TcpConnection {} failed to connect to {}: {}", session_id, target, e);
+                    
+                    // 发送连接失败事件，由上层决定是否重连
+                    let event = NetworkEventData::new(
+                        NetworkEventType::ClientConnectFailed,
+                        ServerType::BackTcp,
+                        session_id,
+                        addr,
+                    );
+                    
+                    event_queue.push(event).await;
+                    
+                    if let Some(notify) = notify {
+                        notify.notify_one();
+                    }
                 }
             }
         });
     }
     
     /// 设置TCP流（用于连接成功后设置流）
-    pub fn set_tcp_stream(&mut self, stream: TcpStream) {
+    pub fn set_tcp_stream(&mut self, stream: NetStream, remote_addr: SocketAddr) {
+        self.remote_addr = remote_addr;
         // local_addr can be obtained from stream when needed
-        let (read_half, write_half) = stream.into_split();
+        let (read_half, write_half) = tokio::io::split(stream);
         self.tcp_read_half = Some(Arc::new(Mutex::new(read_half)));
-        self.tcp_write_half = Some(Arc::new(Mutex::new(write_half)));
+        self.send_queue = Some(self.spawn_writer(write_half));
         self.on_connected();
         
         // 自动启动读取任务
@@ -317,6 +465,7 @@ impl TcpConnection {
     /// 连接建立时的回调
     fn on_connected(&mut self) {
         self.connection_state = ConnectionState::Connected;
+        self.connection.activity.touch();
         debug!("TcpConnection {} connected successfully", self.connection.session_id);
     }
 }
@@ -394,56 +543,33 @@ impl ConnectionTrait for TcpConnection {
             return false;
         }
         
-        // 发送消息
-        if let Some(ref write_half_arc) = self.tcp_write_half {
-            let session_id = self.connection.session_id;
-            
-            // 克隆Arc以便在异步任务中使用
-            let write_half_clone = write_half_arc.clone();
-            
-            // 使用tokio::spawn异步序列化和发送数据
-            tokio::spawn(async move {
-                
-                // 在异步任务中序列化消息
-                match message.serialize_to_buffer() {
-                    Ok(buffer) => {
-                        let msg_id = message.msg_id();
-                        let total_len = buffer.len();
-                        
-                        debug!("TcpConnection {} serialized protobuf message (id={}) {} bytes", 
-                               session_id, msg_id, total_len);
-                        
-                        // 发送数据
-                        let write_result = {
-                            let mut write_half = write_half_clone.lock().await;
-                            let write_result = write_half.write_all(&buffer).await;
-                            if write_result.is_ok() {
-                                write_half.flush().await
-                            } else {
-                                write_result
-                            }
-                        };
-                        
-                        match write_result {
-                            Ok(_) => {
-                                debug!("TcpConnection {} sent {} bytes successfully", 
-                                       session_id, total_len);
-                            }
-                            Err(e) => {
-                                error!("TcpConnection {} failed to send data: {}", session_id, e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("TcpConnection {}: Failed to serialize protobuf message: {}", session_id, e);
-                    }
-                }
-            });
-            
-            true
-        } else {
+        let Some(ref send_queue) = self.send_queue else {
             error!("TcpConnection {}: TCP stream is None", self.connection.session_id);
-            false
+            return false;
+        };
+        
+        // 在调用线程序列化，保证入队顺序即发送顺序
+        let buffer = match message.serialize_to_buffer() {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                error!("TcpConnection {}: Failed to serialize protobuf message: {}", self.connection.session_id, e);
+                return false;
+            }
+        };
+        debug!("TcpConnection {} queued protobuf message (id={}) {} bytes", 
+               self.connection.session_id, message.msg_id(), buffer.len());
+        
+        match send_queue.push(buffer.freeze()) {
+            Ok(()) => true,
+            Err(SendQueueError::Dropped) => false,
+            Err(SendQueueError::Closed) => {
+                debug!("TcpConnection {} writer has stopped, cannot send message", self.connection.session_id);
+                false
+            }
+            Err(SendQueueError::Overflow) => {
+                self.disconnect(DisconnectReason::SlowConsumer);
+                false
+            }
         }
     }
 
@@ -455,9 +581,9 @@ impl ConnectionTrait for TcpConnection {
 
         // 缓冲区已移除，无需清空
         
-        // 关闭TCP流
+        // 关闭TCP流，写任务写完剩余消息后关闭写半部
         self.tcp_read_half = None;
-        self.tcp_write_half = None;
+        self.send_queue = None;
         self.connection_state = ConnectionState::Disconnected;
     }
 
@@ -468,4 +594,46 @@ impl ConnectionTrait for TcpConnection {
             self.connection_state = state;
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::messages::protobuf::message::cluster::ServerConfig;
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::sync::Notify;
+
+    #[tokio::test]
+    async fn writer_reports_disconnect_on_write_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let (peer, _) = listener.accept().await.unwrap();
+        drop(peer);
+
+        let event_queue = NetworkEventQueue::new();
+        let mut connection = TcpConnection::new(
+            1,
+            NetStream::from(stream),
+            addr,
+            event_queue.clone(),
+            Arc::new(Notify::new()),
+            ServerType::FrontTcp,
+            SendQueueOptions::default(),
+        );
+
+        // 读任务没有启动，只有写任务能发现对端已关闭
+        let event = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                connection.send_message(ServerConfig::default());
+                if let Some(event) = event_queue.pop().await {
+                    return event;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("writer should report the write error");
+        assert_eq!(event.event_type, NetworkEventType::Disconnect);
+        assert_eq!(event.session_id, 1);
+        assert_eq!(event.disconnect_reason, Some(DisconnectReason::Error));
+    }
+}
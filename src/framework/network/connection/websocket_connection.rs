@@ -3,28 +3,31 @@ use crate::framework::msg::MsgProcessor;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpStream;
+use crate::framework::network::net_stream::NetStream;
 use tokio_tungstenite::{WebSocketStream, tungstenite::Message, MaybeTlsStream};
 use futures_util::{SinkExt, StreamExt, stream::SplitSink, stream::SplitStream};
 use tracing::{info, error, debug};
 use tokio::sync::Mutex;
-use crate::framework::network::network_event_queue::{NetworkEventQueue, NetworkEventData, NetworkEventType, ServerType};
+use tokio::task::JoinHandle;
+use super::send_queue::{self, SendQueue, SendQueueError, SendQueueOptions};
+use crate::framework::network::network_event_queue::{DisconnectReason, NetworkEventQueue, NetworkEventData, NetworkEventType, ServerType};
 
 /// WebSocket连接实现
-/// 支持服务器端（TcpStream）和客户端（MaybeTlsStream）连接
+/// 支持服务器端（NetStream，明文或TLS）和客户端（MaybeTlsStream）连接
 pub enum WebSocketStream_ {
-    Server(WebSocketStream<TcpStream>),
+    Server(WebSocketStream<NetStream>),
     Client(WebSocketStream<MaybeTlsStream<TcpStream>>),
 }
 
 /// WebSocket发送半部（Sink）
 pub enum WebSocketSink_ {
-    Server(SplitSink<WebSocketStream<TcpStream>, Message>),
+    Server(SplitSink<WebSocketStream<NetStream>, Message>),
     Client(SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>),
 }
 
 /// WebSocket接收半部（Stream）
 pub enum WebSocketStream_Split {
-    Server(SplitStream<WebSocketStream<TcpStream>>),
+    Server(SplitStream<WebSocketStream<NetStream>>),
     Client(SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>),
 }
 
@@ -35,6 +38,10 @@ pub struct WebSocketConnection {
     websocket_sink: Option<Arc<Mutex<WebSocketSink_>>>,
     /// WebSocket接收半部
     websocket_stream: Option<Arc<Mutex<WebSocketStream_Split>>>,
+    /// 发送队列，由写任务按顺序写出
+    send_queue: Option<SendQueue>,
+    /// 读取任务
+    read_task: Option<JoinHandle<()>>,
     /// 远程地址
     remote_addr: Option<SocketAddr>,
     /// 本地地址
@@ -58,6 +65,22 @@ impl WebSocketSink_ {
         }
     }
 
+    /// 写入消息但不立即刷新（处理不同的Sink类型）
+    pub async fn feed(&mut self, msg: Message) -> Result<(), tokio_tungstenite::tungstenite::Error> {
+        match self {
+            WebSocketSink_::Server(sink) => sink.feed(msg).await,
+            WebSocketSink_::Client(sink) => sink.feed(msg).await,
+        }
+    }
+
+    /// 刷新已写入的消息（处理不同的Sink类型）
+    pub async fn flush(&mut self) -> Result<(), tokio_tungstenite::tungstenite::Error> {
+        match self {
+            WebSocketSink_::Server(sink) => sink.flush().await,
+            WebSocketSink_::Client(sink) => sink.flush().await,
+        }
+    }
+
     /// 关闭连接（处理不同的Sink类型）
     pub async fn close(&mut self) -> Result<(), tokio_tungstenite::tungstenite::Error> {
         match self {
@@ -127,20 +150,24 @@ impl WebSocketConnection {
     /// * `event_queue` - 事件队列
     /// * `notify` - 通知器
     /// * `server_type` - 服务器类型
+    /// * `send_queue_options` - 发送队列参数
     pub fn new(
         session_id: u64, 
-        websocket_stream: WebSocketStream<TcpStream>, 
+        websocket_stream: WebSocketStream<NetStream>, 
         remote_addr: SocketAddr,
         event_queue: NetworkEventQueue,
         notify: Arc<tokio::sync::Notify>,
         server_type: ServerType,
+        send_queue_options: SendQueueOptions,
     ) -> Self {
         let ws_stream = WebSocketStream_::Server(websocket_stream);
         let (sink, stream) = ws_stream.split();
-        
-        Self {
+        let sink = Arc::new(Mutex::new(sink));
+        let mut websocket_connection = Self {
             connection: Connection::new(session_id),
-            websocket_sink: Some(Arc::new(Mutex::new(sink))),
+            send_queue: None,
+            read_task: None,
+            websocket_sink: Some(Arc::clone(&sink)),
             websocket_stream: Some(Arc::new(Mutex::new(stream))),
             remote_addr: Some(remote_addr),
             local_addr: None,
@@ -148,11 +175,107 @@ impl WebSocketConnection {
             event_queue,
             notify,
             server_type,
+        };
+        websocket_connection.send_queue = Some(websocket_connection.spawn_writer(sink, &send_queue_options));
+        websocket_connection
+    }
+
+    /// 启动写任务：每条消息仍是一个WebSocket二进制帧，已在队列中的消息合并为一次刷新
+    ///
+    /// 发送队列被丢弃后写完剩余消息，再发送关闭帧；写入失败时发送断开事件
+    fn spawn_writer(&self, sink: Arc<Mutex<WebSocketSink_>>, options: &SendQueueOptions) -> SendQueue {
+        let session_id = self.connection.session_id;
+        let (mut queue, mut receiver) = SendQueue::new(session_id, options);
+        let max_batch_bytes = options.max_batch_bytes;
+        let activity = self.connection.activity.clone();
+        let remote_addr = self.remote_addr;
+        let event_queue = self.event_queue.clone();
+        let notify = Arc::clone(&self.notify);
+        let server_type = self.server_type;
+
+        let writer = tokio::spawn(async move {
+            let mut batch = Vec::new();
+            while send_queue::recv_batch(&mut receiver, &mut batch, max_batch_bytes).await {
+                let write_result = {
+                    let mut sink = sink.lock().await;
+                    let mut write_result = Ok(());
+                    for frame in batch.drain(..) {
+                        write_result = sink.feed(Message::Binary(frame)).await;
+                        if write_result.is_err() {
+                            break;
+                        }
+                    }
+                    match write_result {
+                        Ok(()) => sink.flush().await,
+                        Err(e) => Err(e),
+                    }
+                };
+                
+                if let Err(e) = write_result {
+                    // 读任务可能一直等不到数据，由写任务发送断开事件，会话管理器收到后移除会话
+                    error!("WebSocketConnection {} failed to send data: {}", session_id, e);
+                    let event = NetworkEventData::new_disconnect(
+                        server_type,
+                        session_id,
+                        remote_addr,
+                        DisconnectReason::Error,
+                    );
+                    event_queue.push(event).await;
+                    notify.notify_one();
+                    return;
+                }
+                activity.touch();
+            }
+            
+            if let Err(e) = sink.lock().await.close().await {
+                debug!("WebSocketConnection {} failed to close: {}", session_id, e);
+            }
+        });
+        queue.set_writer(writer);
+        queue
+    }
+
+    /// 发送队列中等待写出的消息数
+    pub fn get_send_queue_depth(&self) -> usize {
+        self.send_queue.as_ref().map_or(0, |queue| queue.depth())
+    }
+
+    /// 主动断开连接：终止读任务，并发送带原因的断开事件，会话管理器收到后移除会话
+    ///
+    /// 发送过慢时丢弃未写出的消息，其他原因写完队列中剩余的消息再发送关闭帧
+    pub fn disconnect(&mut self, reason: DisconnectReason) {
+        if self.connection_state == ConnectionState::Disconnected {
+            return;
         }
+        info!("WebSocketConnection {} disconnecting, reason: {}", self.connection.session_id, reason.as_str());
+        self.connection_state = ConnectionState::Disconnected;
+        if let Some(mut queue) = self.send_queue.take() {
+            if reason == DisconnectReason::SlowConsumer {
+                queue.abort();
+            }
+        }
+        if let Some(read_task) = self.read_task.take() {
+            read_task.abort();
+        }
+        self.websocket_sink = None;
+        self.websocket_stream = None;
+
+        let event = NetworkEventData::new_disconnect(
+            self.server_type,
+            self.connection.session_id,
+            self.remote_addr,
+            reason,
+        );
+        let event_queue = self.event_queue.clone();
+        let notify = Arc::clone(&self.notify);
+        tokio::spawn(async move {
+            event_queue.push(event).await;
+            notify.notify_one();
+        });
     }
 
     /// 启动读取任务
-    pub fn start_read_task(&self) {
+    pub fn start_read_task(&mut self) {
         if let Some(websocket_stream) = &self.websocket_stream {
             if let Some(websocket_sink) = &self.websocket_sink {
                 let session_id = self.connection.session_id;
@@ -163,8 +286,9 @@ impl WebSocketConnection {
                 let notify = Arc::clone(&self.notify);
                 let server_type = self.server_type;
                 let msg_processor = self.connection.msg_processor.clone();
+                let activity = self.connection.activity.clone();
             
-            tokio::spawn(async move {
+            self.read_task = Some(tokio::spawn(async move {
                 let mut receive_buffer = crate::framework::data::dynamic_buffer::DynamicBuffer::new(8192, 1024);
                 
                 loop {
@@ -176,6 +300,7 @@ impl WebSocketConnection {
                     
                     match message_result {
                         Some(Ok(message)) => {
+                            activity.touch();
                             match message {
                                 Message::Binary(data) => {
                                     debug!("WebSocketConnection {} received binary message: {} bytes", 
@@ -188,8 +313,8 @@ impl WebSocketConnection {
                                     while receive_buffer.readable_bytes() >= 4 {
                                         // 查看消息头
                                         if let (Some(msg_id), Some(msg_len)) = (
-                                            receive_buffer.peek_u16(receive_buffer.read_index()),
-                                            receive_buffer.peek_u16(receive_buffer.read_index() + 2)
+                                            receive_buffer.peek_u16(0),
+                                            receive_buffer.peek_u16(2)
                                         ) {
                                             let msg_len = msg_len as usize;
                                             
@@ -273,11 +398,11 @@ impl WebSocketConnection {
                                     debug!("WebSocketConnection {} received close frame: {:?}", 
                                           session_id, close_frame);
                                     // 发送断开连接事件
-                                    let event = NetworkEventData::new(
-                                        NetworkEventType::Disconnect,
+                                    let event = NetworkEventData::new_disconnect(
                                         server_type,
                                         session_id,
                                         remote_addr,
+                                        DisconnectReason::Closed,
                                     );
                                     event_queue.push(event).await;
                                     notify.notify_one();
@@ -291,11 +416,11 @@ impl WebSocketConnection {
                         Some(Err(e)) => {
                             error!("WebSocketConnection {} error: {}", session_id, e);
                             // 发送断开连接事件
-                            let event = NetworkEventData::new(
-                                NetworkEventType::Disconnect,
+                            let event = NetworkEventData::new_disconnect(
                                 server_type,
                                 session_id,
                                 remote_addr,
+                                DisconnectReason::Error,
                             );
                             event_queue.push(event).await;
                             notify.notify_one();
@@ -304,11 +429,11 @@ impl WebSocketConnection {
                         None => {
                             debug!("WebSocketConnection {} stream ended", session_id);
                             // 发送断开连接事件
-                            let event = NetworkEventData::new(
-                                NetworkEventType::Disconnect,
+                            let event = NetworkEventData::new_disconnect(
                                 server_type,
                                 session_id,
                                 remote_addr,
+                                DisconnectReason::Closed,
                             );
                             event_queue.push(event).await;
                             notify.notify_one();
@@ -316,7 +441,7 @@ impl WebSocketConnection {
                         }
                     }
                 }
-            });
+            }));
             } else {
                 error!("Cannot start read task: WebSocket sink is None");
             }
@@ -393,49 +518,33 @@ impl ConnectionTrait for WebSocketConnection {
     where
         T: crate::proto::messages::MessageIdSerialize + Clone + Send + 'static,
     {
-        // 发送消息
-        if let Some(ref ws_sink_arc) = self.websocket_sink {
-            let session_id = self.connection.session_id;
-            
-            // 克隆Arc以便在异步任务中使用
-            let ws_sink_clone = ws_sink_arc.clone();
-            
-            // 使用tokio::spawn异步序列化和发送数据
-            tokio::spawn(async move {
-                
-                // 在异步任务中序列化消息
-                match message.serialize_to_buffer() {
-                    Ok(buffer) => {
-                        let msg_id = message.msg_id();
-                        let total_len = buffer.len();
-                        
-                        debug!("WebSocketConnection {} serialized protobuf message (id={}) {} bytes", 
-                               session_id, msg_id, total_len);
-                        
-                        // 发送数据
-                        let mut sink = ws_sink_clone.lock().await;
-                        let ws_message = Message::Binary(buffer.to_vec().into());
-                        
-                        match sink.send(ws_message).await {
-                            Ok(_) => {
-                                debug!("WebSocketConnection {} sent {} bytes successfully", 
-                                       session_id, total_len);
-                            }
-                            Err(e) => {
-                                error!("WebSocketConnection {} failed to send data: {}", session_id, e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("WebSocketConnection {}: Failed to serialize protobuf message: {}", session_id, e);
-                    }
-                }
-            });
-            
-            true
-        } else {
+        let Some(ref send_queue) = self.send_queue else {
             error!("WebSocketConnection {}: WebSocket stream is None", self.connection.session_id);
-            false
+            return false;
+        };
+        
+        // 在调用线程序列化，保证入队顺序即发送顺序
+        let buffer = match message.serialize_to_buffer() {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                error!("WebSocketConnection {}: Failed to serialize protobuf message: {}", self.connection.session_id, e);
+                return false;
+            }
+        };
+        debug!("WebSocketConnection {} queued protobuf message (id={}) {} bytes", 
+               self.connection.session_id, message.msg_id(), buffer.len());
+        
+        match send_queue.push(buffer.freeze()) {
+            Ok(()) => true,
+            Err(SendQueueError::Dropped) => false,
+            Err(SendQueueError::Closed) => {
+                debug!("WebSocketConnection {} writer has stopped, cannot send message", self.connection.session_id);
+                false
+            }
+            Err(SendQueueError::Overflow) => {
+                self.disconnect(DisconnectReason::SlowConsumer);
+                false
+            }
         }
     }
 
@@ -447,7 +556,8 @@ impl ConnectionTrait for WebSocketConnection {
 
         // 缓冲区已移除，无需清空
         
-        // 关闭WebSocket流
+        // 关闭WebSocket流，写任务写完剩余消息后发送关闭帧
+        self.send_queue = None;
         self.websocket_sink = None;
         self.websocket_stream = None;
         self.connection_state = ConnectionState::Disconnected;
@@ -4,12 +4,16 @@ pub mod network_engine;
 pub mod network_event_queue;
 pub mod network_engine_event_manager;
 pub mod connection;
+pub mod net_addr;
+pub mod net_stream;
+pub mod tls;
 
 pub use tcp_server::TcpServer;
 pub use websocket_server::WebSocketServer;
 pub use network_engine::NetworkEngine;
+pub use net_stream::NetStream;
 pub use network_event_queue::{
-    NetworkEventData, NetworkEventType, NetworkEventQueue, ServerType
+    NetworkEventData, NetworkEventType, NetworkEventQueue
 };
 pub use network_engine_event_manager::{NetworkEngineEventManager, NetworkEventHandler};
 pub use connection::{Connection, ConnectionTrait, ConnectionState, ConnectionType, TcpConnection, WebSocketConnection};
\ No newline at end of file
@@ -0,0 +1,162 @@
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::TcpListener;
+
+/// 监听队列长度
+const LISTEN_BACKLOG: i32 = 1024;
+
+/// 解析IP字面量，IPv6地址可以带方括号（如 [::1]）
+pub fn parse_ip(host: &str) -> Option<IpAddr> {
+    let host = host.trim();
+    let host = host.strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    host.parse::<IpAddr>().ok()
+}
+
+/// 是否为合法的主机名或IP字面量
+pub fn is_valid_host(host: &str) -> bool {
+    if parse_ip(host).is_some() {
+        return true;
+    }
+    if host.is_empty() || host.len() > 253 {
+        return false;
+    }
+    host.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// 是否为通配地址（0.0.0.0 或 ::），只能用于监听，不能对外公布
+pub fn is_unspecified_host(host: &str) -> bool {
+    parse_ip(host).is_some_and(|ip| ip.is_unspecified())
+}
+
+/// 格式化 host:port，IPv6地址加方括号
+pub fn format_addr(host: &str, port: u16) -> String {
+    match parse_ip(host) {
+        Some(ip) => SocketAddr::new(ip, port).to_string(),
+        None => format!("{}:{}", host, port),
+    }
+}
+
+/// 监听地址，要求host为IP字面量
+pub fn bind_addr(host: &str, port: u16) -> Result<SocketAddr, String> {
+    parse_ip(host)
+        .map(|ip| SocketAddr::new(ip, port))
+        .ok_or_else(|| format!("bind host '{}' is not an IP address", host))
+}
+
+/// 协议中u32表示的端口，超出u16范围或为0时返回None
+pub fn port_from_u32(port: u32) -> Option<u16> {
+    u16::try_from(port).ok().filter(|port| *port != 0)
+}
+
+/// 连接地址，host可以是IP字面量或主机名
+///
+/// 主机名由tokio在阻塞线程池中解析，取第一个结果，不阻塞调用者所在的线程
+pub async fn lookup_addr(host: &str, port: u16) -> Result<SocketAddr, String> {
+    if let Some(ip) = parse_ip(host) {
+        return Ok(SocketAddr::new(ip, port));
+    }
+    tokio::net::lookup_host((host, port)).await
+        .map_err(|e| format!("failed to resolve '{}': {}", host, e))?
+        .next()
+        .ok_or_else(|| format!("'{}' resolved to no address", host))
+}
+
+/// 创建并绑定TCP监听套接字
+///
+/// 绑定IPv6通配地址（::）时关闭IPV6_V6ONLY，同时接受IPv4和IPv6连接。
+/// 在同步代码中绑定，端口冲突等错误直接返回给调用者
+pub fn bind_std_listener(addr: SocketAddr) -> io::Result<std::net::TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    if let SocketAddr::V6(v6) = addr {
+        socket.set_only_v6(!v6.ip().is_unspecified())?;
+    }
+    // Windows上SO_REUSEADDR允许抢占已占用的端口，只在其他平台开启
+    #[cfg(not(windows))]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(LISTEN_BACKLOG)?;
+    Ok(socket.into())
+}
+
+/// 将已绑定的监听套接字注册到tokio，需要在tokio运行时中调用
+pub fn into_tokio_listener(listener: std::net::TcpListener) -> io::Result<TcpListener> {
+    TcpListener::from_std(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ip_accepts_bracketed_ipv6() {
+        assert_eq!(parse_ip("[::1]"), Some("::1".parse().unwrap()));
+        assert_eq!(parse_ip(" 10.0.0.1 "), Some("10.0.0.1".parse().unwrap()));
+        assert_eq!(parse_ip("[10.0.0.1"), None);
+        assert_eq!(parse_ip("node-1"), None);
+    }
+
+    #[test]
+    fn valid_host_names() {
+        assert!(is_valid_host("node-1.cluster.local"));
+        assert!(is_valid_host("::"));
+        assert!(is_valid_host("[2001:db8::1]"));
+        assert!(!is_valid_host(""));
+        assert!(!is_valid_host("bad_host"));
+        assert!(!is_valid_host("-node"));
+        assert!(!is_valid_host("node..local"));
+        assert!(!is_valid_host(&"a".repeat(64)));
+    }
+
+    #[test]
+    fn unspecified_hosts_are_wildcards() {
+        assert!(is_unspecified_host("0.0.0.0"));
+        assert!(is_unspecified_host("[::]"));
+        assert!(!is_unspecified_host("127.0.0.1"));
+        assert!(!is_unspecified_host("node-1"));
+    }
+
+    #[test]
+    fn format_addr_brackets_ipv6() {
+        assert_eq!(format_addr("::1", 9000), "[::1]:9000");
+        assert_eq!(format_addr("[::1]", 9000), "[::1]:9000");
+        assert_eq!(format_addr("10.0.0.1", 9000), "10.0.0.1:9000");
+        assert_eq!(format_addr("node-1", 9000), "node-1:9000");
+    }
+
+    #[test]
+    fn bind_addr_requires_ip_literal() {
+        assert_eq!(bind_addr("::", 9000).unwrap(), "[::]:9000".parse().unwrap());
+        assert!(bind_addr("localhost", 9000).is_err());
+    }
+
+    #[test]
+    fn port_from_u32_rejects_out_of_range() {
+        assert_eq!(port_from_u32(9000), Some(9000));
+        assert_eq!(port_from_u32(65535), Some(65535));
+        assert_eq!(port_from_u32(0), None);
+        assert_eq!(port_from_u32(65536), None);
+        assert_eq!(port_from_u32(75535), None);
+    }
+
+    #[tokio::test]
+    async fn lookup_addr_skips_resolver_for_ip_literals() {
+        assert_eq!(lookup_addr("10.0.0.1", 9000).await.unwrap(), "10.0.0.1:9000".parse().unwrap());
+        assert_eq!(lookup_addr("[::1]", 9000).await.unwrap(), "[::1]:9000".parse().unwrap());
+    }
+
+    #[test]
+    fn bind_std_listener_binds_ephemeral_port() {
+        let listener = bind_std_listener("127.0.0.1:0".parse().unwrap()).unwrap();
+        assert_ne!(listener.local_addr().unwrap().port(), 0);
+    }
+}
@@ -0,0 +1,83 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsStream;
+use tokio_rustls::rustls::pki_types::CertificateDer;
+
+/// 连接使用的字节流：明文TCP或TLS
+///
+/// 监听器在握手完成后才产生连接事件，会话和连接不区分是否加密
+#[derive(Debug)]
+pub enum NetStream {
+    Tcp(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl NetStream {
+    /// 是否为TLS连接
+    pub fn is_tls(&self) -> bool {
+        matches!(self, NetStream::Tls(_))
+    }
+
+    /// 底层TCP流
+    pub fn tcp_stream(&self) -> &TcpStream {
+        match self {
+            NetStream::Tcp(stream) => stream,
+            NetStream::Tls(stream) => stream.get_ref().0,
+        }
+    }
+
+    /// TLS对端出示的证书链，明文连接或对端未出示证书时返回None
+    pub fn peer_certificates(&self) -> Option<&[CertificateDer<'static>]> {
+        match self {
+            NetStream::Tcp(_) => None,
+            NetStream::Tls(stream) => stream.get_ref().1.peer_certificates(),
+        }
+    }
+}
+
+impl From<TcpStream> for NetStream {
+    fn from(stream: TcpStream) -> Self {
+        NetStream::Tcp(stream)
+    }
+}
+
+impl From<TlsStream<TcpStream>> for NetStream {
+    fn from(stream: TlsStream<TcpStream>) -> Self {
+        NetStream::Tls(Box::new(stream))
+    }
+}
+
+impl AsyncRead for NetStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            NetStream::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            NetStream::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for NetStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            NetStream::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            NetStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            NetStream::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            NetStream::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            NetStream::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            NetStream::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
@@ -1,7 +1,12 @@
 use std::sync::Arc;
-use tracing::{info, warn};
+use tracing::{error, info, warn};
+use std::time::Duration;
 use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use crate::framework::config::config::{BackTlsConfig, FrontTlsConfig};
 use crate::framework::network::{TcpServer, WebSocketServer};
+use super::net_addr;
+use super::tls::{BackTlsContext, TlsServerContext};
 use super::network_event_queue::{NetworkEventQueue, ServerType};
 
 pub struct NetworkEngine {
@@ -11,6 +16,20 @@ pub struct NetworkEngine {
     back_tcp_server: Option<TcpServer>,
     front_tcp_server: Option<TcpServer>,
     front_websocket_server: Option<WebSocketServer>,
+
+    /// 前端TLS上下文
+    front_tls: Option<Arc<TlsServerContext>>,
+    /// 前端TCP是否使用TLS
+    front_tcp_tls: bool,
+    /// 前端WebSocket是否使用TLS
+    front_ws_tls: bool,
+    /// 证书自动重新加载任务
+    tls_reload_task: Option<JoinHandle<()>>,
+
+    /// 后端双向TLS上下文
+    back_tls: Option<Arc<BackTlsContext>>,
+    /// 后端证书自动重新加载任务
+    back_tls_reload_task: Option<JoinHandle<()>>,
 }
 
 impl NetworkEngine {
@@ -23,6 +42,14 @@ impl NetworkEngine {
             back_tcp_server:None,
             front_tcp_server:None,
             front_websocket_server:None,
+
+            front_tls: None,
+            front_tcp_tls: false,
+            front_ws_tls: false,
+            tls_reload_task: None,
+
+            back_tls: None,
+            back_tls_reload_task: None,
         }
     }
     
@@ -35,26 +62,134 @@ impl NetworkEngine {
         self.notify = Some(notify);
     }
 
+    /// 前端TLS上下文
+    pub fn get_front_tls(&self) -> Option<&Arc<TlsServerContext>> {
+        self.front_tls.as_ref()
+    }
+
+    /// 后端双向TLS上下文
+    pub fn get_back_tls(&self) -> Option<&Arc<BackTlsContext>> {
+        self.back_tls.as_ref()
+    }
+
     // ========== other methods ==========
+    /// 加载前端TLS证书，需要在启动前端监听器之前调用
+    pub fn init_front_tls(&mut self, config: &FrontTlsConfig) -> bool {
+        let context = match TlsServerContext::new(config) {
+            Ok(context) => Arc::new(context),
+            Err(e) => {
+                error!("Failed to load front TLS certificate: {}", e);
+                return false;
+            }
+        };
+
+        if config.reload_interval_ms > 0 {
+            self.tls_reload_task = Some(context.spawn_reload_watcher(Duration::from_millis(config.reload_interval_ms)));
+        }
+        info!("Front TLS enabled (tcp={}, ws={}) with certificate {}", config.tcp, config.ws, config.cert);
+        self.front_tls = Some(context);
+        self.front_tcp_tls = config.tcp;
+        self.front_ws_tls = config.ws;
+        true
+    }
+
+    /// 立即重新加载前端TLS证书，失败时继续使用原来的证书
+    pub fn reload_front_tls(&self) -> bool {
+        match &self.front_tls {
+            Some(context) => match context.reload() {
+                Ok(()) => true,
+                Err(e) => {
+                    error!("Failed to reload front TLS certificate: {}", e);
+                    false
+                }
+            },
+            None => {
+                warn!("Front TLS is not enabled, nothing to reload");
+                false
+            }
+        }
+    }
+
+    /// 加载后端双向TLS证书，需要在启动后端监听器之前调用
+    pub fn init_back_tls(&mut self, config: &BackTlsConfig, server_id: u32) -> bool {
+        let context = match BackTlsContext::new(config, server_id) {
+            Ok(context) => Arc::new(context),
+            Err(e) => {
+                error!("Failed to load back TLS certificate: {}", e);
+                return false;
+            }
+        };
+
+        if config.reload_interval_ms > 0 {
+            self.back_tls_reload_task = Some(context.spawn_reload_watcher(Duration::from_millis(config.reload_interval_ms)));
+        }
+        info!("Back mutual TLS enabled with identity {} and certificate {}", context.identity(server_id), config.cert_path(server_id));
+        self.back_tls = Some(context);
+        true
+    }
+
+    /// 立即重新加载后端TLS证书，失败时继续使用原来的证书
+    pub fn reload_back_tls(&self) -> bool {
+        match &self.back_tls {
+            Some(context) => match context.reload() {
+                Ok(()) => true,
+                Err(e) => {
+                    error!("Failed to reload back TLS certificate: {}", e);
+                    false
+                }
+            },
+            None => {
+                warn!("Back TLS is not enabled, nothing to reload");
+                false
+            }
+        }
+    }
+
+    /// 是否启用了前端或后端TLS
+    pub fn is_tls_enabled(&self) -> bool {
+        self.front_tls.is_some() || self.back_tls.is_some()
+    }
+
+    /// 立即重新加载所有已启用的TLS证书（收到SIGHUP时调用）
+    ///
+    /// # 返回值
+    /// 已启用的证书都重新加载成功时返回true
+    pub fn reload_tls(&self) -> bool {
+        let front_reloaded = self.front_tls.is_none() || self.reload_front_tls();
+        let back_reloaded = self.back_tls.is_none() || self.reload_back_tls();
+        front_reloaded && back_reloaded
+    }
+
     fn trigger_notify(&self) {
         if let Some(notify) = &self.notify {
             notify.notify_one();
         }
     }
 
-    pub fn start_front_tcp(&mut self, port:u16){
+    pub fn start_front_tcp(&mut self, host: &str, port: u16){
         if let Some(notify) = &self.notify {
             let mut server = TcpServer::new(
-                port, 
+                host.to_string(),
+                port,
                 Arc::clone(notify),
                 self.event_queue.clone(),
                 ServerType::FrontTcp
             );
+            if let (true, Some(context)) = (self.front_tcp_tls, &self.front_tls) {
+                match context.tcp_acceptor() {
+                    Ok(acceptor) => server.set_tls(acceptor),
+                    Err(e) => {
+                        // 配置了TLS时不退回明文监听
+                        error!("Failed to start front TCP server with TLS: {}", e);
+                        return;
+                    }
+                }
+            }
             
             if let Err(e) = server.run() {
-                warn!("Failed to start front TCP server on port {}: {}", port, e);
+                warn!("Failed to start front TCP server on {}: {}", net_addr::format_addr(host, port), e);
             } else {
-                info!("Front TCP server started on port {}", port);
+                info!("Front TCP server started on {}", net_addr::format_addr(host, port));
             }
             
             self.front_tcp_server = Some(server);
@@ -63,19 +198,30 @@ impl NetworkEngine {
         }
     }
 
-    pub fn start_front_websocket(&mut self, port:u16){
+    pub fn start_front_websocket(&mut self, host: &str, port: u16){
         if let Some(notify) = &self.notify {
             let mut server = WebSocketServer::new(
-                port, 
+                host.to_string(),
+                port,
                 Arc::clone(notify),
                 self.event_queue.clone(),
                 ServerType::FrontWebSocket
             );
+            if let (true, Some(context)) = (self.front_ws_tls, &self.front_tls) {
+                match context.ws_acceptor() {
+                    Ok(acceptor) => server.set_tls(acceptor),
+                    Err(e) => {
+                        // 配置了TLS时不退回明文监听
+                        error!("Failed to start front WebSocket server with TLS: {}", e);
+                        return;
+                    }
+                }
+            }
             
             if let Err(e) = server.run() {
-                warn!("Failed to start front WebSocket server on port {}: {}", port, e);
+                warn!("Failed to start front WebSocket server on {}: {}", net_addr::format_addr(host, port), e);
             } else {
-                info!("Front WebSocket server started on port {}", port);
+                info!("Front WebSocket server started on {}", net_addr::format_addr(host, port));
             }
             
             self.front_websocket_server = Some(server);
@@ -84,19 +230,23 @@ impl NetworkEngine {
         }
     }
 
-    pub fn start_back_tcp(&mut self, port:u16){
+    pub fn start_back_tcp(&mut self, host: &str, port: u16){
         if let Some(notify) = &self.notify {
             let mut server = TcpServer::new(
-                port, 
+                host.to_string(),
+                port,
                 Arc::clone(notify),
                 self.event_queue.clone(),
                 ServerType::BackTcp
             );
+            if let Some(context) = &self.back_tls {
+                server.set_tls(context.acceptor());
+            }
             
             if let Err(e) = server.run() {
-                warn!("Failed to start back TCP server on port {}: {}", port, e);
+                warn!("Failed to start back TCP server on {}: {}", net_addr::format_addr(host, port), e);
             } else {
-                info!("Back TCP server started on port {}", port);
+                info!("Back TCP server started on {}", net_addr::format_addr(host, port));
             }
             
             self.back_tcp_server = Some(server);
@@ -104,4 +254,25 @@ impl NetworkEngine {
             warn!("Cannot start back TCP server: notify not set");
         }
     }
-}
\ No newline at end of file
+
+    /// 关闭所有监听器和证书重新加载任务
+    pub fn dispose(&mut self) {
+        if let Some(task) = self.tls_reload_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.back_tls_reload_task.take() {
+            task.abort();
+        }
+        if let Some(mut server) = self.back_tcp_server.take() {
+            server.dispose();
+        }
+        if let Some(mut server) = self.front_tcp_server.take() {
+            server.dispose();
+        }
+        if let Some(mut server) = self.front_websocket_server.take() {
+            server.dispose();
+        }
+        self.front_tls = None;
+        self.back_tls = None;
+    }
+}
@@ -2,7 +2,7 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::collections::VecDeque;
 use tokio::sync::Mutex;
-use tokio::net::TcpStream;
+use super::net_stream::NetStream;
 use tokio_tungstenite::WebSocketStream;
 
 // 事件类型枚举
@@ -13,6 +13,7 @@ pub enum NetworkEventType {
     NewTcpConnection,
     NewWebSocketConnection,
     ClientConnectSuccess,
+    ClientConnectFailed,
     Disconnect,
     NewMessage,
     StreamDataNotExpected,
@@ -26,6 +27,38 @@ pub enum ServerType {
     FrontWebSocket,
 }
 
+impl ServerType {
+    /// 是否为前端连接（TCP或WebSocket）
+    pub fn is_front(&self) -> bool {
+        matches!(self, ServerType::FrontTcp | ServerType::FrontWebSocket)
+    }
+}
+
+/// 连接断开原因，随Disconnect事件传给事件处理器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisconnectReason {
+    /// 对端关闭连接
+    Closed,
+    /// 读写出错
+    Error,
+    /// 发送队列溢出，断开发送过慢的连接
+    SlowConsumer,
+    /// 超过空闲时间没有收发数据
+    IdleTimeout,
+}
+
+impl DisconnectReason {
+    /// 原因名称，用于日志
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisconnectReason::Closed => "closed",
+            DisconnectReason::Error => "error",
+            DisconnectReason::SlowConsumer => "slow_consumer",
+            DisconnectReason::IdleTimeout => "idle_timeout",
+        }
+    }
+}
+
 // 网络事件数据
 #[derive(Debug)]
 pub struct NetworkEventData {
@@ -34,10 +67,12 @@ pub struct NetworkEventData {
     pub session_id: u64,
     pub remote_addr: Option<SocketAddr>,
     pub timestamp: std::time::Instant,
-    pub tcp_stream: Option<TcpStream>,
-    pub websocket_stream: Option<WebSocketStream<TcpStream>>,
+    pub tcp_stream: Option<NetStream>,
+    pub websocket_stream: Option<WebSocketStream<NetStream>>,
     pub message: Option<Box<dyn std::any::Any + Send>>,
     pub message_id: Option<u16>,
+    /// 断开原因，只有Disconnect事件有值
+    pub disconnect_reason: Option<DisconnectReason>,
 }
 
 impl NetworkEventData {
@@ -57,6 +92,7 @@ impl NetworkEventData {
             websocket_stream: None,
             message: None,
             message_id: None,
+            disconnect_reason: None,
         }
     }
     
@@ -65,7 +101,7 @@ impl NetworkEventData {
         server_type: ServerType,
         session_id: u64,
         remote_addr: Option<SocketAddr>,
-        tcp_stream: TcpStream,
+        tcp_stream: NetStream,
     ) -> Self {
         Self {
             event_type,
@@ -77,6 +113,7 @@ impl NetworkEventData {
             websocket_stream: None,
             message: None,
             message_id: None,
+            disconnect_reason: None,
         }
     }
     
@@ -85,7 +122,7 @@ impl NetworkEventData {
         server_type: ServerType,
         session_id: u64,
         remote_addr: Option<SocketAddr>,
-        websocket_stream: WebSocketStream<TcpStream>,
+        websocket_stream: WebSocketStream<NetStream>,
     ) -> Self {
         Self {
             event_type,
@@ -97,6 +134,7 @@ impl NetworkEventData {
             websocket_stream: Some(websocket_stream),
             message: None,
             message_id: None,
+            disconnect_reason: None,
         }
     }
     
@@ -118,9 +156,21 @@ impl NetworkEventData {
             websocket_stream: None,
             message: Some(message),
             message_id: Some(message_id),
+            disconnect_reason: None,
         }
     }
     
+    pub fn new_disconnect(
+        server_type: ServerType,
+        session_id: u64,
+        remote_addr: Option<SocketAddr>,
+        reason: DisconnectReason,
+    ) -> Self {
+        let mut event = Self::new(NetworkEventType::Disconnect, server_type, session_id, remote_addr);
+        event.disconnect_reason = Some(reason);
+        event
+    }
+    
 }
 
 // 网络事件队列
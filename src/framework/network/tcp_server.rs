@@ -4,51 +4,65 @@ use tokio::net::TcpListener;
 use tokio::sync::Notify;
 use tokio::task::JoinHandle;
 use tracing::{info, error, debug};
+use super::net_addr;
+use super::net_stream::NetStream;
+use super::tls::TlsServerAcceptor;
 use super::network_event_queue::{NetworkEventQueue, NetworkEventData, NetworkEventType, ServerType};
 
 pub struct TcpServer {
+    host: String,
     port: u16,
     listener: Option<TcpListener>,
     task_handle: Option<JoinHandle<()>>,
     notify: Arc<Notify>,
     event_queue: NetworkEventQueue,
     server_type: ServerType,
+    /// 配置后接受的连接先完成TLS握手
+    tls: Option<TlsServerAcceptor>,
 }
 
 impl TcpServer {
-    pub fn new(port: u16, notify: Arc<Notify>, event_queue: NetworkEventQueue, server_type: ServerType) -> Self {
+    pub fn new(host: String, port: u16, notify: Arc<Notify>, event_queue: NetworkEventQueue, server_type: ServerType) -> Self {
         Self {
+            host,
             port,
             listener: None,
             task_handle: None,
             notify,
             event_queue,
             server_type,
+            tls: None,
         }
     }
 
+    /// 启用TLS，需要在run之前调用
+    pub fn set_tls(&mut self, tls: TlsServerAcceptor) {
+        self.tls = Some(tls);
+    }
+
     fn trigger_notify(&self) {
         self.notify.notify_one();
     }
 
     pub fn run(&mut self) -> Result<(), String> {
-        let addr = format!("127.0.0.1:{}", self.port)
-            .parse::<SocketAddr>()
-            .map_err(|e| format!("Failed to parse address: {}", e))?;
+        let addr = net_addr::bind_addr(&self.host, self.port)?;
+        let std_listener = net_addr::bind_std_listener(addr)
+            .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
         let notify = Arc::clone(&self.notify);
         let event_queue = self.event_queue.clone();
         let server_type = self.server_type;
+        let tls = self.tls.clone();
 
         self.task_handle = Some(tokio::spawn(async move {
-            let listener = match TcpListener::bind(addr).await {
+            let listener = match net_addr::into_tokio_listener(std_listener) {
                 Ok(listener) => listener,
                 Err(e) => {
-                    error!("Failed to bind to address: {}", e);
+                    error!("Failed to register listener on {}: {}", addr, e);
                     return;
                 }
             };
             
-            info!("TCP server ({:?}) listening on {}", server_type, addr);
+            info!("TCP server ({:?}) listening on {}{}", server_type, addr, if tls.is_some() { " with TLS" } else { "" });
             
             // 端口绑定成功，触发ServerOpen事件
             let event = NetworkEventData::new(
@@ -66,22 +80,26 @@ impl TcpServer {
             loop {
                 match listener.accept().await {
                     Ok((stream, client_addr)) => {
-                        
-                        // 触发NewTcpConnection事件，并包含TcpStream
-                        // 注意：此时还没有session，session_id设为0，由SessionManager在创建session时分配
-                        let event = NetworkEventData::new_with_stream(
-                            NetworkEventType::NewTcpConnection,
-                            server_type,
-                            0, // session还未创建，暂时为0
-                            Some(client_addr),
-                            stream,
-                        );
-                        event_queue.push(event).await;
-                        
-                        // 通知主循环处理事件
-                        notify.notify_one();
-                        
-                        // TODO: 处理连接断开事件
+                        match tls.clone() {
+                            Some(tls) => {
+                                // TLS握手在单独的任务中进行，不阻塞accept
+                                let event_queue = event_queue.clone();
+                                let notify = Arc::clone(&notify);
+                                tokio::spawn(async move {
+                                    match tls.accept(stream).await {
+                                        Ok(stream) => {
+                                            Self::push_new_connection(&event_queue, &notify, server_type, client_addr, stream).await;
+                                        }
+                                        Err(e) => {
+                                            debug!("TLS connection from {} rejected: {}", client_addr, e);
+                                        }
+                                    }
+                                });
+                            }
+                            None => {
+                                Self::push_new_connection(&event_queue, &notify, server_type, client_addr, NetStream::from(stream)).await;
+                            }
+                        }
                     }
                     Err(e) => {
                         error!("Failed to accept connection: {}", e);
@@ -93,6 +111,28 @@ impl TcpServer {
         Ok(())
     }
 
+    /// 触发NewTcpConnection事件，并包含连接流
+    /// 注意：此时还没有session，session_id设为0，由SessionManager在创建session时分配
+    async fn push_new_connection(
+        event_queue: &NetworkEventQueue,
+        notify: &Notify,
+        server_type: ServerType,
+        client_addr: SocketAddr,
+        stream: NetStream,
+    ) {
+        let event = NetworkEventData::new_with_stream(
+            NetworkEventType::NewTcpConnection,
+            server_type,
+            0, // session还未创建，暂时为0
+            Some(client_addr),
+            stream,
+        );
+        event_queue.push(event).await;
+        
+        // 通知主循环处理事件
+        notify.notify_one();
+    }
+
     pub fn dispose(&mut self) {
         if let Some(handle) = self.task_handle.take() {
             handle.abort();
@@ -0,0 +1,596 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+use tokio_rustls::rustls::{self, CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, ServerConfig, SignatureScheme};
+use tokio_rustls::rustls::client::ResolvesClientCert;
+use tokio_rustls::rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use tokio_rustls::rustls::crypto::CryptoProvider;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use tokio_rustls::rustls::server::{ClientHello, ParsedCertificate, ResolvesServerCert, WebPkiClientVerifier};
+use tokio_rustls::rustls::sign::CertifiedKey;
+use tracing::{debug, error, info};
+use x509_parser::prelude::{FromDer, X509Certificate};
+use crate::framework::config::config::{BackTlsConfig, FrontTlsConfig};
+use super::net_stream::NetStream;
+
+/// TLS使用的加密实现
+pub fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+/// 读取PEM格式的证书链，第一个为服务器证书
+pub fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    let file = File::open(path).map_err(|e| format!("failed to open certificate {}: {}", path.display(), e))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("failed to parse certificate {}: {}", path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("no certificate found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+/// 读取PEM格式的私钥（PKCS#8、PKCS#1或SEC1）
+pub fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, String> {
+    let file = File::open(path).map_err(|e| format!("failed to open private key {}: {}", path.display(), e))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .map_err(|e| format!("failed to parse private key {}: {}", path.display(), e))?
+        .ok_or_else(|| format!("no private key found in {}", path.display()))
+}
+
+/// 解析逗号分隔的ALPN协议列表，如 "h2,http/1.1"
+pub fn parse_alpn(protocols: &str) -> Vec<Vec<u8>> {
+    protocols.split(',')
+        .map(str::trim)
+        .filter(|protocol| !protocol.is_empty())
+        .map(|protocol| protocol.as_bytes().to_vec())
+        .collect()
+}
+
+/// 读取CA证书作为信任根
+pub fn load_roots(path: &Path) -> Result<RootCertStore, String> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots.add(cert).map_err(|e| format!("invalid CA certificate {}: {}", path.display(), e))?;
+    }
+    Ok(roots)
+}
+
+/// 文件修改时间，用于判断证书是否需要重新加载
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// 证书主题中的CN（commonName），有多个时取第一个
+///
+/// 只读取主题，证书链和签名由TLS握手校验；无法解析的证书返回None
+pub fn cert_common_name(cert: &CertificateDer<'_>) -> Option<String> {
+    let (_, cert) = X509Certificate::from_der(cert.as_ref()).ok()?;
+    let common_name = cert.subject().iter_common_name().next()?;
+    common_name.as_str().ok().map(str::to_string)
+}
+
+/// 可重新加载的证书和私钥，重新加载后新的握手使用新证书，已建立的连接不受影响
+///
+/// 作为服务器证书，也可以作为客户端证书（后端双向TLS）
+#[derive(Debug)]
+struct CertificateStore {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    provider: Arc<CryptoProvider>,
+    current: RwLock<Arc<CertifiedKey>>,
+    /// 已加载文件的修改时间 (证书, 私钥)
+    loaded_time: Mutex<(Option<SystemTime>, Option<SystemTime>)>,
+}
+
+impl CertificateStore {
+    fn new(cert_path: PathBuf, key_path: PathBuf, provider: Arc<CryptoProvider>) -> Result<Self, String> {
+        let loaded_time = (modified_time(&cert_path), modified_time(&key_path));
+        let certified_key = Self::load_certified_key(&cert_path, &key_path, &provider)?;
+        Ok(Self {
+            cert_path,
+            key_path,
+            provider,
+            current: RwLock::new(certified_key),
+            loaded_time: Mutex::new(loaded_time),
+        })
+    }
+
+    fn load_certified_key(cert_path: &Path, key_path: &Path, provider: &CryptoProvider) -> Result<Arc<CertifiedKey>, String> {
+        let certs = load_certs(cert_path)?;
+        let key = load_private_key(key_path)?;
+        CertifiedKey::from_der(certs, key, provider)
+            .map(Arc::new)
+            .map_err(|e| format!("certificate {} does not match private key {}: {}", cert_path.display(), key_path.display(), e))
+    }
+
+    fn current(&self) -> Arc<CertifiedKey> {
+        Arc::clone(&self.current.read().unwrap())
+    }
+
+    /// 加载证书，加载成功后由check检查新证书，检查不通过时不替换
+    fn reload_with(&self, check: impl Fn(&CertifiedKey) -> Result<(), String>) -> Result<(), String> {
+        let loaded_time = (modified_time(&self.cert_path), modified_time(&self.key_path));
+        let certified_key = Self::load_certified_key(&self.cert_path, &self.key_path, &self.provider)?;
+        check(&certified_key)?;
+        *self.current.write().unwrap() = certified_key;
+        *self.loaded_time.lock().unwrap() = loaded_time;
+        info!("TLS certificate reloaded from {}", self.cert_path.display());
+        Ok(())
+    }
+
+    fn is_changed(&self) -> bool {
+        let current = (modified_time(&self.cert_path), modified_time(&self.key_path));
+        *self.loaded_time.lock().unwrap() != current
+    }
+
+    /// 加载失败时记录修改时间，避免重复报错，下次文件变化时再加载
+    fn mark_loaded(&self) {
+        *self.loaded_time.lock().unwrap() = (modified_time(&self.cert_path), modified_time(&self.key_path));
+    }
+}
+
+impl ResolvesServerCert for CertificateStore {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.current())
+    }
+}
+
+impl ResolvesClientCert for CertificateStore {
+    fn resolve(&self, _root_hint_subjects: &[&[u8]], _sigschemes: &[SignatureScheme]) -> Option<Arc<CertifiedKey>> {
+        Some(self.current())
+    }
+
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+
+/// 定期检查证书文件并重新加载
+fn spawn_reload_task<F>(interval: Duration, reload_if_changed: F) -> JoinHandle<()>
+where
+    F: Fn() -> bool + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            reload_if_changed();
+        }
+    })
+}
+
+/// 前端TLS上下文
+///
+/// 持有证书和私钥路径，可以在运行中重新加载证书；
+/// TCP和WebSocket监听器共用证书，各自使用不同的ALPN配置
+pub struct TlsServerContext {
+    certificate: Arc<CertificateStore>,
+    /// TLS握手超时
+    handshake_timeout: Duration,
+    /// TCP监听器的ALPN协议
+    tcp_alpn: Vec<Vec<u8>>,
+    /// WebSocket监听器的ALPN协议
+    ws_alpn: Vec<Vec<u8>>,
+}
+
+impl TlsServerContext {
+    /// 根据配置加载证书
+    pub fn new(config: &FrontTlsConfig) -> Result<Self, String> {
+        let certificate = CertificateStore::new(PathBuf::from(&config.cert), PathBuf::from(&config.key), crypto_provider())?;
+
+        Ok(Self {
+            certificate: Arc::new(certificate),
+            handshake_timeout: Duration::from_millis(config.handshake_timeout_ms),
+            tcp_alpn: parse_alpn(config.tcp_alpn.as_deref().unwrap_or_default()),
+            ws_alpn: parse_alpn(config.ws_alpn.as_deref().unwrap_or_default()),
+        })
+    }
+
+    /// 重新加载证书和私钥，失败时继续使用原来的证书
+    pub fn reload(&self) -> Result<(), String> {
+        self.certificate.reload_with(|_| Ok(()))
+    }
+
+    /// 证书或私钥文件有变化时重新加载
+    ///
+    /// # 返回值
+    /// 是否重新加载了证书
+    pub fn reload_if_changed(&self) -> bool {
+        if !self.certificate.is_changed() {
+            return false;
+        }
+        match self.reload() {
+            Ok(()) => true,
+            Err(e) => {
+                // 证书和私钥可能还没有全部写完
+                self.certificate.mark_loaded();
+                error!("Failed to reload TLS certificate, keeping the previous one: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 定期检查证书文件并重新加载
+    pub fn spawn_reload_watcher(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let context = Arc::clone(self);
+        spawn_reload_task(interval, move || context.reload_if_changed())
+    }
+
+    fn acceptor(&self, alpn: &[Vec<u8>]) -> Result<TlsServerAcceptor, String> {
+        let mut config = ServerConfig::builder_with_provider(Arc::clone(&self.certificate.provider))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("failed to create TLS config: {}", e))?
+            .with_no_client_auth()
+            .with_cert_resolver(Arc::clone(&self.certificate) as Arc<dyn ResolvesServerCert>);
+        config.alpn_protocols = alpn.to_vec();
+
+        Ok(TlsServerAcceptor {
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+            handshake_timeout: self.handshake_timeout,
+        })
+    }
+
+    /// 前端TCP监听器使用的TLS接收器
+    pub fn tcp_acceptor(&self) -> Result<TlsServerAcceptor, String> {
+        self.acceptor(&self.tcp_alpn)
+    }
+
+    /// 前端WebSocket（wss）监听器使用的TLS接收器
+    pub fn ws_acceptor(&self) -> Result<TlsServerAcceptor, String> {
+        self.acceptor(&self.ws_alpn)
+    }
+}
+
+/// 监听器接受连接后执行TLS握手
+#[derive(Clone)]
+pub struct TlsServerAcceptor {
+    acceptor: TlsAcceptor,
+    handshake_timeout: Duration,
+}
+
+impl TlsServerAcceptor {
+    /// 执行TLS握手，超时或失败时返回错误
+    pub async fn accept(&self, stream: TcpStream) -> Result<NetStream, String> {
+        match tokio::time::timeout(self.handshake_timeout, self.acceptor.accept(stream)).await {
+            Ok(Ok(tls_stream)) => {
+                let (_, connection) = tls_stream.get_ref();
+                if let Some(protocol) = connection.alpn_protocol() {
+                    debug!("TLS handshake negotiated ALPN {}", String::from_utf8_lossy(protocol));
+                }
+                Ok(NetStream::from(tokio_rustls::TlsStream::Server(tls_stream)))
+            }
+            Ok(Err(e)) => Err(format!("TLS handshake failed: {}", e)),
+            Err(_) => Err(format!("TLS handshake timed out after {}ms", self.handshake_timeout.as_millis())),
+        }
+    }
+}
+
+/// 校验对端节点证书：证书由集群CA签发，且CN为连接目标的节点身份
+///
+/// 节点之间按服务器ID连接，back_host可能是IP，不使用主机名校验；
+/// 连接时的服务器名为期望的节点身份（如 node-3）
+#[derive(Debug)]
+struct NodeCertVerifier {
+    roots: Arc<RootCertStore>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for NodeCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let cert = ParsedCertificate::try_from(end_entity)?;
+        rustls::client::verify_server_cert_signed_by_trust_anchor(
+            &cert,
+            &self.roots,
+            intermediates,
+            now,
+            self.provider.signature_verification_algorithms.all,
+        )?;
+
+        let expected = match server_name {
+            ServerName::DnsName(name) => name.as_ref(),
+            _ => return Err(rustls::Error::InvalidCertificate(CertificateError::NotValidForName)),
+        };
+        match cert_common_name(end_entity) {
+            Some(name) if name == expected => Ok(ServerCertVerified::assertion()),
+            Some(_) => Err(rustls::Error::InvalidCertificate(CertificateError::NotValidForName)),
+            None => Err(rustls::Error::InvalidCertificate(CertificateError::BadEncoding)),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// 后端双向TLS上下文
+///
+/// 监听器要求对端出示集群CA签发的证书，主动连接时校验对端证书的节点身份；
+/// 节点身份为证书CN，格式为 前缀 + 服务器ID
+pub struct BackTlsContext {
+    certificate: Arc<CertificateStore>,
+    server_config: Arc<ServerConfig>,
+    client_config: Arc<ClientConfig>,
+    /// 当前服务器ID，重新加载证书时检查身份
+    server_id: u32,
+    /// 节点身份前缀
+    identity_prefix: String,
+    /// TLS握手超时
+    handshake_timeout: Duration,
+}
+
+impl BackTlsContext {
+    /// 根据配置加载当前服务器的证书和集群CA
+    pub fn new(config: &BackTlsConfig, server_id: u32) -> Result<Self, String> {
+        let provider = crypto_provider();
+        let certificate = Arc::new(CertificateStore::new(
+            PathBuf::from(config.cert_path(server_id)),
+            PathBuf::from(config.key_path(server_id)),
+            Arc::clone(&provider),
+        )?);
+        let roots = Arc::new(load_roots(Path::new(&config.ca))?);
+
+        let client_verifier = WebPkiClientVerifier::builder_with_provider(Arc::clone(&roots), Arc::clone(&provider))
+            .build()
+            .map_err(|e| format!("failed to create client certificate verifier: {}", e))?;
+        let server_config = ServerConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("failed to create TLS config: {}", e))?
+            .with_client_cert_verifier(client_verifier)
+            .with_cert_resolver(Arc::clone(&certificate) as Arc<dyn ResolvesServerCert>);
+        let client_config = ClientConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("failed to create TLS config: {}", e))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NodeCertVerifier { roots, provider }))
+            .with_client_cert_resolver(Arc::clone(&certificate) as Arc<dyn ResolvesClientCert>);
+
+        let context = Self {
+            certificate,
+            server_config: Arc::new(server_config),
+            client_config: Arc::new(client_config),
+            server_id,
+            identity_prefix: config.identity_prefix.clone(),
+            handshake_timeout: Duration::from_millis(config.handshake_timeout_ms),
+        };
+        context.check_identity(&context.certificate.current())?;
+        // 节点身份需要能作为TLS服务器名使用
+        context.connector(server_id)?;
+        Ok(context)
+    }
+
+    /// 服务器ID对应的节点身份，如 node-3
+    pub fn identity(&self, server_id: u32) -> String {
+        format!("{}{}", self.identity_prefix, server_id)
+    }
+
+    /// 从节点身份中解析服务器ID
+    pub fn parse_identity(&self, identity: &str) -> Option<u32> {
+        identity.strip_prefix(&self.identity_prefix)?.parse::<u32>().ok()
+    }
+
+    /// 对端证书中的服务器ID，明文连接或证书CN不是节点身份时返回None
+    pub fn peer_server_id(&self, stream: &NetStream) -> Option<u32> {
+        let cert = stream.peer_certificates()?.first()?;
+        self.parse_identity(&cert_common_name(cert)?)
+    }
+
+    /// 检查证书身份与当前服务器ID一致，避免用错其他节点的证书
+    fn check_identity(&self, certified_key: &CertifiedKey) -> Result<(), String> {
+        let expected = self.identity(self.server_id);
+        let cert = certified_key.end_entity_cert().map_err(|e| format!("invalid certificate: {}", e))?;
+        match cert_common_name(cert) {
+            Some(name) if name == expected => Ok(()),
+            Some(name) => Err(format!("certificate CN '{}' does not match server identity '{}'", name, expected)),
+            None => Err(format!("certificate has no CN, expected '{}'", expected)),
+        }
+    }
+
+    /// 重新加载证书和私钥，失败或身份不匹配时继续使用原来的证书
+    pub fn reload(&self) -> Result<(), String> {
+        self.certificate.reload_with(|certified_key| self.check_identity(certified_key))
+    }
+
+    /// 证书或私钥文件有变化时重新加载
+    ///
+    /// # 返回值
+    /// 是否重新加载了证书
+    pub fn reload_if_changed(&self) -> bool {
+        if !self.certificate.is_changed() {
+            return false;
+        }
+        match self.reload() {
+            Ok(()) => true,
+            Err(e) => {
+                self.certificate.mark_loaded();
+                error!("Failed to reload back TLS certificate, keeping the previous one: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 定期检查证书文件并重新加载
+    pub fn spawn_reload_watcher(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let context = Arc::clone(self);
+        spawn_reload_task(interval, move || context.reload_if_changed())
+    }
+
+    /// 后端监听器使用的TLS接收器，要求对端出示证书
+    pub fn acceptor(&self) -> TlsServerAcceptor {
+        TlsServerAcceptor {
+            acceptor: TlsAcceptor::from(Arc::clone(&self.server_config)),
+            handshake_timeout: self.handshake_timeout,
+        }
+    }
+
+    /// 连接指定服务器时使用的TLS连接器，对端证书必须是该服务器的身份
+    pub fn connector(&self, server_id: u32) -> Result<TlsClientConnector, String> {
+        let identity = self.identity(server_id);
+        let server_name = ServerName::try_from(identity.clone())
+            .map_err(|e| format!("invalid node identity '{}': {}", identity, e))?;
+        Ok(TlsClientConnector {
+            connector: TlsConnector::from(Arc::clone(&self.client_config)),
+            server_name,
+            handshake_timeout: self.handshake_timeout,
+        })
+    }
+}
+
+/// 主动连接建立后执行TLS握手
+#[derive(Clone)]
+pub struct TlsClientConnector {
+    connector: TlsConnector,
+    server_name: ServerName<'static>,
+    handshake_timeout: Duration,
+}
+
+impl TlsClientConnector {
+    /// 执行TLS握手，超时或失败时返回错误
+    pub async fn connect(&self, stream: TcpStream) -> Result<NetStream, String> {
+        match tokio::time::timeout(self.handshake_timeout, self.connector.connect(self.server_name.clone(), stream)).await {
+            Ok(Ok(tls_stream)) => Ok(NetStream::from(tokio_rustls::TlsStream::Client(tls_stream))),
+            Ok(Err(e)) => Err(format!("TLS handshake failed: {}", e)),
+            Err(_) => Err(format!("TLS handshake timed out after {}ms", self.handshake_timeout.as_millis())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{CertificateParams, DistinguishedName, DnType, KeyPair};
+
+    /// 生成自签名证书，返回 (证书PEM, 私钥PEM, 证书DER)
+    fn self_signed(subject: &[(DnType, &str)]) -> (String, String, CertificateDer<'static>) {
+        let key_pair = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.distinguished_name = DistinguishedName::new();
+        for (dn_type, value) in subject {
+            params.distinguished_name.push(dn_type.clone(), *value);
+        }
+        let cert = params.self_signed(&key_pair).unwrap();
+        (cert.pem(), key_pair.serialize_pem(), CertificateDer::from(cert.der().to_vec()))
+    }
+
+    #[test]
+    fn common_name_from_generated_certificate() {
+        let (_, _, der) = self_signed(&[(DnType::CommonName, "node-3")]);
+        assert_eq!(cert_common_name(&der).as_deref(), Some("node-3"));
+    }
+
+    #[test]
+    fn common_name_among_other_attributes() {
+        let (_, _, der) = self_signed(&[
+            (DnType::CountryName, "CN"),
+            (DnType::OrganizationName, "pantyhose"),
+            (DnType::OrganizationalUnitName, "cluster"),
+            (DnType::CommonName, "node-12"),
+        ]);
+        assert_eq!(cert_common_name(&der).as_deref(), Some("node-12"));
+    }
+
+    #[test]
+    fn missing_common_name_returns_none() {
+        let (_, _, der) = self_signed(&[(DnType::OrganizationName, "pantyhose")]);
+        assert_eq!(cert_common_name(&der), None);
+    }
+
+    #[test]
+    fn long_form_length_common_name() {
+        // 超过127字节的值使用长格式长度
+        let name = format!("node-{}", "1".repeat(200));
+        let (_, _, der) = self_signed(&[(DnType::CommonName, name.as_str())]);
+        assert_eq!(cert_common_name(&der), Some(name));
+    }
+
+    #[test]
+    fn truncated_certificate_returns_none() {
+        let (_, _, der) = self_signed(&[(DnType::CommonName, "node-3")]);
+        for len in 0..der.len() {
+            assert_eq!(cert_common_name(&CertificateDer::from(der[..len].to_vec())), None, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn malformed_input_returns_none() {
+        let inputs: [&[u8]; 5] = [
+            &[],
+            &[0x30],
+            // 长度字段声明了远超数据的长度
+            &[0x30, 0x84, 0xff, 0xff, 0xff, 0xff, 0x30, 0x00],
+            // 长度字段字节数超过usize
+            &[0x30, 0x89, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            &[0x04, 0x03, 0x55, 0x04, 0x03],
+        ];
+        for input in inputs {
+            assert_eq!(cert_common_name(&CertificateDer::from(input.to_vec())), None);
+        }
+    }
+
+    #[test]
+    fn back_tls_reload_rejects_other_identity() {
+        let dir = std::env::temp_dir().join(format!("pantyhose-back-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert_path = dir.join("node-3.crt");
+        let key_path = dir.join("node-3.key");
+        let write_identity = |common_name: &str| {
+            let (cert_pem, key_pem, _) = self_signed(&[(DnType::CommonName, common_name)]);
+            std::fs::write(&cert_path, &cert_pem).unwrap();
+            std::fs::write(&key_path, key_pem).unwrap();
+            cert_pem
+        };
+        let ca_pem = write_identity("node-3");
+        std::fs::write(dir.join("ca.crt"), ca_pem).unwrap();
+
+        let config = BackTlsConfig {
+            cert: dir.join("node-{id}.crt").to_string_lossy().into_owned(),
+            key: dir.join("node-{id}.key").to_string_lossy().into_owned(),
+            ca: dir.join("ca.crt").to_string_lossy().into_owned(),
+            identity_prefix: "node-".to_string(),
+            handshake_timeout_ms: 1000,
+            reload_interval_ms: 0,
+        };
+        assert!(BackTlsContext::new(&config, 4).is_err());
+        let context = BackTlsContext::new(&config, 3).unwrap();
+
+        write_identity("node-4");
+        assert!(context.reload().is_err());
+        assert!(context.check_identity(&context.certificate.current()).is_ok());
+
+        write_identity("node-3");
+        assert!(context.reload().is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
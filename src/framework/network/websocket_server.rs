@@ -1,56 +1,68 @@
-use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tokio::sync::{Notify};
 use tokio::task::JoinHandle;
 use tokio_tungstenite::accept_async;
 use tracing::{info, error, debug};
+use super::net_addr;
+use super::net_stream::NetStream;
+use super::tls::TlsServerAcceptor;
 use super::network_event_queue::{NetworkEventQueue, NetworkEventData, NetworkEventType, ServerType};
 
 pub struct WebSocketServer {
+    host: String,
     port: u16,
     listener: Option<TcpListener>,
     task_handle: Option<JoinHandle<()>>,
     notify: Arc<Notify>,
     event_queue: NetworkEventQueue,
     server_type: ServerType,
+    /// 配置后先完成TLS握手再进行WebSocket握手（wss://）
+    tls: Option<TlsServerAcceptor>,
 }
 
 impl WebSocketServer {
-    pub fn new(port: u16, notify: Arc<Notify>, event_queue: NetworkEventQueue, server_type: ServerType) -> Self {
+    pub fn new(host: String, port: u16, notify: Arc<Notify>, event_queue: NetworkEventQueue, server_type: ServerType) -> Self {
         Self {
+            host,
             port,
             listener: None,
             task_handle: None,
             notify,
             event_queue,
             server_type,
+            tls: None,
         }
     }
 
+    /// 启用TLS，需要在run之前调用
+    pub fn set_tls(&mut self, tls: TlsServerAcceptor) {
+        self.tls = Some(tls);
+    }
+
     fn trigger_notify(&self) {
         self.notify.notify_one();
     }
 
     pub fn run(&mut self) -> Result<(), String> {
-        let addr = format!("127.0.0.1:{}", self.port)
-            .parse::<SocketAddr>()
-            .map_err(|e| format!("Failed to parse address: {}", e))?;
-        
+        let addr = net_addr::bind_addr(&self.host, self.port)?;
+        let std_listener = net_addr::bind_std_listener(addr)
+            .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
         let notify = Arc::clone(&self.notify);
         let event_queue = self.event_queue.clone();
         let server_type = self.server_type;
+        let tls = self.tls.clone();
 
         self.task_handle = Some(tokio::spawn(async move {
-            let listener = match TcpListener::bind(addr).await {
+            let listener = match net_addr::into_tokio_listener(std_listener) {
                 Ok(listener) => listener,
                 Err(e) => {
-                    error!("Failed to bind to address: {}", e);
+                    error!("Failed to register listener on {}: {}", addr, e);
                     return;
                 }
             };
             
-            info!("WebSocket server ({:?}) listening on {}", server_type, addr);
+            info!("WebSocket server ({:?}) listening on {}{}", server_type, addr, if tls.is_some() { " with TLS" } else { "" });
             
             // 端口绑定成功，触发ServerOpen事件
             let event = NetworkEventData::new(
@@ -70,8 +82,20 @@ impl WebSocketServer {
                 
                 let notify_clone = Arc::clone(&notify);
                 let event_queue_clone = event_queue.clone();
+                let tls = tls.clone();
                 
                 tokio::spawn(async move {
+                    let stream = match tls {
+                        Some(tls) => match tls.accept(stream).await {
+                            Ok(stream) => stream,
+                            Err(e) => {
+                                debug!("TLS connection from {} rejected: {}", client_addr, e);
+                                return;
+                            }
+                        },
+                        None => NetStream::from(stream),
+                    };
+                    
                     if let Ok(ws_stream) = accept_async(stream).await {
                         debug!("WebSocket handshake completed for {}", client_addr);
                         
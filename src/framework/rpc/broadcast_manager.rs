@@ -0,0 +1,487 @@
+use crate::framework::rpc::{RpcManager, RpcMessageDispatcher, RpcError, RpcHandlerResult};
+use crate::framework::session::{BackSession, FrontSession, FrontSessionManager, FrontSessionGroupManager};
+use crate::framework::session::session_trait::SessionTrait;
+use crate::proto::messages::protobuf::message::cluster::{RpcBroadcastBRequest, RpcBroadcastBResponse, RpcPushMessageFNotify};
+use crate::proto::messages::MessageIdSerialize;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, error, warn};
+
+/// 广播请求的默认超时时间（毫秒）
+pub const DEFAULT_BROADCAST_TIMEOUT_MS: u64 = 3000;
+
+/// 广播目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BroadcastTarget {
+    /// 全部在线（已登录）玩家
+    All,
+    /// 指定名称的分组，分组可以分布在多个前端服务器上
+    Group(String),
+    /// 指定的用户ID列表
+    Users(Vec<u64>),
+}
+
+impl BroadcastTarget {
+    /// 协议中的目标类型值
+    fn to_u32(&self) -> u32 {
+        match self {
+            BroadcastTarget::All => 1,
+            BroadcastTarget::Group(_) => 2,
+            BroadcastTarget::Users(_) => 3,
+        }
+    }
+
+    /// 从广播请求中还原广播目标，未知的目标类型返回None
+    pub fn from_request(request: &RpcBroadcastBRequest) -> Option<Self> {
+        match request.target {
+            1 => Some(BroadcastTarget::All),
+            2 => Some(BroadcastTarget::Group(request.group_name.clone())),
+            3 => Some(BroadcastTarget::Users(request.user_ids.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// 广播结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BroadcastResult {
+    /// 投递成功的玩家数量
+    pub delivered_count: usize,
+    /// 完成投递的前端服务器数量（包括当前服务器）
+    pub server_count: usize,
+    /// 未连接、超时或投递失败的前端服务器ID
+    pub failed_server_ids: Vec<u32>,
+}
+
+/// 广播完成回调，在主线程调用且只会调用一次
+pub type BroadcastCallback = Box<dyn FnOnce(BroadcastResult) + Send>;
+
+/// 一次广播的汇总状态，所有前端服务器都返回后调用回调
+struct BroadcastAggregate {
+    /// 尚未返回的前端服务器数量
+    remaining: usize,
+    /// 已汇总的结果
+    result: BroadcastResult,
+    /// 完成回调
+    callback: Option<BroadcastCallback>,
+}
+
+impl BroadcastAggregate {
+    /// 记录一个前端服务器的结果，全部返回后调用回调
+    fn complete(&mut self, server_id: u32, delivered_count: Option<usize>) {
+        match delivered_count {
+            Some(delivered_count) => {
+                self.result.delivered_count += delivered_count;
+                self.result.server_count += 1;
+            }
+            None => self.result.failed_server_ids.push(server_id),
+        }
+        self.release();
+    }
+
+    /// 减少一个等待计数，计数归零时调用回调
+    fn release(&mut self) {
+        self.remaining = self.remaining.saturating_sub(1);
+        if self.remaining == 0 {
+            if let Some(callback) = self.callback.take() {
+                callback(std::mem::take(&mut self.result));
+            }
+        }
+    }
+}
+
+// 包装器结构体，使原始指针满足Send + Sync要求（仅用于满足闭包类型约束）
+// 实际运行时所有调用都在主线程，不会有并发访问
+#[derive(Clone, Copy)]
+struct BroadcastManagerPtr(*mut BroadcastManager);
+unsafe impl Send for BroadcastManagerPtr {}
+unsafe impl Sync for BroadcastManagerPtr {}
+
+/// 广播管理器，负责集群范围的广播和多播
+///
+/// 发起方把RpcBroadcastBRequest发给每个前端服务器（当前服务器是前端服务器时直接本地投递），
+/// 前端服务器通过自己的会话和分组管理器投递RpcPushMessageFNotify，并返回投递成功的玩家数量
+///
+/// ## 重要说明
+/// 本类为单例，被Server持有，所有方法都在主线程调用，不存在线程安全问题。
+pub struct BroadcastManager {
+    /// 当前服务器ID
+    server_id: u32,
+    /// 当前服务器类型，本地投递时作为推送方服务器类型
+    server_type: String,
+    /// RPC管理器指针，用于向前端服务器发送广播请求
+    rpc_manager: *mut RpcManager,
+    /// 前端会话管理器指针，用于本地投递
+    front_session_manager: *mut FrontSessionManager,
+    /// 前端会话分组管理器指针，用于按分组投递
+    front_session_group_manager: *mut FrontSessionGroupManager,
+}
+
+// 安全性：BroadcastManager只在单线程环境中使用
+unsafe impl Send for BroadcastManager {}
+
+impl BroadcastManager {
+    /// 创建新的广播管理器
+    pub fn new() -> Self {
+        Self {
+            server_id: 0,
+            server_type: String::new(),
+            rpc_manager: std::ptr::null_mut(),
+            front_session_manager: std::ptr::null_mut(),
+            front_session_group_manager: std::ptr::null_mut(),
+        }
+    }
+
+    /// 初始化广播管理器并注册广播请求处理器
+    ///
+    /// 注意：本方法在主线程调用
+    pub fn init(
+        &mut self,
+        server_id: u32,
+        server_type: &str,
+        rpc_manager: &mut RpcManager,
+        rpc_message_dispatcher: &mut RpcMessageDispatcher,
+        front_session_manager: &mut FrontSessionManager,
+        front_session_group_manager: &mut FrontSessionGroupManager,
+    ) -> bool {
+        self.server_id = server_id;
+        self.server_type = server_type.to_string();
+        self.rpc_manager = rpc_manager as *mut RpcManager;
+        self.front_session_manager = front_session_manager as *mut FrontSessionManager;
+        self.front_session_group_manager = front_session_group_manager as *mut FrontSessionGroupManager;
+
+        let broadcast_manager_ptr = BroadcastManagerPtr(self as *mut BroadcastManager);
+        rpc_message_dispatcher.register_request::<RpcBroadcastBRequest, _, _>(move |session, _msg_unique_id, _front_session_id, request| {
+            unsafe {
+                let ptr = broadcast_manager_ptr;
+                let broadcast_manager = &mut *ptr.0;
+                broadcast_manager.handle_broadcast_request(session, request)
+            }
+        });
+
+        true
+    }
+
+    /// 广播给全部在线玩家
+    ///
+    /// 详见broadcast
+    pub fn broadcast_to_all<T, F>(&mut self, msg: &T, callback: F) -> usize
+    where
+        T: MessageIdSerialize,
+        F: FnOnce(BroadcastResult) + Send + 'static,
+    {
+        self.broadcast(BroadcastTarget::All, msg, callback)
+    }
+
+    /// 广播给指定分组中的玩家，分组可以分布在多个前端服务器上
+    ///
+    /// 详见broadcast
+    pub fn broadcast_to_group<T, F>(&mut self, group_name: &str, msg: &T, callback: F) -> usize
+    where
+        T: MessageIdSerialize,
+        F: FnOnce(BroadcastResult) + Send + 'static,
+    {
+        self.broadcast(BroadcastTarget::Group(group_name.to_string()), msg, callback)
+    }
+
+    /// 多播给指定的用户
+    ///
+    /// 详见broadcast
+    pub fn multicast_to_users<T, F>(&mut self, user_ids: Vec<u64>, msg: &T, callback: F) -> usize
+    where
+        T: MessageIdSerialize,
+        F: FnOnce(BroadcastResult) + Send + 'static,
+    {
+        self.broadcast(BroadcastTarget::Users(user_ids), msg, callback)
+    }
+
+    /// 向集群中的玩家广播消息
+    ///
+    /// 注意：本方法在主线程调用
+    ///
+    /// # 参数
+    /// * `target` - 广播目标
+    /// * `msg` - 要广播的消息，客户端收到的是封装后的RpcPushMessageFNotify
+    /// * `callback` - 所有前端服务器投递完成（或超时、断开）后调用，报告触达的玩家数量；
+    ///   总会被调用一次，没有需要等待的前端服务器时在本方法返回前调用
+    ///
+    /// # 返回值
+    /// 参与投递的前端服务器数量（包括当前服务器）
+    pub fn broadcast<T, F>(&mut self, target: BroadcastTarget, msg: &T, callback: F) -> usize
+    where
+        T: MessageIdSerialize,
+        F: FnOnce(BroadcastResult) + Send + 'static,
+    {
+        let request = RpcBroadcastBRequest {
+            target: target.to_u32(),
+            group_name: match &target {
+                BroadcastTarget::Group(group_name) => group_name.clone(),
+                _ => String::new(),
+            },
+            user_ids: match &target {
+                BroadcastTarget::Users(user_ids) => user_ids.clone(),
+                _ => Vec::new(),
+            },
+            msg_id: T::MSG_ID as u32,
+            message: msg.encode_to_vec(),
+        };
+
+        if self.rpc_manager.is_null() {
+            error!("BroadcastManager not initialized");
+            callback(BroadcastResult::default());
+            return 0;
+        }
+        let rpc_manager = unsafe { &mut *self.rpc_manager };
+
+        let mut front_server_ids: Vec<u32> = rpc_manager.get_front_server_ids().iter().copied().collect();
+        front_server_ids.sort_unstable();
+
+        // 等待计数包括每个前端服务器和发起方自身，避免请求全部发出前提前调用回调
+        let aggregate = Arc::new(Mutex::new(BroadcastAggregate {
+            remaining: front_server_ids.len() + 1,
+            result: BroadcastResult::default(),
+            callback: Some(Box::new(callback)),
+        }));
+
+        let mut server_count = 0;
+        for server_id in front_server_ids {
+            // 当前服务器直接本地投递
+            if server_id == self.server_id {
+                let delivered_count = self.deliver_local(&self.server_type.clone(), &target, request.msg_id, &request.message);
+                aggregate.lock().unwrap().complete(server_id, Some(delivered_count));
+                server_count += 1;
+                continue;
+            }
+
+            let callback_aggregate = Arc::clone(&aggregate);
+            let sent = rpc_manager.request_to_server::<RpcBroadcastBRequest, RpcBroadcastBResponse, _>(
+                server_id,
+                request.clone(),
+                DEFAULT_BROADCAST_TIMEOUT_MS,
+                move |result: Result<RpcBroadcastBResponse, RpcError>| {
+                    let delivered_count = match result {
+                        Ok(response) => Some(response.delivered_count as usize),
+                        Err(rpc_error) => {
+                            warn!("Broadcast to front server {} failed: {:?}", server_id, rpc_error);
+                            None
+                        }
+                    };
+                    callback_aggregate.lock().unwrap().complete(server_id, delivered_count);
+                },
+            );
+            if sent {
+                server_count += 1;
+            } else {
+                aggregate.lock().unwrap().complete(server_id, None);
+            }
+        }
+
+        debug!("Broadcast msg_id={} to {:?} sent to {} front servers", request.msg_id, target, server_count);
+
+        // 所有请求都已发出，释放发起方持有的计数
+        aggregate.lock().unwrap().release();
+        server_count
+    }
+
+    /// 处理其他服务器发来的广播请求，投递给当前服务器上的目标玩家
+    ///
+    /// 注意：本方法在主线程调用
+    fn handle_broadcast_request(&mut self, back_session: &mut BackSession, request: &RpcBroadcastBRequest) -> RpcHandlerResult<RpcBroadcastBResponse> {
+        let target = match BroadcastTarget::from_request(request) {
+            Some(target) => target,
+            None => {
+                warn!("Unknown broadcast target {} from back session {}", request.target, back_session.get_session_id());
+                return Ok(RpcBroadcastBResponse { delivered_count: 0 });
+            }
+        };
+
+        let source_server_type = back_session.get_server_type().cloned().unwrap_or_default();
+        let delivered_count = self.deliver_local(&source_server_type, &target, request.msg_id, &request.message);
+        debug!("Delivered broadcast msg_id={} from {} to {} local sessions", request.msg_id, source_server_type, delivered_count);
+
+        Ok(RpcBroadcastBResponse { delivered_count: delivered_count as u32 })
+    }
+
+    /// 投递给当前服务器上的目标玩家，所有目标类型都只投递给已登录且在线的会话
+    ///
+    /// # 返回值
+    /// 投递成功的玩家数量
+    fn deliver_local(&mut self, source_server_type: &str, target: &BroadcastTarget, msg_id: u32, message: &[u8]) -> usize {
+        if self.front_session_manager.is_null() || self.front_session_group_manager.is_null() {
+            error!("BroadcastManager not initialized");
+            return 0;
+        }
+        let front_session_manager = unsafe { &mut *self.front_session_manager };
+        let front_session_group_manager = unsafe { &*self.front_session_group_manager };
+
+        let push_notify = RpcPushMessageFNotify {
+            server_type: source_server_type.to_string(),
+            msg_id,
+            message: message.to_vec(),
+        };
+
+        match target {
+            BroadcastTarget::All => {
+                let mut delivered_count = 0;
+                front_session_manager.for_each_session(|session| {
+                    if Self::push_to_session(session, &push_notify) {
+                        delivered_count += 1;
+                    }
+                });
+                delivered_count
+            }
+            BroadcastTarget::Group(group_name) => {
+                // 分组只存在于部分前端服务器上，不存在时不是错误
+                let group = match front_session_group_manager.get_group_by_name(group_name) {
+                    Some(group) => group,
+                    None => return 0,
+                };
+                let mut delivered_count = 0;
+                for session_id in group.get_session_ids() {
+                    if let Some(session) = front_session_manager.get_session_mut(session_id) {
+                        if Self::push_to_session(session, &push_notify) {
+                            delivered_count += 1;
+                        }
+                    }
+                }
+                delivered_count
+            }
+            BroadcastTarget::Users(user_ids) => {
+                let mut delivered_count = 0;
+                for user_id in user_ids {
+                    if let Some(session) = front_session_manager.get_session_by_user_id_mut(*user_id) {
+                        if Self::push_to_session(session, &push_notify) {
+                            delivered_count += 1;
+                        }
+                    }
+                }
+                delivered_count
+            }
+        }
+    }
+
+    /// 推送给已登录且在线的前端会话
+    fn push_to_session(session: &mut FrontSession, push_notify: &RpcPushMessageFNotify) -> bool {
+        session.get_user_id().is_some() && session.is_connected() && session.send_message(push_notify.clone())
+    }
+
+    /// 清理管理器
+    ///
+    /// 注意：本方法在主线程调用
+    pub fn dispose(&mut self) {
+        self.rpc_manager = std::ptr::null_mut();
+        self.front_session_manager = std::ptr::null_mut();
+        self.front_session_group_manager = std::ptr::null_mut();
+
+        debug!("BroadcastManager disposed");
+    }
+}
+
+impl Default for BroadcastManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::network::connection::SendQueueOptions;
+    use crate::framework::network::network_event_queue::{NetworkEventQueue, ServerType};
+    use crate::framework::network::NetStream;
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// 创建连接到本地监听端口的前端会话，user_id为None时表示未登录
+    async fn connected_session(session_id: u64, user_id: Option<u64>) -> (FrontSession, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (server_stream, remote_addr) = listener.accept().await.unwrap();
+        let mut session = FrontSession::new_with_tcp(
+            session_id,
+            NetStream::Tcp(server_stream),
+            remote_addr,
+            NetworkEventQueue::new(),
+            Arc::new(tokio::sync::Notify::new()),
+            ServerType::FrontTcp,
+            SendQueueOptions::default(),
+        );
+        if let Some(user_id) = user_id {
+            session.set_user_id(user_id);
+        }
+        (session, client)
+    }
+
+    fn request(target: &BroadcastTarget) -> RpcBroadcastBRequest {
+        RpcBroadcastBRequest {
+            target: target.to_u32(),
+            group_name: match target {
+                BroadcastTarget::Group(group_name) => group_name.clone(),
+                _ => String::new(),
+            },
+            user_ids: match target {
+                BroadcastTarget::Users(user_ids) => user_ids.clone(),
+                _ => Vec::new(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn target_survives_request_round_trip() {
+        let targets = [
+            BroadcastTarget::All,
+            BroadcastTarget::Group("room-1".to_string()),
+            BroadcastTarget::Users(vec![7, 8]),
+        ];
+        for target in targets {
+            assert_eq!(BroadcastTarget::from_request(&request(&target)), Some(target));
+        }
+
+        let unknown = RpcBroadcastBRequest { target: 9, ..Default::default() };
+        assert_eq!(BroadcastTarget::from_request(&unknown), None);
+    }
+
+    #[test]
+    fn aggregate_reports_once_all_servers_finish() {
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let callback_results = results.clone();
+        let mut aggregate = BroadcastAggregate {
+            remaining: 3,
+            result: BroadcastResult::default(),
+            callback: Some(Box::new(move |result| callback_results.lock().unwrap().push(result))),
+        };
+
+        aggregate.complete(1, Some(4));
+        aggregate.complete(2, None);
+        assert!(results.lock().unwrap().is_empty());
+        aggregate.complete(3, Some(2));
+
+        let results = results.lock().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0], BroadcastResult { delivered_count: 6, server_count: 2, failed_server_ids: vec![2] });
+    }
+
+    #[tokio::test]
+    async fn every_target_counts_only_logged_in_sessions() {
+        let mut front_session_manager = FrontSessionManager::new();
+        let mut front_session_group_manager = FrontSessionGroupManager::new();
+        let mut clients = Vec::new();
+        for (session_id, user_id) in [(1, Some(1001)), (2, None), (3, Some(1003))] {
+            let (session, client) = connected_session(session_id, user_id).await;
+            front_session_manager.insert_session(session);
+            clients.push(client);
+        }
+        let group_id = front_session_group_manager.create_group("room".to_string(), None);
+        front_session_group_manager.add_session_to_group(group_id, 1);
+        front_session_group_manager.add_session_to_group(group_id, 2);
+
+        let mut broadcast_manager = BroadcastManager::new();
+        broadcast_manager.front_session_manager = &mut front_session_manager as *mut FrontSessionManager;
+        broadcast_manager.front_session_group_manager = &mut front_session_group_manager as *mut FrontSessionGroupManager;
+
+        let mut deliver = |target: BroadcastTarget| broadcast_manager.deliver_local("chat", &target, 1, &[]);
+        assert_eq!(deliver(BroadcastTarget::All), 2);
+        assert_eq!(deliver(BroadcastTarget::Group("room".to_string())), 1);
+        assert_eq!(deliver(BroadcastTarget::Group("missing".to_string())), 0);
+        assert_eq!(deliver(BroadcastTarget::Users(vec![1001, 1002, 1003])), 2);
+    }
+}
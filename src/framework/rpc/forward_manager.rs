@@ -1,20 +1,16 @@
 use crate::framework::session::{FrontSession, BackSession, FrontSessionMessageDispatcher, BackSessionMessageDispatcher};
 use crate::framework::session::session_trait::SessionTrait;
-use crate::framework::rpc::{RouterManager, RpcManager, RpcMessageDispatcher};
-use crate::framework::session::{FrontSessionManager, BackSessionManager};
+use crate::framework::rpc::{RpcManager, RpcMessageDispatcher};
+use crate::framework::rpc::rpc_call::{build_forward_response, build_front_error_response, RpcErrorCode, RpcHandlerError, RpcHandlerResult, RpcResponse};
+use crate::framework::session::FrontSessionManager;
 use crate::proto::messages::protobuf::cluster::{
     RpcMessageFRequest, RpcMessageFNotify, RpcMessageFResponse,
-    RpcForwardMessageBRequest, RpcForwardMessageBNotify, RpcForwardMessageBResponse
-};
-use crate::proto::messages::protobuf::message::protobuf_message_id::{
-    MSG_ID_RPC_MESSAGE_F_REQUEST, MSG_ID_RPC_MESSAGE_F_NOTIFY,
-    MSG_ID_RPC_FORWARD_MESSAGE_B_REQUEST, MSG_ID_RPC_FORWARD_MESSAGE_B_NOTIFY,
-    MSG_ID_RPC_FORWARD_MESSAGE_B_RESPONSE, MessageFactory
+    RpcForwardMessageBRequest, RpcForwardMessageBNotify, RpcForwardMessageBResponse,
+    RpcPushMessageBNotify, RpcPushMessageFNotify
 };
+use crate::proto::messages::protobuf::message::protobuf_message_id::MessageFactory;
 use crate::framework::data::DynamicBuffer;
-use crate::proto::messages::MessageIdSerialize;
-use crate::framework::task::TaskManager;
-use tracing::{debug, info, error, warn};
+use tracing::{debug, error, warn};
 
 /// 转发管理器
 /// 处理RPC消息转发，负责将前端消息路由到正确的后端服务器
@@ -58,10 +54,7 @@ impl ForwardManager {
         &mut self,
         front_dispatcher: &mut FrontSessionMessageDispatcher,
         back_dispatcher: &mut BackSessionMessageDispatcher,
-        router_manager: &mut RouterManager,
         front_session_manager: &mut FrontSessionManager,
-        back_session_manager: &mut BackSessionManager,
-        task_manager: &mut TaskManager,
         rpc_manager: &mut RpcManager,
         rpc_message_dispatcher: &mut RpcMessageDispatcher,
     ) -> bool {
@@ -75,89 +68,59 @@ impl ForwardManager {
         let forward_manager_ptr = ForwardManagerPtr(self as *mut ForwardManager);
         
         // 注册前端消息处理器：RpcMessageFRequest
-        front_dispatcher.register_handler(
-            MSG_ID_RPC_MESSAGE_F_REQUEST,
-            Box::new(move |session, message| {
-                if let Some(request) = message.downcast_ref::<RpcMessageFRequest>() {
-                    // 在unsafe块内部才解引用，避免闭包直接捕获原始指针
-                    unsafe {
-                        let ptr = forward_manager_ptr;
-                        let forward_manager = &mut *ptr.0;
-                        forward_manager.handle_rpc_message_request(session, request);
-                    }
-                }
-            }),
-        );
-        
-        // 重新创建指针（因为前一个已经被move）
-        let forward_manager_ptr = ForwardManagerPtr(self as *mut ForwardManager);
+        front_dispatcher.register::<RpcMessageFRequest, _>(move |session, request| {
+            // 在unsafe块内部才解引用，避免闭包直接捕获原始指针
+            unsafe {
+                let ptr = forward_manager_ptr;
+                let forward_manager = &mut *ptr.0;
+                forward_manager.handle_rpc_message_request(session, request);
+            }
+        });
         
         // 注册前端消息处理器：RpcMessageFNotify
-        front_dispatcher.register_handler(
-            MSG_ID_RPC_MESSAGE_F_NOTIFY,
-            Box::new(move |session, message| {
-                if let Some(notify) = message.downcast_ref::<RpcMessageFNotify>() {
-                    // 在unsafe块内部才解引用，避免闭包直接捕获原始指针
-                    unsafe {
-                        let ptr = forward_manager_ptr;
-                        let forward_manager = &mut *ptr.0;
-                        forward_manager.handle_rpc_message_notify(session, notify);
-                    }
-                }
-            }),
-        );
-        
-        // 重新创建指针（因为前一个已经被move）
-        let forward_manager_ptr = ForwardManagerPtr(self as *mut ForwardManager);
+        front_dispatcher.register::<RpcMessageFNotify, _>(move |session, notify| {
+            unsafe {
+                let ptr = forward_manager_ptr;
+                let forward_manager = &mut *ptr.0;
+                forward_manager.handle_rpc_message_notify(session, notify);
+            }
+        });
         
         // 注册后端消息处理器：RpcForwardMessageBRequest
-        back_dispatcher.register_handler(
-            MSG_ID_RPC_FORWARD_MESSAGE_B_REQUEST,
-            Box::new(move |session, message| {
-                if let Some(request) = message.downcast_ref::<RpcForwardMessageBRequest>() {
-                    unsafe {
-                        let ptr = forward_manager_ptr;
-                        let forward_manager = &mut *ptr.0;
-                        forward_manager.handle_rpc_forward_message_request(session, request);
-                    }
-                }
-            }),
-        );
-        
-        // 重新创建指针
-        let forward_manager_ptr = ForwardManagerPtr(self as *mut ForwardManager);
+        back_dispatcher.register::<RpcForwardMessageBRequest, _>(move |session, request| {
+            unsafe {
+                let ptr = forward_manager_ptr;
+                let forward_manager = &mut *ptr.0;
+                forward_manager.handle_rpc_forward_message_request(session, request);
+            }
+        });
         
         // 注册后端消息处理器：RpcForwardMessageBNotify
-        back_dispatcher.register_handler(
-            MSG_ID_RPC_FORWARD_MESSAGE_B_NOTIFY,
-            Box::new(move |session, message| {
-                if let Some(notify) = message.downcast_ref::<RpcForwardMessageBNotify>() {
-                    unsafe {
-                        let ptr = forward_manager_ptr;
-                        let forward_manager = &mut *ptr.0;
-                        forward_manager.handle_rpc_forward_message_notify(session, notify);
-                    }
-                }
-            }),
-        );
-        
-        // 重新创建指针
-        let forward_manager_ptr = ForwardManagerPtr(self as *mut ForwardManager);
+        back_dispatcher.register::<RpcForwardMessageBNotify, _>(move |session, notify| {
+            unsafe {
+                let ptr = forward_manager_ptr;
+                let forward_manager = &mut *ptr.0;
+                forward_manager.handle_rpc_forward_message_notify(session, notify);
+            }
+        });
         
         // 注册后端消息处理器：RpcForwardMessageBResponse
-        back_dispatcher.register_handler(
-            MSG_ID_RPC_FORWARD_MESSAGE_B_RESPONSE,
-            Box::new(move |session, message| {
-                if let Some(response) = message.downcast_ref::<RpcForwardMessageBResponse>() {
-                    // 在unsafe块内部才解引用，避免闭包直接捕获原始指针
-                    unsafe {
-                        let ptr = forward_manager_ptr;
-                        let forward_manager = &mut *ptr.0;
-                        forward_manager.handle_rpc_forward_message_response(session, response);
-                    }
-                }
-            }),
-        );
+        back_dispatcher.register::<RpcForwardMessageBResponse, _>(move |session, response| {
+            unsafe {
+                let ptr = forward_manager_ptr;
+                let forward_manager = &mut *ptr.0;
+                forward_manager.handle_rpc_forward_message_response(session, response);
+            }
+        });
+        
+        // 注册后端消息处理器：RpcPushMessageBNotify
+        back_dispatcher.register::<RpcPushMessageBNotify, _>(move |session, notify| {
+            unsafe {
+                let ptr = forward_manager_ptr;
+                let forward_manager = &mut *ptr.0;
+                forward_manager.handle_rpc_push_message_notify(session, notify);
+            }
+        });
 
         true
     }
@@ -165,6 +128,8 @@ impl ForwardManager {
     
     /// 处理RpcMessageFRequest
     /// 
+    /// 无法转发时立即向前端会话回传错误响应，转发后的超时和断线由RpcManager::update处理
+    /// 
     /// 注意：本方法在主线程调用
     pub fn handle_rpc_message_request(&mut self, front_session: &mut FrontSession, request: &RpcMessageFRequest) {
         let session_id = front_session.get_session_id();
@@ -180,18 +145,24 @@ impl ForwardManager {
             message: request.message.clone(),
         };
         
-        // 直接调用RPC发送消息
-        unsafe {
+        // 转发请求并记录到等待回传表
+        let result = unsafe {
             if !self.rpc_manager.is_null() {
                 let rpc_manager = &mut *self.rpc_manager;
-                let success = rpc_manager.call_with_session(&request.server_type, forward_request, front_session);
-                if success {
-                    debug!("Successfully sent RpcForwardMessageBRequest to server_type {}", request.server_type);
-                } else {
-                    error!("Failed to send RpcForwardMessageBRequest to server_type {}", request.server_type);
-                }
+                rpc_manager.forward_request(&request.server_type, forward_request, front_session)
             } else {
                 error!("RpcManager is null");
+                Err(RpcHandlerError::from_code(RpcErrorCode::Internal, "rpc manager not initialized"))
+            }
+        };
+        
+        match result {
+            Ok(()) => {
+                debug!("Successfully sent RpcForwardMessageBRequest to server_type {}", request.server_type);
+            }
+            Err(forward_error) => {
+                error!("Failed to send RpcForwardMessageBRequest to server_type {}: {}", request.server_type, forward_error.message);
+                front_session.send_message(build_front_error_response(request.msg_unique_id, forward_error));
             }
         }
     }
@@ -230,33 +201,57 @@ impl ForwardManager {
     
     /// 处理后端RpcForwardMessageBRequest（消息转发到业务处理器）
     /// 
+    /// 业务处理器的返回值会封装为RpcForwardMessageBResponse，通过收到请求的后端会话回传；
+    /// 消息ID未知、解码失败或没有处理器时回传对应的错误响应
+    /// 
     /// 注意：本方法在主线程调用
     pub fn handle_rpc_forward_message_request(&mut self, back_session: &mut BackSession, request: &RpcForwardMessageBRequest) {
         debug!("Handling RpcForwardMessageBRequest: msg_unique_id={}, msg_id={}, message_size={}", 
                request.msg_unique_id, request.msg_id, request.message.len());
         
+        let result = self.dispatch_forward_request(back_session, request);
+        if let Err(handler_error) = &result {
+            warn!("RPC request msg_unique_id={}, msg_id={} failed with error {}: {}",
+                  request.msg_unique_id, request.msg_id, handler_error.code, handler_error.message);
+        }
+        
+        let forward_response = build_forward_response(request.msg_unique_id, request.front_session_id, result);
+        if back_session.send_message(forward_response) {
+            debug!("Sent RpcForwardMessageBResponse for msg_unique_id={} to back session {}", request.msg_unique_id, back_session.get_session_id());
+        } else {
+            error!("Failed to send RpcForwardMessageBResponse for msg_unique_id={} to back session {}", request.msg_unique_id, back_session.get_session_id());
+        }
+    }
+    
+    /// 解码内部消息并分发给业务处理器，返回处理结果
+    fn dispatch_forward_request(&mut self, back_session: &mut BackSession, request: &RpcForwardMessageBRequest) -> RpcHandlerResult<RpcResponse> {
+        let msg_id = request.msg_id as u16;
+        if MessageFactory::message_name(msg_id).is_none() {
+            return Err(RpcHandlerError::from_code(RpcErrorCode::UnknownMessage, format!("unknown msg_id {}", request.msg_id)));
+        }
+        
         // 根据msg_id反序列化内部消息
         let mut buffer = DynamicBuffer::new(request.message.len(), 1024);
         buffer.write_slice(&request.message);
         
-        if let Some(inner_message) = MessageFactory::decode_message(request.msg_id as u16, &mut buffer, request.message.len()) {
-            debug!("Successfully decoded inner message with msg_id={}", request.msg_id);
-            
-            // 通过RPC消息分发器分发内部消息给业务处理器
-            unsafe {
-                if !self.rpc_message_dispatcher.is_null() {
-                    let rpc_dispatcher = &mut *self.rpc_message_dispatcher;
-                    if rpc_dispatcher.dispatch_request_message(request.msg_id as u16, back_session, request.msg_unique_id, request.front_session_id, inner_message.as_ref()) {
-                        debug!("Successfully dispatched RPC message with msg_id={}", request.msg_id);
-                    } else {
-                        warn!("No handler found for RPC message with msg_id={}", request.msg_id);
-                    }
-                } else {
-                    error!("RpcMessageDispatcher is null");
-                }
+        let inner_message = match MessageFactory::decode_message(msg_id, &mut buffer, request.message.len()) {
+            Some(inner_message) => inner_message,
+            None => {
+                error!("Failed to decode inner message with msg_id={}", request.msg_id);
+                return Err(RpcHandlerError::from_code(RpcErrorCode::DecodeFailed, format!("failed to decode msg_id {}", request.msg_id)));
             }
-        } else {
-            error!("Failed to decode inner message with msg_id={}", request.msg_id);
+        };
+        debug!("Successfully decoded inner message with msg_id={}", request.msg_id);
+        
+        // 通过RPC消息分发器分发内部消息给业务处理器
+        if self.rpc_message_dispatcher.is_null() {
+            error!("RpcMessageDispatcher is null");
+            return Err(RpcHandlerError::from_code(RpcErrorCode::Internal, "rpc message dispatcher not initialized"));
+        }
+        let rpc_dispatcher = unsafe { &mut *self.rpc_message_dispatcher };
+        match rpc_dispatcher.dispatch_request_message(msg_id, back_session, request.msg_unique_id, request.front_session_id, inner_message.as_ref()) {
+            Some(result) => result,
+            None => Err(RpcHandlerError::from_code(RpcErrorCode::UnknownMessage, format!("no handler for msg_id {}", request.msg_id))),
         }
     }
     
@@ -300,11 +295,43 @@ impl ForwardManager {
         debug!("Handling RpcForwardMessageBResponse from back session {}: msg_unique_id={}, front_session_id={}, msg_id={}, message_size={}", 
                back_session_id, response.msg_unique_id, response.front_session_id, response.msg_id, response.message.len());
         
+        // front_session_id为0表示后端之间的RPC调用，交给RpcManager匹配等待中的调用
+        if response.front_session_id == 0 {
+            unsafe {
+                if !self.rpc_manager.is_null() {
+                    let rpc_manager = &mut *self.rpc_manager;
+                    rpc_manager.handle_response(back_session_id, response);
+                } else {
+                    error!("RpcManager is null");
+                }
+            }
+            return;
+        }
+        
+        // 已超时（错误响应已回传）的请求不再转发响应
+        unsafe {
+            if !self.rpc_manager.is_null() {
+                let rpc_manager = &mut *self.rpc_manager;
+                if !rpc_manager.complete_forward(back_session_id, response.front_session_id, response.msg_unique_id) {
+                    warn!("Dropping late RpcForwardMessageBResponse msg_unique_id={} for front session {}",
+                          response.msg_unique_id, response.front_session_id);
+                    return;
+                }
+            }
+        }
+        
+        if response.error_code != 0 {
+            warn!("RPC request msg_unique_id={} for front session {} failed with error {}: {}",
+                  response.msg_unique_id, response.front_session_id, response.error_code, response.error_message);
+        }
+        
         // 创建RpcMessageFResponse
         let front_response = RpcMessageFResponse {
             msg_unique_id: response.msg_unique_id,
             msg_id: response.msg_id,
             message: response.message.clone(),
+            error_code: response.error_code,
+            error_message: response.error_message.clone(),
         };
         
         // 获取前端会话并发送响应
@@ -328,6 +355,53 @@ impl ForwardManager {
         }
     }
     
+    /// 处理RpcPushMessageBNotify（后端服务器主动推送给前端会话）
+    /// 
+    /// 按前端会话ID查找会话，未指定时按用户ID查找；同时指定时要求会话的用户ID一致，
+    /// 避免会话已被其他用户复用时推送到错误的客户端。会话不在当前服务器时丢弃
+    /// 
+    /// 注意：本方法在主线程调用
+    pub fn handle_rpc_push_message_notify(&mut self, back_session: &mut BackSession, notify: &RpcPushMessageBNotify) {
+        debug!("Handling RpcPushMessageBNotify from back session {}: front_session_id={}, user_id={}, msg_id={}, message_size={}",
+               back_session.get_session_id(), notify.front_session_id, notify.user_id, notify.msg_id, notify.message.len());
+        
+        if self.front_session_manager.is_null() {
+            error!("FrontSessionManager is null");
+            return;
+        }
+        let front_session_manager = unsafe { &mut *self.front_session_manager };
+        
+        let front_session = if notify.front_session_id != 0 {
+            front_session_manager.get_session_mut(notify.front_session_id)
+                .filter(|session| notify.user_id == 0 || session.get_user_id() == Some(notify.user_id))
+        } else if notify.user_id != 0 {
+            front_session_manager.get_session_by_user_id_mut(notify.user_id)
+        } else {
+            warn!("RpcPushMessageBNotify without front_session_id or user_id, msg_id={}", notify.msg_id);
+            return;
+        };
+        
+        let front_session = match front_session {
+            Some(front_session) => front_session,
+            None => {
+                debug!("Push target front_session_id={}, user_id={} not on this server, dropped", notify.front_session_id, notify.user_id);
+                return;
+            }
+        };
+        
+        let push_notify = RpcPushMessageFNotify {
+            server_type: back_session.get_server_type().cloned().unwrap_or_default(),
+            msg_id: notify.msg_id,
+            message: notify.message.clone(),
+        };
+        let front_session_id = front_session.get_session_id();
+        if front_session.send_message(push_notify) {
+            debug!("Successfully pushed msg_id={} to front session {}", notify.msg_id, front_session_id);
+        } else {
+            error!("Failed to push msg_id={} to front session {}", notify.msg_id, front_session_id);
+        }
+    }
+    
     /// 清理管理器
     /// 
     /// 注意：本方法在主线程调用
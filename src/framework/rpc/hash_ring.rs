@@ -0,0 +1,384 @@
+use crate::framework::data::stable_hash;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 每个节点默认的虚拟节点数量
+pub const DEFAULT_VIRTUAL_NODES: u32 = 160;
+
+/// 哈希环上的一段区间 (start, end]，start >= end 时表示跨越0点的区间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyRange {
+    /// 区间起点（不包含）
+    pub start: u64,
+    /// 区间终点（包含）
+    pub end: u64,
+}
+
+impl KeyRange {
+    /// 哈希值是否落在区间内
+    pub fn contains_hash(&self, hash: u64) -> bool {
+        if self.start < self.end {
+            hash > self.start && hash <= self.end
+        } else {
+            hash > self.start || hash <= self.end
+        }
+    }
+
+    /// 键（如用户ID）是否落在区间内
+    pub fn contains_key(&self, key: u64) -> bool {
+        self.contains_hash(HashRing::key_hash(key))
+    }
+}
+
+/// 节点增减导致的一段区间归属变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyRangeMove {
+    /// 迁移的区间
+    pub range: KeyRange,
+    /// 原归属服务器ID
+    pub from: u32,
+    /// 新归属服务器ID
+    pub to: u32,
+}
+
+/// 一致性哈希环，每个服务器ID在环上对应多个虚拟节点
+///
+/// 键映射到顺时针方向遇到的第一个虚拟节点，节点增减时只有相邻区间的键会迁移
+#[derive(Debug, Clone)]
+pub struct HashRing {
+    /// 每个节点的虚拟节点数量
+    virtual_nodes: u32,
+    /// 环上的虚拟节点 <哈希值, 服务器ID>，哈希冲突时归属服务器ID较小的节点
+    ring: BTreeMap<u64, u32>,
+    /// 哈希冲突时未占到位置的虚拟节点 <哈希值, 服务器ID集合>，占位节点移除后由ID最小的接替
+    shadowed: BTreeMap<u64, BTreeSet<u32>>,
+    /// 环上的服务器ID及其虚拟节点哈希值
+    nodes: BTreeMap<u32, Vec<u64>>,
+}
+
+impl HashRing {
+    /// 创建空的哈希环
+    pub fn new(virtual_nodes: u32) -> Self {
+        Self {
+            virtual_nodes: virtual_nodes.max(1),
+            ring: BTreeMap::new(),
+            shadowed: BTreeMap::new(),
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    /// 计算节点第index个虚拟节点的哈希值
+    fn virtual_node_hash(server_id: u32, index: u32) -> u64 {
+        stable_hash(format!("{}#{}", server_id, index).as_bytes())
+    }
+
+    /// 计算键在环上的哈希值
+    pub fn key_hash(key: u64) -> u64 {
+        stable_hash(&key.to_be_bytes())
+    }
+
+    /// 添加节点
+    ///
+    /// # 返回值
+    /// 从其他节点迁移到新节点的区间；节点已存在或环原本为空时为空
+    pub fn add_node(&mut self, server_id: u32) -> Vec<KeyRangeMove> {
+        if self.nodes.contains_key(&server_id) {
+            return Vec::new();
+        }
+        let points = (0..self.virtual_nodes)
+            .map(|index| Self::virtual_node_hash(server_id, index))
+            .collect();
+        self.insert_points(server_id, points)
+    }
+
+    /// 把节点的虚拟节点放到环上
+    ///
+    /// 哈希冲突时由服务器ID较小的节点占位，结果与节点加入顺序无关，保证所有节点得到同样的环
+    fn insert_points(&mut self, server_id: u32, points: Vec<u64>) -> Vec<KeyRangeMove> {
+        for &hash in &points {
+            match self.ring.get(&hash).copied() {
+                None => {
+                    self.ring.insert(hash, server_id);
+                }
+                Some(owner) if owner == server_id => {}
+                Some(owner) => {
+                    let shadowed = self.shadowed.entry(hash).or_default();
+                    if server_id < owner {
+                        shadowed.insert(owner);
+                        self.ring.insert(hash, server_id);
+                    } else {
+                        shadowed.insert(server_id);
+                    }
+                }
+            }
+        }
+        self.nodes.insert(server_id, points);
+
+        self.owned_ranges(server_id)
+            .into_iter()
+            .map(|(range, successor)| KeyRangeMove { range, from: successor, to: server_id })
+            .collect()
+    }
+
+    /// 移除节点
+    ///
+    /// # 返回值
+    /// 从被移除节点迁移到其他节点的区间；节点不存在或移除后环为空时为空
+    pub fn remove_node(&mut self, server_id: u32) -> Vec<KeyRangeMove> {
+        if !self.nodes.contains_key(&server_id) {
+            return Vec::new();
+        }
+        let moves = self.owned_ranges(server_id)
+            .into_iter()
+            .map(|(range, successor)| KeyRangeMove { range, from: server_id, to: successor })
+            .collect();
+
+        let points = self.nodes.remove(&server_id).unwrap_or_default();
+        for hash in points {
+            if self.ring.get(&hash) == Some(&server_id) {
+                // 占位节点移除后由冲突的其他节点接替
+                match self.shadowed.get_mut(&hash).and_then(|shadowed| shadowed.pop_first()) {
+                    Some(next) => {
+                        self.ring.insert(hash, next);
+                    }
+                    None => {
+                        self.ring.remove(&hash);
+                    }
+                }
+            } else if let Some(shadowed) = self.shadowed.get_mut(&hash) {
+                shadowed.remove(&server_id);
+            }
+            if self.shadowed.get(&hash).is_some_and(|shadowed| shadowed.is_empty()) {
+                self.shadowed.remove(&hash);
+            }
+        }
+        moves
+    }
+
+    /// 没有指定节点时虚拟节点位置的归属节点
+    fn owner_without(&self, hash: u64, owner: u32, server_id: u32) -> Option<u32> {
+        if owner != server_id {
+            return Some(owner);
+        }
+        self.shadowed.get(&hash).and_then(|shadowed| shadowed.first().copied())
+    }
+
+    /// 计算节点拥有的区间，以及没有该节点时每个区间的归属节点（冲突的其他节点或顺时针方向下一个其他节点）
+    ///
+    /// 相邻且归属相同的区间会合并
+    fn owned_ranges(&self, server_id: u32) -> Vec<(KeyRange, u32)> {
+        let mut ranges: Vec<(KeyRange, u32)> = Vec::new();
+        if self.nodes.len() < 2 {
+            return ranges;
+        }
+
+        for (&hash, &node) in self.ring.iter() {
+            if node != server_id {
+                continue;
+            }
+            let predecessor = self.ring.range(..hash)
+                .next_back()
+                .or_else(|| self.ring.iter().next_back())
+                .map(|(point, _)| *point)
+                .unwrap_or(hash);
+            let successor = self.ring.range(hash..)
+                .chain(self.ring.range(..hash))
+                .find_map(|(point, node)| self.owner_without(*point, *node, server_id));
+            let successor = match successor {
+                Some(successor) => successor,
+                None => continue,
+            };
+
+            match ranges.last_mut() {
+                Some((last, last_successor)) if last.end == predecessor && *last_successor == successor => {
+                    last.end = hash;
+                }
+                _ => ranges.push((KeyRange { start: predecessor, end: hash }, successor)),
+            }
+        }
+
+        // 首尾区间相接时合并（跨越0点）
+        if ranges.len() > 1 {
+            let (first, first_successor) = ranges[0];
+            let (last, last_successor) = ranges[ranges.len() - 1];
+            if last.end == first.start && last_successor == first_successor {
+                ranges[0].0.start = last.start;
+                ranges.pop();
+            }
+        }
+        ranges
+    }
+
+    /// 获取键所属的服务器ID，环为空时返回None
+    pub fn get_node(&self, key: u64) -> Option<u32> {
+        self.get_node_by_hash(Self::key_hash(key))
+    }
+
+    /// 获取哈希值所属的服务器ID，环为空时返回None
+    pub fn get_node_by_hash(&self, hash: u64) -> Option<u32> {
+        self.ring.range(hash..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, server_id)| *server_id)
+    }
+
+    /// 是否包含指定节点
+    pub fn contains(&self, server_id: u32) -> bool {
+        self.nodes.contains_key(&server_id)
+    }
+
+    /// 环上的服务器ID（升序）
+    pub fn get_nodes(&self) -> Vec<u32> {
+        self.nodes.keys().copied().collect()
+    }
+
+    /// 环上的节点数量
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// 环是否为空
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl Default for HashRing {
+    fn default() -> Self {
+        Self::new(DEFAULT_VIRTUAL_NODES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 校验区间迁移与变化前后get_node_by_hash的结果一致
+    fn assert_moves_match(before: &HashRing, after: &HashRing, moves: &[KeyRangeMove], hashes: impl Iterator<Item = u64>) {
+        for hash in hashes {
+            let old_node = before.get_node_by_hash(hash);
+            let new_node = after.get_node_by_hash(hash);
+            let covering: Vec<&KeyRangeMove> = moves.iter().filter(|m| m.range.contains_hash(hash)).collect();
+            assert!(covering.len() <= 1, "hash {} is covered by several moves: {:?}", hash, covering);
+            match covering.first() {
+                Some(m) => {
+                    assert_eq!(old_node, Some(m.from), "hash {} before change", hash);
+                    assert_eq!(new_node, Some(m.to), "hash {} after change", hash);
+                }
+                None => assert_eq!(old_node, new_node, "hash {} moved without a KeyRangeMove", hash),
+            }
+        }
+    }
+
+    fn sample_hashes() -> impl Iterator<Item = u64> {
+        (0..20000u64).map(HashRing::key_hash)
+    }
+
+    #[test]
+    fn empty_and_single_node() {
+        let mut ring = HashRing::new(16);
+        assert_eq!(ring.get_node(1), None);
+        assert!(ring.add_node(1).is_empty());
+        assert!(ring.add_node(1).is_empty());
+        assert_eq!(ring.get_node(42), Some(1));
+        assert!(ring.remove_node(2).is_empty());
+        assert!(ring.remove_node(1).is_empty());
+        assert!(ring.is_empty());
+        assert_eq!(ring.get_node(42), None);
+    }
+
+    #[test]
+    fn add_node_moves_match_get_node() {
+        let mut ring = HashRing::new(32);
+        for server_id in [1, 2, 3] {
+            let before = ring.clone();
+            let moves = ring.add_node(server_id);
+            if before.is_empty() {
+                assert!(moves.is_empty());
+                continue;
+            }
+            assert!(!moves.is_empty());
+            assert!(moves.iter().all(|m| m.to == server_id && m.from != server_id));
+            assert_moves_match(&before, &ring, &moves, sample_hashes());
+        }
+    }
+
+    #[test]
+    fn remove_node_moves_match_get_node() {
+        let mut ring = HashRing::new(32);
+        for server_id in [1, 2, 3, 4] {
+            ring.add_node(server_id);
+        }
+        for server_id in [2, 4, 1] {
+            let before = ring.clone();
+            let moves = ring.remove_node(server_id);
+            assert!(!moves.is_empty());
+            assert!(moves.iter().all(|m| m.from == server_id && m.to != server_id));
+            assert_moves_match(&before, &ring, &moves, sample_hashes());
+        }
+        assert_eq!(ring.get_nodes(), vec![3]);
+    }
+
+    #[test]
+    fn ring_is_independent_of_join_order() {
+        let mut first = HashRing::new(32);
+        let mut second = HashRing::new(32);
+        for server_id in [1, 2, 3] {
+            first.add_node(server_id);
+        }
+        for server_id in [3, 1, 2] {
+            second.add_node(server_id);
+        }
+        assert!(sample_hashes().all(|hash| first.get_node_by_hash(hash) == second.get_node_by_hash(hash)));
+    }
+
+    fn collision_hashes() -> impl Iterator<Item = u64> {
+        [0, 50, 100, 101, 150, 200, 201, 250, 300, 301, 400, u64::MAX].into_iter()
+    }
+
+    #[test]
+    fn collision_is_owned_by_smaller_id_regardless_of_order() {
+        let mut first = HashRing::new(2);
+        first.insert_points(1, vec![100, 200]);
+        first.insert_points(2, vec![200, 300]);
+
+        let mut second = HashRing::new(2);
+        second.insert_points(2, vec![200, 300]);
+        second.insert_points(1, vec![100, 200]);
+
+        for hash in collision_hashes() {
+            assert_eq!(first.get_node_by_hash(hash), second.get_node_by_hash(hash), "hash {}", hash);
+        }
+        assert_eq!(first.get_node_by_hash(150), Some(1));
+        assert_eq!(first.get_node_by_hash(200), Some(1));
+    }
+
+    #[test]
+    fn collision_moves_match_get_node() {
+        let mut ring = HashRing::new(2);
+        ring.insert_points(2, vec![200, 300]);
+
+        // 节点1在200处与节点2冲突并占位
+        let before = ring.clone();
+        let moves = ring.insert_points(1, vec![100, 200]);
+        assert_moves_match(&before, &ring, &moves, collision_hashes());
+
+        // 节点3在200处冲突但没有占位，不应迁移任何区间
+        let before = ring.clone();
+        let moves = ring.insert_points(3, vec![200, 400]);
+        assert_moves_match(&before, &ring, &moves, collision_hashes());
+        assert_eq!(ring.get_node_by_hash(200), Some(1));
+
+        // 节点1移除后200处由ID最小的冲突节点接替，而不是从环上消失
+        let before = ring.clone();
+        let moves = ring.remove_node(1);
+        assert_moves_match(&before, &ring, &moves, collision_hashes());
+        assert_eq!(ring.get_node_by_hash(150), Some(2));
+        assert_eq!(ring.get_node_by_hash(200), Some(2));
+
+        let before = ring.clone();
+        let moves = ring.remove_node(2);
+        assert_moves_match(&before, &ring, &moves, collision_hashes());
+        assert!(collision_hashes().all(|hash| ring.get_node_by_hash(hash) == Some(3)));
+        assert!(ring.shadowed.is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+use super::hash_ring::{HashRing, KeyRangeMove, DEFAULT_VIRTUAL_NODES};
+use crate::framework::cluster::{ServerManager, ServerChangeEvent};
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// 哈希环节点变化通知
+#[derive(Debug, Clone)]
+pub struct HashRingChange {
+    /// 服务器类型
+    pub server_type: String,
+    /// 加入或离开的服务器ID
+    pub server_id: u32,
+    /// true为加入，false为离开
+    pub joined: bool,
+    /// 归属发生变化的区间，可用KeyRange::contains_key判断某个用户是否需要迁移状态
+    pub moves: Vec<KeyRangeMove>,
+}
+
+/// 哈希环节点变化监听器
+pub type HashRingListener = Box<dyn FnMut(&HashRingChange) + Send>;
+
+// 包装器结构体，使原始指针满足Send要求（仅用于满足闭包类型约束）
+// 实际运行时所有调用都在主线程，不会有并发访问
+#[derive(Clone, Copy)]
+struct HashRingManagerPtr(*mut HashRingManager);
+unsafe impl Send for HashRingManagerPtr {}
+
+/// 哈希环管理器，为每个服务器类型维护一个一致性哈希环
+///
+/// 环上的节点与ServerManager中的服务器列表（包括当前节点自身）保持一致，
+/// 所有节点对同一个用户ID得到同样的服务器，节点增减时通知监听器哪些区间发生了迁移
+pub struct HashRingManager {
+    /// 每个节点的虚拟节点数量
+    virtual_nodes: u32,
+    /// 哈希环 <服务器类型, 哈希环>
+    rings: HashMap<String, HashRing>,
+    /// 节点变化监听器
+    listeners: Vec<HashRingListener>,
+}
+
+impl HashRingManager {
+    /// 创建新的哈希环管理器
+    pub fn new() -> Self {
+        Self {
+            virtual_nodes: DEFAULT_VIRTUAL_NODES,
+            rings: HashMap::new(),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// 监听服务器管理器的服务器变化，保持哈希环与服务器列表一致
+    ///
+    /// 注意：本方法在主线程调用，HashRingManager的地址在服务器运行期间不能变化
+    pub fn attach(&mut self, server_manager: &mut ServerManager) {
+        for server in server_manager.get_all_servers() {
+            self.add_node(&server.server_type, server.server_id);
+        }
+
+        let manager_ptr = HashRingManagerPtr(self as *mut HashRingManager);
+        server_manager.add_listener(Box::new(move |event| {
+            let ptr = manager_ptr;
+            let manager = unsafe { &mut *ptr.0 };
+            match event {
+                ServerChangeEvent::Added(server) => manager.add_node(&server.server_type, server.server_id),
+                ServerChangeEvent::Removed(server) => manager.remove_node(&server.server_type, server.server_id),
+            }
+        }));
+    }
+
+    /// 添加节点变化监听器，用于在区间迁移时交接用户状态
+    pub fn add_listener(&mut self, listener: HashRingListener) {
+        self.listeners.push(listener);
+    }
+
+    /// 添加节点
+    pub fn add_node(&mut self, server_type: &str, server_id: u32) {
+        let virtual_nodes = self.virtual_nodes;
+        let ring = self.rings.entry(server_type.to_string())
+            .or_insert_with(|| HashRing::new(virtual_nodes));
+        if ring.contains(server_id) {
+            return;
+        }
+        let moves = ring.add_node(server_id);
+        info!("Server {} joined hash ring of type {} ({} nodes, {} ranges moved)",
+              server_id, server_type, ring.node_count(), moves.len());
+        self.notify_listeners(HashRingChange {
+            server_type: server_type.to_string(),
+            server_id,
+            joined: true,
+            moves,
+        });
+    }
+
+    /// 移除节点
+    pub fn remove_node(&mut self, server_type: &str, server_id: u32) {
+        let ring = match self.rings.get_mut(server_type) {
+            Some(ring) if ring.contains(server_id) => ring,
+            _ => return,
+        };
+        let moves = ring.remove_node(server_id);
+        info!("Server {} left hash ring of type {} ({} nodes, {} ranges moved)",
+              server_id, server_type, ring.node_count(), moves.len());
+        if ring.is_empty() {
+            self.rings.remove(server_type);
+        }
+        self.notify_listeners(HashRingChange {
+            server_type: server_type.to_string(),
+            server_id,
+            joined: false,
+            moves,
+        });
+    }
+
+    /// 通知所有监听器
+    fn notify_listeners(&mut self, change: HashRingChange) {
+        debug!("Notify hash ring change of type {} to {} listeners", change.server_type, self.listeners.len());
+        for listener in self.listeners.iter_mut() {
+            listener(&change);
+        }
+    }
+
+    /// 获取键（如用户ID）在指定服务器类型中所属的服务器ID
+    ///
+    /// # 返回值
+    /// 该类型没有任何服务器时返回None
+    pub fn get_node(&self, server_type: &str, key: u64) -> Option<u32> {
+        self.rings.get(server_type)?.get_node(key)
+    }
+
+    /// 获取指定服务器类型的哈希环
+    pub fn get_ring(&self, server_type: &str) -> Option<&HashRing> {
+        self.rings.get(server_type)
+    }
+
+    /// 销毁哈希环管理器
+    pub fn dispose(&mut self) {
+        self.rings.clear();
+        self.listeners.clear();
+    }
+}
+
+impl Default for HashRingManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
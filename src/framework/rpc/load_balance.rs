@@ -0,0 +1,309 @@
+use super::hash_ring::{HashRing, DEFAULT_VIRTUAL_NODES};
+use crate::framework::session::BackSession;
+use crate::framework::session::session_trait::SessionTrait;
+use std::collections::HashMap;
+use tracing::warn;
+
+/// 内置的负载均衡策略类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadBalanceKind {
+    /// 随机选择
+    Random,
+    /// 轮询
+    RoundRobin,
+    /// 选择等待响应的请求最少的服务器
+    LeastPending,
+    /// 按config.xml中server的weight平滑加权轮询
+    Weighted,
+    /// 按用户ID一致性哈希
+    ConsistentHash,
+    /// 选择心跳上报负载最低的服务器
+    LeastLoad,
+}
+
+impl LoadBalanceKind {
+    /// 从配置字符串转换（random / round_robin / least_pending / weighted / consistent_hash / least_load）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "random" => Some(LoadBalanceKind::Random),
+            "round_robin" => Some(LoadBalanceKind::RoundRobin),
+            "least_pending" => Some(LoadBalanceKind::LeastPending),
+            "weighted" => Some(LoadBalanceKind::Weighted),
+            "consistent_hash" => Some(LoadBalanceKind::ConsistentHash),
+            "least_load" => Some(LoadBalanceKind::LeastLoad),
+            _ => None,
+        }
+    }
+}
+
+/// 负载均衡策略，从同一类型的在线后端会话中选择一个
+pub trait LoadBalanceStrategy: Send {
+    /// 选择后端会话
+    ///
+    /// # 参数
+    /// * `candidates` - 在线的后端会话，按服务器ID升序排列且不为空
+    /// * `route_key` - 路由键（前端会话的用户ID，未登录时为前端会话ID），后端之间的调用为None
+    /// * `hash_ring` - 该服务器类型在集群中的一致性哈希环，包括尚未连接的服务器
+    ///
+    /// # 返回值
+    /// 选中的后端会话ID
+    fn select(&mut self, candidates: &[&BackSession], route_key: Option<u64>, hash_ring: Option<&HashRing>) -> Option<u64>;
+
+    /// 选中的服务器是否绑定到前端会话元数据，之后的请求直接使用绑定的服务器
+    fn is_sticky(&self) -> bool {
+        true
+    }
+}
+
+/// 根据策略类型创建策略实例
+///
+/// # 参数
+/// * `kind` - 策略类型
+/// * `weights` - 服务器权重 <服务器ID, 权重>，仅Weighted使用
+pub fn create_strategy(kind: LoadBalanceKind, weights: &HashMap<u32, u32>) -> Box<dyn LoadBalanceStrategy> {
+    match kind {
+        LoadBalanceKind::Random => Box::new(RandomStrategy),
+        LoadBalanceKind::RoundRobin => Box::new(RoundRobinStrategy::new()),
+        LoadBalanceKind::LeastPending => Box::new(LeastPendingStrategy::new()),
+        LoadBalanceKind::Weighted => Box::new(WeightedStrategy::new(weights.clone())),
+        LoadBalanceKind::ConsistentHash => Box::new(ConsistentHashStrategy::new(DEFAULT_VIRTUAL_NODES)),
+        LoadBalanceKind::LeastLoad => Box::new(LeastLoadStrategy::new()),
+    }
+}
+
+/// 从start开始轮转查找指标最小的会话，指标相同时依次选择不同的会话
+fn select_min_by_key<F>(candidates: &[&BackSession], start: usize, key: F) -> Option<u64>
+where
+    F: Fn(&BackSession) -> u32,
+{
+    let len = candidates.len();
+    (0..len)
+        .map(|offset| candidates[(start + offset) % len])
+        .min_by_key(|session| key(session))
+        .map(|session| session.get_session_id())
+}
+
+/// 随机选择
+pub struct RandomStrategy;
+
+impl LoadBalanceStrategy for RandomStrategy {
+    fn select(&mut self, candidates: &[&BackSession], _route_key: Option<u64>, _hash_ring: Option<&HashRing>) -> Option<u64> {
+        use rand::Rng;
+        if candidates.is_empty() {
+            return None;
+        }
+        let index = rand::thread_rng().gen_range(0..candidates.len());
+        Some(candidates[index].get_session_id())
+    }
+}
+
+/// 轮询
+pub struct RoundRobinStrategy {
+    next_index: usize,
+}
+
+impl RoundRobinStrategy {
+    pub fn new() -> Self {
+        Self { next_index: 0 }
+    }
+}
+
+impl LoadBalanceStrategy for RoundRobinStrategy {
+    fn select(&mut self, candidates: &[&BackSession], _route_key: Option<u64>, _hash_ring: Option<&HashRing>) -> Option<u64> {
+        if candidates.is_empty() {
+            return None;
+        }
+        let index = self.next_index % candidates.len();
+        self.next_index = self.next_index.wrapping_add(1);
+        Some(candidates[index].get_session_id())
+    }
+}
+
+/// 选择等待响应的请求最少的服务器
+pub struct LeastPendingStrategy {
+    next_index: usize,
+}
+
+impl LeastPendingStrategy {
+    pub fn new() -> Self {
+        Self { next_index: 0 }
+    }
+}
+
+impl LoadBalanceStrategy for LeastPendingStrategy {
+    fn select(&mut self, candidates: &[&BackSession], _route_key: Option<u64>, _hash_ring: Option<&HashRing>) -> Option<u64> {
+        let start = self.next_index;
+        self.next_index = self.next_index.wrapping_add(1);
+        select_min_by_key(candidates, start, |session| session.get_pending_request_count())
+    }
+}
+
+/// 平滑加权轮询，未配置权重的服务器按1计算，权重为0的服务器不会被选中（除非全部为0）
+pub struct WeightedStrategy {
+    /// 服务器权重 <服务器ID, 权重>
+    weights: HashMap<u32, u32>,
+    /// 当前权重 <服务器ID, 当前权重>
+    current_weights: HashMap<u32, i64>,
+}
+
+impl WeightedStrategy {
+    pub fn new(weights: HashMap<u32, u32>) -> Self {
+        Self {
+            weights,
+            current_weights: HashMap::new(),
+        }
+    }
+
+    fn get_weight(&self, server_id: u32) -> i64 {
+        self.weights.get(&server_id).copied().unwrap_or(1) as i64
+    }
+}
+
+impl LoadBalanceStrategy for WeightedStrategy {
+    fn select(&mut self, candidates: &[&BackSession], _route_key: Option<u64>, _hash_ring: Option<&HashRing>) -> Option<u64> {
+        let total_weight: i64 = candidates.iter().map(|session| self.get_weight(session.get_server_id())).sum();
+        if total_weight == 0 {
+            return candidates.first().map(|session| session.get_session_id());
+        }
+
+        // 已下线服务器的当前权重不再保留
+        self.current_weights.retain(|server_id, _| candidates.iter().any(|session| session.get_server_id() == *server_id));
+
+        let mut selected: Option<(&BackSession, i64)> = None;
+        for session in candidates {
+            let server_id = session.get_server_id();
+            let weight = self.get_weight(server_id);
+            let current_weight = self.current_weights.entry(server_id).or_insert(0);
+            *current_weight += weight;
+            if weight > 0 && selected.is_none_or(|(_, best)| *current_weight > best) {
+                selected = Some((session, *current_weight));
+            }
+        }
+
+        let (session, _) = selected?;
+        if let Some(current_weight) = self.current_weights.get_mut(&session.get_server_id()) {
+            *current_weight -= total_weight;
+        }
+        Some(session.get_session_id())
+    }
+}
+
+/// 按路由键一致性哈希，节点增减时只有少量用户迁移；哈希环本身决定归属，不使用会话绑定
+///
+/// 优先使用集群共享的哈希环（与服务器列表一致），键所属的服务器未连接时不会改选其他服务器；
+/// 没有共享哈希环时使用按在线服务器维护的本地哈希环
+pub struct ConsistentHashStrategy {
+    ring: HashRing,
+}
+
+impl ConsistentHashStrategy {
+    pub fn new(virtual_nodes: u32) -> Self {
+        Self {
+            ring: HashRing::new(virtual_nodes),
+        }
+    }
+
+    /// 使本地哈希环上的节点与在线服务器一致
+    fn sync_ring(&mut self, candidates: &[&BackSession]) {
+        let server_ids: Vec<u32> = candidates.iter().map(|session| session.get_server_id()).collect();
+        for server_id in self.ring.get_nodes() {
+            if !server_ids.contains(&server_id) {
+                self.ring.remove_node(server_id);
+            }
+        }
+        for server_id in server_ids {
+            self.ring.add_node(server_id);
+        }
+    }
+}
+
+impl LoadBalanceStrategy for ConsistentHashStrategy {
+    fn select(&mut self, candidates: &[&BackSession], route_key: Option<u64>, hash_ring: Option<&HashRing>) -> Option<u64> {
+        // 没有路由键（后端之间的调用）时退化为随机选择
+        let route_key = match route_key {
+            Some(route_key) => route_key,
+            None => return RandomStrategy.select(candidates, None, None),
+        };
+
+        let server_id = match hash_ring.filter(|ring| !ring.is_empty()) {
+            Some(ring) => ring.get_node(route_key)?,
+            None => {
+                self.sync_ring(candidates);
+                self.ring.get_node(route_key)?
+            }
+        };
+
+        let session_id = candidates.iter()
+            .find(|session| session.get_server_id() == server_id)
+            .map(|session| session.get_session_id());
+        if session_id.is_none() {
+            warn!("Server {} owning route key {} is not connected", server_id, route_key);
+        }
+        session_id
+    }
+
+    fn is_sticky(&self) -> bool {
+        false
+    }
+}
+
+/// 选择心跳上报负载最低的服务器
+pub struct LeastLoadStrategy {
+    next_index: usize,
+}
+
+impl LeastLoadStrategy {
+    pub fn new() -> Self {
+        Self { next_index: 0 }
+    }
+}
+
+impl LoadBalanceStrategy for LeastLoadStrategy {
+    fn select(&mut self, candidates: &[&BackSession], _route_key: Option<u64>, _hash_ring: Option<&HashRing>) -> Option<u64> {
+        let start = self.next_index;
+        self.next_index = self.next_index.wrapping_add(1);
+        select_min_by_key(candidates, start, |session| session.get_reported_load())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(session_id: u64, server_id: u32, load: u32) -> BackSession {
+        let mut session = BackSession::new(session_id, server_id, None, None);
+        session.set_reported_load(load);
+        session
+    }
+
+    #[test]
+    fn least_load_selects_least_loaded_server() {
+        let sessions = [session(1, 101, 30), session(2, 102, 5), session(3, 103, 12)];
+        let candidates: Vec<&BackSession> = sessions.iter().collect();
+        let mut strategy = LeastLoadStrategy::new();
+        for _ in 0..candidates.len() {
+            assert_eq!(strategy.select(&candidates, None, None), Some(2));
+        }
+    }
+
+    #[test]
+    fn least_load_follows_reported_load_changes() {
+        let mut sessions = [session(1, 101, 0), session(2, 102, 8)];
+        let mut strategy = LeastLoadStrategy::new();
+        let candidates: Vec<&BackSession> = sessions.iter().collect();
+        assert_eq!(strategy.select(&candidates, None, None), Some(1));
+
+        sessions[0].set_reported_load(20);
+        let candidates: Vec<&BackSession> = sessions.iter().collect();
+        assert_eq!(strategy.select(&candidates, None, None), Some(2));
+    }
+
+    #[test]
+    fn least_load_rotates_between_equal_loads() {
+        let sessions = [session(1, 101, 3), session(2, 102, 3), session(3, 103, 9)];
+        let candidates: Vec<&BackSession> = sessions.iter().collect();
+        let mut strategy = LeastLoadStrategy::new();
+        assert_eq!(strategy.select(&candidates, None, None), Some(1));
+        assert_eq!(strategy.select(&candidates, None, None), Some(2));
+    }
+}
@@ -1,9 +1,18 @@
 pub mod router_manager;
+pub mod load_balance;
+pub mod hash_ring;
+pub mod hash_ring_manager;
 pub mod rpc_manager;
+pub mod rpc_call;
 pub mod forward_manager;
+pub mod broadcast_manager;
 pub mod rpc_message_dispatcher;
 
 pub use router_manager::RouterManager;
+pub use load_balance::{LoadBalanceKind, LoadBalanceStrategy};
+pub use hash_ring::HashRing;
 pub use rpc_manager::RpcManager;
+pub use rpc_call::{RpcError, RpcHandlerResult};
 pub use forward_manager::ForwardManager;
-pub use rpc_message_dispatcher::RpcMessageDispatcher;
\ No newline at end of file
+pub use broadcast_manager::BroadcastManager;
+pub use rpc_message_dispatcher::RpcMessageDispatcher;
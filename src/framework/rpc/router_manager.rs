@@ -1,3 +1,7 @@
+use super::{LoadBalanceKind, LoadBalanceStrategy};
+use super::load_balance::create_strategy;
+use super::hash_ring_manager::HashRingManager;
+use crate::framework::config::config::Config;
 use crate::framework::session::{FrontSession, BackSessionManager};
 use crate::framework::session::session_trait::SessionTrait;
 use std::collections::HashMap;
@@ -16,77 +20,182 @@ pub type RouterFunction = Box<dyn Fn(
     &BackSessionManager,
 ) -> Option<u64> + Send + Sync>;
 
-/// 路由管理器，管理多个路由函数
+/// 路由管理器，管理多个路由函数和按服务器类型配置的负载均衡策略
+/// 
+/// 路由顺序：
+/// 1. 该服务器类型注册了自定义路由函数时，完全交给路由函数
+/// 2. 会话亲和：策略支持绑定且前端会话已绑定该类型的在线服务器时，路由到绑定的服务器
+/// 3. 按负载均衡策略（未配置时为随机）从在线服务器中选择，并把服务器ID绑定到前端会话元数据中
 pub struct RouterManager {
     /// 路由函数映射表 <服务器类型, 路由函数>
     routers: HashMap<String, RouterFunction>,
-    /// 默认路由函数，用于未配置的server_type
-    default_router: RouterFunction,
+    /// 负载均衡策略 <服务器类型, 策略>
+    strategies: HashMap<String, Box<dyn LoadBalanceStrategy>>,
+    /// 配置的策略类型 <服务器类型, 策略类型>，未配置的服务器类型使用随机策略
+    strategy_kinds: HashMap<String, LoadBalanceKind>,
+    /// 服务器权重 <服务器ID, 权重>
+    server_weights: HashMap<u32, u32>,
+    /// 按服务器类型划分的一致性哈希环
+    hash_ring_manager: HashRingManager,
 }
 
 impl RouterManager {
     /// 创建新的路由管理器
     pub fn new() -> Self {
-        // 默认路由函数：优先检查 front session metadata，否则随机选择
-        let default_router: RouterFunction = Box::new(|target_server_type, mut front_session, back_session_manager| {
-            debug!("Default router handling: {}", target_server_type);
-            
-            // 首先尝试从 front session metadata 获取
-            if let Some(ref front) = front_session {
-                let metadata = front.get_metadata();
-                if let Some(server_id) = metadata.get_server_id(target_server_type) {
-                    info!("Found server ID {} from metadata for type: {}", server_id, target_server_type);
-                    
-                    // 检查该 server_id 对应的会话是否存在
-                    if back_session_manager.get_session(server_id as u64).is_some() {
-                        debug!("Found back session with ID: {}", server_id);
-                        return Some(server_id as u64);
-                    } else {
-                        warn!("Server ID {} from metadata not found in active sessions", server_id);
-                    }
+        Self {
+            routers: HashMap::new(),
+            strategies: HashMap::new(),
+            strategy_kinds: HashMap::new(),
+            server_weights: HashMap::new(),
+            hash_ring_manager: HashRingManager::new(),
+        }
+    }
+
+    /// 初始化路由管理器，读取各服务器类型的负载均衡策略和服务器权重
+    pub fn init(&mut self, config: &Config) -> bool {
+        for group in &config.servers.group {
+            for server in &group.server {
+                self.server_weights.insert(server.id, server.weight);
+            }
+        }
+
+        for router_config in &config.routers.router {
+            match LoadBalanceKind::parse(&router_config.strategy) {
+                Some(kind) => {
+                    info!("Server type {} uses load balance strategy {:?}", router_config.server_type, kind);
+                    self.strategy_kinds.insert(router_config.server_type.clone(), kind);
+                }
+                None => {
+                    error!("Unknown load balance strategy '{}' for server type {}", router_config.strategy, router_config.server_type);
+                    return false;
                 }
             }
-            
-            // 如果没有 front session 或获取 server_id 失败，随机选择一个
-            debug!("Attempting to randomly select a back session for type: {}", target_server_type);
-            
-            // 获取指定类型的所有活跃会话
-            let active_sessions = back_session_manager.get_active_sessions(target_server_type);
-            
-            if !active_sessions.is_empty() {
-                // 随机选择一个会话
-                use rand::Rng;
-                let mut rng = rand::thread_rng();
-                let index = rng.gen_range(0..active_sessions.len());
-                let selected_session = &active_sessions[index];
-                let session_id = selected_session.get_session_id();
-                
-                info!("Selected back session {} for type: {} (from {} available)", 
-                      session_id, target_server_type, active_sessions.len());
-                
-                // 如果有front session，将选中的server_id设置到metadata中
-                if let Some(ref mut front) = front_session {
-                    let metadata = front.get_metadata_mut();
-                    metadata.add_server_meta(target_server_type.to_string(), session_id as u32);
-                    debug!("Set server_id {} for type {} in front session metadata", session_id, target_server_type);
+        }
+        true
+    }
+
+    /// 设置服务器类型的负载均衡策略，替换配置中的策略
+    pub fn set_strategy(&mut self, server_type: &str, strategy: Box<dyn LoadBalanceStrategy>) {
+        self.strategies.insert(server_type.to_string(), strategy);
+        info!("Set custom load balance strategy for server type: {}", server_type);
+    }
+
+    /// 获取服务器类型配置的策略类型，未配置时为随机
+    pub fn get_strategy_kind(&self, server_type: &str) -> LoadBalanceKind {
+        self.strategy_kinds.get(server_type).copied().unwrap_or(LoadBalanceKind::Random)
+    }
+
+    /// 确保服务器类型的策略实例存在，不存在时按配置创建
+    fn ensure_strategy(&mut self, server_type: &str) {
+        if !self.strategies.contains_key(server_type) {
+            let strategy = create_strategy(self.get_strategy_kind(server_type), &self.server_weights);
+            self.strategies.insert(server_type.to_string(), strategy);
+        }
+    }
+
+    /// 获取哈希环管理器
+    pub fn get_hash_ring_manager(&self) -> &HashRingManager {
+        &self.hash_ring_manager
+    }
+
+    /// 获取可变哈希环管理器，用于监听区间迁移
+    pub fn get_hash_ring_manager_mut(&mut self) -> &mut HashRingManager {
+        &mut self.hash_ring_manager
+    }
+
+    /// 按一致性哈希获取用户ID在指定服务器类型中所属的服务器ID
+    ///
+    /// 结果只取决于集群的服务器列表，所有节点对同一个用户ID得到同样的服务器，服务器不一定已连接
+    pub fn get_server_by_user_id(&self, server_type: &str, user_id: u64) -> Option<u32> {
+        self.hash_ring_manager.get_node(server_type, user_id)
+    }
+
+    /// 按会话的用户ID获取所属的服务器ID，会话未登录时返回None
+    pub fn get_server_by_session<S: SessionTrait>(&self, server_type: &str, session: &S) -> Option<u32> {
+        self.get_server_by_user_id(server_type, session.get_user_id()?)
+    }
+
+    /// 为目标服务器类型选择后端会话
+    /// 
+    /// # 参数
+    /// * `target_server_type` - 目标服务器类型
+    /// * `front_session` - 前端会话（前端发起的请求），用于会话亲和和绑定
+    /// * `back_session_manager` - 后端会话管理器
+    /// 
+    /// # 返回值
+    /// 选中的后端会话ID，没有可用服务器时返回None
+    pub fn route(
+        &mut self,
+        target_server_type: &str,
+        front_session: Option<&mut FrontSession>,
+        back_session_manager: &BackSessionManager,
+    ) -> Option<u64> {
+        if let Some(router_fn) = self.routers.get(target_server_type) {
+            return router_fn(target_server_type, front_session, back_session_manager);
+        }
+        
+        self.ensure_strategy(target_server_type);
+        let hash_ring = self.hash_ring_manager.get_ring(target_server_type);
+        let strategy = self.strategies.get_mut(target_server_type)?;
+        let sticky = strategy.is_sticky();
+        
+        // 首先尝试从 front session metadata 获取绑定的服务器
+        if sticky {
+            if let Some(front) = front_session.as_deref() {
+                if let Some(server_id) = front.get_metadata().get_server_id(target_server_type) {
+                    match Self::find_server_session(back_session_manager, target_server_type, server_id) {
+                        Some(session_id) => {
+                            debug!("Routing type {} to bound server {} (back session {})", target_server_type, server_id, session_id);
+                            return Some(session_id);
+                        }
+                        None => {
+                            warn!("Bound server {} of type {} is offline, rebinding front session {}",
+                                  server_id, target_server_type, front.get_session_id());
+                        }
+                    }
                 }
-                
-                return Some(session_id);
             }
-            
+        }
+        
+        // 没有绑定或绑定的服务器已下线，按策略选择
+        let mut candidates = back_session_manager.get_active_sessions(target_server_type);
+        if candidates.is_empty() {
             warn!("No active back sessions found for type: {}", target_server_type);
-            None
-        });
+            return None;
+        }
+        candidates.sort_by_key(|session| session.get_server_id());
         
-        Self {
-            routers: HashMap::new(),
-            default_router,
+        // 路由键：已登录用户使用用户ID，否则使用前端会话ID
+        let route_key = front_session.as_deref()
+            .map(|front| front.get_user_id().unwrap_or_else(|| front.get_session_id()));
+        
+        let session_id = strategy.select(&candidates, route_key, hash_ring)?;
+        let server_id = candidates.iter()
+            .find(|session| session.get_session_id() == session_id)
+            .map(|session| session.get_server_id())?;
+        
+        debug!("Selected server {} (back session {}) for type: {} (from {} available)", 
+               server_id, session_id, target_server_type, candidates.len());
+        
+        // 如果有front session，将选中的server_id绑定到metadata中
+        if sticky {
+            if let Some(front) = front_session {
+                front.get_metadata_mut().add_server_meta(target_server_type.to_string(), server_id);
+                debug!("Bound server_id {} for type {} in front session metadata", server_id, target_server_type);
+            }
         }
+        
+        Some(session_id)
     }
 
-    /// 初始化路由管理器
-    pub fn init(&mut self) -> bool {
-        true
+    /// 查找指定服务器ID对应的在线后端会话，服务器类型不一致时视为不存在
+    /// 
+    /// # 返回值
+    /// 在线时返回后端会话ID，否则返回None
+    pub fn find_server_session(back_session_manager: &BackSessionManager, server_type: &str, server_id: u32) -> Option<u64> {
+        back_session_manager.find_session_by_server_id(server_id)
+            .filter(|session| session.is_connected() && session.get_server_type().is_some_and(|s| s == server_type))
+            .map(|session| session.get_session_id())
     }
 
     /// 添加路由函数
@@ -131,9 +240,9 @@ impl RouterManager {
     /// * `server_type` - 服务器类型
     /// 
     /// # 返回值
-    /// 路由函数的引用，如果不存在返回默认路由函数
-    pub fn get_router(&self, server_type: &str) -> &RouterFunction {
-        self.routers.get(server_type).unwrap_or(&self.default_router)
+    /// 路由函数的引用，没有注册自定义路由函数时返回None
+    pub fn get_router(&self, server_type: &str) -> Option<&RouterFunction> {
+        self.routers.get(server_type)
     }
 
     /// 检查是否存在指定类型的路由器
@@ -162,8 +271,10 @@ impl RouterManager {
     pub fn dispose(&mut self) {
         info!("Disposing RouterManager with {} routers", self.routers.len());
         
-        // 清空所有路由函数
+        // 清空所有路由函数和策略
         self.routers.clear();
+        self.strategies.clear();
+        self.hash_ring_manager.dispose();
         
         info!("RouterManager disposed");
     }
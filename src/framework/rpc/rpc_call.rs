@@ -0,0 +1,185 @@
+use crate::proto::messages::MessageIdSerialize;
+use crate::proto::messages::protobuf::message::cluster::{RpcForwardMessageBResponse, RpcMessageFResponse};
+use std::any::Any;
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::warn;
+
+/// 默认RPC调用超时时间（毫秒）
+pub const DEFAULT_RPC_TIMEOUT_MS: u64 = 5000;
+
+/// 业务错误码的起始值，小于该值的错误码由框架保留
+pub const RPC_ERROR_CODE_USER_BEGIN: u32 = 1000;
+
+/// 框架定义的RPC错误码，对应响应信封中的error_code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcErrorCode {
+    /// 框架内部错误
+    Internal = 1,
+    /// 没有可用的目标类型服务器
+    NoServer = 2,
+    /// 未知的消息ID或目标服务器没有注册处理器
+    UnknownMessage = 3,
+    /// 请求消息解码失败
+    DecodeFailed = 4,
+    /// 请求处理器执行时panic
+    HandlerPanicked = 5,
+    /// 在超时时间内没有收到响应
+    Timeout = 6,
+    /// 响应返回前目标服务器连接已断开
+    Disconnected = 7,
+}
+
+impl RpcErrorCode {
+    /// 从协议中的数值转换，业务错误码返回None
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(RpcErrorCode::Internal),
+            2 => Some(RpcErrorCode::NoServer),
+            3 => Some(RpcErrorCode::UnknownMessage),
+            4 => Some(RpcErrorCode::DecodeFailed),
+            5 => Some(RpcErrorCode::HandlerPanicked),
+            6 => Some(RpcErrorCode::Timeout),
+            7 => Some(RpcErrorCode::Disconnected),
+            _ => None,
+        }
+    }
+}
+
+/// RPC调用失败原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcError {
+    /// 在超时时间内没有收到响应
+    Timeout,
+    /// 响应返回前目标后端会话已断开
+    Disconnected,
+    /// 响应消息解码失败
+    DecodeFailed,
+    /// 响应消息类型与调用方期望的类型不一致
+    UnexpectedResponse,
+    /// 对端请求处理器返回了错误
+    Remote { code: u32, message: String },
+}
+
+/// RPC调用结果，成功时为解码后的响应消息
+pub type RpcResult = Result<Box<dyn Any + Send>, RpcError>;
+
+/// RPC调用回调函数类型，在主线程中调用且只会调用一次
+pub type RpcCallback = Box<dyn FnOnce(RpcResult) + Send>;
+
+/// 转发给后端、等待响应回传给前端会话的请求
+pub struct PendingForwardRequest {
+    /// 发送请求所用的后端会话ID
+    pub back_session_id: u64,
+    /// 请求的内部消息ID
+    pub msg_id: u32,
+    /// 超时时间点
+    pub deadline: Instant,
+}
+
+/// 等待响应的RPC调用
+pub struct PendingRpcCall {
+    /// 发送请求所用的后端会话ID
+    pub back_session_id: u64,
+    /// 请求的内部消息ID
+    pub msg_id: u32,
+    /// 超时时间点
+    pub deadline: Instant,
+    /// 响应回调
+    pub callback: RpcCallback,
+}
+
+/// RPC请求处理器返回的结构化错误，会以error_code/error_message回传给调用方
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcHandlerError {
+    /// 错误码，必须非0（0表示成功）
+    pub code: u32,
+    /// 错误描述
+    pub message: String,
+}
+
+impl RpcHandlerError {
+    /// 创建处理器错误，业务错误码应不小于RPC_ERROR_CODE_USER_BEGIN，否则回传时会被改为Internal
+    pub fn new(code: u32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// 创建框架错误
+    pub fn from_code(code: RpcErrorCode, message: impl Into<String>) -> Self {
+        Self::new(code as u32, message)
+    }
+
+    /// 校验错误码：0和框架保留但未定义的错误码会被调用方误判，统一改为Internal
+    fn normalize(self) -> Self {
+        if self.code >= RPC_ERROR_CODE_USER_BEGIN || RpcErrorCode::from_u32(self.code).is_some() {
+            return self;
+        }
+        warn!("Rpc handler returned reserved error code {} ({}), user error codes must be >= {}",
+            self.code, self.message, RPC_ERROR_CODE_USER_BEGIN);
+        Self::from_code(RpcErrorCode::Internal, self.message)
+    }
+}
+
+/// RPC请求处理器的返回值
+pub type RpcHandlerResult<R> = Result<R, RpcHandlerError>;
+
+/// 已编码的RPC响应消息
+#[derive(Debug, Clone)]
+pub struct RpcResponse {
+    /// 响应消息ID
+    pub msg_id: u32,
+    /// prost编码后的响应消息内容（不含消息头）
+    pub message: Vec<u8>,
+}
+
+impl RpcResponse {
+    /// 编码类型化的响应消息
+    pub fn from_message<R: MessageIdSerialize>(response: &R) -> Self {
+        Self {
+            msg_id: R::MSG_ID as u32,
+            message: response.encode_to_vec(),
+        }
+    }
+}
+
+/// 根据处理结果构造回传给调用方的RpcForwardMessageBResponse
+pub fn build_forward_response(msg_unique_id: u32, front_session_id: u64, result: RpcHandlerResult<RpcResponse>) -> RpcForwardMessageBResponse {
+    match result {
+        Ok(response) => RpcForwardMessageBResponse {
+            msg_unique_id,
+            front_session_id,
+            meta: HashMap::new(),
+            msg_id: response.msg_id,
+            message: response.message,
+            error_code: 0,
+            error_message: String::new(),
+        },
+        Err(error) => {
+            let error = error.normalize();
+            RpcForwardMessageBResponse {
+                msg_unique_id,
+                front_session_id,
+                meta: HashMap::new(),
+                msg_id: 0,
+                message: Vec::new(),
+                error_code: error.code,
+                error_message: error.message,
+            }
+        }
+    }
+}
+
+/// 构造返回给前端会话的错误响应
+pub fn build_front_error_response(msg_unique_id: u32, error: RpcHandlerError) -> RpcMessageFResponse {
+    let error = error.normalize();
+    RpcMessageFResponse {
+        msg_unique_id,
+        msg_id: 0,
+        message: Vec::new(),
+        error_code: error.code,
+        error_message: error.message,
+    }
+}
@@ -1,8 +1,19 @@
 use super::router_manager::{RouterManager, RouterFunction};
+use super::rpc_call::{
+    build_forward_response, build_front_error_response, PendingForwardRequest, PendingRpcCall, RpcCallback, RpcError,
+    RpcErrorCode, RpcHandlerError, RpcHandlerResult, RpcResponse, RpcResult, DEFAULT_RPC_TIMEOUT_MS,
+};
+use crate::framework::cluster::{UserDirectory, UserLocation};
+use crate::framework::config::config::Config;
+use crate::framework::data::DynamicBuffer;
 use crate::framework::session::{BackSessionManager, FrontSessionManager, FrontSession};
-use crate::proto::messages::protobuf::message::cluster::RpcForwardMessageBResponse;
+use crate::proto::messages::protobuf::message::cluster::{RpcForwardMessageBRequest, RpcForwardMessageBResponse, RpcPushMessageBNotify};
+use crate::proto::messages::protobuf::message::protobuf_message_id::MessageFactory;
 use crate::proto::messages::MessageIdSerialize;
-use tracing::{info, error, debug};
+use crate::framework::session::session_trait::SessionTrait;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tracing::{info, error, debug, warn};
 
 /// RPC管理器，负责管理RPC调用和路由
 /// 
@@ -15,8 +26,25 @@ pub struct RpcManager {
     front_session_manager: *mut FrontSessionManager,
     /// 后端会话管理器指针
     back_session_manager: *mut BackSessionManager,
+    /// 等待响应的RPC调用表 <msg_unique_id, 调用信息>
+    pending_calls: HashMap<u32, PendingRpcCall>,
+    /// 等待响应回传给前端会话的转发请求表 <(前端会话ID, msg_unique_id), 请求信息>
+    pending_forwards: HashMap<(u64, u32), PendingForwardRequest>,
+    /// 下一个可用的消息唯一ID
+    next_msg_unique_id: u32,
+    /// 承载前端会话的服务器ID（配置了front_tcp_port或front_ws_port）
+    front_server_ids: HashSet<u32>,
+    /// 在线用户目录指针，用于查询推送目标用户所在的前端服务器
+    user_directory: *mut UserDirectory,
 }
 
+// 包装器结构体，使原始指针满足Send + Sync要求（仅用于满足闭包类型约束）
+// 实际运行时所有调用都在主线程，不会有并发访问
+#[derive(Clone, Copy)]
+struct RpcManagerPtr(*mut RpcManager);
+unsafe impl Send for RpcManagerPtr {}
+unsafe impl Sync for RpcManagerPtr {}
+
 impl RpcManager {
     /// 创建新的RPC管理器
     /// 
@@ -26,6 +54,11 @@ impl RpcManager {
             router_manager: RouterManager::new(),
             front_session_manager: std::ptr::null_mut(),
             back_session_manager: std::ptr::null_mut(),
+            pending_calls: HashMap::new(),
+            pending_forwards: HashMap::new(),
+            next_msg_unique_id: 1,
+            front_server_ids: HashSet::new(),
+            user_directory: std::ptr::null_mut(),
         }
     }
 
@@ -36,23 +69,38 @@ impl RpcManager {
     /// # 参数
     /// * `front_session_manager` - 前端会话管理器
     /// * `back_session_manager` - 后端会话管理器
+    /// * `config` - 配置，用于读取负载均衡策略和服务器权重
     pub fn init(
         &mut self,
         front_session_manager: &mut FrontSessionManager,
         back_session_manager: &mut BackSessionManager,
+        config: &Config,
     ) -> bool {
         // 保存管理器指针
         self.front_session_manager = front_session_manager as *mut FrontSessionManager;
         self.back_session_manager = back_session_manager as *mut BackSessionManager;
         
-        if !self.router_manager.init() {
+        if !self.router_manager.init(config) {
             error!("Failed to initialize RouterManager");
             return false;
         }
 
+        self.front_server_ids = config.servers.group.iter()
+            .flat_map(|group| group.server.iter())
+            .filter(|server| server.front_tcp_port.is_some() || server.front_ws_port.is_some())
+            .map(|server| server.id)
+            .collect();
+
         true
     }
 
+    /// 设置在线用户目录，push_to_user通过目录查询用户所在的前端服务器
+    /// 
+    /// 注意：本方法在主线程调用
+    pub fn set_user_directory(&mut self, user_directory: &mut UserDirectory) {
+        self.user_directory = user_directory as *mut UserDirectory;
+    }
+
     /// 获取路由管理器的引用
     pub fn get_router_manager(&self) -> &RouterManager {
         &self.router_manager
@@ -96,6 +144,53 @@ impl RpcManager {
         self.router_manager.get_server_types()
     }
 
+    /// 将前端会话的指定服务器类型绑定到服务器ID，之后该类型的请求都路由到该服务器，直到服务器下线或解除绑定
+    /// 
+    /// 注意：本方法在主线程调用
+    /// 
+    /// # 返回值
+    /// 前端会话存在返回true，否则返回false
+    pub fn bind_server(&mut self, front_session_id: u64, server_type: &str, server_id: u32) -> bool {
+        match self.get_front_session_mut(front_session_id) {
+            Some(front_session) => front_session.get_metadata_mut().add_server_meta(server_type.to_string(), server_id),
+            None => {
+                warn!("Cannot bind server {} of type {}: front session {} not found", server_id, server_type, front_session_id);
+                false
+            }
+        }
+    }
+
+    /// 解除前端会话对指定服务器类型的绑定，下一次请求将重新选择服务器
+    /// 
+    /// 注意：本方法在主线程调用
+    /// 
+    /// # 返回值
+    /// 存在绑定并成功解除返回true，否则返回false
+    pub fn unbind_server(&mut self, front_session_id: u64, server_type: &str) -> bool {
+        self.get_front_session_mut(front_session_id)
+            .is_some_and(|front_session| front_session.get_metadata_mut().remove_server_meta(server_type))
+    }
+
+    /// 获取前端会话对指定服务器类型绑定的服务器ID
+    pub fn get_bound_server(&self, front_session_id: u64, server_type: &str) -> Option<u32> {
+        if self.front_session_manager.is_null() {
+            return None;
+        }
+        let front_session_manager = unsafe { &*self.front_session_manager };
+        front_session_manager.get_session(front_session_id)
+            .and_then(|front_session| front_session.get_metadata().get_server_id(server_type))
+    }
+
+    /// 获取前端会话
+    fn get_front_session_mut(&mut self, front_session_id: u64) -> Option<&mut FrontSession> {
+        if self.front_session_manager.is_null() {
+            error!("RpcManager not initialized with session managers");
+            return None;
+        }
+        let front_session_manager = unsafe { &mut *self.front_session_manager };
+        front_session_manager.get_session_mut(front_session_id)
+    }
+
     /// 发送通知消息到指定服务器类型（带前端会话）
     /// 
     /// 注意：本方法在主线程调用
@@ -124,15 +219,12 @@ impl RpcManager {
         
         debug!("Sending notify to server type: {} with front session", server_type);
         
-        // 获取路由函数
-        let router_fn = self.router_manager.get_router(server_type);
-        
         // 安全地使用管理器指针
         unsafe {
             let back_session_manager = &mut *self.back_session_manager;
             
-            // 执行路由函数，获取目标后端会话ID
-            if let Some(target_session_id) = router_fn(
+            // 路由获取目标后端会话ID
+            if let Some(target_session_id) = self.router_manager.route(
                 server_type,
                 Some(front_session),
                 back_session_manager
@@ -170,7 +262,7 @@ impl RpcManager {
     /// 成功发送返回true，无法找到有效的后端会话返回false
     pub fn call_to_server<T>(
         &mut self,
-        server_id: u64,
+        server_id: u32,
         msg: T,
     ) -> bool 
     where
@@ -188,82 +280,615 @@ impl RpcManager {
         unsafe {
             let back_session_manager = &mut *self.back_session_manager;
             
-            // 直接根据server_id获取会话并发送消息
-            if let Some(back_session) = back_session_manager.get_session_mut(server_id) {
+            // 根据server_id查找已授权的后端会话并发送消息
+            if let Some(back_session) = back_session_manager.find_session_by_server_id_mut(server_id) {
                 if back_session.send_message(msg) {
-                    debug!("Successfully sent notify to back session {}", server_id);
+                    debug!("Successfully sent notify to server {}", server_id);
                     return true;
                 } else {
-                    error!("Failed to send message to back session {}", server_id);
+                    error!("Failed to send message to server {}", server_id);
                 }
             } else {
-                error!("Back session {} not found", server_id);
+                error!("No back session found for server {}", server_id);
             }
         }
         
         false
     }
 
-    /// 发送响应转发消息到前端会话
+    /// 主动推送消息给指定服务器上的前端会话
+    /// 
+    /// 前端会话所在的服务器收到后封装为RpcPushMessageFNotify发给客户端，会话已断开时消息被丢弃
     /// 
     /// 注意：本方法在主线程调用
     /// 
     /// # 参数
-    /// * `msg_unique_id` - 消息唯一ID
+    /// * `session_server_id` - 前端会话所在的服务器ID（如请求处理器收到请求的后端会话的服务器ID）
     /// * `front_session_id` - 前端会话ID
-    /// * `response_msg` - 响应消息（必须实现MessageIdSerialize trait）
+    /// * `msg` - 要推送的消息
+    /// 
+    /// # 返回值
+    /// 成功发送到前端会话所在的服务器返回true
+    pub fn push_to_front_session<T>(&mut self, session_server_id: u32, front_session_id: u64, msg: &T) -> bool
+    where
+        T: MessageIdSerialize,
+    {
+        let push_notify = RpcPushMessageBNotify {
+            front_session_id,
+            user_id: 0,
+            msg_id: T::MSG_ID as u32,
+            message: msg.encode_to_vec(),
+        };
+        self.call_to_server(session_server_id, push_notify)
+    }
+
+    /// 主动推送消息给指定用户
+    /// 
+    /// 先通过在线用户目录查询用户所在的前端服务器，只发给该服务器，由其封装为RpcPushMessageFNotify发给客户端；
+    /// 用户不在线时丢弃。目录不可用或查询失败时无法确定位置，改为发给所有已连接的前端服务器
+    /// 
+    /// 注意：本方法在主线程调用
+    /// 
+    /// # 参数
+    /// * `user_id` - 目标用户ID
+    /// * `msg` - 要推送的消息
+    /// 
+    /// # 返回值
+    /// 已发出目录查询，或至少发给了一个前端服务器返回true
+    pub fn push_to_user<T>(&mut self, user_id: u64, msg: &T) -> bool
+    where
+        T: MessageIdSerialize,
+    {
+        if self.back_session_manager.is_null() {
+            error!("RpcManager not initialized with session managers");
+            return false;
+        }
+
+        let push_notify = RpcPushMessageBNotify {
+            front_session_id: 0,
+            user_id,
+            msg_id: T::MSG_ID as u32,
+            message: msg.encode_to_vec(),
+        };
+
+        if !self.user_directory.is_null() {
+            let rpc_manager_ptr = RpcManagerPtr(self as *mut RpcManager);
+            let located_notify = push_notify.clone();
+            let user_directory = unsafe { &mut *self.user_directory };
+            let located = user_directory.locate(user_id, move |result| {
+                let ptr = &rpc_manager_ptr;
+                let rpc_manager = unsafe { &mut *ptr.0 };
+                rpc_manager.push_to_location(user_id, located_notify, result);
+            });
+            if located {
+                return true;
+            }
+            debug!("Directory unavailable for user {}, pushing to all front servers", user_id);
+        }
+
+        self.push_to_all_front_servers(push_notify) > 0
+    }
+
+    /// 按目录查询结果发送推送
+    fn push_to_location(&mut self, user_id: u64, push_notify: RpcPushMessageBNotify, result: Result<Option<UserLocation>, RpcError>) {
+        match result {
+            Ok(Some(location)) => {
+                if self.call_to_server(location.server_id, push_notify) {
+                    debug!("Sent push for user {} to front server {}", user_id, location.server_id);
+                } else {
+                    warn!("Failed to send push for user {} to front server {}", user_id, location.server_id);
+                }
+            }
+            Ok(None) => {
+                debug!("User {} is offline, push msg_id={} dropped", user_id, push_notify.msg_id);
+            }
+            Err(error) => {
+                warn!("Failed to locate user {} for push: {:?}, pushing to all front servers", user_id, error);
+                self.push_to_all_front_servers(push_notify);
+            }
+        }
+    }
+
+    /// 把推送发给所有已连接的前端服务器，由用户在线的服务器投递
+    /// 
+    /// # 返回值
+    /// 推送发往的前端服务器数量
+    fn push_to_all_front_servers(&mut self, push_notify: RpcPushMessageBNotify) -> usize {
+        let back_session_manager = unsafe { &mut *self.back_session_manager };
+        let mut sent_count = 0;
+        for &server_id in &self.front_server_ids {
+            if let Some(back_session) = back_session_manager.find_session_by_server_id_mut(server_id) {
+                if back_session.send_message(push_notify.clone()) {
+                    sent_count += 1;
+                } else {
+                    error!("Failed to send push for user {} to server {}", push_notify.user_id, server_id);
+                }
+            }
+        }
+
+        if sent_count == 0 {
+            warn!("No front server connected, push for user {} dropped", push_notify.user_id);
+        } else {
+            debug!("Sent push msg_id={} for user {} to {} front servers", push_notify.msg_id, push_notify.user_id, sent_count);
+        }
+        sent_count
+    }
+
+    /// 承载前端会话的服务器ID
+    pub fn get_front_server_ids(&self) -> &HashSet<u32> {
+        &self.front_server_ids
+    }
+
+    /// 将前端会话的RPC请求转发到指定服务器类型，并记录到等待回传表
+    /// 
+    /// 请求在超时时间内没有收到响应或目标连接断开时，由update向前端会话回传错误响应
+    /// 
+    /// 注意：本方法在主线程调用
     /// 
     /// # 返回值
-    /// 成功发送返回true，无法找到前端会话返回false
+    /// 请求发出返回Ok；无可用服务器或发送失败返回Err，调用方负责把错误回传给前端会话
+    pub fn forward_request(
+        &mut self,
+        server_type: &str,
+        forward_request: RpcForwardMessageBRequest,
+        front_session: &mut FrontSession,
+    ) -> Result<(), RpcHandlerError> {
+        if self.back_session_manager.is_null() {
+            error!("RpcManager not initialized with session managers");
+            return Err(RpcHandlerError::from_code(RpcErrorCode::Internal, "rpc manager not initialized"));
+        }
+
+        let front_session_id = front_session.get_session_id();
+        let msg_unique_id = forward_request.msg_unique_id;
+        let msg_id = forward_request.msg_id;
+        unsafe {
+            let back_session_manager = &mut *self.back_session_manager;
+
+            let back_session_id = match self.router_manager.route(server_type, Some(front_session), back_session_manager) {
+                Some(back_session_id) => back_session_id,
+                None => {
+                    debug!("No suitable back session found for server type: {}", server_type);
+                    return Err(RpcHandlerError::from_code(RpcErrorCode::NoServer, format!("no available server of type {}", server_type)));
+                }
+            };
+
+            match back_session_manager.get_session_mut(back_session_id) {
+                Some(back_session) => {
+                    if !back_session.send_message(forward_request) {
+                        error!("Failed to send forwarded request msg_id={} to back session {}", msg_id, back_session_id);
+                        return Err(RpcHandlerError::from_code(RpcErrorCode::Disconnected, format!("failed to send request to server type {}", server_type)));
+                    }
+                    back_session.increase_pending_requests();
+                }
+                None => {
+                    error!("Back session {} not found", back_session_id);
+                    return Err(RpcHandlerError::from_code(RpcErrorCode::NoServer, format!("no available server of type {}", server_type)));
+                }
+            }
+
+            let replaced = self.pending_forwards.insert((front_session_id, msg_unique_id), PendingForwardRequest {
+                back_session_id,
+                msg_id,
+                deadline: Instant::now() + Duration::from_millis(DEFAULT_RPC_TIMEOUT_MS),
+            });
+            // 客户端重复使用了仍在等待的msg_unique_id，旧请求的响应将不再回传
+            if let Some(replaced) = replaced {
+                warn!("Front session {} reused pending msg_unique_id={}", front_session_id, msg_unique_id);
+                self.release_pending_request(replaced.back_session_id);
+            }
+            debug!("Forwarded request msg_unique_id={}, msg_id={} from front session {} to back session {}",
+                   msg_unique_id, msg_id, front_session_id, back_session_id);
+        }
+
+        Ok(())
+    }
+
+    /// 转发请求的响应已返回，从等待回传表中移除
+    /// 
+    /// 注意：本方法在主线程调用
+    /// 
+    /// # 参数
+    /// * `back_session_id` - 收到响应的后端会话ID，必须与请求发往的会话一致
+    /// 
+    /// # 返回值
+    /// 请求仍在等待中返回true；已超时、未知或来自其他会话的响应返回false，此时响应不应再回传给前端会话
+    pub fn complete_forward(&mut self, back_session_id: u64, front_session_id: u64, msg_unique_id: u32) -> bool {
+        let key = (front_session_id, msg_unique_id);
+        match self.pending_forwards.get(&key) {
+            Some(pending_forward) if pending_forward.back_session_id != back_session_id => {
+                warn!("Rejecting forwarded response msg_unique_id={} for front session {} from back session {}, expected {}",
+                      msg_unique_id, front_session_id, back_session_id, pending_forward.back_session_id);
+                return false;
+            }
+            Some(_) => {}
+            None => return false,
+        }
+        self.pending_forwards.remove(&key);
+        self.release_pending_request(back_session_id);
+        true
+    }
+
+    /// 请求收到响应、超时或失败后，减少后端会话上等待响应的请求计数
+    fn release_pending_request(&mut self, back_session_id: u64) {
+        if self.back_session_manager.is_null() {
+            return;
+        }
+        let back_session_manager = unsafe { &mut *self.back_session_manager };
+        if let Some(back_session) = back_session_manager.get_session_mut(back_session_id) {
+            back_session.decrease_pending_requests();
+        }
+    }
+
+    /// 向指定服务器类型发起RPC请求，并在收到响应或超时后回调
+    /// 
+    /// 注意：本方法在主线程调用，回调同样在主线程执行
+    /// 
+    /// # 参数
+    /// * `server_type` - 目标服务器类型，通过路由函数选择具体服务器
+    /// * `msg` - 请求消息
+    /// * `timeout_ms` - 超时时间（毫秒），0表示使用默认超时
+    /// * `callback` - 响应回调，参数为类型化的响应消息或错误
+    /// 
+    /// # 返回值
+    /// 请求发出返回true，此时回调保证会被调用一次；
+    /// 无可用服务器或发送失败返回false，回调不会被调用
+    pub fn request<Req, Resp, F>(
+        &mut self,
+        server_type: &str,
+        msg: Req,
+        timeout_ms: u64,
+        callback: F,
+    ) -> bool
+    where
+        Req: MessageIdSerialize,
+        Resp: 'static,
+        F: FnOnce(Result<Resp, RpcError>) + Send + 'static,
+    {
+        if self.back_session_manager.is_null() {
+            error!("RpcManager not initialized with session managers");
+            return false;
+        }
+
+        let back_session_id = unsafe {
+            let back_session_manager = &*self.back_session_manager;
+            self.router_manager.route(server_type, None, back_session_manager)
+        };
+
+        match back_session_id {
+            Some(back_session_id) => {
+                self.send_request(back_session_id, msg, timeout_ms, Self::typed_callback::<Resp, F>(callback))
+            }
+            None => {
+                error!("No available back session for RPC request to server type {}", server_type);
+                false
+            }
+        }
+    }
+
+    /// 向指定服务器ID发起RPC请求，并在收到响应或超时后回调
+    /// 
+    /// 注意：本方法在主线程调用，回调同样在主线程执行
+    /// 
+    /// # 参数
+    /// * `server_id` - 目标服务器ID
+    /// * `msg` - 请求消息
+    /// * `timeout_ms` - 超时时间（毫秒），0表示使用默认超时
+    /// * `callback` - 响应回调，参数为类型化的响应消息或错误
+    /// 
+    /// # 返回值
+    /// 请求发出返回true，此时回调保证会被调用一次；
+    /// 找不到服务器或发送失败返回false，回调不会被调用
+    pub fn request_to_server<Req, Resp, F>(
+        &mut self,
+        server_id: u32,
+        msg: Req,
+        timeout_ms: u64,
+        callback: F,
+    ) -> bool
+    where
+        Req: MessageIdSerialize,
+        Resp: 'static,
+        F: FnOnce(Result<Resp, RpcError>) + Send + 'static,
+    {
+        if self.back_session_manager.is_null() {
+            error!("RpcManager not initialized with session managers");
+            return false;
+        }
+
+        let back_session_id = unsafe {
+            let back_session_manager = &*self.back_session_manager;
+            back_session_manager.find_session_by_server_id(server_id).map(|session| session.get_session_id())
+        };
+
+        match back_session_id {
+            Some(back_session_id) => {
+                self.send_request(back_session_id, msg, timeout_ms, Self::typed_callback::<Resp, F>(callback))
+            }
+            None => {
+                error!("No back session found for RPC request to server {}", server_id);
+                false
+            }
+        }
+    }
+
+    /// 通过指定后端会话发送RPC请求，并记录到等待响应表
+    /// 
+    /// 注意：本方法在主线程调用
+    /// 
+    /// # 返回值
+    /// 发送成功返回true，失败返回false且不会调用回调
+    pub fn send_request<Req>(
+        &mut self,
+        back_session_id: u64,
+        msg: Req,
+        timeout_ms: u64,
+        callback: RpcCallback,
+    ) -> bool
+    where
+        Req: MessageIdSerialize,
+    {
+        if self.back_session_manager.is_null() {
+            error!("RpcManager not initialized with session managers");
+            return false;
+        }
+
+        let msg_unique_id = self.generate_msg_unique_id();
+        let msg_id = msg.msg_id() as u32;
+
+        // 后端之间的请求没有前端会话，front_session_id固定为0
+        let forward_request = RpcForwardMessageBRequest {
+            msg_unique_id,
+            front_session_id: 0,
+            meta: HashMap::new(),
+            msg_id,
+            message: msg.encode_to_vec(),
+        };
+
+        unsafe {
+            let back_session_manager = &mut *self.back_session_manager;
+            match back_session_manager.get_session_mut(back_session_id) {
+                Some(back_session) => {
+                    if !back_session.send_message(forward_request) {
+                        error!("Failed to send RPC request msg_id={} to back session {}", msg_id, back_session_id);
+                        return false;
+                    }
+                    back_session.increase_pending_requests();
+                }
+                None => {
+                    error!("Back session {} not found for RPC request msg_id={}", back_session_id, msg_id);
+                    return false;
+                }
+            }
+        }
+
+        let timeout_ms = if timeout_ms == 0 { DEFAULT_RPC_TIMEOUT_MS } else { timeout_ms };
+        self.pending_calls.insert(msg_unique_id, PendingRpcCall {
+            back_session_id,
+            msg_id,
+            deadline: Instant::now() + Duration::from_millis(timeout_ms),
+            callback,
+        });
+
+        debug!("Sent RPC request msg_unique_id={}, msg_id={} to back session {}, timeout {}ms",
+               msg_unique_id, msg_id, back_session_id, timeout_ms);
+        true
+    }
+
+    /// 处理后端之间RPC请求的响应
+    /// 
+    /// 注意：本方法在主线程调用
+    /// 
+    /// # 返回值
+    /// 响应匹配到等待中的调用返回true，否则返回false；
+    /// 来自其他后端会话的响应被拒绝，等待中的调用保留到正确的响应到达或超时
+    pub fn handle_response(&mut self, back_session_id: u64, response: &RpcForwardMessageBResponse) -> bool {
+        match self.pending_calls.get(&response.msg_unique_id) {
+            Some(pending_call) if pending_call.back_session_id != back_session_id => {
+                warn!("Rejecting RPC response msg_unique_id={} from back session {}, request was sent to {}",
+                      response.msg_unique_id, back_session_id, pending_call.back_session_id);
+                return false;
+            }
+            Some(_) => {}
+            None => {
+                warn!("Received RPC response msg_unique_id={} from back session {} with no pending call (maybe timed out)",
+                      response.msg_unique_id, back_session_id);
+                return false;
+            }
+        }
+        let pending_call = match self.pending_calls.remove(&response.msg_unique_id) {
+            Some(pending_call) => pending_call,
+            None => return false,
+        };
+        self.release_pending_request(back_session_id);
+
+        if response.error_code != 0 {
+            warn!("RPC request msg_unique_id={} failed on remote with error {}: {}",
+                  response.msg_unique_id, response.error_code, response.error_message);
+            (pending_call.callback)(Err(RpcError::Remote {
+                code: response.error_code,
+                message: response.error_message.clone(),
+            }));
+            return true;
+        }
+
+        let mut buffer = DynamicBuffer::new(response.message.len(), 1024);
+        buffer.write_slice(&response.message);
+        let result: RpcResult = match MessageFactory::decode_message(response.msg_id as u16, &mut buffer, response.message.len()) {
+            Some(message) => Ok(message),
+            None => {
+                error!("Failed to decode RPC response msg_id={} for msg_unique_id={}", response.msg_id, response.msg_unique_id);
+                Err(RpcError::DecodeFailed)
+            }
+        };
+
+        (pending_call.callback)(result);
+        true
+    }
+
+    /// 检查等待中的RPC调用和转发请求，处理超时或连接已断开的请求
+    /// 
+    /// 注意：本方法在主线程的主循环中调用
+    pub fn update(&mut self) {
+        if self.back_session_manager.is_null() {
+            return;
+        }
+
+        let now = Instant::now();
+        self.update_pending_calls(now);
+        self.update_pending_forwards(now);
+    }
+
+    /// 回调超时或连接已断开的RPC调用
+    fn update_pending_calls(&mut self, now: Instant) {
+        if self.pending_calls.is_empty() {
+            return;
+        }
+
+        let back_session_manager = unsafe { &*self.back_session_manager };
+        let mut failed_calls = Vec::new();
+
+        for (msg_unique_id, pending_call) in self.pending_calls.iter() {
+            if back_session_manager.get_session(pending_call.back_session_id).is_none() {
+                failed_calls.push((*msg_unique_id, RpcError::Disconnected));
+            } else if pending_call.deadline <= now {
+                failed_calls.push((*msg_unique_id, RpcError::Timeout));
+            }
+        }
+
+        for (msg_unique_id, rpc_error) in failed_calls {
+            if let Some(pending_call) = self.pending_calls.remove(&msg_unique_id) {
+                self.release_pending_request(pending_call.back_session_id);
+                warn!("RPC call msg_unique_id={}, msg_id={} on back session {} failed: {:?}",
+                      msg_unique_id, pending_call.msg_id, pending_call.back_session_id, rpc_error);
+                (pending_call.callback)(Err(rpc_error));
+            }
+        }
+    }
+
+    /// 向超时或目标连接已断开的转发请求的前端会话回传错误响应，前端会话已断开的请求直接丢弃
+    fn update_pending_forwards(&mut self, now: Instant) {
+        if self.pending_forwards.is_empty() || self.front_session_manager.is_null() {
+            return;
+        }
+
+        let back_session_manager = unsafe { &*self.back_session_manager };
+        let front_session_manager = unsafe { &mut *self.front_session_manager };
+        let mut failed_forwards = Vec::new();
+
+        for (key, pending_forward) in self.pending_forwards.iter() {
+            if front_session_manager.get_session(key.0).is_none() {
+                failed_forwards.push((*key, None));
+            } else if back_session_manager.get_session(pending_forward.back_session_id).is_none() {
+                failed_forwards.push((*key, Some(RpcErrorCode::Disconnected)));
+            } else if pending_forward.deadline <= now {
+                failed_forwards.push((*key, Some(RpcErrorCode::Timeout)));
+            }
+        }
+
+        for ((front_session_id, msg_unique_id), error_code) in failed_forwards {
+            let pending_forward = match self.pending_forwards.remove(&(front_session_id, msg_unique_id)) {
+                Some(pending_forward) => pending_forward,
+                None => continue,
+            };
+            self.release_pending_request(pending_forward.back_session_id);
+            let error_code = match error_code {
+                Some(error_code) => error_code,
+                None => {
+                    debug!("Front session {} closed, dropping pending forward msg_unique_id={}", front_session_id, msg_unique_id);
+                    continue;
+                }
+            };
+
+            warn!("Forwarded RPC request msg_unique_id={}, msg_id={} from front session {} on back session {} failed: {:?}",
+                  msg_unique_id, pending_forward.msg_id, front_session_id, pending_forward.back_session_id, error_code);
+            let error = RpcHandlerError::from_code(error_code, format!("request msg_id {} failed: {:?}", pending_forward.msg_id, error_code));
+            if let Some(front_session) = front_session_manager.get_session_mut(front_session_id) {
+                front_session.send_message(build_front_error_response(msg_unique_id, error));
+            }
+        }
+    }
+
+    /// 获取等待响应的RPC调用数量
+    pub fn get_pending_call_count(&self) -> usize {
+        self.pending_calls.len()
+    }
+
+    /// 生成消息唯一ID，跳过0和仍在等待响应的ID
+    fn generate_msg_unique_id(&mut self) -> u32 {
+        loop {
+            let msg_unique_id = self.next_msg_unique_id;
+            self.next_msg_unique_id = self.next_msg_unique_id.wrapping_add(1);
+            if msg_unique_id != 0 && !self.pending_calls.contains_key(&msg_unique_id) {
+                return msg_unique_id;
+            }
+        }
+    }
+
+    /// 将类型化回调包装为通用回调，响应类型不符时返回UnexpectedResponse
+    fn typed_callback<Resp, F>(callback: F) -> RpcCallback
+    where
+        Resp: 'static,
+        F: FnOnce(Result<Resp, RpcError>) + Send + 'static,
+    {
+        Box::new(move |result: RpcResult| {
+            let typed_result = result.and_then(|message| {
+                message.downcast::<Resp>()
+                    .map(|message| *message)
+                    .map_err(|_| RpcError::UnexpectedResponse)
+            });
+            callback(typed_result);
+        })
+    }
+
+    /// 通过后端会话回传RPC请求的处理结果
+    /// 
+    /// 用于请求处理器无法立即给出结果的情况：处理器记录请求所在的后端会话ID、消息唯一ID和前端会话ID，
+    /// 结果就绪后调用本方法回传
+    /// 
+    /// 注意：本方法在主线程调用
+    /// 
+    /// # 参数
+    /// * `back_session_id` - 收到请求的后端会话ID
+    /// * `msg_unique_id` - 消息唯一ID
+    /// * `front_session_id` - 前端会话ID，后端之间的调用为0
+    /// * `result` - 响应消息或结构化错误
+    /// 
+    /// # 返回值
+    /// 成功发送返回true，后端会话不存在或发送失败返回false
     pub fn send_response_forward_message<T>(
         &mut self,
+        back_session_id: u64,
         msg_unique_id: u32,
         front_session_id: u64,
-        response_msg: T,
+        result: RpcHandlerResult<T>,
     ) -> bool 
     where
-        T: MessageIdSerialize + Clone + Send + 'static,
+        T: MessageIdSerialize,
     {
         // 检查管理器是否已初始化
-        if self.front_session_manager.is_null() {
+        if self.back_session_manager.is_null() {
             error!("RpcManager not initialized with session managers");
             return false;
         }
 
-        debug!("Sending response forward message to front session {}, msg_unique_id={}", 
-               front_session_id, msg_unique_id);
-
-        // 序列化响应消息
-        match response_msg.serialize_to_buffer() {
-            Ok(serialized_response) => {
-                // 创建RPC转发响应
-                let rpc_response = RpcForwardMessageBResponse {
-                    msg_unique_id,
-                    front_session_id,
-                    meta: std::collections::HashMap::new(),  // 空的元数据map
-                    msg_id: response_msg.msg_id() as u32,
-                    message: serialized_response.to_vec(),
-                };
-
-                // 安全地使用前端会话管理器指针
-                unsafe {
-                    let front_session_manager = &mut *self.front_session_manager;
-                    
-                    // 获取前端会话并发送响应
-                    if let Some(front_session) = front_session_manager.get_session_mut(front_session_id) {
-                        if front_session.send_message(rpc_response) {
-                            debug!("Successfully sent response forward message to front session {}", front_session_id);
-                            return true;
-                        } else {
-                            error!("Failed to send response forward message to front session {}", front_session_id);
-                        }
-                    } else {
-                        error!("Front session {} not found", front_session_id);
-                    }
+        debug!("Sending response forward message to back session {}, msg_unique_id={}, front_session_id={}", 
+               back_session_id, msg_unique_id, front_session_id);
+
+        let rpc_response = build_forward_response(msg_unique_id, front_session_id, result.map(|response| RpcResponse::from_message(&response)));
+
+        unsafe {
+            let back_session_manager = &mut *self.back_session_manager;
+            
+            // 获取后端会话并发送响应
+            if let Some(back_session) = back_session_manager.get_session_mut(back_session_id) {
+                if back_session.send_message(rpc_response) {
+                    debug!("Successfully sent response forward message to back session {}", back_session_id);
+                    return true;
+                } else {
+                    error!("Failed to send response forward message to back session {}", back_session_id);
                 }
-            }
-            Err(e) => {
-                error!("Failed to serialize response message: {:?}", e);
+            } else {
+                error!("Back session {} not found", back_session_id);
             }
         }
         
@@ -275,10 +900,17 @@ impl RpcManager {
     /// 注意：本方法在主线程调用
     pub fn dispose(&mut self) {
         info!("Disposing RpcManager");
+
+        if !self.pending_calls.is_empty() {
+            warn!("Dropping {} pending RPC calls", self.pending_calls.len());
+            self.pending_calls.clear();
+        }
+        self.pending_forwards.clear();
         
         // 清空管理器指针
         self.front_session_manager = std::ptr::null_mut();
         self.back_session_manager = std::ptr::null_mut();
+        self.user_directory = std::ptr::null_mut();
         
         // 销毁路由管理器
         self.router_manager.dispose();
@@ -291,4 +923,96 @@ impl Drop for RpcManager {
     fn drop(&mut self) {
         self.dispose();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::session::BackSession;
+    use std::sync::{Arc, Mutex};
+
+    /// 会话1、2已连接的RPC管理器
+    fn setup(back_session_manager: &mut BackSessionManager) -> RpcManager {
+        back_session_manager.insert_session(BackSession::new(1, 101, None, None));
+        back_session_manager.insert_session(BackSession::new(2, 102, None, None));
+        let mut rpc_manager = RpcManager::new();
+        rpc_manager.back_session_manager = back_session_manager as *mut BackSessionManager;
+        rpc_manager
+    }
+
+    /// 加入一个等待中的调用，返回记录回调结果的槽位
+    fn add_pending_call(rpc_manager: &mut RpcManager, msg_unique_id: u32, back_session_id: u64, deadline: Instant) -> Arc<Mutex<Vec<Result<(), RpcError>>>> {
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let callback_results = results.clone();
+        rpc_manager.pending_calls.insert(msg_unique_id, PendingRpcCall {
+            back_session_id,
+            msg_id: 1,
+            deadline,
+            callback: Box::new(move |result| callback_results.lock().unwrap().push(result.map(|_| ()))),
+        });
+        results
+    }
+
+    fn error_response(msg_unique_id: u32) -> RpcForwardMessageBResponse {
+        RpcForwardMessageBResponse {
+            msg_unique_id,
+            front_session_id: 0,
+            meta: HashMap::new(),
+            msg_id: 0,
+            message: Vec::new(),
+            error_code: 1000,
+            error_message: "remote failure".to_string(),
+        }
+    }
+
+    #[test]
+    fn pending_call_times_out() {
+        let mut back_session_manager = BackSessionManager::new();
+        let mut rpc_manager = setup(&mut back_session_manager);
+        let now = Instant::now();
+        let expired = add_pending_call(&mut rpc_manager, 1, 1, now - Duration::from_millis(1));
+        let waiting = add_pending_call(&mut rpc_manager, 2, 1, now + Duration::from_secs(60));
+        let disconnected = add_pending_call(&mut rpc_manager, 3, 3, now + Duration::from_secs(60));
+
+        rpc_manager.update();
+        assert_eq!(*expired.lock().unwrap(), vec![Err(RpcError::Timeout)]);
+        assert!(waiting.lock().unwrap().is_empty());
+        assert_eq!(*disconnected.lock().unwrap(), vec![Err(RpcError::Disconnected)]);
+        assert_eq!(rpc_manager.get_pending_call_count(), 1);
+
+        // 超时后到达的响应被丢弃，回调不会再次调用
+        assert!(!rpc_manager.handle_response(1, &error_response(1)));
+        assert_eq!(expired.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn response_from_other_session_is_rejected() {
+        let mut back_session_manager = BackSessionManager::new();
+        let mut rpc_manager = setup(&mut back_session_manager);
+        let results = add_pending_call(&mut rpc_manager, 7, 1, Instant::now() + Duration::from_secs(60));
+
+        assert!(!rpc_manager.handle_response(2, &error_response(7)));
+        assert!(results.lock().unwrap().is_empty());
+        assert_eq!(rpc_manager.get_pending_call_count(), 1);
+
+        assert!(rpc_manager.handle_response(1, &error_response(7)));
+        assert_eq!(*results.lock().unwrap(), vec![Err(RpcError::Remote { code: 1000, message: "remote failure".to_string() })]);
+        assert_eq!(rpc_manager.get_pending_call_count(), 0);
+    }
+
+    #[test]
+    fn forward_response_from_other_session_is_rejected() {
+        let mut back_session_manager = BackSessionManager::new();
+        let mut rpc_manager = setup(&mut back_session_manager);
+        rpc_manager.pending_forwards.insert((10, 7), PendingForwardRequest {
+            back_session_id: 1,
+            msg_id: 1,
+            deadline: Instant::now() + Duration::from_secs(60),
+        });
+
+        assert!(!rpc_manager.complete_forward(2, 10, 7));
+        assert!(rpc_manager.pending_forwards.contains_key(&(10, 7)));
+        assert!(rpc_manager.complete_forward(1, 10, 7));
+        assert!(!rpc_manager.complete_forward(1, 10, 7));
+    }
+}
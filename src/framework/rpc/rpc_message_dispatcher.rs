@@ -1,11 +1,16 @@
+use super::rpc_call::{RpcErrorCode, RpcHandlerError, RpcHandlerResult, RpcResponse};
 use crate::framework::session::BackSession;
+use crate::proto::messages::{MessageId, MessageIdSerialize};
+use crate::proto::messages::protobuf::message::protobuf_message_id::MessageFactory;
 use tracing::{debug, error, warn};
 use std::collections::HashMap;
 use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
 
 /// RPC请求消息处理函数类型
 /// 参数：会话、消息唯一ID、前端会话ID、消息ID、内部消息
-pub type RpcRequestHandler = Box<dyn Fn(&mut BackSession, u32, u64, u32, &dyn Any) + Send + Sync>;
+/// 返回值：编码后的响应或结构化错误，由框架封装为RpcForwardMessageBResponse回传
+pub type RpcRequestHandler = Box<dyn Fn(&mut BackSession, u32, u64, u32, &dyn Any) -> RpcHandlerResult<RpcResponse> + Send + Sync>;
 
 /// RPC通知消息处理函数类型
 /// 参数：会话、前端会话ID、消息ID、内部消息（通知消息没有唯一ID但有前端会话ID）
@@ -29,6 +34,55 @@ impl RpcMessageDispatcher {
         }
     }
 
+    /// 注册类型化的RPC请求处理器，消息ID由消息类型的MessageId::MSG_ID决定
+    /// 
+    /// 处理函数参数：会话、消息唯一ID、前端会话ID、具体的请求消息
+    /// 
+    /// 处理函数返回类型化的响应消息或结构化错误，框架负责编码并通过收到请求的后端会话回传
+    pub fn register_request<M, R, F>(&mut self, handler: F)
+    where
+        M: MessageId + 'static,
+        R: MessageIdSerialize,
+        F: Fn(&mut BackSession, u32, u64, &M) -> RpcHandlerResult<R> + Send + Sync + 'static,
+    {
+        self.register_request_handler(M::MSG_ID, Box::new(move |session, msg_unique_id, front_session_id, _msg_id, message| {
+            match message.downcast_ref::<M>() {
+                Some(message) => handler(session, msg_unique_id, front_session_id, message)
+                    .map(|response| RpcResponse::from_message(&response)),
+                None => {
+                    error!("RPC message id {} does not match handler type {}", M::MSG_ID, std::any::type_name::<M>());
+                    Err(RpcHandlerError::from_code(RpcErrorCode::Internal, "handler type mismatch"))
+                }
+            }
+        }));
+    }
+
+    /// 注册类型化的RPC通知处理器，消息ID由消息类型的MessageId::MSG_ID决定
+    /// 
+    /// 处理函数参数：会话、前端会话ID、具体的通知消息
+    pub fn register_notify<M, F>(&mut self, handler: F)
+    where
+        M: MessageId + 'static,
+        F: Fn(&mut BackSession, u64, &M) + Send + Sync + 'static,
+    {
+        self.register_notify_handler(M::MSG_ID, Box::new(move |session, front_session_id, _msg_id, message| {
+            match message.downcast_ref::<M>() {
+                Some(message) => handler(session, front_session_id, message),
+                None => error!("RPC message id {} does not match handler type {}", M::MSG_ID, std::any::type_name::<M>()),
+            }
+        }));
+    }
+
+    /// 移除请求消息类型对应的处理器
+    pub fn unregister_request<M: MessageId>(&mut self) -> bool {
+        self.unregister_request_handler(M::MSG_ID)
+    }
+
+    /// 移除通知消息类型对应的处理器
+    pub fn unregister_notify<M: MessageId>(&mut self) -> bool {
+        self.unregister_notify_handler(M::MSG_ID)
+    }
+
     /// 注册RPC请求消息处理器
     /// 
     /// # 参数
@@ -89,15 +143,21 @@ impl RpcMessageDispatcher {
     /// * `inner_message` - 解码后的内部消息
     /// 
     /// # 返回值
-    /// 找到处理器并成功分发返回true，否则返回false
-    pub fn dispatch_request_message(&self, message_id: u16, session: &mut BackSession, msg_unique_id: u32, front_session_id: u64, inner_message: &dyn Any) -> bool {
+    /// 找到处理器时返回处理器的处理结果（处理器panic时为HandlerPanicked错误），没有处理器返回None
+    pub fn dispatch_request_message(&self, message_id: u16, session: &mut BackSession, msg_unique_id: u32, front_session_id: u64, inner_message: &dyn Any) -> Option<RpcHandlerResult<RpcResponse>> {
         if let Some(handler) = self.request_handlers.get(&message_id) {
             debug!("Dispatching RPC request message with id {} to handler", message_id);
-            handler(session, msg_unique_id, front_session_id, message_id as u32, inner_message);
-            true
+            // 处理器panic时返回HandlerPanicked错误，避免请求方一直等待
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                handler(session, msg_unique_id, front_session_id, message_id as u32, inner_message)
+            }));
+            Some(result.unwrap_or_else(|_| {
+                error!("RPC request handler for message id {} panicked", message_id);
+                Err(RpcHandlerError::from_code(RpcErrorCode::HandlerPanicked, format!("handler for msg_id {} panicked", message_id)))
+            }))
         } else {
-            warn!("No RPC request handler found for message id {}", message_id);
-            false
+            warn!("No RPC request handler found for message id {} ({})", message_id, MessageFactory::message_name(message_id).unwrap_or("unknown"));
+            None
         }
     }
 
@@ -117,7 +177,7 @@ impl RpcMessageDispatcher {
             handler(session, front_session_id, message_id as u32, inner_message);
             true
         } else {
-            warn!("No RPC notify handler found for message id {}", message_id);
+            warn!("No RPC notify handler found for message id {} ({})", message_id, MessageFactory::message_name(message_id).unwrap_or("unknown"));
             false
         }
     }
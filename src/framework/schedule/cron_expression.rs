@@ -0,0 +1,301 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike};
+
+/// 向后搜索下一次触发时间的最大天数（覆盖2月29日等稀疏表达式）
+const MAX_SEARCH_DAYS: u32 = 366 * 8;
+
+const MONTH_NAMES: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// 日字段覆盖1-31全部取值时的位图
+const ALL_DAYS_OF_MONTH: u32 = 0xFFFF_FFFE;
+/// 星期字段覆盖0-6全部取值时的位图（7已合并到0）
+const ALL_DAYS_OF_WEEK: u8 = 0x7F;
+
+/// 类cron表达式：`分 时 日 月 周`
+/// 
+/// 每个字段支持 `*`、`5`、`1-5`、`*/15`、`10-50/10` 以及逗号分隔的列表，
+/// 月份和星期支持英文缩写（JAN、MON等），星期的0和7都表示周日。
+/// 同时支持 `@yearly`、`@monthly`、`@weekly`、`@daily`、`@hourly` 简写。
+/// 日和星期同时指定时，满足任意一个即触发（与标准cron一致）；
+/// 覆盖全部取值的字段（如 `*/1`、`1-31`、`0-7`）视同 `*`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpression {
+    source: String,
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    day_of_month_any: bool,
+    day_of_week_any: bool,
+}
+
+impl CronExpression {
+    /// 解析cron表达式
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let source = expression.trim();
+        let expanded = match source.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => source,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("cron expression '{}' must have 5 fields (minute hour day month weekday)", source));
+        }
+
+        let minutes = parse_field(fields[0], 0, 59, &[])
+            .map_err(|e| format!("invalid minute field in '{}': {}", source, e))?;
+        let hours = parse_field(fields[1], 0, 23, &[])
+            .map_err(|e| format!("invalid hour field in '{}': {}", source, e))?;
+        let days_of_month = parse_field(fields[2], 1, 31, &[])
+            .map_err(|e| format!("invalid day field in '{}': {}", source, e))?;
+        let months = parse_field(fields[3], 1, 12, &MONTH_NAMES)
+            .map_err(|e| format!("invalid month field in '{}': {}", source, e))?;
+        let mut days_of_week = parse_field(fields[4], 0, 7, &WEEKDAY_NAMES)
+            .map_err(|e| format!("invalid weekday field in '{}': {}", source, e))?;
+        // 7 和 0 都表示周日
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        let days_of_month = days_of_month as u32;
+        let days_of_week = days_of_week as u8;
+        Ok(Self {
+            source: source.to_string(),
+            minutes,
+            hours: hours as u32,
+            days_of_month,
+            months: months as u16,
+            days_of_week,
+            day_of_month_any: days_of_month == ALL_DAYS_OF_MONTH,
+            day_of_week_any: days_of_week == ALL_DAYS_OF_WEEK,
+        })
+    }
+
+    /// 原始表达式
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    /// 计算严格晚于after的下一次触发时间（精确到分钟），按after所在时区的墙上时间匹配，找不到时返回None
+    ///
+    /// 夏令时跳过的本地时间不会触发；回拨时重复的本地时间只在第一次出现时触发
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+
+        let mut date = start.date();
+        let mut from_hour = start.hour();
+        let mut from_minute = start.minute();
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_date(date) {
+                for hour in from_hour..24 {
+                    if self.hours & (1 << hour) == 0 {
+                        continue;
+                    }
+                    let first_minute = if hour == from_hour { from_minute } else { 0 };
+                    for minute in first_minute..60 {
+                        if self.minutes & (1 << minute) == 0 {
+                            continue;
+                        }
+                        let local = date.and_hms_opt(hour, minute, 0)?;
+                        match timezone.from_local_datetime(&local).earliest() {
+                            Some(time) if time > *after => return Some(time),
+                            _ => continue,
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+            from_hour = 0;
+            from_minute = 0;
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+
+        let day_of_month_match = self.days_of_month & (1 << date.day()) != 0;
+        let day_of_week_match = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.day_of_month_any || self.day_of_week_any {
+            day_of_month_match && day_of_week_match
+        } else {
+            day_of_month_match || day_of_week_match
+        }
+    }
+}
+
+/// 解析单个字段为位图，第n位表示值n
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be greater than 0".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, min, names)?, parse_value(end, min, names)?)
+        } else {
+            let value = parse_value(range, min, names)?;
+            // 形如 5/10 表示从5开始到最大值
+            if step > 1 { (value, max) } else { (value, value) }
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!("'{}' is out of range {}-{}", part, min, max));
+        }
+
+        let mut value = start;
+        while value <= end {
+            bits |= 1 << value;
+            value += step;
+        }
+    }
+    Ok(bits)
+}
+
+/// 解析数字或英文缩写
+fn parse_value(value: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+    if let Ok(number) = value.parse::<u32>() {
+        return Ok(number);
+    }
+    names.iter()
+        .position(|name| name.eq_ignore_ascii_case(value))
+        .map(|index| index as u32 + min)
+        .ok_or_else(|| format!("invalid value '{}'", value))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn time(value: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(value).unwrap()
+    }
+
+    fn next(expression: &str, after: &str) -> String {
+        CronExpression::parse(expression).unwrap()
+            .next_after(&time(after))
+            .unwrap()
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn next_after_is_strictly_later() {
+        assert_eq!(next("* * * * *", "2026-03-10T08:15:00+08:00"), "2026-03-10T08:16:00+08:00");
+        assert_eq!(next("* * * * *", "2026-03-10T08:15:59+08:00"), "2026-03-10T08:16:00+08:00");
+        assert_eq!(next("30 8 * * *", "2026-03-10T08:30:00+08:00"), "2026-03-11T08:30:00+08:00");
+    }
+
+    #[test]
+    fn next_after_ranges() {
+        assert_eq!(next("0 9-17 * * *", "2026-03-10T17:30:00Z"), "2026-03-11T09:00:00+00:00");
+        assert_eq!(next("0 9-17 * * *", "2026-03-10T08:59:00Z"), "2026-03-10T09:00:00+00:00");
+        assert_eq!(next("10-12 * * * *", "2026-03-10T08:12:00Z"), "2026-03-10T09:10:00+00:00");
+    }
+
+    #[test]
+    fn next_after_steps() {
+        assert_eq!(next("*/15 * * * *", "2026-03-10T08:16:00Z"), "2026-03-10T08:30:00+00:00");
+        assert_eq!(next("*/15 * * * *", "2026-03-10T08:45:00Z"), "2026-03-10T09:00:00+00:00");
+        assert_eq!(next("10-50/20 * * * *", "2026-03-10T08:31:00Z"), "2026-03-10T08:50:00+00:00");
+        assert_eq!(next("5/20 * * * *", "2026-03-10T08:46:00Z"), "2026-03-10T09:05:00+00:00");
+    }
+
+    #[test]
+    fn next_after_lists() {
+        assert_eq!(next("0 6,12,18 * * *", "2026-03-10T12:00:00Z"), "2026-03-10T18:00:00+00:00");
+        assert_eq!(next("0 6,12,18 * * *", "2026-03-10T18:00:00Z"), "2026-03-11T06:00:00+00:00");
+        assert_eq!(next("0,30 1-2,5 * * *", "2026-03-10T02:30:00Z"), "2026-03-10T05:00:00+00:00");
+    }
+
+    #[test]
+    fn next_after_weekday_names() {
+        // 2026-03-10 是周二
+        assert_eq!(next("0 10 * * MON", "2026-03-10T00:00:00Z"), "2026-03-16T10:00:00+00:00");
+        assert_eq!(next("0 10 * * mon-wed", "2026-03-10T00:00:00Z"), "2026-03-10T10:00:00+00:00");
+        assert_eq!(next("0 10 * * FRI,SUN", "2026-03-10T00:00:00Z"), "2026-03-13T10:00:00+00:00");
+        assert_eq!(next("0 0 * * 7", "2026-03-10T00:00:00Z"), next("0 0 * * SUN", "2026-03-10T00:00:00Z"));
+    }
+
+    #[test]
+    fn next_after_month_rollover() {
+        assert_eq!(next("0 0 1 * *", "2026-01-31T12:00:00Z"), "2026-02-01T00:00:00+00:00");
+        assert_eq!(next("59 23 31 * *", "2026-04-01T00:00:00Z"), "2026-05-31T23:59:00+00:00");
+        assert_eq!(next("0 0 1 JAN *", "2026-12-31T23:59:00Z"), "2027-01-01T00:00:00+00:00");
+        assert_eq!(next("0 0 * FEB *", "2026-03-01T00:00:00Z"), "2027-02-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn next_after_feb_29() {
+        assert_eq!(next("0 12 29 2 *", "2026-03-10T00:00:00Z"), "2028-02-29T12:00:00+00:00");
+        assert_eq!(next("0 12 29 2 *", "2028-02-29T12:00:00Z"), "2032-02-29T12:00:00+00:00");
+    }
+
+    #[test]
+    fn next_after_keeps_offset() {
+        assert_eq!(next("@daily", "2026-03-10T23:30:00+08:00"), "2026-03-11T00:00:00+08:00");
+        assert_eq!(next("0 9 * * *", "2026-03-10T09:30:00-05:00"), "2026-03-11T09:00:00-05:00");
+    }
+
+    fn next_in(expression: &str, timezone: chrono_tz::Tz, after: &str) -> String {
+        CronExpression::parse(expression).unwrap()
+            .next_after(&time(after).with_timezone(&timezone))
+            .unwrap()
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn next_after_follows_daylight_saving() {
+        let new_york = chrono_tz::America::New_York;
+        // 2026-03-08 02:00 EST跳到03:00 EDT
+        assert_eq!(next_in("0 9 * * *", new_york, "2026-03-07T10:00:00-05:00"), "2026-03-08T09:00:00-04:00");
+        assert_eq!(next_in("30 2 * * *", new_york, "2026-03-07T03:00:00-05:00"), "2026-03-09T02:30:00-04:00");
+        // 2026-11-01 02:00 EDT回拨到01:00 EST，重复的01:30只触发一次
+        assert_eq!(next_in("30 1 * * *", new_york, "2026-10-31T12:00:00-04:00"), "2026-11-01T01:30:00-04:00");
+        assert_eq!(next_in("30 1 * * *", new_york, "2026-11-01T01:30:00-04:00"), "2026-11-02T01:30:00-05:00");
+        assert_eq!(next_in("0 9 * * *", new_york, "2026-10-31T10:00:00-04:00"), "2026-11-01T09:00:00-05:00");
+    }
+
+    #[test]
+    fn day_and_weekday_combine_with_or() {
+        // 每月13号或周五
+        assert_eq!(next("0 0 13 * FRI", "2026-03-10T00:00:00Z"), "2026-03-13T00:00:00+00:00");
+        assert_eq!(next("0 0 13 * FRI", "2026-03-13T00:00:00Z"), "2026-03-20T00:00:00+00:00");
+        assert_eq!(next("0 0 1 * MON", "2026-03-10T00:00:00Z"), "2026-03-16T00:00:00+00:00");
+    }
+
+    #[test]
+    fn full_range_fields_act_as_any() {
+        for expression in ["0 0 */1 * MON", "0 0 1-31 * MON", "0 0 1-31/1 * MON"] {
+            assert_eq!(next(expression, "2026-03-10T00:00:00Z"), "2026-03-16T00:00:00+00:00", "{}", expression);
+        }
+        for expression in ["0 0 15 * */1", "0 0 15 * 0-6", "0 0 15 * 0-7", "0 0 15 * SUN-SAT"] {
+            assert_eq!(next(expression, "2026-03-10T00:00:00Z"), "2026-03-15T00:00:00+00:00", "{}", expression);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid_expressions() {
+        for expression in ["", "* * * *", "60 * * * *", "* 24 * * *", "* * 0 * *", "* * * 13 *", "* * * * 8", "*/0 * * * *", "5-1 * * * *", "* * * * FOO"] {
+            assert!(CronExpression::parse(expression).is_err(), "{}", expression);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod cron_expression;
+pub mod schedule_job;
+pub mod schedule_manager;
+
+pub use cron_expression::CronExpression;
+pub use schedule_job::{CatchUpPolicy, ScheduleCallback, ScheduleJob, ScheduleTrigger};
+pub use schedule_manager::ScheduleManager;
\ No newline at end of file
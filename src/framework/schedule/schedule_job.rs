@@ -0,0 +1,86 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+use super::cron_expression::CronExpression;
+
+pub type ScheduleCallback = Box<dyn FnMut() + Send>;
+
+/// 错过执行时间（如主线程长时间阻塞、进程被挂起）后的补偿策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatchUpPolicy {
+    /// 跳过错过的执行，等待下一次触发时间
+    Skip,
+    /// 补执行一次（错过多次也只执行一次）
+    FireOnce,
+}
+
+impl CatchUpPolicy {
+    /// 从配置字符串转换（skip / fire_once）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "skip" => Some(CatchUpPolicy::Skip),
+            "fire_once" => Some(CatchUpPolicy::FireOnce),
+            _ => None,
+        }
+    }
+}
+
+/// 定时任务的触发方式
+#[derive(Debug, Clone)]
+pub enum ScheduleTrigger {
+    /// 按cron表达式重复触发
+    Cron(CronExpression),
+    /// 在指定的时刻触发一次
+    At(DateTime<Tz>),
+}
+
+impl ScheduleTrigger {
+    /// 计算首次触发时间（一次性任务即使已经过期也返回原时间，由补偿策略决定是否执行）
+    pub fn first_run(&self, now: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        match self {
+            ScheduleTrigger::Cron(expression) => expression.next_after(&now),
+            ScheduleTrigger::At(time) => Some(*time),
+        }
+    }
+
+    /// 计算now之后的下一次触发时间
+    pub fn next_run(&self, now: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        match self {
+            ScheduleTrigger::Cron(expression) => expression.next_after(&now),
+            ScheduleTrigger::At(_) => None,
+        }
+    }
+}
+
+/// 定时任务
+pub struct ScheduleJob {
+    pub name: String,
+    pub trigger: ScheduleTrigger,
+    pub catch_up: CatchUpPolicy,
+    /// 下一次触发时间，为None时任务已结束
+    pub next_run: Option<DateTime<Tz>>,
+    /// 任务回调，配置文件中声明的任务在业务代码设置回调之前为None
+    pub callback: Option<ScheduleCallback>,
+}
+
+impl ScheduleJob {
+    pub fn new(name: String, trigger: ScheduleTrigger, catch_up: CatchUpPolicy, now: DateTime<Tz>) -> Self {
+        let next_run = trigger.first_run(now);
+        Self {
+            name,
+            trigger,
+            catch_up,
+            next_run,
+            callback: None,
+        }
+    }
+
+    /// 获取任务信息字符串
+    pub fn get_info(&self) -> String {
+        let trigger = match &self.trigger {
+            ScheduleTrigger::Cron(expression) => format!("cron='{}'", expression.get_source()),
+            ScheduleTrigger::At(time) => format!("at={}", time),
+        };
+        let next_run = self.next_run.map_or("none".to_string(), |time| time.to_string());
+        format!("ScheduleJob[name={}, {}, catch_up={:?}, next_run={}]", self.name, trigger, self.catch_up, next_run)
+    }
+}
\ No newline at end of file
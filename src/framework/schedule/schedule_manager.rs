@@ -0,0 +1,291 @@
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use crate::framework::config::config::ScheduleConfig;
+use super::{CatchUpPolicy, CronExpression, ScheduleCallback, ScheduleJob, ScheduleTrigger};
+use tracing::{debug, error, info, warn};
+
+/// 触发时间已过去超过该秒数时视为错过执行，按补偿策略处理
+const MISFIRE_THRESHOLD_SECS: i64 = 60;
+
+/// 定时任务管理器：按墙上时间（可配置时区，支持IANA时区名和夏令时）执行cron任务和一次性任务
+/// 
+/// 由Server持有，在主循环中调用update，任务回调在主线程执行
+pub struct ScheduleManager {
+    jobs: Vec<ScheduleJob>,
+    timezone: Tz,
+}
+
+impl ScheduleManager {
+    // ========== new methods ==========
+    pub fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            timezone: Tz::UTC,
+        }
+    }
+
+    // ========== init/dispose methods ==========
+    /// 初始化：设置时区并加载配置文件中属于当前服务器组的任务
+    /// 
+    /// 配置中的任务需要业务代码通过set_job_handler设置回调
+    pub fn init(&mut self, config: &ScheduleConfig, server_group_name: &str) -> bool {
+        self.timezone = match Self::parse_timezone(&config.timezone) {
+            Some(timezone) => timezone,
+            None => {
+                error!("Invalid schedule timezone '{}', expected an IANA name such as Asia/Shanghai, UTC or a whole-hour offset such as +08:00", config.timezone);
+                return false;
+            }
+        };
+
+        for job_config in &config.jobs {
+            if job_config.group.as_ref().is_some_and(|group| group != server_group_name) {
+                continue;
+            }
+
+            let catch_up = match CatchUpPolicy::parse(&job_config.catch_up) {
+                Some(policy) => policy,
+                None => {
+                    error!("Schedule job '{}' has invalid catch_up '{}', expected skip or fire_once", 
+                           job_config.name, job_config.catch_up);
+                    return false;
+                }
+            };
+
+            let trigger = match (&job_config.cron, &job_config.at) {
+                (Some(cron), None) => match CronExpression::parse(cron) {
+                    Ok(expression) => ScheduleTrigger::Cron(expression),
+                    Err(e) => {
+                        error!("Schedule job '{}': {}", job_config.name, e);
+                        return false;
+                    }
+                },
+                (None, Some(at)) => match self.parse_local_time(at) {
+                    Some(time) => ScheduleTrigger::At(time),
+                    None => {
+                        error!("Schedule job '{}' has invalid at '{}', expected YYYY-MM-DD HH:MM[:SS]", job_config.name, at);
+                        return false;
+                    }
+                },
+                _ => {
+                    error!("Schedule job '{}' must have exactly one of cron or at", job_config.name);
+                    return false;
+                }
+            };
+
+            if !self.add_job(&job_config.name, trigger, catch_up, None) {
+                return false;
+            }
+        }
+
+        info!("Schedule manager initialized with timezone {} and {} jobs", self.timezone, self.jobs.len());
+        true
+    }
+
+    pub fn dispose(&mut self) {
+        self.jobs.clear();
+    }
+
+    // ========== get/set methods ==========
+    /// 当前时区
+    pub fn get_timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// 当前时区下的墙上时间
+    pub fn now(&self) -> DateTime<Tz> {
+        Utc::now().with_timezone(&self.timezone)
+    }
+
+    pub fn job_count(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn has_job(&self, name: &str) -> bool {
+        self.jobs.iter().any(|job| job.name == name)
+    }
+
+    /// 任务的下一次触发时间
+    pub fn get_next_run(&self, name: &str) -> Option<DateTime<Tz>> {
+        self.jobs.iter().find(|job| job.name == name).and_then(|job| job.next_run)
+    }
+
+    // ========== job methods ==========
+    /// 添加cron任务，表达式按当前时区解释
+    pub fn add_cron_job(&mut self, name: &str, cron: &str, catch_up: CatchUpPolicy, callback: ScheduleCallback) -> bool {
+        match CronExpression::parse(cron) {
+            Ok(expression) => self.add_job(name, ScheduleTrigger::Cron(expression), catch_up, Some(callback)),
+            Err(e) => {
+                error!("Schedule job '{}': {}", name, e);
+                false
+            }
+        }
+    }
+
+    /// 添加在指定时刻执行一次的任务
+    pub fn add_at_job(&mut self, name: &str, time: DateTime<Tz>, catch_up: CatchUpPolicy, callback: ScheduleCallback) -> bool {
+        self.add_job(name, ScheduleTrigger::At(time), catch_up, Some(callback))
+    }
+
+    /// 为配置文件中声明的任务设置回调
+    pub fn set_job_handler(&mut self, name: &str, callback: ScheduleCallback) -> bool {
+        match self.jobs.iter_mut().find(|job| job.name == name) {
+            Some(job) => {
+                job.callback = Some(callback);
+                true
+            }
+            None => {
+                warn!("Schedule job '{}' not found, handler not set", name);
+                false
+            }
+        }
+    }
+
+    pub fn remove_job(&mut self, name: &str) -> bool {
+        let count = self.jobs.len();
+        self.jobs.retain(|job| job.name != name);
+        count != self.jobs.len()
+    }
+
+    fn add_job(&mut self, name: &str, trigger: ScheduleTrigger, catch_up: CatchUpPolicy, callback: Option<ScheduleCallback>) -> bool {
+        if self.has_job(name) {
+            error!("Schedule job '{}' already exists", name);
+            return false;
+        }
+
+        let mut job = ScheduleJob::new(name.to_string(), trigger, catch_up, self.now());
+        job.callback = callback;
+        if job.next_run.is_none() {
+            warn!("Schedule job '{}' will never run", name);
+        }
+        info!("Added {}", job.get_info());
+        self.jobs.push(job);
+        true
+    }
+
+    // ========== update methods ==========
+    /// 主循环中调用：执行所有到期的任务
+    pub fn update(&mut self) {
+        if self.jobs.is_empty() {
+            return;
+        }
+
+        let now = self.now();
+        let misfire_threshold = chrono::Duration::seconds(MISFIRE_THRESHOLD_SECS);
+
+        // 先计算下一次触发时间，再执行回调，回调中可以安全地增删任务
+        let mut due_jobs = Vec::new();
+        for job in self.jobs.iter_mut() {
+            let scheduled_time = match job.next_run {
+                Some(time) if time <= now => time,
+                _ => continue,
+            };
+
+            job.next_run = job.trigger.next_run(now);
+
+            if now - scheduled_time > misfire_threshold && job.catch_up == CatchUpPolicy::Skip {
+                warn!("Schedule job '{}' missed run at {}, skipped", job.name, scheduled_time);
+                continue;
+            }
+            due_jobs.push((job.name.clone(), scheduled_time));
+        }
+
+        for (name, scheduled_time) in due_jobs {
+            if let Some(job) = self.jobs.iter_mut().find(|job| job.name == name) {
+                match job.callback.as_mut() {
+                    Some(callback) => {
+                        debug!("Running schedule job '{}' scheduled at {}", name, scheduled_time);
+                        callback();
+                    }
+                    None => warn!("Schedule job '{}' has no handler", name),
+                }
+            }
+        }
+
+        // 删除已经结束的一次性任务
+        self.jobs.retain(|job| job.next_run.is_some());
+    }
+
+    // ========== parse methods ==========
+    /// 解析时区：IANA时区名（Asia/Shanghai、America/New_York）、UTC、Z，或整点偏移（+08:00、+0800、-05）
+    ///
+    /// 整点偏移映射为Etc/GMT时区，不随夏令时切换；非整点偏移请使用IANA时区名（如Asia/Kolkata）
+    pub fn parse_timezone(value: &str) -> Option<Tz> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("utc") || value.eq_ignore_ascii_case("z") {
+            return Some(Tz::UTC);
+        }
+
+        let (sign, rest) = match value.chars().next()? {
+            '+' => ('-', &value[1..]),
+            '-' => ('+', &value[1..]),
+            _ => return value.parse::<Tz>().ok(),
+        };
+        let (hours, minutes) = match rest.split_once(':') {
+            Some((hours, minutes)) => (hours, minutes),
+            None if rest.len() == 4 => rest.split_at(2),
+            None => (rest, "00"),
+        };
+        // 只接受数字，避免 "+-5" 这类带两个符号的值被parse接受
+        let is_digits = |part: &str| !part.is_empty() && part.len() <= 2 && part.bytes().all(|byte| byte.is_ascii_digit());
+        if !is_digits(hours) || !is_digits(minutes) || minutes.parse::<u32>().ok()? != 0 {
+            return None;
+        }
+        let hours: u32 = hours.parse().ok()?;
+        if hours == 0 {
+            return Some(Tz::UTC);
+        }
+        // Etc/GMT时区的符号与常用写法相反：+08:00对应Etc/GMT-8
+        format!("Etc/GMT{}{}", sign, hours).parse::<Tz>().ok()
+    }
+
+    /// 按当前时区解析时间：YYYY-MM-DD HH:MM[:SS]
+    pub fn parse_local_time(&self, value: &str) -> Option<DateTime<Tz>> {
+        let value = value.trim();
+        let local = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+            .ok()?;
+        self.timezone.from_local_datetime(&local).earliest()
+    }
+}
+
+impl Default for ScheduleManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timezone_accepts_iana_names_and_offsets() {
+        assert_eq!(ScheduleManager::parse_timezone("UTC"), Some(Tz::UTC));
+        assert_eq!(ScheduleManager::parse_timezone("z"), Some(Tz::UTC));
+        assert_eq!(ScheduleManager::parse_timezone("Asia/Shanghai"), Some(chrono_tz::Asia::Shanghai));
+        assert_eq!(ScheduleManager::parse_timezone(" America/New_York "), Some(chrono_tz::America::New_York));
+        assert_eq!(ScheduleManager::parse_timezone("+08:00"), Some(chrono_tz::Etc::GMTMinus8));
+        assert_eq!(ScheduleManager::parse_timezone("+0800"), Some(chrono_tz::Etc::GMTMinus8));
+        assert_eq!(ScheduleManager::parse_timezone("-05"), Some(chrono_tz::Etc::GMTPlus5));
+        assert_eq!(ScheduleManager::parse_timezone("+00:00"), Some(Tz::UTC));
+    }
+
+    #[test]
+    fn parse_timezone_rejects_invalid_values() {
+        for value in ["", "+", "+-5", "-+5", "++8", "+8:-0", "+08:30", "+5:30", "+24", "+123", "Mars/Olympus", "08:00"] {
+            assert_eq!(ScheduleManager::parse_timezone(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn parse_local_time_uses_timezone() {
+        let mut schedule_manager = ScheduleManager::new();
+        schedule_manager.timezone = chrono_tz::America::New_York;
+        let time = schedule_manager.parse_local_time("2026-07-01 09:00").unwrap();
+        assert_eq!(time.to_rfc3339(), "2026-07-01T09:00:00-04:00");
+        let time = schedule_manager.parse_local_time("2026-01-01 09:00:30").unwrap();
+        assert_eq!(time.to_rfc3339(), "2026-01-01T09:00:30-05:00");
+        // 夏令时跳过的本地时间不存在
+        assert_eq!(schedule_manager.parse_local_time("2026-03-08 02:30"), None);
+    }
+}
@@ -3,16 +3,21 @@ use crate::framework::config::server_config_manager::ServerConfigManager;
 use crate::framework::log::log::LogGuard;
 use crate::framework::session::{BackSessionManager, BackSessionMessageDispatcher, FrontSessionManager, FrontSessionGroupManager, FrontSessionMessageDispatcher};
 use crate::framework::network::{NetworkEngine, NetworkEngineEventManager};
-use crate::framework::cluster::{ClusterManager, ClusterMessageHandler, ServerManager};
+use crate::framework::network::connection::SendQueueOptions;
+use crate::framework::cluster::{ClusterManager, ClusterMessageHandler, ClusterNodeOptions, ServerManager, UserDirectory};
 use crate::framework::rpc::{RpcManager, RouterManager, RpcMessageDispatcher};
 use crate::framework::msg::{MsgProcessor, ProtobufMsgProcessor};
-use crate::framework::timer::TimeManager;
+use crate::framework::timer::{TimeManager, TimerHandle};
+use crate::framework::schedule::ScheduleManager;
 use crate::framework::task::TaskManager;
 use crate::framework::rpc::ForwardManager;
+use crate::framework::rpc::BroadcastManager;
 use crate::framework::db::db_manager::DBManager;
+use crate::framework::author::server_token::NodeAuthenticator;
 use std::sync::Arc;
-use std::time::Duration;
-use tracing::{error, info};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 use tokio::sync::Notify;
 use tokio::time::timeout;
 
@@ -23,6 +28,10 @@ pub trait ServerTrait {
     async fn run(&mut self);
 }
 
+/// 空闲检查定时器回调中使用的服务器指针，定时器只在主线程执行
+struct ServerPtr(*mut Server);
+unsafe impl Send for ServerPtr {}
+
 pub struct Server {
     server_id: u32,
     server_config: ServerConfig,
@@ -40,17 +49,23 @@ pub struct Server {
     back_message_dispatcher: BackSessionMessageDispatcher,
     front_message_dispatcher: FrontSessionMessageDispatcher,
     rpc_manager: RpcManager,
-    router_manager: RouterManager,
     rpc_message_dispatcher: RpcMessageDispatcher,
     msg_processor: Arc<dyn MsgProcessor>,
     time_manager: TimeManager,
+    schedule_manager: ScheduleManager,
     task_manager: TaskManager,
     forward_manager: ForwardManager,
+    broadcast_manager: BroadcastManager,
+    user_directory: UserDirectory,
     db_manager: DBManager,
 
     // notify
     is_running:bool,
     notify:Arc<Notify>,
+    /// 收到SIGTERM或Ctrl+C后由信号任务设置，主循环检测到后退出
+    shutdown_signal: Arc<AtomicBool>,
+    /// 收到SIGHUP后由信号任务设置，主循环检测到后重新加载TLS证书
+    tls_reload_signal: Arc<AtomicBool>,
 }
 
 impl ServerTrait for Server {
@@ -91,15 +106,42 @@ impl ServerTrait for Server {
         // Set notify for network engine
         self.network_engine.set_notify(Arc::clone(&self.notify));
         
-        // Initialize RPC manager with session managers
-        if self.rpc_manager.init(&mut self.front_session_manager, &mut self.back_session_manager) == false {
-            error!("Failed to initialize RPC manager");
-            return false;
+        // 加载前端TLS证书
+        if let Some(ref front_tls) = config.front_tls {
+            let has_front = self.server_config.front_tcp_port.is_some() || self.server_config.front_ws_port.is_some();
+            if has_front && !self.network_engine.init_front_tls(front_tls) {
+                error!("Failed to initialize front TLS");
+                return false;
+            }
+        }
+        
+        // 加载后端双向TLS证书
+        if let Some(ref back_tls) = config.back_tls {
+            if !self.network_engine.init_back_tls(back_tls, server_id) {
+                error!("Failed to initialize back TLS");
+                return false;
+            }
         }
         
-        // Initialize router manager
-        if self.router_manager.init() == false {
-            error!("Failed to initialize router manager");
+        // 解析连接发送队列参数
+        let front_send_queue = match SendQueueOptions::from_config(&config.send_queue, &config.send_queue.front_policy) {
+            Ok(options) => options,
+            Err(e) => {
+                error!("Invalid front send queue config: {}", e);
+                return false;
+            }
+        };
+        let back_send_queue = match SendQueueOptions::from_config(&config.send_queue, &config.send_queue.back_policy) {
+            Ok(options) => options,
+            Err(e) => {
+                error!("Invalid back send queue config: {}", e);
+                return false;
+            }
+        };
+        
+        // Initialize RPC manager with session managers
+        if !self.rpc_manager.init(&mut self.front_session_manager, &mut self.back_session_manager, config) {
+            error!("Failed to initialize RPC manager");
             return false;
         }
         
@@ -109,6 +151,12 @@ impl ServerTrait for Server {
             return false;
         }
         
+        // Initialize schedule manager
+        if !self.schedule_manager.init(&config.schedules, &self.group_name) {
+            error!("Failed to initialize schedule manager");
+            return false;
+        }
+        
         // 消息处理器已经是无状态的，不需要初始化
         
         // Initialize session managers with required parameters
@@ -118,12 +166,32 @@ impl ServerTrait for Server {
             self.network_engine.get_event_queue(),
             Arc::clone(&self.notify)
         );
+        self.back_session_manager.set_back_tls(self.network_engine.get_back_tls().cloned());
+        self.back_session_manager.set_send_queue_options(back_send_queue);
         self.front_session_manager.init(
             &mut self.network_event_manager,
             Arc::clone(&self.msg_processor),
             self.network_engine.get_event_queue(),
             Arc::clone(&self.notify)
         );
+        self.front_session_manager.set_send_queue_options(front_send_queue);
+        
+        // 定时关闭空闲连接
+        let idle_timeout = &config.idle_timeout;
+        self.front_session_manager.set_idle_timeout(
+            Duration::from_millis(idle_timeout.front_ms),
+            Duration::from_millis(idle_timeout.unauthenticated_ms),
+        );
+        self.back_session_manager.set_idle_timeout(Duration::from_millis(idle_timeout.back_ms));
+        let has_idle_timeout = idle_timeout.front_ms > 0 || idle_timeout.unauthenticated_ms > 0 || idle_timeout.back_ms > 0;
+        if has_idle_timeout && idle_timeout.check_interval_ms > 0 {
+            let server_ptr = ServerPtr(self as *mut Server);
+            self.get_timer_handle().add_repeat(idle_timeout.check_interval_ms, move || {
+                let ptr = &server_ptr;
+                let server = unsafe { &mut *ptr.0 };
+                server.close_idle_sessions();
+            });
+        }
         
         // Initialize back message dispatcher
         if self.back_message_dispatcher.init(&mut self.network_event_manager, &mut self.back_session_manager) == false {
@@ -138,13 +206,10 @@ impl ServerTrait for Server {
         }
         
         // Initialize forward manager
-        if self.forward_manager.init(
+        if !self.forward_manager.init(
             &mut self.front_message_dispatcher,
             &mut self.back_message_dispatcher,
-            &mut self.router_manager,
             &mut self.front_session_manager,
-            &mut self.back_session_manager,
-            &mut self.task_manager,
             &mut self.rpc_manager,
             &mut self.rpc_message_dispatcher,
         ) {
@@ -152,24 +217,85 @@ impl ServerTrait for Server {
             return false;
         }
         
+        // 前端会话断开时从分组中移除
+        self.network_event_manager.add_handler(&mut self.front_session_group_manager as *mut FrontSessionGroupManager);
+        
+        // Initialize broadcast manager
+        if !self.broadcast_manager.init(
+            self.server_id,
+            &self.group_name,
+            &mut self.rpc_manager,
+            &mut self.rpc_message_dispatcher,
+            &mut self.front_session_manager,
+            &mut self.front_session_group_manager,
+        ) {
+            error!("Failed to initialize broadcast manager");
+            return false;
+        }
+        
         // Initialize server manager
         if self.server_manager.init() == false {
             error!("Failed to initialize server manager");
             return false;
         }
         
-        if self.cluster_manager.init(
-            self.group_name.clone(),
+        // 一致性哈希环跟随服务器列表变化，当前节点自身也在环上
+        {
+            let hash_ring_manager = self.rpc_manager.get_router_manager_mut().get_hash_ring_manager_mut();
+            hash_ring_manager.add_node(&self.group_name, self.server_id);
+            hash_ring_manager.attach(&mut self.server_manager);
+        }
+        
+        // Initialize user directory
+        if !self.user_directory.init(
+            self.server_id,
+            &self.group_name,
+            config,
+            &mut self.rpc_manager,
+            &mut self.rpc_message_dispatcher,
+            &mut self.back_message_dispatcher,
+            &mut self.front_session_manager,
+            &mut self.server_manager,
+        ) {
+            error!("Failed to initialize user directory");
+            return false;
+        }
+        // 前端会话断开时上报用户下线
+        self.network_event_manager.add_handler(&mut self.user_directory as *mut UserDirectory);
+        // 与目录服务器重新建立连接时重新上报用户
+        self.user_directory.attach(&mut self.back_session_manager);
+        
+        // 集群节点认证，cluster_manager（连接方）和cluster_message_handler（监听方）共用
+        let node_authenticator = NodeAuthenticator::new(&config.author);
+        if !node_authenticator.has_key() {
+            error!("Author key is empty, cluster nodes cannot authenticate");
+            return false;
+        }
+        
+        let node_options = ClusterNodeOptions {
+            server_group_name: self.group_name.clone(),
+            master_config: self.config_manager.get_master_config().cloned(),
+            server_config: Some(self.server_config.clone()),
+            node_authenticator: node_authenticator.clone(),
+        };
+        if !self.cluster_manager.init(
             &mut self.network_engine,
             &mut self.back_session_manager,
+            &mut self.server_manager,
             &mut self.network_event_manager,
-            self.config_manager.get_master_config().cloned(),
-            Some(self.server_config.clone()),
-            self.config_manager.get_author_key().to_string(),
+            node_options,
+            &config.cluster,
         ) {
             error!("Failed to initialize cluster manager");
             return false;
         }
+        // 默认按当前节点的前端会话数量上报负载，供least_load路由使用
+        let server_ptr = ServerPtr(self as *mut Server);
+        self.cluster_manager.set_load_provider(Box::new(move || {
+            let ptr = &server_ptr;
+            let server = unsafe { &*ptr.0 };
+            server.front_session_manager.get_session_count() as u32
+        }));
         
         // Initialize cluster message handler with server_manager, cluster_manager, back_session_manager and server_config
         let server_manager_ptr = &mut self.server_manager as *mut ServerManager;
@@ -184,7 +310,7 @@ impl ServerTrait for Server {
             &self.server_config, 
             &self.group_name, 
             master_server_id,
-            self.config_manager.get_author_key().to_string());
+            node_authenticator);
 
         // Initialize DB manager if MongoDB configuration is present
         if let Some(mongodb_config) = &config.mongodb {
@@ -206,6 +332,9 @@ impl ServerTrait for Server {
     fn dispose(&mut self) {
         // Dispose in reverse order of initialization
         
+        // 主循环结束时已经通知master离开集群；未进入主循环时这里只停止重连
+        self.cluster_manager.leave_cluster();
+        
         // Dispose cluster message handler (last initialized)
         self.cluster_message_handler.dispose(&mut self.back_message_dispatcher);
         
@@ -218,6 +347,12 @@ impl ServerTrait for Server {
         // Dispose forward manager
         self.forward_manager.dispose();
         
+        // Dispose broadcast manager
+        self.broadcast_manager.dispose();
+        
+        // Dispose user directory
+        self.user_directory.dispose();
+        
         // Dispose session managers (reverse order)
         self.front_session_manager.dispose();
         self.back_session_manager.dispose();
@@ -228,15 +363,18 @@ impl ServerTrait for Server {
         // Dispose task manager
         self.task_manager.dispose();
         
-        // Dispose router manager
-        self.router_manager.dispose();
-        
         // Dispose RPC manager
         self.rpc_manager.dispose();
         
+        // Dispose schedule manager
+        self.schedule_manager.dispose();
+        
         // Clear all timers (cleanup)
         self.time_manager.clear_all_timers();
 
+        // Close listeners
+        self.network_engine.dispose();
+
         // Dispose DB manager
         self.db_manager.dispose();
 
@@ -246,19 +384,26 @@ impl ServerTrait for Server {
     async fn run(&mut self) {
         // Main server loop
         if self.server_config.back_tcp_port != 0 {
-            self.network_engine.start_back_tcp(self.server_config.back_tcp_port);
+            self.network_engine.start_back_tcp(self.server_config.get_back_bind_host(), self.server_config.back_tcp_port);
         }
         if let Some(front_tcp_port) = self.server_config.front_tcp_port {
-            self.network_engine.start_front_tcp(front_tcp_port);
+            self.network_engine.start_front_tcp(self.server_config.get_front_bind_host(), front_tcp_port);
         }
         if let Some(front_ws_port) = self.server_config.front_ws_port {
-            self.network_engine.start_front_websocket(front_ws_port);
+            self.network_engine.start_front_websocket(self.server_config.get_front_bind_host(), front_ws_port);
         }
 
         // 获取事件队列
         let event_queue = self.network_engine.get_event_queue();
         
+        // 启动定时器时钟
+        self.time_manager.start();
+        
         self.is_running = true;
+        self.spawn_shutdown_signal_listener();
+        if self.network_engine.is_tls_enabled() {
+            self.spawn_tls_reload_signal_listener();
+        }
         loop {
             // 先处理网络事件队列
             while event_queue.is_empty().await == false {
@@ -268,9 +413,39 @@ impl ServerTrait for Server {
                 }
             }
             
+            // 执行到期的定时器
+            self.time_manager.tick();
+            
+            // 执行到期的定时任务
+            self.schedule_manager.update();
+            
             // 处理完成的任务（直接调用，内部会检查是否为空）
             self.task_manager.process_finished_tasks();
             
+            // 检查RPC调用超时
+            self.rpc_manager.update();
+            
+            // 集群心跳发送与超时检测
+            self.cluster_manager.update();
+            
+            // 集群要求当前节点退出（如服务器ID已被在线节点使用）
+            if self.cluster_manager.is_shutdown_requested() {
+                error!("Cluster requested shutdown, stopping server {}", self.server_id);
+                self.stop();
+            }
+            
+            // 收到退出信号
+            if self.shutdown_signal.load(Ordering::Relaxed) && self.is_running {
+                info!("Received shutdown signal, stopping server {}", self.server_id);
+                self.stop();
+            }
+            
+            // 收到SIGHUP，立即重新加载TLS证书，失败时继续使用原来的证书
+            if self.tls_reload_signal.swap(false, Ordering::Relaxed) {
+                info!("Received SIGHUP, reloading TLS certificates");
+                self.network_engine.reload_tls();
+            }
+            
             // 然后等待下一次循环
             let wait_time = self.time_manager.first_time_wait();
             timeout(Duration::from_millis(wait_time), self.notify.notified()).await;
@@ -279,6 +454,9 @@ impl ServerTrait for Server {
                 break;
             }
         }
+        
+        // 通知master离开集群，等待确认后再释放资源
+        self.leave_cluster_gracefully().await;
     }
 }
 
@@ -292,9 +470,12 @@ impl Server {
                 host:"".to_string(),
                 front_host:"".to_string(),
                 back_host:"".to_string(),
+                front_bind_host: None,
+                back_bind_host: None,
                 back_tcp_port: 0,
                 front_tcp_port: None,
                 front_ws_port:None,
+                weight: 1,
             },
             group_name: "".to_string(),
             config_manager: ServerConfigManager::new(),
@@ -310,16 +491,20 @@ impl Server {
             back_message_dispatcher: BackSessionMessageDispatcher::new(),
             front_message_dispatcher: FrontSessionMessageDispatcher::new(),
             rpc_manager: RpcManager::new(),
-            router_manager: RouterManager::new(),
             rpc_message_dispatcher: RpcMessageDispatcher::new(),
             msg_processor: Arc::new(ProtobufMsgProcessor::new()),
             time_manager: TimeManager::new(),
+            schedule_manager: ScheduleManager::new(),
             task_manager: TaskManager::new(),
             forward_manager: ForwardManager::new(),
+            broadcast_manager: BroadcastManager::new(),
+            user_directory: UserDirectory::new(),
             db_manager: DBManager::new(),
 
             is_running:(false),
             notify:(Arc::new(Notify::new())),
+            shutdown_signal: Arc::new(AtomicBool::new(false)),
+            tls_reload_signal: Arc::new(AtomicBool::new(false)),
         }
     }
     
@@ -387,6 +572,26 @@ impl Server {
         &mut self.front_session_group_manager
     }
 
+    /// Get broadcast manager
+    pub fn get_broadcast_manager(&self) -> &BroadcastManager {
+        &self.broadcast_manager
+    }
+
+    /// Get mutable broadcast manager
+    pub fn get_broadcast_manager_mut(&mut self) -> &mut BroadcastManager {
+        &mut self.broadcast_manager
+    }
+
+    /// Get user directory
+    pub fn get_user_directory(&self) -> &UserDirectory {
+        &self.user_directory
+    }
+
+    /// Get mutable user directory
+    pub fn get_user_directory_mut(&mut self) -> &mut UserDirectory {
+        &mut self.user_directory
+    }
+
     /// Get network engine
     pub fn get_network_engine(&self) -> &NetworkEngine {
         &self.network_engine
@@ -432,14 +637,14 @@ impl Server {
         &mut self.back_message_dispatcher
     }
 
-    /// Get router manager
+    /// Get router manager (owned by the RPC manager)
     pub fn get_router_manager(&self) -> &RouterManager {
-        &self.router_manager
+        self.rpc_manager.get_router_manager()
     }
 
-    /// Get mutable router manager
+    /// Get mutable router manager (owned by the RPC manager)
     pub fn get_router_manager_mut(&mut self) -> &mut RouterManager {
-        &mut self.router_manager
+        self.rpc_manager.get_router_manager_mut()
     }
 
     /// Get message processor
@@ -457,6 +662,30 @@ impl Server {
         &mut self.time_manager
     }
 
+    /// Get schedule manager
+    pub fn get_schedule_manager(&self) -> &ScheduleManager {
+        &self.schedule_manager
+    }
+
+    /// Get mutable schedule manager
+    pub fn get_schedule_manager_mut(&mut self) -> &mut ScheduleManager {
+        &mut self.schedule_manager
+    }
+
+    /// Get timer handle, can be stored in handlers and used on the main thread
+    pub fn get_timer_handle(&mut self) -> TimerHandle {
+        TimerHandle::new(&mut self.time_manager as *mut TimeManager)
+    }
+
+    /// 关闭超过空闲时间没有收发数据的前端和后端会话，由空闲检查定时器调用
+    fn close_idle_sessions(&mut self) {
+        let front_count = self.front_session_manager.close_idle_sessions();
+        let back_count = self.back_session_manager.close_idle_sessions();
+        if front_count > 0 || back_count > 0 {
+            debug!("Closed {} idle front sessions and {} idle back sessions", front_count, back_count);
+        }
+    }
+
     /// Get task manager
     pub fn get_task_manager(&self) -> &TaskManager {
         &self.task_manager
@@ -477,6 +706,66 @@ impl Server {
         &mut self.rpc_message_dispatcher
     }
 
+    /// 监听SIGTERM和Ctrl+C，收到后设置退出标记并唤醒主循环
+    fn spawn_shutdown_signal_listener(&self) {
+        let shutdown_signal = Arc::clone(&self.shutdown_signal);
+        let notify = Arc::clone(&self.notify);
+        tokio::spawn(async move {
+            wait_for_shutdown_signal().await;
+            shutdown_signal.store(true, Ordering::Relaxed);
+            notify.notify_one();
+        });
+    }
+
+    /// 监听SIGHUP（仅Unix），收到后设置重新加载标记并唤醒主循环
+    fn spawn_tls_reload_signal_listener(&self) {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut sighup = match signal(SignalKind::hangup()) {
+                Ok(sighup) => sighup,
+                Err(e) => {
+                    error!("Failed to listen for SIGHUP: {}", e);
+                    return;
+                }
+            };
+            let tls_reload_signal = Arc::clone(&self.tls_reload_signal);
+            let notify = Arc::clone(&self.notify);
+            tokio::spawn(async move {
+                while sighup.recv().await.is_some() {
+                    tls_reload_signal.store(true, Ordering::Relaxed);
+                    notify.notify_one();
+                }
+            });
+        }
+    }
+
+    /// 向master发送离开请求，继续处理网络事件，直到master关闭会话（确认离开）或超过leave_timeout_ms
+    /// 
+    /// 在主循环结束后、dispose之前调用，保证离开请求在连接关闭前写出
+    async fn leave_cluster_gracefully(&mut self) {
+        if !self.cluster_manager.leave_cluster() {
+            return;
+        }
+        
+        let event_queue = self.network_engine.get_event_queue();
+        let deadline = Instant::now() + self.cluster_manager.get_leave_timeout();
+        while self.cluster_manager.is_leave_pending() {
+            let now = Instant::now();
+            if now >= deadline {
+                warn!("Master did not confirm leave within {}ms, leaving cluster anyway", 
+                      self.cluster_manager.get_leave_timeout().as_millis());
+                return;
+            }
+            
+            while let Some(mut event) = event_queue.pop().await {
+                self.network_event_manager.dispatch(&mut event);
+            }
+            let _ = timeout(deadline - now, self.notify.notified()).await;
+        }
+        info!("Master confirmed server {} left the cluster", self.server_id);
+    }
+
     pub fn stop(&mut self) {
         self.is_running = false;
     }
@@ -488,3 +777,27 @@ impl Server {
         self.notify.notify_one();
     }
 }
+
+/// 等待退出信号：Unix上为SIGTERM或Ctrl+C，其他平台为Ctrl+C
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+                return;
+            }
+            Err(e) => error!("Failed to listen for SIGTERM: {}", e),
+        }
+    }
+    
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        error!("Failed to listen for Ctrl+C: {}", e);
+        // 无法监听信号时不触发退出
+        std::future::pending::<()>().await;
+    }
+}
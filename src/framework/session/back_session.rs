@@ -1,19 +1,60 @@
 use super::session_trait::SessionTrait;
 use crate::framework::network::connection::tcp_connection::TcpConnection;
-use crate::framework::network::connection::ConnectionTrait;
-use crate::framework::network::network_event_queue::ServerType;
+use crate::framework::network::connection::{ConnectionTrait, SendQueueOptions};
+use crate::framework::network::network_event_queue::{DisconnectReason, ServerType};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{info, error, debug};
 
+/// 后端会话握手状态
+///
+/// 主动发起的连接：Connecting → Handshaking → Authorized → Closing；
+/// 接受的连接从Handshaking开始。只有Authorized状态的会话可以收发握手以外的消息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackSessionState {
+    /// 主动发起的连接，TCP尚未建立
+    Connecting,
+    /// TCP已建立，正在进行节点认证
+    Handshaking,
+    /// 收到对端的认证响应，握手完成
+    Authorized,
+    /// 会话已关闭
+    Closing,
+}
+
+impl BackSessionState {
+    /// 状态名称，用于日志和状态查询
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackSessionState::Connecting => "connecting",
+            BackSessionState::Handshaking => "handshaking",
+            BackSessionState::Authorized => "authorized",
+            BackSessionState::Closing => "closing",
+        }
+    }
+}
+
 pub struct BackSession {
     session_id: u64,
     server_id: u32,
     user_id: Option<u64>,
     remote_addr: Option<SocketAddr>,
-    authenticated: bool,
+    /// 握手状态
+    state: BackSessionState,
+    /// 进入当前状态的时间，用于握手超时
+    state_time: Instant,
     server_type: Option<String>,
     tcp_connection: Option<TcpConnection>,
+    last_heartbeat_time: Instant,
+    /// 对端在心跳中上报的负载
+    reported_load: u32,
+    /// 通过本会话发出、尚未收到响应的RPC请求数量
+    pending_request_count: u32,
+    /// 节点认证随机数：监听方为发给对端的挑战，连接方为自己发出的随机数，使用一次后清空
+    auth_nonce: Vec<u8>,
+    /// 后端双向TLS时对端证书中的服务器ID，明文连接为None
+    cert_server_id: Option<u32>,
 }
 
 impl BackSession {
@@ -24,9 +65,15 @@ impl BackSession {
             server_id,
             user_id: None,
             remote_addr,
-            authenticated: false,
+            state: BackSessionState::Handshaking,
+            state_time: Instant::now(),
             server_type: None,
             tcp_connection,
+            last_heartbeat_time: Instant::now(),
+            reported_load: 0,
+            pending_request_count: 0,
+            auth_nonce: Vec::new(),
+            cert_server_id: None,
         }
     }
 
@@ -34,23 +81,21 @@ impl BackSession {
     pub fn new_client(
         session_id: u64,
         server_id: u32,
-        host: &str,
-        port: u16,
         event_queue: crate::framework::network::network_event_queue::NetworkEventQueue,
         notify: Arc<tokio::sync::Notify>,
+        send_queue_options: SendQueueOptions,
     ) -> Self {
-        let addr = format!("{}:{}", host, port);
-        let remote_addr = addr.parse::<SocketAddr>().expect("Invalid socket address");
-        
         let tcp_connection = TcpConnection::new_for_client(
             session_id, 
-            remote_addr,
             event_queue,
             notify,
             ServerType::BackTcp,
+            send_queue_options,
         );
         
-        Self::new(session_id, server_id, Some(tcp_connection), Some(remote_addr))
+        let mut session = Self::new(session_id, server_id, Some(tcp_connection), None);
+        session.state = BackSessionState::Connecting;
+        session
     }
 
 
@@ -75,6 +120,107 @@ impl BackSession {
         self.server_type = server_type;
     }
 
+    /// 获取握手状态
+    pub fn get_state(&self) -> BackSessionState {
+        self.state
+    }
+
+    /// 切换握手状态
+    pub fn set_state(&mut self, state: BackSessionState) {
+        if self.state != state {
+            debug!("BackSession {} state changed: {} -> {}", self.session_id, self.state.as_str(), state.as_str());
+            self.state = state;
+            self.state_time = Instant::now();
+        }
+    }
+
+    /// 进入当前状态后经过的时间
+    pub fn get_state_elapsed(&self) -> Duration {
+        self.state_time.elapsed()
+    }
+
+    /// 获取最近一次收到心跳的时间
+    pub fn get_last_heartbeat_time(&self) -> Instant {
+        self.last_heartbeat_time
+    }
+
+    /// 刷新心跳时间（收到心跳请求或响应时调用）
+    pub fn refresh_heartbeat_time(&mut self) {
+        self.last_heartbeat_time = Instant::now();
+    }
+
+    /// 获取对端在心跳中上报的负载
+    pub fn get_reported_load(&self) -> u32 {
+        self.reported_load
+    }
+
+    /// 更新对端上报的负载（收到心跳请求时调用）
+    pub fn set_reported_load(&mut self, load: u32) {
+        self.reported_load = load;
+    }
+
+    /// 获取尚未收到响应的RPC请求数量
+    pub fn get_pending_request_count(&self) -> u32 {
+        self.pending_request_count
+    }
+
+    /// 发出RPC请求时调用
+    pub fn increase_pending_requests(&mut self) {
+        self.pending_request_count = self.pending_request_count.saturating_add(1);
+    }
+
+    /// RPC请求收到响应、超时或失败时调用
+    pub fn decrease_pending_requests(&mut self) {
+        self.pending_request_count = self.pending_request_count.saturating_sub(1);
+    }
+
+    /// 保存节点认证随机数
+    pub fn set_auth_nonce(&mut self, nonce: Vec<u8>) {
+        self.auth_nonce = nonce;
+    }
+
+    /// 是否有等待校验的节点认证随机数
+    pub fn has_auth_nonce(&self) -> bool {
+        !self.auth_nonce.is_empty()
+    }
+
+    /// 取出节点认证随机数，取出后清空，保证随机数只被校验一次
+    pub fn take_auth_nonce(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.auth_nonce)
+    }
+
+    /// 对端证书中的服务器ID
+    pub fn get_cert_server_id(&self) -> Option<u32> {
+        self.cert_server_id
+    }
+
+    /// 设置对端证书中的服务器ID（TLS握手完成后调用）
+    pub fn set_cert_server_id(&mut self, server_id: Option<u32>) {
+        self.cert_server_id = server_id;
+    }
+
+    /// 设置远程地址（主动连接在连接成功后才知道解析出的地址）
+    pub fn set_remote_addr(&mut self, remote_addr: Option<SocketAddr>) {
+        self.remote_addr = remote_addr;
+    }
+
+    /// 发送队列中等待写出的消息数
+    pub fn get_send_queue_depth(&self) -> usize {
+        self.tcp_connection.as_ref().map_or(0, |tcp_connection| tcp_connection.get_send_queue_depth())
+    }
+
+    /// 超过timeout没有收到或写出数据
+    pub fn is_timeout(&self, timeout: Duration) -> bool {
+        self.tcp_connection.as_ref().is_some_and(|tcp_connection| tcp_connection.is_timeout(timeout))
+    }
+
+    /// 主动断开连接，事件处理器收到带有原因的Disconnect事件，会话随后被移除
+    pub fn disconnect(&mut self, reason: DisconnectReason) {
+        if let Some(ref mut tcp_connection) = self.tcp_connection {
+            tcp_connection.disconnect(reason);
+        }
+    }
+
     /// 获取TCP连接的可变引用
     pub fn get_tcp_connection_mut(&mut self) -> Option<&mut TcpConnection> {
         self.tcp_connection.as_mut()
@@ -118,18 +264,21 @@ impl SessionTrait for BackSession {
     }
 
     fn is_authenticated(&self) -> bool {
-        self.authenticated
+        self.state == BackSessionState::Authorized
     }
 
+    /// 设置为已认证即进入Authorized状态；取消认证时回到Handshaking
     fn set_authenticated(&mut self, authenticated: bool) -> bool {
-        if self.authenticated != authenticated {
-            self.authenticated = authenticated;
-            debug!("BackSession {} authentication status changed to: {}", self.session_id, authenticated);
+        if authenticated {
+            self.set_state(BackSessionState::Authorized);
+        } else if self.state == BackSessionState::Authorized {
+            self.set_state(BackSessionState::Handshaking);
         }
         true
     }
 
     fn close(&mut self) -> bool {
+        self.set_state(BackSessionState::Closing);
         if self.is_connected() {
             // 优雅关闭TCP连接
             if let Some(mut tcp_conn) = self.tcp_connection.take() {
@@ -1,11 +1,18 @@
-use super::{BackSession, SessionTrait};
+use super::{BackSession, BackSessionState, SessionTrait};
 use crate::framework::msg::MsgProcessor;
-use crate::framework::network::{NetworkEventHandler, NetworkEngineEventManager, network_event_queue::{NetworkEventData, NetworkEventType, NetworkEventQueue, ServerType}, connection::ConnectionTrait};
+use crate::framework::network::net_addr;
+use crate::framework::network::{NetworkEventHandler, NetworkEngineEventManager, network_event_queue::{DisconnectReason, NetworkEventData, NetworkEventType, NetworkEventQueue, ServerType}, connection::ConnectionTrait};
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::net::TcpStream;
-use tracing::{info, error, debug};
+use crate::framework::network::connection::SendQueueOptions;
+use std::time::Duration;
+use crate::framework::network::NetStream;
+use crate::framework::network::tls::BackTlsContext;
+use tracing::{info, error, debug, warn};
+
+/// 后端会话授权监听器，参数为对端服务器ID和服务器类型
+pub type BackSessionAuthorizedListener = Box<dyn FnMut(u32, &str) + Send>;
 
 pub struct BackSessionManager {
     sessions: HashMap<u64, BackSession>,
@@ -15,6 +22,14 @@ pub struct BackSessionManager {
     msg_processor: Option<Arc<dyn MsgProcessor>>,
     event_queue: Option<NetworkEventQueue>,
     notify: Option<Arc<tokio::sync::Notify>>,
+    /// 新建连接的发送队列参数
+    send_queue_options: SendQueueOptions,
+    /// 后端双向TLS上下文，None表示后端使用明文TCP
+    back_tls: Option<Arc<BackTlsContext>>,
+    /// 已授权会话的最长空闲时间，0表示不检查
+    idle_timeout: Duration,
+    /// 会话完成授权时回调的监听器
+    authorized_listeners: Vec<BackSessionAuthorizedListener>,
     is_initialized: bool,
 }
 
@@ -31,6 +46,10 @@ impl BackSessionManager {
             msg_processor: None,
             event_queue: None,
             notify: None,
+            send_queue_options: SendQueueOptions::default(),
+            back_tls: None,
+            idle_timeout: Duration::ZERO,
+            authorized_listeners: Vec::new(),
             is_initialized: false,
         }
     }
@@ -69,12 +88,58 @@ impl BackSessionManager {
         // 关闭所有会话
         self.close_all();
         self.close_all_unauthorized();
+        self.authorized_listeners.clear();
 
         self.is_initialized = false;
         
         info!("BackSessionManager disposed");
     }
 
+    /// 设置后端双向TLS上下文，主动连接使用TLS并校验对端身份，接受的连接记录证书中的服务器ID
+    pub fn set_back_tls(&mut self, back_tls: Option<Arc<BackTlsContext>>) {
+        self.back_tls = back_tls;
+    }
+
+    /// 后端是否启用双向TLS
+    pub fn is_back_tls_enabled(&self) -> bool {
+        self.back_tls.is_some()
+    }
+
+    /// 设置新建连接的发送队列参数（send_queue配置中的back_policy）
+    pub fn set_send_queue_options(&mut self, send_queue_options: SendQueueOptions) {
+        self.send_queue_options = send_queue_options;
+    }
+
+    /// 设置已授权会话的空闲超时（idle_timeout配置中的back_ms），为0时不检查
+    ///
+    /// 未授权的会话由集群握手超时处理
+    pub fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.idle_timeout = idle_timeout;
+    }
+
+    /// 关闭超过空闲时间没有收发数据的已授权会话
+    ///
+    /// 会话在处理原因为IdleTimeout的断开事件时移除，主动发起的连接随后进入重连流程
+    ///
+    /// # 返回值
+    /// 本次关闭的会话数
+    pub fn close_idle_sessions(&mut self) -> usize {
+        if self.idle_timeout.is_zero() {
+            return 0;
+        }
+        let mut closed_count = 0;
+        for (session_id, session) in self.sessions.iter_mut() {
+            if !session.is_connected() || !session.is_timeout(self.idle_timeout) {
+                continue;
+            }
+            warn!("Back session {} (server {}) idle for more than {}ms, closing ({} queued messages)",
+                  session_id, session.get_server_id(), self.idle_timeout.as_millis(), session.get_send_queue_depth());
+            session.disconnect(DisconnectReason::IdleTimeout);
+            closed_count += 1;
+        }
+        closed_count
+    }
+
     /// Get message processor reference
     pub fn get_msg_processor(&self) -> Option<Arc<dyn MsgProcessor>> {
         self.msg_processor.clone()
@@ -100,6 +165,10 @@ impl BackSessionManager {
         self.sessions.values().find(|session| session.get_server_id() == server_id)
     }
 
+    pub fn find_session_by_server_id_mut(&mut self, server_id: u32) -> Option<&mut BackSession> {
+        self.sessions.values_mut().find(|session| session.get_server_id() == server_id)
+    }
+
     pub fn get_session_count(&self) -> usize {
         self.sessions.len()
     }
@@ -116,6 +185,18 @@ impl BackSessionManager {
         self.unauthorized_sessions.len()
     }
 
+    /// 正在握手的会话数量
+    pub fn get_handshaking_count(&self) -> usize {
+        self.unauthorized_sessions.values()
+            .filter(|session| session.get_state() == BackSessionState::Handshaking)
+            .count()
+    }
+
+    /// 获取会话的握手状态
+    pub fn get_session_state(&self, session_id: u64) -> Option<BackSessionState> {
+        self.get_any_session(session_id).map(|session| session.get_state())
+    }
+
     pub fn get_unauthorized_session(&self, session_id: u64) -> Option<&BackSession> {
         self.unauthorized_sessions.get(&session_id)
     }
@@ -164,10 +245,22 @@ impl BackSessionManager {
         }
     }
 
+    /// 直接加入已授权会话，供单元测试构造会话表
+    #[cfg(test)]
+    pub(crate) fn insert_session(&mut self, session: BackSession) {
+        self.sessions.insert(session.get_session_id(), session);
+    }
+
+    /// 添加会话授权监听器，与其他服务器的连接（包括断开后重连）完成握手时回调
+    pub fn add_authorized_listener(&mut self, listener: BackSessionAuthorizedListener) {
+        self.authorized_listeners.push(listener);
+    }
+
     /// 将未授权会话移动到已授权会话，并更新服务器信息
     pub fn authorize_session(&mut self, session_id: u64, server_id: u32, server_type: String) -> bool {
         if let Some(mut session) = self.unauthorized_sessions.remove(&session_id) {
             session.set_authenticated(true);  // 设置为已认证
+            session.refresh_heartbeat_time();  // 从授权时开始计算心跳超时
             
             // 更新服务器信息
             session.set_server_id(server_id);
@@ -176,6 +269,9 @@ impl BackSessionManager {
             
             self.sessions.insert(session_id, session);
             debug!("Session {} moved from unauthorized to authorized", session_id);
+            for listener in self.authorized_listeners.iter_mut() {
+                listener(server_id, &server_type);
+            }
             true
         } else {
             error!("Session {} not found in unauthorized sessions", session_id);
@@ -205,6 +301,26 @@ impl BackSessionManager {
         true
     }
 
+    /// 关闭握手超时的会话
+    /// 
+    /// # 返回值
+    /// 被关闭的 <会话ID, 服务器ID>，主动发起的连接需要由调用者重连
+    pub fn close_expired_handshakes(&mut self, timeout: Duration) -> Vec<(u64, u32)> {
+        let expired: Vec<(u64, u32)> = self.unauthorized_sessions.values()
+            .filter(|session| session.get_state() == BackSessionState::Handshaking && session.get_state_elapsed() > timeout)
+            .map(|session| (session.get_session_id(), session.get_server_id()))
+            .collect();
+
+        for &(session_id, server_id) in &expired {
+            if let Some(mut session) = self.unauthorized_sessions.remove(&session_id) {
+                warn!("Back session {} (server {}) did not finish handshake within {}ms, closing", 
+                      session_id, server_id, timeout.as_millis());
+                session.close();
+            }
+        }
+        expired
+    }
+
     /// 更新所有未授权会话
     pub fn update_all_unauthorized(&mut self) -> bool {
         let mut disconnected_sessions = Vec::new();
@@ -276,7 +392,19 @@ impl BackSessionManager {
             .count()
     }
 
-    pub fn create_session(&mut self, server_id: u32, tcp_stream: TcpStream, remote_addr: SocketAddr) {
+    pub fn create_session(&mut self, server_id: u32, tcp_stream: NetStream, remote_addr: SocketAddr) {
+        // 启用双向TLS时从对端证书中取得服务器ID，随后的注册/连接请求必须与之一致
+        let cert_server_id = match self.back_tls {
+            Some(ref back_tls) => match back_tls.peer_server_id(&tcp_stream) {
+                Some(cert_server_id) => Some(cert_server_id),
+                None => {
+                    warn!("Security: Rejected back connection from {}: peer certificate has no node identity", remote_addr);
+                    return;
+                }
+            },
+            None => None,
+        };
+
         let session_id = self.next_session_id;
         self.next_session_id += 1;
 
@@ -288,6 +416,7 @@ impl BackSessionManager {
             self.event_queue.as_ref().expect("BackSessionManager event_queue should be initialized").clone(),
             self.notify.as_ref().expect("BackSessionManager notify should be initialized").clone(),
             ServerType::BackTcp,
+            self.send_queue_options.clone(),
         );
         
         // 设置消息处理器
@@ -304,6 +433,7 @@ impl BackSessionManager {
         
         let mut session = BackSession::new(session_id, server_id, Some(tcp_connection), Some(remote_addr));
         session.set_authenticated(false);  // 明确设置为未认证
+        session.set_cert_server_id(cert_server_id);
         
         // 新创建的session先加入未授权列表
         self.unauthorized_sessions.insert(session_id, session);
@@ -311,18 +441,26 @@ impl BackSessionManager {
         debug!("Created unauthorized back session {} from {} for server {} with read task", session_id, remote_addr, server_id);
     }
 
-    /// 创建新的客户端会话
-    pub fn create_client_session(&mut self, server_id: u32, host: &str, port: u16) {
+    /// 创建新的客户端会话，主机名在连接任务中异步解析
+    /// 
+    /// # 返回值
+    /// 新创建会话的ID，地址无效时返回None
+    pub fn create_client_session(&mut self, server_id: u32, host: &str, port: u16) -> Option<u64> {
+        let target = net_addr::format_addr(host, port);
+        if !net_addr::is_valid_host(host) || port == 0 {
+            error!("Invalid address {} for server {}", target, server_id);
+            return None;
+        }
+
         let session_id = self.next_session_id;
         self.next_session_id += 1;
 
         let mut session = BackSession::new_client(
             session_id, 
             server_id, 
-            host, 
-            port,
             self.event_queue.as_ref().expect("BackSessionManager event_queue should be initialized").clone(),
             self.notify.as_ref().expect("BackSessionManager notify should be initialized").clone(),
+            self.send_queue_options.clone(),
         );
         
         // 设置消息处理器
@@ -333,24 +471,36 @@ impl BackSessionManager {
             error!("No message processor available for client session {}", session_id);
         }
         
-        // 获取远程地址并调用connect_to
-        let addr_str = format!("{}:{}", host, port);
-        if let Ok(socket_addr) = addr_str.parse::<SocketAddr>() {
-            if let Some(tcp_connection) = session.get_tcp_connection_mut() {
-                if let (Some(ref event_queue), Some(ref notify)) = (&self.event_queue, &self.notify) {
-                    tcp_connection.connect_to(socket_addr, event_queue.clone(), Some(Arc::clone(notify)));
-                    debug!("TCP connection connecting to {}", socket_addr);
-                } else {
-                    error!("BackSessionManager: Network components not set, cannot connect");
+        // 启用双向TLS时，对端证书必须是目标服务器的身份
+        if let Some(ref back_tls) = self.back_tls {
+            match back_tls.connector(server_id) {
+                Ok(connector) => {
+                    if let Some(tcp_connection) = session.get_tcp_connection_mut() {
+                        tcp_connection.set_tls_connector(connector);
+                    }
+                    session.set_cert_server_id(Some(server_id));
+                }
+                Err(e) => {
+                    error!("Failed to create TLS connector for server {}: {}", server_id, e);
+                    return None;
                 }
             }
-        } else {
-            error!("Invalid address: {}:{}", host, port);
+        }
+        
+        // 调用connect_to连接远程地址
+        if let Some(tcp_connection) = session.get_tcp_connection_mut() {
+            if let (Some(ref event_queue), Some(ref notify)) = (&self.event_queue, &self.notify) {
+                tcp_connection.connect_to(host.to_string(), port, event_queue.clone(), Some(Arc::clone(notify)));
+                debug!("TCP connection connecting to {}", target);
+            } else {
+                error!("BackSessionManager: Network components not set, cannot connect");
+            }
         }
         
         session.set_authenticated(false);  // 客户端会话初始状态为未认证
         self.unauthorized_sessions.insert(session_id, session);
-        debug!("Created client back session {} for server {} connecting to {}:{}", session_id, server_id, host, port);
+        debug!("Created client back session {} for server {} connecting to {}", session_id, server_id, target);
+        Some(session_id)
     }
 
     pub fn remove_session(&mut self, session_id: u64) -> bool {
@@ -441,12 +591,15 @@ impl BackSessionManager {
         
         // 先检查session是否存在（可能是front session，所以不存在是正常的）
         if let Some(session) = self.get_any_session_mut(session_id) {
-            // session存在，再取出tcp_stream
-            if let Some(tcp_stream) = event_data.tcp_stream.take() {
+            // session存在，再取出tcp_stream和解析出的地址
+            if let (Some(tcp_stream), Some(remote_addr)) = (event_data.tcp_stream.take(), event_data.remote_addr) {
+                session.set_remote_addr(Some(remote_addr));
                 // 获取tcp_connection并设置stream
                 if let Some(tcp_connection) = session.get_tcp_connection_mut() {
-                    tcp_connection.set_tcp_stream(tcp_stream);
+                    tcp_connection.set_tcp_stream(tcp_stream, remote_addr);
                     debug!("BackSessionManager: Successfully set TCP stream for session {}", session_id);
+                    // TCP已建立，开始节点认证
+                    session.set_state(BackSessionState::Handshaking);
                 } else {
                     error!("BackSessionManager: Session {} has no TCP connection", session_id);
                 }
@@ -457,6 +610,17 @@ impl BackSessionManager {
     }
     
     
+    /// 处理客户端连接失败事件
+    fn process_client_connect_failed(&mut self, event_data: &NetworkEventData) {
+        let session_id = event_data.session_id;
+        
+        if self.unauthorized_sessions.remove(&session_id).is_some() {
+            info!("BackSessionManager: Removed client session {} after connect failure", session_id);
+        } else {
+            debug!("BackSessionManager: ClientConnectFailed event for unknown session {}", session_id);
+        }
+    }
+    
     /// 处理连接断开事件
     fn process_disconnect(&mut self, event_data: &NetworkEventData) {
         let session_id = event_data.session_id;
@@ -511,6 +675,9 @@ impl NetworkEventHandler for BackSessionManager {
             NetworkEventType::ClientConnectSuccess => {
                 self.process_client_connect_event(event);
             }
+            NetworkEventType::ClientConnectFailed => {
+                self.process_client_connect_failed(event);
+            }
             NetworkEventType::Disconnect => {
                 // 处理连接断开
                 if event.server_type == ServerType::BackTcp {
@@ -1,8 +1,10 @@
 use crate::framework::network::network_engine_event_manager::{NetworkEventHandler, NetworkEngineEventManager};
 use crate::framework::network::network_event_queue::{NetworkEventData, NetworkEventType, ServerType};
 use super::{BackSession, BackSessionManager};
-use tracing::{debug, info, error, warn};
-use std::collections::HashMap;
+use crate::proto::messages::MessageId;
+use crate::proto::messages::protobuf::message::protobuf_message_id::MessageFactory;
+use tracing::{debug, error, warn};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
 /// 消息处理函数类型
@@ -15,6 +17,8 @@ pub type BackMessageHandler = Box<dyn Fn(&mut BackSession, &dyn std::any::Any) +
 pub struct BackSessionMessageDispatcher {
     /// 消息处理器映射 (message_id -> handler)
     handlers: HashMap<u16, BackMessageHandler>,
+    /// 握手消息ID，握手完成前的会话只能收到这些消息
+    handshake_message_ids: HashSet<u16>,
     /// 后端会话管理器指针
     session_manager: *mut BackSessionManager,
 }
@@ -27,6 +31,7 @@ impl BackSessionMessageDispatcher {
     pub fn new() -> Self {
         Self {
             handlers: HashMap::new(),
+            handshake_message_ids: HashSet::new(),
             session_manager: std::ptr::null_mut(),
         }
     }
@@ -48,6 +53,39 @@ impl BackSessionMessageDispatcher {
     }
     
 
+    /// 注册类型化的消息处理器，消息ID由消息类型的MessageId::MSG_ID决定
+    /// 
+    /// 处理函数直接接收具体的消息类型，分发时自动完成downcast
+    pub fn register<M, F>(&mut self, handler: F)
+    where
+        M: MessageId + 'static,
+        F: Fn(&mut BackSession, &M) + Send + Sync + 'static,
+    {
+        self.register_handler(M::MSG_ID, Box::new(move |session, message| {
+            match message.downcast_ref::<M>() {
+                Some(message) => handler(session, message),
+                None => error!("Message id {} does not match handler type {}", M::MSG_ID, std::any::type_name::<M>()),
+            }
+        }));
+    }
+
+    /// 注册握手消息处理器，握手完成前的会话也可以收到该消息
+    /// 
+    /// 其他消息在会话完成握手（Authorized）之前都会被拒绝
+    pub fn register_handshake<M, F>(&mut self, handler: F)
+    where
+        M: MessageId + 'static,
+        F: Fn(&mut BackSession, &M) + Send + Sync + 'static,
+    {
+        self.register::<M, F>(handler);
+        self.handshake_message_ids.insert(M::MSG_ID);
+    }
+
+    /// 移除消息类型对应的处理器
+    pub fn unregister<M: MessageId>(&mut self) -> bool {
+        self.unregister_handler(M::MSG_ID)
+    }
+
     /// 注册消息处理器
     pub fn register_handler(&mut self, message_id: u16, handler: BackMessageHandler) {
         self.handlers.insert(message_id, handler);
@@ -55,6 +93,7 @@ impl BackSessionMessageDispatcher {
 
     /// 移除指定消息ID的处理器
     pub fn unregister_handler(&mut self, message_id: u16) -> bool {
+        self.handshake_message_ids.remove(&message_id);
         if self.handlers.remove(&message_id).is_some() {
             debug!("Unregistered handler for message id {}", message_id);
             return true;
@@ -66,6 +105,7 @@ impl BackSessionMessageDispatcher {
     pub fn clear_all_handlers(&mut self) {
         let total_count = self.handlers.len();
         self.handlers.clear();
+        self.handshake_message_ids.clear();
         debug!("Cleared {} message handlers", total_count);
     }
 
@@ -129,16 +169,23 @@ impl NetworkEventHandler for BackSessionMessageDispatcher {
                             debug!("Found authorized session {} for message dispatch", event.session_id);
                             handler(session, message.as_ref());
                         } 
-                        // 如果在已授权会话中找不到，尝试未授权会话
+                        // 如果在已授权会话中找不到，尝试未授权会话，握手完成前只接受握手消息
                         else if let Some(session) = session_manager.get_unauthorized_session_mut(event.session_id) {
-                            debug!("Found unauthorized session {} for message dispatch", event.session_id);
-                            handler(session, message.as_ref());
+                            if self.handshake_message_ids.contains(&message_id) {
+                                debug!("Found unauthorized session {} for message dispatch", event.session_id);
+                                handler(session, message.as_ref());
+                            } else {
+                                warn!("Security: Rejected message id {} ({}) from back session {} in state {}, closing session",
+                                      message_id, MessageFactory::message_name(message_id).unwrap_or("unknown"),
+                                      event.session_id, session.get_state().as_str());
+                                session_manager.remove_unauthorized_session(event.session_id);
+                            }
                         } else {
                             error!("BackSession {} not found in both authorized and unauthorized sessions for message dispatch", event.session_id);
                         }
                     }
                 } else {
-                    debug!("No handler found for message id {}", message_id);
+                    debug!("No handler found for message id {} ({})", message_id, MessageFactory::message_name(message_id).unwrap_or("unknown"));
                 }
             }
         }
@@ -1,13 +1,12 @@
 use super::session_trait::SessionTrait;
 use super::front_session_meta_data::FrontSessionMetaData;
-use crate::framework::network::connection::{ConnectionTrait, TcpConnection, WebSocketConnection};
-use crate::framework::network::{NetworkEventQueue, network_event_queue::ServerType};
+use crate::framework::network::connection::{ConnectionTrait, SendQueueOptions, TcpConnection, WebSocketConnection};
+use crate::framework::network::{NetworkEventQueue, network_event_queue::{DisconnectReason, ServerType}};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::Duration;
 use tokio_tungstenite::WebSocketStream;
-use tokio::net::TcpStream;
-use tungstenite::Message;
+use crate::framework::network::NetStream;
 use tracing::{info, error, debug};
 
 pub enum ConnectionType {
@@ -22,8 +21,6 @@ pub struct FrontSession {
     authenticated: bool,
     metadata: FrontSessionMetaData,
     connection: Option<ConnectionType>,
-    send_queue: mpsc::UnboundedSender<Message>,
-    _recv_queue: mpsc::UnboundedReceiver<Message>,
 }
 
 impl FrontSession {
@@ -31,14 +28,13 @@ impl FrontSession {
     /// 使用TCP连接创建FrontSession
     pub fn new_with_tcp(
         session_id: u64, 
-        tcp_stream: TcpStream, 
+        tcp_stream: NetStream, 
         remote_addr: SocketAddr,
         event_queue: NetworkEventQueue,
         notify: Arc<tokio::sync::Notify>,
         server_type: ServerType,
+        send_queue_options: SendQueueOptions,
     ) -> Self {
-        let (send_tx, recv_rx) = mpsc::unbounded_channel();
-        
         let tcp_connection = TcpConnection::new(
             session_id, 
             tcp_stream, 
@@ -46,6 +42,7 @@ impl FrontSession {
             event_queue,
             notify,
             server_type,
+            send_queue_options,
         );
         
         // 注意：不在这里启动读取任务，需要在设置了msg_processor之后手动启动
@@ -60,22 +57,19 @@ impl FrontSession {
             authenticated: false,
             metadata: FrontSessionMetaData::new(),
             connection: Some(connection),
-            send_queue: send_tx,
-            _recv_queue: recv_rx,
         }
     }
     
     /// 使用WebSocket连接创建FrontSession
     pub fn new_with_websocket(
         session_id: u64, 
-        ws_stream: WebSocketStream<TcpStream>, 
+        ws_stream: WebSocketStream<NetStream>, 
         remote_addr: SocketAddr,
         event_queue: crate::framework::network::NetworkEventQueue,
         notify: Arc<tokio::sync::Notify>,
         server_type: crate::framework::network::network_event_queue::ServerType,
+        send_queue_options: SendQueueOptions,
     ) -> Self {
-        let (send_tx, recv_rx) = mpsc::unbounded_channel();
-        
         let connection = ConnectionType::WebSocket(WebSocketConnection::new(
             session_id, 
             ws_stream, 
@@ -83,6 +77,7 @@ impl FrontSession {
             event_queue,
             notify,
             server_type,
+            send_queue_options,
         ));
         
         Self {
@@ -92,15 +87,11 @@ impl FrontSession {
             authenticated: false,
             metadata: FrontSessionMetaData::new(),
             connection: Some(connection),
-            send_queue: send_tx,
-            _recv_queue: recv_rx,
         }
     }
     
     /// 使用已有的TcpConnection创建FrontSession
     pub fn new_with_tcp_connection(session_id: u64, tcp_connection: TcpConnection, remote_addr: SocketAddr) -> Self {
-        let (send_tx, recv_rx) = mpsc::unbounded_channel();
-        
         let connection = ConnectionType::Tcp(tcp_connection);
         
         Self {
@@ -110,8 +101,6 @@ impl FrontSession {
             authenticated: false,
             metadata: FrontSessionMetaData::new(),
             connection: Some(connection),
-            send_queue: send_tx,
-            _recv_queue: recv_rx,
         }
     }
 
@@ -126,6 +115,33 @@ impl FrontSession {
         self.connection.as_mut()
     }
 
+    /// 发送队列中等待写出的消息数
+    pub fn get_send_queue_depth(&self) -> usize {
+        match self.connection {
+            Some(ConnectionType::Tcp(ref tcp)) => tcp.get_send_queue_depth(),
+            Some(ConnectionType::WebSocket(ref ws)) => ws.get_send_queue_depth(),
+            None => 0,
+        }
+    }
+
+    /// 超过timeout没有收到或写出数据
+    pub fn is_timeout(&self, timeout: Duration) -> bool {
+        match self.connection {
+            Some(ConnectionType::Tcp(ref tcp)) => tcp.is_timeout(timeout),
+            Some(ConnectionType::WebSocket(ref ws)) => ws.is_timeout(timeout),
+            None => false,
+        }
+    }
+
+    /// 主动断开连接，事件处理器收到带有原因的Disconnect事件，会话随后被移除
+    pub fn disconnect(&mut self, reason: DisconnectReason) {
+        match self.connection {
+            Some(ConnectionType::Tcp(ref mut tcp)) => tcp.disconnect(reason),
+            Some(ConnectionType::WebSocket(ref mut ws)) => ws.disconnect(reason),
+            None => {}
+        }
+    }
+
     /// 获取会话元数据
     pub fn get_metadata(&self) -> &FrontSessionMetaData {
         &self.metadata
@@ -1,5 +1,6 @@
 use super::front_session_group::FrontSessionGroup;
 use super::session_trait::SessionTrait;
+use crate::framework::network::{NetworkEventHandler, network_event_queue::{NetworkEventData, NetworkEventType}};
 use std::collections::HashMap;
 use tracing::{info, debug, warn, error};
 
@@ -208,4 +209,58 @@ impl FrontSessionGroupManager {
             info!("Cleared {} groups and {} session mappings", group_count, session_count);
         }
     }
-}
\ No newline at end of file
+}
+
+impl NetworkEventHandler for FrontSessionGroupManager {
+    /// 前端连接断开时把会话移出所在分组，避免分组中残留已断开的会话
+    fn handle_event(&mut self, event: &mut NetworkEventData) {
+        if !event.server_type.is_front() {
+            return;
+        }
+        match event.event_type {
+            NetworkEventType::Disconnect | NetworkEventType::StreamDataNotExpected
+                if self.session_to_group.contains_key(&event.session_id) =>
+            {
+                self.remove_session_from_group(event.session_id);
+                debug!("Removed disconnected session {} from its group", event.session_id);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::network::network_event_queue::ServerType;
+
+    fn disconnect(server_type: ServerType, session_id: u64) -> NetworkEventData {
+        NetworkEventData::new(NetworkEventType::Disconnect, server_type, session_id, None)
+    }
+
+    #[test]
+    fn front_disconnect_removes_session_from_group() {
+        let mut group_manager = FrontSessionGroupManager::new();
+        let group_id = group_manager.create_group("room".to_string(), None);
+        group_manager.add_session_to_group(group_id, 1);
+        group_manager.add_session_to_group(group_id, 2);
+
+        group_manager.handle_event(&mut disconnect(ServerType::FrontTcp, 1));
+        group_manager.handle_event(&mut disconnect(ServerType::FrontWebSocket, 2));
+
+        assert_eq!(group_manager.get_session_group_id(1), None);
+        assert_eq!(group_manager.get_session_group_id(2), None);
+        assert!(group_manager.get_group(group_id).is_none_or(|group| group.is_empty()));
+    }
+
+    #[test]
+    fn back_disconnect_keeps_front_session_with_same_id() {
+        let mut group_manager = FrontSessionGroupManager::new();
+        let group_id = group_manager.create_group("room".to_string(), None);
+        group_manager.add_session_to_group(group_id, 1);
+
+        group_manager.handle_event(&mut disconnect(ServerType::BackTcp, 1));
+
+        assert_eq!(group_manager.get_session_group_id(1), Some(group_id));
+    }
+}
@@ -1,11 +1,13 @@
 use super::{FrontSession, SessionTrait};
 use crate::framework::msg::MsgProcessor;
-use crate::framework::network::{NetworkEventHandler, NetworkEngineEventManager, network_event_queue::{NetworkEventData, NetworkEventType, NetworkEventQueue, ServerType}};
+use crate::framework::network::{NetworkEventHandler, NetworkEngineEventManager, network_event_queue::{DisconnectReason, NetworkEventData, NetworkEventType, NetworkEventQueue, ServerType}};
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+use crate::framework::network::connection::SendQueueOptions;
 use tokio_tungstenite::WebSocketStream;
-use tokio::net::TcpStream;
+use crate::framework::network::NetStream;
 use tracing::{info, error, debug};
 
 pub struct FrontSessionManager {
@@ -14,6 +16,12 @@ pub struct FrontSessionManager {
     msg_processor: Option<Arc<dyn MsgProcessor>>,
     event_queue: Option<NetworkEventQueue>,
     notify: Option<Arc<tokio::sync::Notify>>,
+    /// 新建连接的发送队列参数
+    send_queue_options: SendQueueOptions,
+    /// 已认证会话的最长空闲时间，0表示不检查
+    idle_timeout: Duration,
+    /// 未认证会话的最长空闲时间，0表示不检查
+    unauthenticated_idle_timeout: Duration,
     is_initialized: bool,
 }
 
@@ -25,6 +33,9 @@ impl FrontSessionManager {
             msg_processor: None,
             event_queue: None,
             notify: None,
+            send_queue_options: SendQueueOptions::default(),
+            idle_timeout: Duration::ZERO,
+            unauthenticated_idle_timeout: Duration::ZERO,
             is_initialized: false,
         }
     }
@@ -66,6 +77,45 @@ impl FrontSessionManager {
         info!("FrontSessionManager disposed");
     }
 
+    /// 设置新建连接的发送队列参数（send_queue配置中的front_policy）
+    pub fn set_send_queue_options(&mut self, send_queue_options: SendQueueOptions) {
+        self.send_queue_options = send_queue_options;
+    }
+
+    /// 设置空闲超时（idle_timeout配置中的front_ms和unauthenticated_ms），为0时不检查
+    pub fn set_idle_timeout(&mut self, idle_timeout: Duration, unauthenticated_idle_timeout: Duration) {
+        self.idle_timeout = idle_timeout;
+        self.unauthenticated_idle_timeout = unauthenticated_idle_timeout;
+    }
+
+    /// 关闭超过空闲时间没有收发数据的会话，未认证的会话使用单独的限制
+    ///
+    /// 会话在处理原因为IdleTimeout的断开事件时移除
+    ///
+    /// # 返回值
+    /// 本次关闭的会话数
+    pub fn close_idle_sessions(&mut self) -> usize {
+        let mut closed_count = 0;
+        for (session_id, session) in self.sessions.iter_mut() {
+            if !session.is_connected() {
+                continue;
+            }
+            let timeout = if session.is_authenticated() {
+                self.idle_timeout
+            } else {
+                self.unauthenticated_idle_timeout
+            };
+            if timeout.is_zero() || !session.is_timeout(timeout) {
+                continue;
+            }
+            info!("FrontSession {} (authenticated: {}) idle for more than {}ms, closing ({} queued messages)",
+                  session_id, session.is_authenticated(), timeout.as_millis(), session.get_send_queue_depth());
+            session.disconnect(DisconnectReason::IdleTimeout);
+            closed_count += 1;
+        }
+        closed_count
+    }
+
     /// Get message processor reference
     pub fn get_msg_processor(&self) -> Option<Arc<dyn MsgProcessor>> {
         self.msg_processor.clone()
@@ -87,6 +137,16 @@ impl FrontSessionManager {
         self.sessions.values_mut().find(|session| session.get_user_id() == Some(user_id))
     }
 
+    /// 对所有会话执行操作
+    pub fn for_each_session<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut FrontSession)
+    {
+        for session in self.sessions.values_mut() {
+            f(session);
+        }
+    }
+
     pub fn get_session_count(&self) -> usize {
         self.sessions.len()
     }
@@ -101,7 +161,7 @@ impl FrontSessionManager {
 
 
     /// 创建一个TCP会话
-    pub fn create_tcp_session(&mut self, tcp_stream: TcpStream, remote_addr: SocketAddr)  {
+    pub fn create_tcp_session(&mut self, tcp_stream: NetStream, remote_addr: SocketAddr) -> u64 {
         let session_id = self.next_session_id;
         self.next_session_id += 1;
 
@@ -113,6 +173,7 @@ impl FrontSessionManager {
             self.event_queue.as_ref().expect("FrontSessionManager event_queue should be initialized").clone(),
             self.notify.as_ref().expect("FrontSessionManager notify should be initialized").clone(),
             ServerType::FrontTcp,
+            self.send_queue_options.clone(),
         );
         
         // Set message processor to the TCP connection if we have one
@@ -143,10 +204,11 @@ impl FrontSessionManager {
         self.sessions.insert(session_id, session);
         
         debug!("Created TCP front session {} from {} with read task", session_id, remote_addr);
+        session_id
     }
     
     /// 创建一个WebSocket会话
-    pub fn create_websocket_session(&mut self, websocket: WebSocketStream<TcpStream>, remote_addr: SocketAddr) {
+    pub fn create_websocket_session(&mut self, websocket: WebSocketStream<NetStream>, remote_addr: SocketAddr) -> u64 {
         let session_id = self.next_session_id;
         self.next_session_id += 1;
 
@@ -161,6 +223,7 @@ impl FrontSessionManager {
             event_queue,
             notify,
             crate::framework::network::network_event_queue::ServerType::FrontWebSocket,
+            self.send_queue_options.clone(),
         );
         
         // Set message processor to the WebSocket connection if we have one
@@ -183,8 +246,15 @@ impl FrontSessionManager {
         self.sessions.insert(session_id, session);
         
         debug!("Created WebSocket front session {} from {}", session_id, remote_addr);
+        session_id
     }
     
+    /// 直接加入会话，供单元测试构造会话表
+    #[cfg(test)]
+    pub(crate) fn insert_session(&mut self, session: FrontSession) {
+        self.sessions.insert(session.get_session_id(), session);
+    }
+
     pub fn remove_session(&mut self, session_id: u64) -> bool {
         if let Some(mut session) = self.sessions.remove(&session_id) {
             session.close();
@@ -227,8 +297,8 @@ impl FrontSessionManager {
         // 取出tcp_stream
         if let Some(tcp_stream) = event_data.tcp_stream.take() {
             if let Some(remote_addr) = event_data.remote_addr {
-                // 创建新的TCP front session
-                self.create_tcp_session(tcp_stream, remote_addr);
+                // 创建新的TCP front session，并把分配的会话ID写回事件供后续的事件处理器使用
+                event_data.session_id = self.create_tcp_session(tcp_stream, remote_addr);
             } else {
                 error!("FrontSessionManager: NewTcpConnection event has no remote address");
             }
@@ -242,8 +312,8 @@ impl FrontSessionManager {
         // 取出websocket_stream
         if let Some(websocket_stream) = event_data.websocket_stream.take() {
             if let Some(remote_addr) = event_data.remote_addr {
-                // 创建新的WebSocket front session
-                self.create_websocket_session(websocket_stream, remote_addr);
+                // 创建新的WebSocket front session，并把分配的会话ID写回事件供后续的事件处理器使用
+                event_data.session_id = self.create_websocket_session(websocket_stream, remote_addr);
             } else {
                 error!("FrontSessionManager: NewWebSocketConnection event has no remote address");
             }
@@ -281,12 +351,11 @@ impl FrontSessionManager {
 
 impl NetworkEventHandler for FrontSessionManager {
     fn handle_event(&mut self, event: &mut NetworkEventData) {
-        if event.server_type != ServerType::FrontTcp {
+        if !event.server_type.is_front() {
             return;
         }
         match event.event_type {
             NetworkEventType::NewTcpConnection => {
-                // 只处理 FrontTcp 类型的连接
                 self.process_new_tcp_connection(event);
             }
             NetworkEventType::NewWebSocketConnection => {
@@ -1,6 +1,8 @@
 use crate::framework::network::network_engine_event_manager::{NetworkEventHandler, NetworkEngineEventManager};
 use crate::framework::network::network_event_queue::{NetworkEventData, NetworkEventType, ServerType};
 use super::{FrontSession, FrontSessionManager};
+use crate::proto::messages::MessageId;
+use crate::proto::messages::protobuf::message::protobuf_message_id::MessageFactory;
 use tracing::{debug, info, error};
 use std::collections::HashMap;
 
@@ -46,6 +48,27 @@ impl FrontSessionMessageDispatcher {
         true
     }
 
+    /// 注册类型化的消息处理器，消息ID由消息类型的MessageId::MSG_ID决定
+    /// 
+    /// 处理函数直接接收具体的消息类型，分发时自动完成downcast
+    pub fn register<M, F>(&mut self, handler: F)
+    where
+        M: MessageId + 'static,
+        F: Fn(&mut FrontSession, &M) + Send + Sync + 'static,
+    {
+        self.register_handler(M::MSG_ID, Box::new(move |session, message| {
+            match message.downcast_ref::<M>() {
+                Some(message) => handler(session, message),
+                None => error!("Message id {} does not match handler type {}", M::MSG_ID, std::any::type_name::<M>()),
+            }
+        }));
+    }
+
+    /// 移除消息类型对应的处理器
+    pub fn unregister<M: MessageId>(&mut self) -> bool {
+        self.unregister_handler(M::MSG_ID)
+    }
+
     /// 注册消息处理器
     pub fn register_handler(&mut self, message_id: u16, handler: FrontMessageHandler) {
         self.handlers.insert(message_id, handler);
@@ -127,7 +150,7 @@ impl NetworkEventHandler for FrontSessionMessageDispatcher {
                                 }
                             }
                         } else {
-                            debug!("No handler found for message id {}", message_id);
+                            debug!("No handler found for message id {} ({})", message_id, MessageFactory::message_name(message_id).unwrap_or("unknown"));
                         }
                     }
                 }
@@ -10,7 +10,7 @@ pub mod back_session_message_dispatcher;
 pub mod front_session_message_dispatcher;
 
 pub use session_trait::SessionTrait;
-pub use back_session::BackSession;
+pub use back_session::{BackSession, BackSessionState};
 pub use front_session::FrontSession;
 pub use front_session_meta_data::FrontSessionMetaData;
 pub use back_session_manager::BackSessionManager;
@@ -1,5 +1,6 @@
 use super::task::Task;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicU64, Ordering};
 use tracing::{debug, info, warn, error};
@@ -64,7 +65,10 @@ impl TaskManager {
     }
     
     /// 添加任务（线程安全）- 自动分配任务ID
-    pub fn add_task(&self, mut task: Box<dyn Task>) {
+    ///
+    /// # 返回值
+    /// 分配的任务ID
+    pub fn add_task(&self, mut task: Box<dyn Task>) -> u64 {
         // 生成唯一的任务ID
         let task_id = self.generate_task_id();
         
@@ -75,27 +79,62 @@ impl TaskManager {
         let mut task_list = self.task_list.lock().unwrap();
         task_list.insert(task_id, task);
         debug!("Added task with auto-assigned id {}", task_id);
+        task_id
+    }
+    
+    /// 添加任务并在Tokio工作线程上执行future
+    ///
+    /// future完成后任务自动移入finish_task_list，之后在主线程调用任务的done；
+    /// future通常把结果写入与任务共享的位置（如Arc<Mutex<Option<T>>>）
+    ///
+    /// # 返回值
+    /// 分配的任务ID
+    pub fn spawn_task<F>(&self, task: Box<dyn Task>, future: F) -> u64
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let task_id = self.add_task(task);
+        
+        let task_list = Arc::clone(&self.task_list);
+        let finish_task_list = Arc::clone(&self.finish_task_list);
+        let notify = self.notify.clone();
+        tokio::spawn(async move {
+            future.await;
+            Self::move_to_finished(&task_list, &finish_task_list, notify.as_ref(), task_id);
+        });
+        
+        task_id
     }
     
     /// 完成任务 - 将任务从task_list移到finish_task_list并通知Server
     /// 这个方法可以从任何线程调用
     pub fn finish_task(&self, task_id: u64) {
+        Self::move_to_finished(&self.task_list, &self.finish_task_list, self.notify.as_ref(), task_id);
+    }
+    
+    /// 将任务从task_list移到finish_task_list并唤醒主循环
+    fn move_to_finished(
+        task_list: &Mutex<HashMap<u64, Box<dyn Task>>>,
+        finish_task_list: &Mutex<HashMap<u64, Box<dyn Task>>>,
+        notify: Option<&Arc<Notify>>,
+        task_id: u64,
+    ) {
         // 从task_list获取任务
         let task = {
-            let mut task_list = self.task_list.lock().unwrap();
+            let mut task_list = task_list.lock().unwrap();
             task_list.remove(&task_id)
         };
         
         if let Some(task) = task {
             // 放入finish_task_list
             {
-                let mut finish_list = self.finish_task_list.lock().unwrap();
+                let mut finish_list = finish_task_list.lock().unwrap();
                 finish_list.insert(task_id, task);
             }
             debug!("Task {} moved to finish_task_list", task_id);
             
             // 调用server的notify
-            if let Some(ref notify) = self.notify {
+            if let Some(notify) = notify {
                 notify.notify_one();
                 debug!("Notified server for task {}", task_id);
             } else {
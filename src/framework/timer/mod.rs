@@ -1,5 +1,7 @@
 pub mod timer;
 pub mod time_manager;
+pub mod timer_handle;
 
 pub use timer::{Timer, TimerId, TimerCallback};
-pub use time_manager::TimeManager;
\ No newline at end of file
+pub use time_manager::TimeManager;
+pub use timer_handle::TimerHandle;
\ No newline at end of file
@@ -1,13 +1,17 @@
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
-use crate::framework::{data::MinHeap, timer};
+use std::time::Instant;
+use crate::framework::data::MinHeap;
 use super::timer::{Timer, TimerId, TimerCallback};
 
+/// 没有定时器时主循环的最长等待时间（毫秒）
+const MAX_WAIT_TIME_MS: u64 = 1000;
+
 pub struct TimeManager {
     timers: MinHeap<Timer>,
     timer_map: HashMap<TimerId, bool>,
     next_id: TimerId,
-    now:u64
+    start_time: Instant,  // 单调时钟起点
+    now:u64  // 距离start_time的毫秒数
 }
 
 impl TimeManager {
@@ -17,13 +21,15 @@ impl TimeManager {
             timers: MinHeap::new(),
             timer_map: HashMap::new(),
             next_id: 1,
+            start_time: Instant::now(),
             now:0
         }
     }
 
     // ========== init methods ==========
     pub fn start(&mut self){
-        self.now = Instant::now().elapsed().as_millis() as u64
+        self.start_time = Instant::now();
+        self.now = 0;
     }
 
     // ========== get/set methods ==========
@@ -31,19 +37,39 @@ impl TimeManager {
         self.timer_map.len()
     }
 
+    /// 当前时间（距离start的毫秒数，单调递增）
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
     fn get_next_id(&mut self) -> TimerId {
         let id = self.next_id;
         self.next_id += 1;
         id
     }
 
+    fn refresh_now(&mut self) {
+        self.now = self.start_time.elapsed().as_millis() as u64;
+    }
+
     // ========== other methods ==========
+    /// 添加定时器，delay_time毫秒后执行，共执行repeat_time次
+    /// 
+    /// repeat_time小于0（TIMER_REPEAT_FOREVER）表示无限重复，等于0时不添加
     pub fn add_timer(
         &mut self,
         delay_time: u64,
         callback: TimerCallback,
-        repeat_time: u8
+        repeat_time: i32
     ) -> TimerId {
+        if repeat_time == 0 {
+            return 0;
+        }
+
+        // 重复定时器的间隔至少为1毫秒，避免主循环空转
+        let delay_time = if repeat_time == 1 { delay_time } else { delay_time.max(1) };
+
+        self.refresh_now();
         let id = self.get_next_id();
         let timer = Timer::new(id, delay_time, repeat_time, self.now, callback);
         
@@ -53,23 +79,35 @@ impl TimeManager {
         id
     }
 
+    /// 取消定时器，可以在定时器回调中调用
     pub fn remove_timer(&mut self, timer_id: TimerId) -> bool {
-        self.timer_map.remove(&timer_id).is_some()
+        if self.timer_map.remove(&timer_id).is_none() {
+            return false;
+        }
+
+        // 正在执行的定时器不在堆中，执行完成后不会再放回
+        self.timers.remove_first(|timer| timer.id == timer_id);
+        true
     }
 
+    /// 距离下一个定时器触发的等待时间（毫秒），最长不超过MAX_WAIT_TIME_MS
     pub fn first_time_wait(&mut self) -> u64 {
         // 刷新时间
-        self.now = Instant::now().elapsed().as_millis() as u64;
+        self.refresh_now();
 
         if let Some(timer) = self.timers.peek() {
-            let o = timer.next_trigger;
-           return  o - self.now;
+            timer.next_trigger.saturating_sub(self.now).min(MAX_WAIT_TIME_MS)
         } else {
-            1000
+            MAX_WAIT_TIME_MS
         }
     }
 
+    /// 执行所有到期的定时器（在主循环中调用）
+    /// 
+    /// 回调中新增的定时器最早在下一次tick执行
     pub fn tick(&mut self) {
+        self.refresh_now();
+
         let mut ready_timers = Vec::new();
         while let Some(timer) = self.timers.peek() {
             if timer.is_ready(self.now) {
@@ -82,21 +120,27 @@ impl TimeManager {
         }
 
         for mut timer in ready_timers {
-            if self.timer_map.contains_key(&timer.id) {
-                timer.execute();
-                
-                if timer.repeat_time < 0 {
-                    timer.reset_next_trigger(self.now);
-                    self.timers.insert(timer);
-                } else {
-                    timer.repeat_time -= 1;
-                    if timer.repeat_time > 0 {
-                        timer.reset_next_trigger(self.now);
-                        self.timers.insert(timer);
-                    }else{
-                        self.timer_map.remove(&timer.id).is_some();
-                    }
-                }
+            // 可能已在前面的回调中被取消
+            if !self.timer_map.contains_key(&timer.id) {
+                continue;
+            }
+
+            timer.execute();
+
+            // 回调中取消了自身
+            if !self.timer_map.contains_key(&timer.id) {
+                continue;
+            }
+
+            if timer.repeat_time > 0 {
+                timer.repeat_time -= 1;
+            }
+
+            if timer.repeat_time != 0 {
+                timer.reset_next_trigger(self.now);
+                self.timers.insert(timer);
+            } else {
+                self.timer_map.remove(&timer.id);
             }
         }
     }
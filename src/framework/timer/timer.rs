@@ -3,10 +3,14 @@ use std::cmp::Ordering;
 pub type TimerId = u64;
 pub type TimerCallback = Box<dyn FnMut() + Send>;
 
+/// 无限重复的定时器
+pub const TIMER_REPEAT_FOREVER: i32 = -1;
+
 pub struct Timer {
     pub id: TimerId,
     pub delay_time: u64,
-    pub repeat_time: u8,
+    /// 剩余执行次数，小于0表示无限重复
+    pub repeat_time: i32,
     pub next_trigger: u64,
     pub callback: TimerCallback,
 }
@@ -15,7 +19,7 @@ impl Timer {
     pub fn new(
         id: TimerId,
         delay_time: u64,
-        repeat_time: u8,
+        repeat_time: i32,
         now:u64,
         callback: TimerCallback,
     ) -> Self {
@@ -43,7 +47,7 @@ impl Timer {
 
 impl PartialEq for Timer {
     fn eq(&self, other: &Self) -> bool {
-        self.next_trigger == other.next_trigger
+        self.next_trigger == other.next_trigger && self.id == other.id
     }
 }
 
@@ -56,7 +60,9 @@ impl PartialOrd for Timer {
 }
 
 impl Ord for Timer {
+    // MinHeap堆顶为最早触发的定时器，触发时间相同时先添加的先执行
     fn cmp(&self, other: &Self) -> Ordering {
-        other.next_trigger.cmp(&self.next_trigger)
+        self.next_trigger.cmp(&other.next_trigger)
+            .then_with(|| self.id.cmp(&other.id))
     }
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use super::time_manager::TimeManager;
+use super::timer::{TimerId, TimerCallback, TIMER_REPEAT_FOREVER};
+
+/// 定时器句柄，通过Server::get_timer_handle获取
+/// 
+/// 可以复制并保存在消息处理器或定时器回调中，所有调用都必须在主线程进行
+#[derive(Clone, Copy)]
+pub struct TimerHandle {
+    time_manager: *mut TimeManager,
+}
+
+// 安全性：TimerHandle只在主线程中使用
+unsafe impl Send for TimerHandle {}
+unsafe impl Sync for TimerHandle {}
+
+impl TimerHandle {
+    pub fn new(time_manager: *mut TimeManager) -> Self {
+        Self { time_manager }
+    }
+
+    /// delay_time毫秒后执行一次
+    pub fn add_once<F>(&self, delay_time: u64, callback: F) -> TimerId
+    where
+        F: FnMut() + Send + 'static,
+    {
+        self.add_timer(delay_time, Box::new(callback), 1)
+    }
+
+    /// 每隔interval毫秒执行一次，直到被取消
+    pub fn add_repeat<F>(&self, interval: u64, callback: F) -> TimerId
+    where
+        F: FnMut() + Send + 'static,
+    {
+        self.add_timer(interval, Box::new(callback), TIMER_REPEAT_FOREVER)
+    }
+
+    /// 添加定时器，repeat_time小于0表示无限重复；失败时返回0
+    pub fn add_timer(&self, delay_time: u64, callback: TimerCallback, repeat_time: i32) -> TimerId {
+        // SAFETY: time_manager指向Server中的TimeManager，Server在主循环结束前不会移动或释放；
+        // 句柄只在主线程使用（消息处理函数和定时器回调中），同一时刻不会有其他对TimeManager的引用被使用，
+        // 定时器回调中调用时TimeManager::tick已经把正在执行的定时器移出堆
+        match unsafe { self.time_manager.as_mut() } {
+            Some(time_manager) => time_manager.add_timer(delay_time, callback, repeat_time),
+            None => 0,
+        }
+    }
+
+    /// 取消定时器
+    pub fn remove_timer(&self, timer_id: TimerId) -> bool {
+        // SAFETY: 同add_timer，只在主线程调用
+        unsafe { self.time_manager.as_mut() }
+            .is_some_and(|time_manager| time_manager.remove_timer(timer_id))
+    }
+}
\ No newline at end of file
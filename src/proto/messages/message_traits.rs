@@ -5,6 +5,9 @@ use bytes::BytesMut;
 
 /// Trait for messages with an ID
 pub trait MessageId {
+    /// Message ID of this message type, used for typed handler registration
+    const MSG_ID: u16;
+
     fn msg_id(&self) -> u16;
 }
 
@@ -17,6 +17,33 @@ pub struct ServerConfig {
     #[prost(uint32, tag = "4")]
     pub back_tcp_port: u32,
 }
+/// 节点认证挑战请求（连接方建立连接后首先发送）
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeChallengeBRequest {
+    /// 连接方服务器ID
+    #[prost(uint32, tag = "1")]
+    pub server_id: u32,
+    /// 连接方随机数，监听方用它证明自己持有密钥
+    #[prost(bytes = "vec", tag = "2")]
+    pub nonce: ::prost::alloc::vec::Vec<u8>,
+}
+/// 节点认证挑战响应（监听方返回）
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeChallengeBResponse {
+    /// 监听方服务器ID
+    #[prost(uint32, tag = "1")]
+    pub server_id: u32,
+    /// 监听方随机数，连接方在注册/连接请求中对它签名
+    #[prost(bytes = "vec", tag = "2")]
+    pub nonce: ::prost::alloc::vec::Vec<u8>,
+    /// 监听方对连接方随机数的HMAC
+    #[prost(bytes = "vec", tag = "3")]
+    pub proof: ::prost::alloc::vec::Vec<u8>,
+}
 /// 节点注册请求 (继承SerialMessage)
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -25,9 +52,9 @@ pub struct NodeRegisterBRequest {
     /// 消息ID (从SerialMessage继承)
     #[prost(uint32, tag = "1")]
     pub msg_unique_id: u32,
-    /// 客户端令牌
-    #[prost(string, tag = "2")]
-    pub client_token: ::prost::alloc::string::String,
+    /// 对监听方随机数和本服务器ID的HMAC
+    #[prost(bytes = "vec", tag = "2")]
+    pub auth_proof: ::prost::alloc::vec::Vec<u8>,
     /// 服务器配置
     #[prost(message, optional, tag = "3")]
     pub server_config: ::core::option::Option<ServerConfig>,
@@ -43,6 +70,9 @@ pub struct NodeRegisterBResponse {
     /// 服务器列表
     #[prost(message, repeated, tag = "2")]
     pub server_list: ::prost::alloc::vec::Vec<ServerConfig>,
+    /// 错误码：0成功，1服务器ID已被在线节点使用（请求方应退出）
+    #[prost(uint32, tag = "3")]
+    pub error_code: u32,
 }
 /// 节点连接请求 (继承SerialMessage)
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -52,9 +82,9 @@ pub struct NodeConnectBRequest {
     /// 消息ID (从SerialMessage继承)
     #[prost(uint32, tag = "1")]
     pub msg_unique_id: u32,
-    /// 客户端令牌
-    #[prost(string, tag = "2")]
-    pub client_token: ::prost::alloc::string::String,
+    /// 对监听方随机数和本服务器ID的HMAC
+    #[prost(bytes = "vec", tag = "2")]
+    pub auth_proof: ::prost::alloc::vec::Vec<u8>,
     /// 服务器配置
     #[prost(message, optional, tag = "3")]
     pub server_config: ::core::option::Option<ServerConfig>,
@@ -80,6 +110,57 @@ pub struct NodeRegisterBNotify {
     #[prost(message, optional, tag = "1")]
     pub server_config: ::core::option::Option<ServerConfig>,
 }
+/// 节点心跳请求
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeHeartbeatBRequest {
+    /// 消息唯一ID
+    #[prost(uint32, tag = "1")]
+    pub msg_unique_id: u32,
+    /// 发送时间戳（毫秒）
+    #[prost(int64, tag = "2")]
+    pub timestamp: i64,
+    /// 发送方当前负载，用于按负载路由
+    #[prost(uint32, tag = "3")]
+    pub load: u32,
+}
+/// 节点心跳响应
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeHeartbeatBResponse {
+    /// 消息唯一ID
+    #[prost(uint32, tag = "1")]
+    pub msg_unique_id: u32,
+    /// 请求中的发送时间戳（原样返回）
+    #[prost(int64, tag = "2")]
+    pub timestamp: i64,
+}
+/// 节点注销请求（节点主动离开集群时发送给master，无需响应）
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeUnregisterBRequest {
+    /// 消息唯一ID
+    #[prost(uint32, tag = "1")]
+    pub msg_unique_id: u32,
+    /// 离开集群的服务器ID
+    #[prost(uint32, tag = "2")]
+    pub server_id: u32,
+}
+/// 节点注销通知（master广播节点已从集群移除）
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeUnregisterBNotify {
+    /// 被移除的服务器ID
+    #[prost(uint32, tag = "1")]
+    pub server_id: u32,
+    /// 移除原因
+    #[prost(uint32, tag = "2")]
+    pub reason: u32,
+}
 /// RPC消息转发请求（前端）
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -112,6 +193,12 @@ pub struct RpcMessageFResponse {
     /// 消息内容（字节数组）
     #[prost(bytes = "vec", tag = "3")]
     pub message: ::prost::alloc::vec::Vec<u8>,
+    /// 错误码，0表示成功
+    #[prost(uint32, tag = "4")]
+    pub error_code: u32,
+    /// 错误描述，成功时为空
+    #[prost(string, tag = "5")]
+    pub error_message: ::prost::alloc::string::String,
 }
 /// RPC消息转发通知（前端） - 不需要响应
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -169,6 +256,12 @@ pub struct RpcForwardMessageBResponse {
     /// 消息内容（字节数组）
     #[prost(bytes = "vec", tag = "5")]
     pub message: ::prost::alloc::vec::Vec<u8>,
+    /// 错误码，0表示成功
+    #[prost(uint32, tag = "6")]
+    pub error_code: u32,
+    /// 错误描述，成功时为空
+    #[prost(string, tag = "7")]
+    pub error_message: ::prost::alloc::string::String,
 }
 /// RPC消息转发通知（后端） - 不需要响应
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -188,3 +281,150 @@ pub struct RpcForwardMessageBNotify {
     #[prost(bytes = "vec", tag = "4")]
     pub message: ::prost::alloc::vec::Vec<u8>,
 }
+/// RPC推送通知（前端） - 服务器主动推送给客户端，不需要响应
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RpcPushMessageFNotify {
+    /// 推送方服务器类型
+    #[prost(string, tag = "1")]
+    pub server_type: ::prost::alloc::string::String,
+    /// 消息ID
+    #[prost(uint32, tag = "2")]
+    pub msg_id: u32,
+    /// 消息内容（字节数组）
+    #[prost(bytes = "vec", tag = "3")]
+    pub message: ::prost::alloc::vec::Vec<u8>,
+}
+/// RPC推送通知（后端） - 后端服务器发给前端会话所在的服务器，不需要响应
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RpcPushMessageBNotify {
+    /// 目标前端会话ID，为0时按用户ID查找
+    #[prost(uint64, tag = "1")]
+    pub front_session_id: u64,
+    /// 目标用户ID，为0时只按前端会话ID查找
+    #[prost(uint64, tag = "2")]
+    pub user_id: u64,
+    /// 消息ID
+    #[prost(uint32, tag = "3")]
+    pub msg_id: u32,
+    /// 消息内容（字节数组）
+    #[prost(bytes = "vec", tag = "4")]
+    pub message: ::prost::alloc::vec::Vec<u8>,
+}
+/// 集群广播请求（后端） - 发给每个前端服务器，由其投递给本服务器上的目标玩家
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RpcBroadcastBRequest {
+    /// 广播目标：1全部在线玩家，2指定分组，3指定用户
+    #[prost(uint32, tag = "1")]
+    pub target: u32,
+    /// 分组名称（target为2时）
+    #[prost(string, tag = "2")]
+    pub group_name: ::prost::alloc::string::String,
+    /// 用户ID列表（target为3时）
+    #[prost(uint64, repeated, tag = "3")]
+    pub user_ids: ::prost::alloc::vec::Vec<u64>,
+    /// 消息ID
+    #[prost(uint32, tag = "4")]
+    pub msg_id: u32,
+    /// 消息内容（字节数组）
+    #[prost(bytes = "vec", tag = "5")]
+    pub message: ::prost::alloc::vec::Vec<u8>,
+}
+/// 集群广播响应（后端）
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RpcBroadcastBResponse {
+    /// 本服务器投递成功的玩家数量
+    #[prost(uint32, tag = "1")]
+    pub delivered_count: u32,
+}
+/// 用户上线请求（后端） - 前端服务器在用户登录后发给目录服务器
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserOnlineBRequest {
+    /// 用户ID
+    #[prost(uint64, tag = "1")]
+    pub user_id: u64,
+    /// 用户所在的前端服务器ID
+    #[prost(uint32, tag = "2")]
+    pub server_id: u32,
+    /// 用户的前端会话ID
+    #[prost(uint64, tag = "3")]
+    pub front_session_id: u64,
+}
+/// 用户上线响应（后端）
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserOnlineBResponse {
+    /// 是否踢掉了之前的登录
+    #[prost(bool, tag = "1")]
+    pub kicked: bool,
+    /// 之前登录所在的前端服务器ID，没有时为0
+    #[prost(uint32, tag = "2")]
+    pub previous_server_id: u32,
+    /// 之前登录的前端会话ID，没有时为0
+    #[prost(uint64, tag = "3")]
+    pub previous_front_session_id: u64,
+}
+/// 用户下线通知（后端） - 前端服务器在用户断开后发给目录服务器
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserOfflineBNotify {
+    /// 用户ID
+    #[prost(uint64, tag = "1")]
+    pub user_id: u64,
+    /// 用户所在的前端服务器ID
+    #[prost(uint32, tag = "2")]
+    pub server_id: u32,
+    /// 用户的前端会话ID
+    #[prost(uint64, tag = "3")]
+    pub front_session_id: u64,
+}
+/// 查询用户位置请求（后端）
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserLocateBRequest {
+    /// 用户ID
+    #[prost(uint64, tag = "1")]
+    pub user_id: u64,
+}
+/// 查询用户位置响应（后端）
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserLocateBResponse {
+    /// 是否在线
+    #[prost(bool, tag = "1")]
+    pub online: bool,
+    /// 用户所在的前端服务器ID
+    #[prost(uint32, tag = "2")]
+    pub server_id: u32,
+    /// 用户的前端会话ID
+    #[prost(uint64, tag = "3")]
+    pub front_session_id: u64,
+}
+/// 踢下线通知（后端） - 目录服务器发给用户所在的前端服务器
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserKickBNotify {
+    /// 用户ID
+    #[prost(uint64, tag = "1")]
+    pub user_id: u64,
+    /// 要踢掉的前端会话ID
+    #[prost(uint64, tag = "2")]
+    pub front_session_id: u64,
+    /// 原因：1重复登录
+    #[prost(uint32, tag = "3")]
+    pub reason: u32,
+}
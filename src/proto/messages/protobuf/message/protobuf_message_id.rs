@@ -9,127 +9,316 @@ use prost::Message;
 pub const MSG_ID_CHAT_TEST_B_REQUEST: u16 = 1;
 pub const MSG_ID_CHAT_TEST_B_RESPONSE: u16 = 2;
 pub const MSG_ID_SERVER_CONFIG: u16 = 3;
-pub const MSG_ID_NODE_REGISTER_B_REQUEST: u16 = 4;
-pub const MSG_ID_NODE_REGISTER_B_RESPONSE: u16 = 5;
-pub const MSG_ID_NODE_CONNECT_B_REQUEST: u16 = 6;
-pub const MSG_ID_NODE_CONNECT_B_RESPONSE: u16 = 7;
-pub const MSG_ID_NODE_REGISTER_B_NOTIFY: u16 = 8;
-pub const MSG_ID_RPC_MESSAGE_F_REQUEST: u16 = 9;
-pub const MSG_ID_RPC_MESSAGE_F_RESPONSE: u16 = 10;
-pub const MSG_ID_RPC_MESSAGE_F_NOTIFY: u16 = 11;
-pub const MSG_ID_RPC_FORWARD_MESSAGE_B_REQUEST: u16 = 12;
-pub const MSG_ID_RPC_FORWARD_MESSAGE_B_RESPONSE: u16 = 13;
-pub const MSG_ID_RPC_FORWARD_MESSAGE_B_NOTIFY: u16 = 14;
-pub const MSG_ID_BASE_MESSAGE: u16 = 15;
-pub const MSG_ID_SERIAL_MESSAGE: u16 = 16;
-pub const MSG_ID_LOGIN_F_REQUEST: u16 = 17;
-pub const MSG_ID_LOGIN_F_RESPONSE: u16 = 18;
+pub const MSG_ID_NODE_CHALLENGE_B_REQUEST: u16 = 4;
+pub const MSG_ID_NODE_CHALLENGE_B_RESPONSE: u16 = 5;
+pub const MSG_ID_NODE_REGISTER_B_REQUEST: u16 = 6;
+pub const MSG_ID_NODE_REGISTER_B_RESPONSE: u16 = 7;
+pub const MSG_ID_NODE_CONNECT_B_REQUEST: u16 = 8;
+pub const MSG_ID_NODE_CONNECT_B_RESPONSE: u16 = 9;
+pub const MSG_ID_NODE_REGISTER_B_NOTIFY: u16 = 10;
+pub const MSG_ID_NODE_HEARTBEAT_B_REQUEST: u16 = 11;
+pub const MSG_ID_NODE_HEARTBEAT_B_RESPONSE: u16 = 12;
+pub const MSG_ID_NODE_UNREGISTER_B_REQUEST: u16 = 13;
+pub const MSG_ID_NODE_UNREGISTER_B_NOTIFY: u16 = 14;
+pub const MSG_ID_RPC_MESSAGE_F_REQUEST: u16 = 15;
+pub const MSG_ID_RPC_MESSAGE_F_RESPONSE: u16 = 16;
+pub const MSG_ID_RPC_MESSAGE_F_NOTIFY: u16 = 17;
+pub const MSG_ID_RPC_FORWARD_MESSAGE_B_REQUEST: u16 = 18;
+pub const MSG_ID_RPC_FORWARD_MESSAGE_B_RESPONSE: u16 = 19;
+pub const MSG_ID_RPC_FORWARD_MESSAGE_B_NOTIFY: u16 = 20;
+pub const MSG_ID_RPC_PUSH_MESSAGE_F_NOTIFY: u16 = 21;
+pub const MSG_ID_RPC_PUSH_MESSAGE_B_NOTIFY: u16 = 22;
+pub const MSG_ID_RPC_BROADCAST_B_REQUEST: u16 = 23;
+pub const MSG_ID_RPC_BROADCAST_B_RESPONSE: u16 = 24;
+pub const MSG_ID_USER_ONLINE_B_REQUEST: u16 = 25;
+pub const MSG_ID_USER_ONLINE_B_RESPONSE: u16 = 26;
+pub const MSG_ID_USER_OFFLINE_B_NOTIFY: u16 = 27;
+pub const MSG_ID_USER_LOCATE_B_REQUEST: u16 = 28;
+pub const MSG_ID_USER_LOCATE_B_RESPONSE: u16 = 29;
+pub const MSG_ID_USER_KICK_B_NOTIFY: u16 = 30;
+pub const MSG_ID_BASE_MESSAGE: u16 = 31;
+pub const MSG_ID_SERIAL_MESSAGE: u16 = 32;
+pub const MSG_ID_LOGIN_F_REQUEST: u16 = 33;
+pub const MSG_ID_LOGIN_F_RESPONSE: u16 = 34;
+pub const MSG_ID_KICK_F_NOTIFY: u16 = 35;
 
 impl MessageId for super::chat::ChatTestBRequest {
+    const MSG_ID: u16 = MSG_ID_CHAT_TEST_B_REQUEST;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_CHAT_TEST_B_REQUEST
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::chat::ChatTestBResponse {
+    const MSG_ID: u16 = MSG_ID_CHAT_TEST_B_RESPONSE;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_CHAT_TEST_B_RESPONSE
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::ServerConfig {
+    const MSG_ID: u16 = MSG_ID_SERVER_CONFIG;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::NodeChallengeBRequest {
+    const MSG_ID: u16 = MSG_ID_NODE_CHALLENGE_B_REQUEST;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_SERVER_CONFIG
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::NodeChallengeBResponse {
+    const MSG_ID: u16 = MSG_ID_NODE_CHALLENGE_B_RESPONSE;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::NodeRegisterBRequest {
+    const MSG_ID: u16 = MSG_ID_NODE_REGISTER_B_REQUEST;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_NODE_REGISTER_B_REQUEST
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::NodeRegisterBResponse {
+    const MSG_ID: u16 = MSG_ID_NODE_REGISTER_B_RESPONSE;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_NODE_REGISTER_B_RESPONSE
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::NodeConnectBRequest {
+    const MSG_ID: u16 = MSG_ID_NODE_CONNECT_B_REQUEST;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_NODE_CONNECT_B_REQUEST
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::NodeConnectBResponse {
+    const MSG_ID: u16 = MSG_ID_NODE_CONNECT_B_RESPONSE;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_NODE_CONNECT_B_RESPONSE
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::NodeRegisterBNotify {
+    const MSG_ID: u16 = MSG_ID_NODE_REGISTER_B_NOTIFY;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::NodeHeartbeatBRequest {
+    const MSG_ID: u16 = MSG_ID_NODE_HEARTBEAT_B_REQUEST;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::NodeHeartbeatBResponse {
+    const MSG_ID: u16 = MSG_ID_NODE_HEARTBEAT_B_RESPONSE;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::NodeUnregisterBRequest {
+    const MSG_ID: u16 = MSG_ID_NODE_UNREGISTER_B_REQUEST;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::NodeUnregisterBNotify {
+    const MSG_ID: u16 = MSG_ID_NODE_UNREGISTER_B_NOTIFY;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_NODE_REGISTER_B_NOTIFY
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::RpcMessageFRequest {
+    const MSG_ID: u16 = MSG_ID_RPC_MESSAGE_F_REQUEST;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_RPC_MESSAGE_F_REQUEST
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::RpcMessageFResponse {
+    const MSG_ID: u16 = MSG_ID_RPC_MESSAGE_F_RESPONSE;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_RPC_MESSAGE_F_RESPONSE
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::RpcMessageFNotify {
+    const MSG_ID: u16 = MSG_ID_RPC_MESSAGE_F_NOTIFY;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_RPC_MESSAGE_F_NOTIFY
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::RpcForwardMessageBRequest {
+    const MSG_ID: u16 = MSG_ID_RPC_FORWARD_MESSAGE_B_REQUEST;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_RPC_FORWARD_MESSAGE_B_REQUEST
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::RpcForwardMessageBResponse {
+    const MSG_ID: u16 = MSG_ID_RPC_FORWARD_MESSAGE_B_RESPONSE;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_RPC_FORWARD_MESSAGE_B_RESPONSE
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::cluster::RpcForwardMessageBNotify {
+    const MSG_ID: u16 = MSG_ID_RPC_FORWARD_MESSAGE_B_NOTIFY;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::RpcPushMessageFNotify {
+    const MSG_ID: u16 = MSG_ID_RPC_PUSH_MESSAGE_F_NOTIFY;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::RpcPushMessageBNotify {
+    const MSG_ID: u16 = MSG_ID_RPC_PUSH_MESSAGE_B_NOTIFY;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::RpcBroadcastBRequest {
+    const MSG_ID: u16 = MSG_ID_RPC_BROADCAST_B_REQUEST;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::RpcBroadcastBResponse {
+    const MSG_ID: u16 = MSG_ID_RPC_BROADCAST_B_RESPONSE;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_RPC_FORWARD_MESSAGE_B_NOTIFY
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::UserOnlineBRequest {
+    const MSG_ID: u16 = MSG_ID_USER_ONLINE_B_REQUEST;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::UserOnlineBResponse {
+    const MSG_ID: u16 = MSG_ID_USER_ONLINE_B_RESPONSE;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::UserOfflineBNotify {
+    const MSG_ID: u16 = MSG_ID_USER_OFFLINE_B_NOTIFY;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::UserLocateBRequest {
+    const MSG_ID: u16 = MSG_ID_USER_LOCATE_B_REQUEST;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::UserLocateBResponse {
+    const MSG_ID: u16 = MSG_ID_USER_LOCATE_B_RESPONSE;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::cluster::UserKickBNotify {
+    const MSG_ID: u16 = MSG_ID_USER_KICK_B_NOTIFY;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::common::BaseMessage {
+    const MSG_ID: u16 = MSG_ID_BASE_MESSAGE;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_BASE_MESSAGE
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::common::SerialMessage {
+    const MSG_ID: u16 = MSG_ID_SERIAL_MESSAGE;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_SERIAL_MESSAGE
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::session::LoginFRequest {
+    const MSG_ID: u16 = MSG_ID_LOGIN_F_REQUEST;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_LOGIN_F_REQUEST
+        Self::MSG_ID
     }
 }
 
 impl MessageId for super::session::LoginFResponse {
+    const MSG_ID: u16 = MSG_ID_LOGIN_F_RESPONSE;
+
+    fn msg_id(&self) -> u16 {
+        Self::MSG_ID
+    }
+}
+
+impl MessageId for super::session::KickFNotify {
+    const MSG_ID: u16 = MSG_ID_KICK_F_NOTIFY;
+
     fn msg_id(&self) -> u16 {
-        MSG_ID_LOGIN_F_RESPONSE
+        Self::MSG_ID
     }
 }
 
@@ -211,6 +400,58 @@ impl MessageIdSerialize for super::cluster::ServerConfig {
     }
 }
 
+impl MessageIdSerialize for super::cluster::NodeChallengeBRequest {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::cluster::NodeChallengeBResponse {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
 impl MessageIdSerialize for super::cluster::NodeRegisterBRequest {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
@@ -341,7 +582,7 @@ impl MessageIdSerialize for super::cluster::NodeRegisterBNotify {
     }
 }
 
-impl MessageIdSerialize for super::cluster::RpcMessageFRequest {
+impl MessageIdSerialize for super::cluster::NodeHeartbeatBRequest {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -367,7 +608,7 @@ impl MessageIdSerialize for super::cluster::RpcMessageFRequest {
     }
 }
 
-impl MessageIdSerialize for super::cluster::RpcMessageFResponse {
+impl MessageIdSerialize for super::cluster::NodeHeartbeatBResponse {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -393,7 +634,7 @@ impl MessageIdSerialize for super::cluster::RpcMessageFResponse {
     }
 }
 
-impl MessageIdSerialize for super::cluster::RpcMessageFNotify {
+impl MessageIdSerialize for super::cluster::NodeUnregisterBRequest {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -419,7 +660,7 @@ impl MessageIdSerialize for super::cluster::RpcMessageFNotify {
     }
 }
 
-impl MessageIdSerialize for super::cluster::RpcForwardMessageBRequest {
+impl MessageIdSerialize for super::cluster::NodeUnregisterBNotify {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -445,7 +686,7 @@ impl MessageIdSerialize for super::cluster::RpcForwardMessageBRequest {
     }
 }
 
-impl MessageIdSerialize for super::cluster::RpcForwardMessageBResponse {
+impl MessageIdSerialize for super::cluster::RpcMessageFRequest {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -471,7 +712,7 @@ impl MessageIdSerialize for super::cluster::RpcForwardMessageBResponse {
     }
 }
 
-impl MessageIdSerialize for super::cluster::RpcForwardMessageBNotify {
+impl MessageIdSerialize for super::cluster::RpcMessageFResponse {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -497,7 +738,7 @@ impl MessageIdSerialize for super::cluster::RpcForwardMessageBNotify {
     }
 }
 
-impl MessageIdSerialize for super::common::BaseMessage {
+impl MessageIdSerialize for super::cluster::RpcMessageFNotify {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -523,7 +764,7 @@ impl MessageIdSerialize for super::common::BaseMessage {
     }
 }
 
-impl MessageIdSerialize for super::common::SerialMessage {
+impl MessageIdSerialize for super::cluster::RpcForwardMessageBRequest {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -549,7 +790,7 @@ impl MessageIdSerialize for super::common::SerialMessage {
     }
 }
 
-impl MessageIdSerialize for super::session::LoginFRequest {
+impl MessageIdSerialize for super::cluster::RpcForwardMessageBResponse {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -575,7 +816,7 @@ impl MessageIdSerialize for super::session::LoginFRequest {
     }
 }
 
-impl MessageIdSerialize for super::session::LoginFResponse {
+impl MessageIdSerialize for super::cluster::RpcForwardMessageBNotify {
     fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
         // Get message ID
         let msg_id = self.msg_id();
@@ -601,51 +842,459 @@ impl MessageIdSerialize for super::session::LoginFResponse {
     }
 }
 
-/// Message decoding factory
-pub struct MessageFactory;
-
-impl MessageFactory {
-    /// Decode ChatTestBRequest from DynamicBuffer
-    pub fn decode_chat_test_b_request(buffer: &mut DynamicBuffer, length: usize) -> Option<super::chat::ChatTestBRequest> {
-        // Check if we have enough data
-        if buffer.readable_bytes() < length {
-            return None;
-        }
-
-        // Read exact length of data from buffer
-        let mut data = vec![0u8; length];
-        let bytes_read = buffer.read_bytes(&mut data, 0, length);
-        if bytes_read != length {
-            return None;
-        }
-
-        // Decode using prost
-        super::chat::ChatTestBRequest::decode(&data[..]).ok()
+impl MessageIdSerialize for super::cluster::RpcPushMessageFNotify {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
     }
+}
 
-    /// Decode ChatTestBResponse from DynamicBuffer
-    pub fn decode_chat_test_b_response(buffer: &mut DynamicBuffer, length: usize) -> Option<super::chat::ChatTestBResponse> {
-        // Check if we have enough data
-        if buffer.readable_bytes() < length {
-            return None;
-        }
-
-        // Read exact length of data from buffer
-        let mut data = vec![0u8; length];
-        let bytes_read = buffer.read_bytes(&mut data, 0, length);
-        if bytes_read != length {
-            return None;
-        }
-
-        // Decode using prost
-        super::chat::ChatTestBResponse::decode(&data[..]).ok()
+impl MessageIdSerialize for super::cluster::RpcPushMessageBNotify {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
     }
+}
 
-    /// Decode ServerConfig from DynamicBuffer
-    pub fn decode_server_config(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::ServerConfig> {
-        // Check if we have enough data
-        if buffer.readable_bytes() < length {
-            return None;
+impl MessageIdSerialize for super::cluster::RpcBroadcastBRequest {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::cluster::RpcBroadcastBResponse {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::cluster::UserOnlineBRequest {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::cluster::UserOnlineBResponse {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::cluster::UserOfflineBNotify {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::cluster::UserLocateBRequest {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::cluster::UserLocateBResponse {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::cluster::UserKickBNotify {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::common::BaseMessage {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::common::SerialMessage {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::session::LoginFRequest {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::session::LoginFResponse {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+impl MessageIdSerialize for super::session::KickFNotify {
+    fn serialize_to_buffer(&self) -> Result<bytes::BytesMut, Box<dyn std::error::Error + Send + Sync>> {
+        // Get message ID
+        let msg_id = self.msg_id();
+        
+        // Encode message using prost
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data_len = data.len();
+        
+        // Create buffer with exact size needed (2 for msgid + 2 for length + data)
+        let mut buffer = bytes::BytesMut::with_capacity(2 + 2 + data_len);
+        
+        // Write message ID (big-endian)
+        buffer.extend_from_slice(&msg_id.to_be_bytes());
+        
+        // Write length as u16 (big-endian)
+        buffer.extend_from_slice(&(data_len as u16).to_be_bytes());
+        
+        // Write message data
+        buffer.extend_from_slice(&data);
+        
+        Ok(buffer)
+    }
+}
+
+/// Message decoding factory
+pub struct MessageFactory;
+
+impl MessageFactory {
+    /// Decode ChatTestBRequest from DynamicBuffer
+    pub fn decode_chat_test_b_request(buffer: &mut DynamicBuffer, length: usize) -> Option<super::chat::ChatTestBRequest> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::chat::ChatTestBRequest::decode(&data[..]).ok()
+    }
+
+    /// Decode ChatTestBResponse from DynamicBuffer
+    pub fn decode_chat_test_b_response(buffer: &mut DynamicBuffer, length: usize) -> Option<super::chat::ChatTestBResponse> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::chat::ChatTestBResponse::decode(&data[..]).ok()
+    }
+
+    /// Decode ServerConfig from DynamicBuffer
+    pub fn decode_server_config(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::ServerConfig> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::ServerConfig::decode(&data[..]).ok()
+    }
+
+    /// Decode NodeChallengeBRequest from DynamicBuffer
+    pub fn decode_node_challenge_b_request(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::NodeChallengeBRequest> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
         }
 
         // Read exact length of data from buffer
@@ -656,7 +1305,25 @@ impl MessageFactory {
         }
 
         // Decode using prost
-        super::cluster::ServerConfig::decode(&data[..]).ok()
+        super::cluster::NodeChallengeBRequest::decode(&data[..]).ok()
+    }
+
+    /// Decode NodeChallengeBResponse from DynamicBuffer
+    pub fn decode_node_challenge_b_response(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::NodeChallengeBResponse> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::NodeChallengeBResponse::decode(&data[..]).ok()
     }
 
     /// Decode NodeRegisterBRequest from DynamicBuffer
@@ -749,6 +1416,78 @@ impl MessageFactory {
         super::cluster::NodeRegisterBNotify::decode(&data[..]).ok()
     }
 
+    /// Decode NodeHeartbeatBRequest from DynamicBuffer
+    pub fn decode_node_heartbeat_b_request(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::NodeHeartbeatBRequest> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::NodeHeartbeatBRequest::decode(&data[..]).ok()
+    }
+
+    /// Decode NodeHeartbeatBResponse from DynamicBuffer
+    pub fn decode_node_heartbeat_b_response(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::NodeHeartbeatBResponse> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::NodeHeartbeatBResponse::decode(&data[..]).ok()
+    }
+
+    /// Decode NodeUnregisterBRequest from DynamicBuffer
+    pub fn decode_node_unregister_b_request(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::NodeUnregisterBRequest> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::NodeUnregisterBRequest::decode(&data[..]).ok()
+    }
+
+    /// Decode NodeUnregisterBNotify from DynamicBuffer
+    pub fn decode_node_unregister_b_notify(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::NodeUnregisterBNotify> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::NodeUnregisterBNotify::decode(&data[..]).ok()
+    }
+
     /// Decode RpcMessageFRequest from DynamicBuffer
     pub fn decode_rpc_message_f_request(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::RpcMessageFRequest> {
         // Check if we have enough data
@@ -857,6 +1596,186 @@ impl MessageFactory {
         super::cluster::RpcForwardMessageBNotify::decode(&data[..]).ok()
     }
 
+    /// Decode RpcPushMessageFNotify from DynamicBuffer
+    pub fn decode_rpc_push_message_f_notify(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::RpcPushMessageFNotify> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::RpcPushMessageFNotify::decode(&data[..]).ok()
+    }
+
+    /// Decode RpcPushMessageBNotify from DynamicBuffer
+    pub fn decode_rpc_push_message_b_notify(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::RpcPushMessageBNotify> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::RpcPushMessageBNotify::decode(&data[..]).ok()
+    }
+
+    /// Decode RpcBroadcastBRequest from DynamicBuffer
+    pub fn decode_rpc_broadcast_b_request(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::RpcBroadcastBRequest> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::RpcBroadcastBRequest::decode(&data[..]).ok()
+    }
+
+    /// Decode RpcBroadcastBResponse from DynamicBuffer
+    pub fn decode_rpc_broadcast_b_response(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::RpcBroadcastBResponse> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::RpcBroadcastBResponse::decode(&data[..]).ok()
+    }
+
+    /// Decode UserOnlineBRequest from DynamicBuffer
+    pub fn decode_user_online_b_request(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::UserOnlineBRequest> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::UserOnlineBRequest::decode(&data[..]).ok()
+    }
+
+    /// Decode UserOnlineBResponse from DynamicBuffer
+    pub fn decode_user_online_b_response(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::UserOnlineBResponse> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::UserOnlineBResponse::decode(&data[..]).ok()
+    }
+
+    /// Decode UserOfflineBNotify from DynamicBuffer
+    pub fn decode_user_offline_b_notify(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::UserOfflineBNotify> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::UserOfflineBNotify::decode(&data[..]).ok()
+    }
+
+    /// Decode UserLocateBRequest from DynamicBuffer
+    pub fn decode_user_locate_b_request(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::UserLocateBRequest> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::UserLocateBRequest::decode(&data[..]).ok()
+    }
+
+    /// Decode UserLocateBResponse from DynamicBuffer
+    pub fn decode_user_locate_b_response(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::UserLocateBResponse> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::UserLocateBResponse::decode(&data[..]).ok()
+    }
+
+    /// Decode UserKickBNotify from DynamicBuffer
+    pub fn decode_user_kick_b_notify(buffer: &mut DynamicBuffer, length: usize) -> Option<super::cluster::UserKickBNotify> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::cluster::UserKickBNotify::decode(&data[..]).ok()
+    }
+
     /// Decode BaseMessage from DynamicBuffer
     pub fn decode_base_message(buffer: &mut DynamicBuffer, length: usize) -> Option<super::common::BaseMessage> {
         // Check if we have enough data
@@ -929,27 +1848,104 @@ impl MessageFactory {
         super::session::LoginFResponse::decode(&data[..]).ok()
     }
 
+    /// Decode KickFNotify from DynamicBuffer
+    pub fn decode_kick_f_notify(buffer: &mut DynamicBuffer, length: usize) -> Option<super::session::KickFNotify> {
+        // Check if we have enough data
+        if buffer.readable_bytes() < length {
+            return None;
+        }
+
+        // Read exact length of data from buffer
+        let mut data = vec![0u8; length];
+        let bytes_read = buffer.read_bytes(&mut data, 0, length);
+        if bytes_read != length {
+            return None;
+        }
+
+        // Decode using prost
+        super::session::KickFNotify::decode(&data[..]).ok()
+    }
+
     /// Decode message by ID from DynamicBuffer
     pub fn decode_message(msg_id: u16, buffer: &mut DynamicBuffer, length: usize) -> Option<Box<dyn std::any::Any + Send>> {
         match msg_id {
             MSG_ID_CHAT_TEST_B_REQUEST => Self::decode_chat_test_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_CHAT_TEST_B_RESPONSE => Self::decode_chat_test_b_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_SERVER_CONFIG => Self::decode_server_config(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_NODE_CHALLENGE_B_REQUEST => Self::decode_node_challenge_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_NODE_CHALLENGE_B_RESPONSE => Self::decode_node_challenge_b_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_NODE_REGISTER_B_REQUEST => Self::decode_node_register_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_NODE_REGISTER_B_RESPONSE => Self::decode_node_register_b_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_NODE_CONNECT_B_REQUEST => Self::decode_node_connect_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_NODE_CONNECT_B_RESPONSE => Self::decode_node_connect_b_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_NODE_REGISTER_B_NOTIFY => Self::decode_node_register_b_notify(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_NODE_HEARTBEAT_B_REQUEST => Self::decode_node_heartbeat_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_NODE_HEARTBEAT_B_RESPONSE => Self::decode_node_heartbeat_b_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_NODE_UNREGISTER_B_REQUEST => Self::decode_node_unregister_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_NODE_UNREGISTER_B_NOTIFY => Self::decode_node_unregister_b_notify(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_RPC_MESSAGE_F_REQUEST => Self::decode_rpc_message_f_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_RPC_MESSAGE_F_RESPONSE => Self::decode_rpc_message_f_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_RPC_MESSAGE_F_NOTIFY => Self::decode_rpc_message_f_notify(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_RPC_FORWARD_MESSAGE_B_REQUEST => Self::decode_rpc_forward_message_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_RPC_FORWARD_MESSAGE_B_RESPONSE => Self::decode_rpc_forward_message_b_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_RPC_FORWARD_MESSAGE_B_NOTIFY => Self::decode_rpc_forward_message_b_notify(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_RPC_PUSH_MESSAGE_F_NOTIFY => Self::decode_rpc_push_message_f_notify(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_RPC_PUSH_MESSAGE_B_NOTIFY => Self::decode_rpc_push_message_b_notify(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_RPC_BROADCAST_B_REQUEST => Self::decode_rpc_broadcast_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_RPC_BROADCAST_B_RESPONSE => Self::decode_rpc_broadcast_b_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_USER_ONLINE_B_REQUEST => Self::decode_user_online_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_USER_ONLINE_B_RESPONSE => Self::decode_user_online_b_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_USER_OFFLINE_B_NOTIFY => Self::decode_user_offline_b_notify(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_USER_LOCATE_B_REQUEST => Self::decode_user_locate_b_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_USER_LOCATE_B_RESPONSE => Self::decode_user_locate_b_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_USER_KICK_B_NOTIFY => Self::decode_user_kick_b_notify(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_BASE_MESSAGE => Self::decode_base_message(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_SERIAL_MESSAGE => Self::decode_serial_message(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_LOGIN_F_REQUEST => Self::decode_login_f_request(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
             MSG_ID_LOGIN_F_RESPONSE => Self::decode_login_f_response(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            MSG_ID_KICK_F_NOTIFY => Self::decode_kick_f_notify(buffer, length).map(|m| Box::new(m) as Box<dyn std::any::Any + Send>),
+            _ => None,
+        }
+    }
+
+    /// Get message name by ID
+    pub fn message_name(msg_id: u16) -> Option<&'static str> {
+        match msg_id {
+            MSG_ID_CHAT_TEST_B_REQUEST => Some("ChatTestBRequest"),
+            MSG_ID_CHAT_TEST_B_RESPONSE => Some("ChatTestBResponse"),
+            MSG_ID_SERVER_CONFIG => Some("ServerConfig"),
+            MSG_ID_NODE_CHALLENGE_B_REQUEST => Some("NodeChallengeBRequest"),
+            MSG_ID_NODE_CHALLENGE_B_RESPONSE => Some("NodeChallengeBResponse"),
+            MSG_ID_NODE_REGISTER_B_REQUEST => Some("NodeRegisterBRequest"),
+            MSG_ID_NODE_REGISTER_B_RESPONSE => Some("NodeRegisterBResponse"),
+            MSG_ID_NODE_CONNECT_B_REQUEST => Some("NodeConnectBRequest"),
+            MSG_ID_NODE_CONNECT_B_RESPONSE => Some("NodeConnectBResponse"),
+            MSG_ID_NODE_REGISTER_B_NOTIFY => Some("NodeRegisterBNotify"),
+            MSG_ID_NODE_HEARTBEAT_B_REQUEST => Some("NodeHeartbeatBRequest"),
+            MSG_ID_NODE_HEARTBEAT_B_RESPONSE => Some("NodeHeartbeatBResponse"),
+            MSG_ID_NODE_UNREGISTER_B_REQUEST => Some("NodeUnregisterBRequest"),
+            MSG_ID_NODE_UNREGISTER_B_NOTIFY => Some("NodeUnregisterBNotify"),
+            MSG_ID_RPC_MESSAGE_F_REQUEST => Some("RpcMessageFRequest"),
+            MSG_ID_RPC_MESSAGE_F_RESPONSE => Some("RpcMessageFResponse"),
+            MSG_ID_RPC_MESSAGE_F_NOTIFY => Some("RpcMessageFNotify"),
+            MSG_ID_RPC_FORWARD_MESSAGE_B_REQUEST => Some("RpcForwardMessageBRequest"),
+            MSG_ID_RPC_FORWARD_MESSAGE_B_RESPONSE => Some("RpcForwardMessageBResponse"),
+            MSG_ID_RPC_FORWARD_MESSAGE_B_NOTIFY => Some("RpcForwardMessageBNotify"),
+            MSG_ID_RPC_PUSH_MESSAGE_F_NOTIFY => Some("RpcPushMessageFNotify"),
+            MSG_ID_RPC_PUSH_MESSAGE_B_NOTIFY => Some("RpcPushMessageBNotify"),
+            MSG_ID_RPC_BROADCAST_B_REQUEST => Some("RpcBroadcastBRequest"),
+            MSG_ID_RPC_BROADCAST_B_RESPONSE => Some("RpcBroadcastBResponse"),
+            MSG_ID_USER_ONLINE_B_REQUEST => Some("UserOnlineBRequest"),
+            MSG_ID_USER_ONLINE_B_RESPONSE => Some("UserOnlineBResponse"),
+            MSG_ID_USER_OFFLINE_B_NOTIFY => Some("UserOfflineBNotify"),
+            MSG_ID_USER_LOCATE_B_REQUEST => Some("UserLocateBRequest"),
+            MSG_ID_USER_LOCATE_B_RESPONSE => Some("UserLocateBResponse"),
+            MSG_ID_USER_KICK_B_NOTIFY => Some("UserKickBNotify"),
+            MSG_ID_BASE_MESSAGE => Some("BaseMessage"),
+            MSG_ID_SERIAL_MESSAGE => Some("SerialMessage"),
+            MSG_ID_LOGIN_F_REQUEST => Some("LoginFRequest"),
+            MSG_ID_LOGIN_F_RESPONSE => Some("LoginFResponse"),
+            MSG_ID_KICK_F_NOTIFY => Some("KickFNotify"),
             _ => None,
         }
     }
@@ -16,4 +16,16 @@ pub struct LoginFResponse {
     pub player_id: u64,
     #[prost(string, tag = "2")]
     pub name: ::prost::alloc::string::String,
+    /// 0: success, 1: invalid token, 2: login in progress, 3: already logged in, 4: timeout, 5: internal error, 6: token expired
+    #[prost(uint32, tag = "3")]
+    pub error_code: u32,
+}
+/// Kick notify, sent before the server closes the session
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KickFNotify {
+    /// 1: duplicate login
+    #[prost(uint32, tag = "1")]
+    pub reason: u32,
 }
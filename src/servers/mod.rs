@@ -13,5 +13,7 @@ pub mod session {
     pub mod login {
         pub mod player;
         pub mod player_manager;
+        pub mod db_player;
+        pub mod player_loader;
     }
 }
@@ -1,6 +1,7 @@
 /// 数据库玩家信息
 ///
 /// 用于存储从数据库加载的玩家基础信息
+#[derive(Debug, Clone)]
 pub struct DBPlayer {
     /// 玩家ID
     pub player_id: u64,
@@ -1,9 +1,13 @@
+use super::db_player::DBPlayer;
+
 /// 已登录玩家信息
 pub struct Player {
     /// 玩家ID
     pub player_id: u64,
     /// 玩家名称
     pub name: String,
+    /// 玩家等级
+    pub level: u32,
     /// 会话ID
     pub session_id: u64,
     /// 登录时间
@@ -11,12 +15,13 @@ pub struct Player {
 }
 
 impl Player {
-    /// 创建新的玩家
-    pub fn new(player_id: u64, name: String, session_id: u64) -> Self {
+    /// 用数据库中加载的玩家信息创建玩家
+    pub fn new(db_player: DBPlayer, session_id: u64) -> Self {
         let now = std::time::Instant::now();
         Self {
-            player_id,
-            name,
+            player_id: db_player.player_id,
+            name: db_player.nick_name,
+            level: db_player.level,
             session_id,
             login_time: now,
         }
@@ -32,6 +37,7 @@ impl Player {
         // 需要保存的玩家数据：
         // - player_id: 玩家ID
         // - name: 玩家名称
+        // - level: 玩家等级
         // - 其他需要持久化的玩家属性
 
         true
@@ -0,0 +1,29 @@
+use super::db_player::DBPlayer;
+use std::future::Future;
+use std::pin::Pin;
+use tracing::debug;
+
+/// 玩家数据加载结果，失败时为错误描述
+pub type PlayerLoadFuture = Pin<Box<dyn Future<Output = Result<DBPlayer, String>> + Send + 'static>>;
+
+/// 玩家数据加载器，在登录任务的工作线程中调用
+///
+/// 令牌校验通过后按用户ID加载玩家数据，通过LoginMessageHandler::set_player_loader替换为数据库实现
+pub trait PlayerLoader: Send + Sync {
+    /// 加载玩家数据，玩家不存在时由实现决定创建新玩家或返回错误
+    fn load(&self, player_id: u64) -> PlayerLoadFuture;
+}
+
+/// 没有接入玩家数据库时使用的加载器
+///
+/// 每次登录都创建1级、昵称为空的新玩家，不读取也不保留任何数据
+pub struct NewPlayerLoader;
+
+impl PlayerLoader for NewPlayerLoader {
+    fn load(&self, player_id: u64) -> PlayerLoadFuture {
+        Box::pin(async move {
+            debug!("No player database configured, creating new player {}", player_id);
+            Ok(DBPlayer::new(player_id, String::new(), 1))
+        })
+    }
+}
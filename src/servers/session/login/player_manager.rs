@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use tracing::error;
+use tracing::{debug, error, info, warn};
 use super::player::Player;
 
 /// 已登录玩家管理器
@@ -29,15 +29,90 @@ impl PlayerManager {
 
     /// 初始化管理器
     pub fn init(&mut self) -> bool {
-        
+
         true
     }
 
-    /// 清理管理器
+    /// 添加已登录玩家
+    ///
+    /// # 返回值
+    /// 同一玩家已在其他会话登录时返回被替换的玩家，旧会话随后断开时不会再移除新的玩家
+    pub fn add_player(&mut self, player: Player) -> Option<Player> {
+        let replaced = self.remove_player(player.player_id);
+        if let Some(ref replaced) = replaced {
+            warn!("Player {} logged in again from session {}, replacing session {}",
+                  player.player_id, player.session_id, replaced.session_id);
+        }
+
+        debug!("Added player: player_id={}, name={}, session_id={}",
+               player.player_id, player.name, player.session_id);
+        self.session_to_player.insert(player.session_id, player.player_id);
+        self.name_to_player.insert(player.name.clone(), player.player_id);
+        self.players_by_id.insert(player.player_id, player);
+        replaced
+    }
+
+    /// 按玩家ID移除玩家
+    pub fn remove_player(&mut self, player_id: u64) -> Option<Player> {
+        let player = self.players_by_id.remove(&player_id)?;
+        self.session_to_player.remove(&player.session_id);
+        if self.name_to_player.get(&player.name) == Some(&player_id) {
+            self.name_to_player.remove(&player.name);
+        }
+        debug!("Removed player: player_id={}, session_id={}", player_id, player.session_id);
+        Some(player)
+    }
+
+    /// 按会话ID移除玩家，会话断开时调用
+    pub fn remove_player_by_session(&mut self, session_id: u64) -> Option<Player> {
+        let player_id = self.session_to_player.get(&session_id).copied()?;
+        self.remove_player(player_id)
+    }
+
+    /// 获取玩家
+    pub fn get_player(&self, player_id: u64) -> Option<&Player> {
+        self.players_by_id.get(&player_id)
+    }
+
+    /// 获取玩家（可变引用）
+    pub fn get_player_mut(&mut self, player_id: u64) -> Option<&mut Player> {
+        self.players_by_id.get_mut(&player_id)
+    }
+
+    /// 按会话ID获取玩家
+    pub fn get_player_by_session(&self, session_id: u64) -> Option<&Player> {
+        let player_id = self.session_to_player.get(&session_id)?;
+        self.players_by_id.get(player_id)
+    }
+
+    /// 按玩家名称获取玩家
+    pub fn get_player_by_name(&self, name: &str) -> Option<&Player> {
+        let player_id = self.name_to_player.get(name)?;
+        self.players_by_id.get(player_id)
+    }
+
+    /// 会话是否已登录
+    pub fn has_session(&self, session_id: u64) -> bool {
+        self.session_to_player.contains_key(&session_id)
+    }
+
+    /// 获取当前已登录玩家数量
+    pub fn get_player_count(&self) -> usize {
+        self.players_by_id.len()
+    }
+
+    /// 清理管理器，保存所有玩家数据
     pub fn dispose(&mut self) {
         let player_count = self.players_by_id.len();
+        for player in self.players_by_id.values() {
+            if !player.save_to_db() {
+                error!("Failed to save player {} on dispose", player.player_id);
+            }
+        }
         self.players_by_id.clear();
         self.session_to_player.clear();
         self.name_to_player.clear();
+
+        info!("PlayerManager disposed, cleared {} players", player_count);
     }
-}
\ No newline at end of file
+}
@@ -1,76 +1,381 @@
-use crate::framework::session::{FrontSessionMessageDispatcher, FrontSession, SessionTrait};
+use crate::framework::author::token_verifier::TokenVerifier;
+use crate::framework::cluster::UserDirectory;
+use crate::framework::network::{NetworkEventHandler, network_event_queue::{DisconnectReason, NetworkEventData, NetworkEventType}};
+use crate::framework::session::{FrontSessionMessageDispatcher, FrontSessionManager, FrontSession, SessionTrait};
+use crate::framework::task::TaskManager;
+use crate::framework::timer::TimerHandle;
 use crate::proto::messages::protobuf::message::session::{LoginFRequest, LoginFResponse};
-use crate::proto::messages::protobuf::message::protobuf_message_id::MSG_ID_LOGIN_F_REQUEST;
-use tracing::{info, debug, error};
-use std::any::Any;
+use super::login::player::Player;
+use super::login::player_loader::PlayerLoader;
+use super::login::player_manager::PlayerManager;
+use super::unlogin::login_task::{LoginTask, LoginResult, LoginErrorCode};
+use super::unlogin::unlogin_player_manager::UnloginPlayerManager;
+use std::sync::Arc;
+use tracing::{info, debug, error, warn};
+
+// 包装器结构体，使原始指针满足Send + Sync要求（仅用于满足闭包类型约束）
+// 实际运行时所有调用都在主线程，不会有并发访问
+#[derive(Clone, Copy)]
+struct LoginMessageHandlerPtr(*mut LoginMessageHandler);
+unsafe impl Send for LoginMessageHandlerPtr {}
+unsafe impl Sync for LoginMessageHandlerPtr {}
 
 /// 登录消息处理器
+///
+/// 前端连接建立时登记为未登录玩家并启动登录超时定时器；收到LoginFRequest后通过TaskManager执行LoginTask，
+/// 任务完成后在主线程把玩家移入PlayerManager并回复LoginFResponse
 pub struct LoginMessageHandler {
-    // 可以添加需要的字段，比如数据库连接等
+    /// 前端会话管理器指针
+    front_session_manager: *mut FrontSessionManager,
+    /// 未登录玩家管理器指针
+    unlogin_player_manager: *mut UnloginPlayerManager,
+    /// 已登录玩家管理器指针
+    player_manager: *mut PlayerManager,
+    /// 任务管理器指针，用于执行登录任务
+    task_manager: *mut TaskManager,
+    /// 在线用户目录指针，用于上报登录
+    user_directory: *mut UserDirectory,
+    /// 定时器句柄，用于登录超时
+    timer_handle: TimerHandle,
+    /// 令牌校验器
+    token_verifier: Option<Arc<dyn TokenVerifier>>,
+    /// 玩家数据加载器
+    player_loader: Option<Arc<dyn PlayerLoader>>,
 }
 
+// 安全性：LoginMessageHandler只在主线程中使用
+unsafe impl Send for LoginMessageHandler {}
+
 impl LoginMessageHandler {
     /// 创建新的登录消息处理器
     pub fn new() -> Self {
         Self {
-            // 初始化字段
+            front_session_manager: std::ptr::null_mut(),
+            unlogin_player_manager: std::ptr::null_mut(),
+            player_manager: std::ptr::null_mut(),
+            task_manager: std::ptr::null_mut(),
+            user_directory: std::ptr::null_mut(),
+            timer_handle: TimerHandle::new(std::ptr::null_mut()),
+            token_verifier: None,
+            player_loader: None,
         }
     }
 
+    /// 设置令牌校验器，可以替换为自定义的TokenVerifier实现
+    pub fn set_token_verifier(&mut self, token_verifier: Arc<dyn TokenVerifier>) {
+        self.token_verifier = Some(token_verifier);
+    }
+
+    /// 设置玩家数据加载器，接入数据库时替换为自定义的PlayerLoader实现
+    pub fn set_player_loader(&mut self, player_loader: Arc<dyn PlayerLoader>) {
+        self.player_loader = Some(player_loader);
+    }
+
     /// 初始化处理器，注册前端消息处理函数
-    pub fn init(&mut self, dispatcher: &mut FrontSessionMessageDispatcher) {
+    ///
+    /// 注意：处理器还需要注册为网络事件处理器，以便在连接建立和断开时维护未登录/已登录玩家
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(
+        &mut self,
+        dispatcher: &mut FrontSessionMessageDispatcher,
+        front_session_manager: &mut FrontSessionManager,
+        unlogin_player_manager: &mut UnloginPlayerManager,
+        player_manager: &mut PlayerManager,
+        task_manager: &mut TaskManager,
+        user_directory: &mut UserDirectory,
+        timer_handle: TimerHandle,
+    ) {
+        self.front_session_manager = front_session_manager as *mut FrontSessionManager;
+        self.unlogin_player_manager = unlogin_player_manager as *mut UnloginPlayerManager;
+        self.player_manager = player_manager as *mut PlayerManager;
+        self.task_manager = task_manager as *mut TaskManager;
+        self.user_directory = user_directory as *mut UserDirectory;
+        self.timer_handle = timer_handle;
+
         // 注册LoginFRequest的处理器
-        dispatcher.register_handler(
-            MSG_ID_LOGIN_F_REQUEST,
-            Box::new(move |session, message| {
-                Self::handle_login_request(session, message);
-            }),
-        );
+        let handler_ptr = LoginMessageHandlerPtr(self as *mut LoginMessageHandler);
+        dispatcher.register::<LoginFRequest, _>(move |session, request| {
+            unsafe {
+                let ptr = handler_ptr;
+                let handler = &mut *ptr.0;
+                handler.handle_login_request(session, request);
+            }
+        });
+    }
+
+    /// 前端连接建立，登记为未登录玩家并启动登录超时定时器
+    fn handle_session_connected(&mut self, session_id: u64) {
+        if self.unlogin_player_manager.is_null() {
+            error!("LoginMessageHandler not initialized");
+            return;
+        }
+        let unlogin_player_manager = unsafe { &mut *self.unlogin_player_manager };
+        if !unlogin_player_manager.add_player(session_id) {
+            return;
+        }
+
+        let login_timeout = unlogin_player_manager.get_login_timeout();
+        if login_timeout == 0 {
+            return;
+        }
+        let handler_ptr = LoginMessageHandlerPtr(self as *mut LoginMessageHandler);
+        let timer_id = self.timer_handle.add_once(login_timeout, move || {
+            unsafe {
+                let ptr = handler_ptr;
+                let handler = &mut *ptr.0;
+                handler.handle_login_timeout(session_id);
+            }
+        });
+        if let Some(player) = unlogin_player_manager.get_player_mut(session_id) {
+            player.timeout_timer_id = timer_id;
+        }
+    }
+
+    /// 前端连接断开，清理未登录或已登录玩家
+    fn handle_session_disconnected(&mut self, session_id: u64, reason: Option<DisconnectReason>) {
+        if self.unlogin_player_manager.is_null() || self.player_manager.is_null() {
+            return;
+        }
+        let unlogin_player_manager = unsafe { &mut *self.unlogin_player_manager };
+        if let Some(player) = unlogin_player_manager.remove_player(session_id) {
+            if player.timeout_timer_id != 0 {
+                self.timer_handle.remove_timer(player.timeout_timer_id);
+            }
+            return;
+        }
+
+        let player_manager = unsafe { &mut *self.player_manager };
+        if let Some(player) = player_manager.remove_player_by_session(session_id) {
+            info!("Player {} logged out from session {}, reason: {}",
+                  player.player_id, session_id, reason.map_or("unexpected_data", |reason| reason.as_str()));
+            if !player.save_to_db() {
+                error!("Failed to save player {} on logout", player.player_id);
+            }
+        }
+    }
+
+    /// 登录超时，回复超时错误并关闭会话
+    fn handle_login_timeout(&mut self, session_id: u64) {
+        let unlogin_player_manager = unsafe { &mut *self.unlogin_player_manager };
+        let player = match unlogin_player_manager.remove_player(session_id) {
+            Some(player) => player,
+            None => return,
+        };
+
+        warn!("Session {} did not login within {} ms (waited {}s), closing",
+              session_id, unlogin_player_manager.get_login_timeout(), player.get_wait_time());
+        let front_session_manager = unsafe { &mut *self.front_session_manager };
+        if let Some(session) = front_session_manager.get_session_mut(session_id) {
+            Self::send_login_response(session, 0, String::new(), LoginErrorCode::Timeout);
+            session.close();
+        }
     }
 
     /// 处理登录请求
     pub fn handle_login_request(
+        &mut self,
         session: &mut FrontSession,
-        message: &dyn Any
+        request: &LoginFRequest
     ) {
-        debug!("Handling LoginFRequest from session {}", session.get_session_id());
-
-        // 尝试将消息转换为LoginFRequest
-        if let Some(request) = message.downcast_ref::<LoginFRequest>() {
-            info!("Received LoginFRequest with token: {} from session {}", 
-                  request.token, session.get_session_id());
-
-            // TODO: 这里应该验证token，查询数据库获取玩家信息
-            // 现在先返回模拟数据
-            
-            // 生成模拟的玩家ID和名称
-            let player_id = 10000 + session.get_session_id(); // 简单的ID生成
-            let player_name = format!("Player_{}", player_id);
-
-            // 创建响应消息
-            let response = LoginFResponse {
-                player_id,
-                name: player_name.clone(),
-            };
-
-            // 发送响应
-            if session.send_message(response) {
-                info!("Sent LoginFResponse to session {} - player_id: {}, name: {}", 
-                      session.get_session_id(), player_id, player_name);
-                
-                // 设置session的用户ID
-                session.set_user_id(player_id);
-                session.set_authenticated(true);
-            } else {
-                error!("Failed to send LoginFResponse to session {}", session.get_session_id());
+        let session_id = session.get_session_id();
+        debug!("Handling LoginFRequest from session {}", session_id);
+
+        info!("Received LoginFRequest from session {}", session_id);
+
+        let token_verifier = match self.token_verifier {
+            Some(ref token_verifier) => Arc::clone(token_verifier),
+            None => {
+                error!("No token verifier configured, rejecting login from session {}", session_id);
+                Self::send_login_response(session, 0, String::new(), LoginErrorCode::InternalError);
+                return;
+            }
+        };
+        let player_loader = match self.player_loader {
+            Some(ref player_loader) => Arc::clone(player_loader),
+            None => {
+                error!("No player loader configured, rejecting login from session {}", session_id);
+                Self::send_login_response(session, 0, String::new(), LoginErrorCode::InternalError);
+                return;
+            }
+        };
+        if self.unlogin_player_manager.is_null() || self.player_manager.is_null() || self.task_manager.is_null() {
+            error!("LoginMessageHandler not initialized");
+            Self::send_login_response(session, 0, String::new(), LoginErrorCode::InternalError);
+            return;
+        }
+
+        let player_manager = unsafe { &mut *self.player_manager };
+        if player_manager.has_session(session_id) {
+            warn!("Session {} is already logged in", session_id);
+            Self::send_login_response(session, 0, String::new(), LoginErrorCode::AlreadyLoggedIn);
+            return;
+        }
+
+        // 不在未登录列表中说明已经超时
+        let unlogin_player_manager = unsafe { &mut *self.unlogin_player_manager };
+        let unlogin_player = match unlogin_player_manager.get_player_mut(session_id) {
+            Some(player) => player,
+            None => {
+                warn!("Session {} is not waiting for login", session_id);
+                Self::send_login_response(session, 0, String::new(), LoginErrorCode::Timeout);
+                return;
             }
+        };
+        if unlogin_player.logging_in {
+            warn!("Session {} already has a login in progress", session_id);
+            Self::send_login_response(session, 0, String::new(), LoginErrorCode::LoginInProgress);
+            return;
+        }
+        if request.token.is_empty() {
+            self.handle_login_failure(session, LoginErrorCode::InvalidToken);
+            return;
+        }
+        unlogin_player.update_token(request.token.clone());
+        unlogin_player.logging_in = true;
+
+        // 在工作线程解析token和加载玩家数据，完成后在主线程回调
+        let handler_ptr = LoginMessageHandlerPtr(self as *mut LoginMessageHandler);
+        let task = LoginTask::new(session_id, request.token.clone(), token_verifier, player_loader, Box::new(move |_success, result| {
+            unsafe {
+                let ptr = handler_ptr;
+                let handler = &mut *ptr.0;
+                handler.handle_login_result(session_id, result);
+            }
+        }));
+        let future = task.run_async();
+        let task_manager = unsafe { &mut *self.task_manager };
+        let task_id = task_manager.spawn_task(Box::new(task), future);
+        debug!("Started login task {} for session {}", task_id, session_id);
+    }
+
+    /// 登录任务完成，在主线程调用
+    fn handle_login_result(&mut self, session_id: u64, result: LoginResult) {
+        // 会话在任务执行期间断开或超时
+        let unlogin_player_manager = unsafe { &mut *self.unlogin_player_manager };
+        if !unlogin_player_manager.get_player(session_id).is_some_and(|player| player.logging_in) {
+            debug!("Session {} left before its login task finished", session_id);
+            return;
+        }
+        let front_session_manager = unsafe { &mut *self.front_session_manager };
+        let session = match front_session_manager.get_session_mut(session_id) {
+            Some(session) => session,
+            None => {
+                unlogin_player_manager.remove_player(session_id);
+                return;
+            }
+        };
+
+        let db_player = match result.db_player {
+            Some(db_player) if result.success => db_player,
+            _ => {
+                warn!("Login failed for session {}: {:?} {}", session_id, result.error_code,
+                      result.error_message.as_deref().unwrap_or(""));
+                if let Some(player) = unlogin_player_manager.get_player_mut(session_id) {
+                    player.logging_in = false;
+                }
+                self.handle_login_failure(session, result.error_code);
+                return;
+            }
+        };
+
+        // 从未登录列表移到已登录列表
+        if let Some(player) = unlogin_player_manager.remove_player(session_id) {
+            if player.timeout_timer_id != 0 {
+                self.timer_handle.remove_timer(player.timeout_timer_id);
+            }
+        }
+        let player_id = db_player.player_id;
+        let player_name = db_player.nick_name.clone();
+        let player_manager = unsafe { &mut *self.player_manager };
+        if let Some(replaced) = player_manager.add_player(Player::new(db_player, session_id)) {
+            if !replaced.save_to_db() {
+                error!("Failed to save replaced player {}", replaced.player_id);
+            }
+        }
+
+        // 设置session的用户ID
+        session.set_user_id(player_id);
+        session.set_authenticated(true);
+
+        if Self::send_login_response(session, player_id, player_name.clone(), LoginErrorCode::Success) {
+            info!("Sent LoginFResponse to session {} - player_id: {}, name: {}",
+                  session_id, player_id, player_name);
         } else {
-            error!("Failed to cast message to LoginFRequest");
+            error!("Failed to send LoginFResponse to session {}", session_id);
+        }
+
+        // 上报在线用户目录，重复登录时目录会踢掉之前的会话
+        if !self.user_directory.is_null() {
+            let user_directory = unsafe { &mut *self.user_directory };
+            user_directory.report_online(session_id, player_id, move |result| match result {
+                Ok(Some(previous)) => info!("Player {} was previously online at server {} front session {}",
+                                            player_id, previous.server_id, previous.front_session_id),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to report player {} online: {:?}", player_id, e),
+            });
         }
     }
 
+    /// 登录失败，超时前可以重试；失败次数达到上限时回复TooManyAttempts并关闭会话
+    fn handle_login_failure(&mut self, session: &mut FrontSession, error_code: LoginErrorCode) {
+        let session_id = session.get_session_id();
+        let unlogin_player_manager = unsafe { &mut *self.unlogin_player_manager };
+        if !unlogin_player_manager.record_failed_attempt(session_id) {
+            Self::send_login_response(session, 0, String::new(), error_code);
+            return;
+        }
+
+        warn!("Session {} reached the login attempt limit, closing", session_id);
+        if let Some(player) = unlogin_player_manager.remove_player(session_id) {
+            if player.timeout_timer_id != 0 {
+                self.timer_handle.remove_timer(player.timeout_timer_id);
+            }
+        }
+        Self::send_login_response(session, 0, String::new(), LoginErrorCode::TooManyAttempts);
+        session.close();
+    }
+
+    /// 发送登录响应
+    fn send_login_response(session: &mut FrontSession, player_id: u64, name: String, error_code: LoginErrorCode) -> bool {
+        session.send_message(LoginFResponse {
+            player_id,
+            name,
+            error_code: error_code as u32,
+        })
+    }
+
     /// 清理处理器
     pub fn dispose(&mut self) {
+        self.front_session_manager = std::ptr::null_mut();
+        self.unlogin_player_manager = std::ptr::null_mut();
+        self.player_manager = std::ptr::null_mut();
+        self.task_manager = std::ptr::null_mut();
+        self.user_directory = std::ptr::null_mut();
+        self.timer_handle = TimerHandle::new(std::ptr::null_mut());
+        self.token_verifier = None;
+        self.player_loader = None;
         debug!("LoginMessageHandler disposed");
     }
-}
\ No newline at end of file
+}
+
+impl NetworkEventHandler for LoginMessageHandler {
+    /// 前端连接建立时登记未登录玩家，断开时清理玩家
+    fn handle_event(&mut self, event: &mut NetworkEventData) {
+        if !event.server_type.is_front() {
+            return;
+        }
+        // 会话创建失败时事件中的会话ID为0
+        if event.session_id == 0 {
+            return;
+        }
+        match event.event_type {
+            NetworkEventType::NewTcpConnection | NetworkEventType::NewWebSocketConnection => {
+                self.handle_session_connected(event.session_id);
+            }
+            NetworkEventType::Disconnect | NetworkEventType::StreamDataNotExpected => {
+                self.handle_session_disconnected(event.session_id, event.disconnect_reason);
+            }
+            _ => {}
+        }
+    }
+}
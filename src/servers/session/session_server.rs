@@ -1,8 +1,11 @@
 use crate::framework::server::{ServerTrait, Server};
 use crate::framework::config::config::Config;
+use crate::framework::author::token_verifier::create_token_verifier;
 use super::login_message_handler::LoginMessageHandler;
 use super::unlogin::unlogin_player_manager::UnloginPlayerManager;
 use super::login::player_manager::PlayerManager;
+use super::login::player_loader::NewPlayerLoader;
+use std::sync::Arc;
 
 pub struct SessionServer {
     pub base_server: Server,
@@ -34,7 +37,7 @@ impl ServerTrait for SessionServer {
         }
         
         // 初始化管理器
-        if self.unlogin_player_manager.init() == false {
+        if !self.unlogin_player_manager.init(&config.login) {
             return false;
         }
         
@@ -42,6 +45,15 @@ impl ServerTrait for SessionServer {
             return false;
         }
         
+        // 根据配置创建登录令牌校验器
+        match create_token_verifier(&config.login) {
+            Some(token_verifier) => self.login_handler.set_token_verifier(token_verifier),
+            None => return false,
+        }
+        
+        // 尚未接入玩家数据库，登录时创建新玩家
+        self.login_handler.set_player_loader(Arc::new(NewPlayerLoader));
+        
         true
     }
     
@@ -52,7 +64,26 @@ impl ServerTrait for SessionServer {
         }
         
         // 注册登录消息处理器
-        self.login_handler.init(self.base_server.get_front_message_dispatcher_mut());
+        let front_dispatcher_ptr = self.base_server.get_front_message_dispatcher_mut() as *mut _;
+        let front_session_manager_ptr = self.base_server.get_front_session_manager_mut() as *mut _;
+        let task_manager_ptr = self.base_server.get_task_manager_mut() as *mut _;
+        let user_directory_ptr = self.base_server.get_user_directory_mut() as *mut _;
+        let timer_handle = self.base_server.get_timer_handle();
+        unsafe {
+            self.login_handler.init(
+                &mut *front_dispatcher_ptr,
+                &mut *front_session_manager_ptr,
+                &mut self.unlogin_player_manager,
+                &mut self.player_manager,
+                &mut *task_manager_ptr,
+                &mut *user_directory_ptr,
+                timer_handle,
+            );
+        }
+        
+        // 前端连接建立和断开时维护未登录/已登录玩家
+        let login_handler_ptr = &mut self.login_handler as *mut LoginMessageHandler;
+        self.base_server.get_network_event_manager_mut().add_handler(login_handler_ptr);
         
         true
     }
@@ -1,33 +1,77 @@
-use tracing::{debug, info, warn, error};
+use crate::framework::author::token_verifier::{TokenVerifier, TokenError};
+use crate::framework::task::Task;
+use super::super::login::db_player::DBPlayer;
+use super::super::login::player_loader::PlayerLoader;
+use tracing::{debug, error, info, warn};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// 登录错误码，通过LoginFResponse的error_code返回给客户端
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginErrorCode {
+    /// 登录成功
+    Success = 0,
+    /// token无效
+    InvalidToken = 1,
+    /// 上一次登录请求还在处理中
+    LoginInProgress = 2,
+    /// 会话已经登录
+    AlreadyLoggedIn = 3,
+    /// 未在规定时间内完成登录
+    Timeout = 4,
+    /// 服务器内部错误
+    InternalError = 5,
+    /// token已过期
+    TokenExpired = 6,
+    /// 登录失败次数过多，会话将被关闭
+    TooManyAttempts = 7,
+}
+
+impl LoginErrorCode {
+    /// 从协议中的数值转换
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(LoginErrorCode::Success),
+            1 => Some(LoginErrorCode::InvalidToken),
+            2 => Some(LoginErrorCode::LoginInProgress),
+            3 => Some(LoginErrorCode::AlreadyLoggedIn),
+            4 => Some(LoginErrorCode::Timeout),
+            5 => Some(LoginErrorCode::InternalError),
+            6 => Some(LoginErrorCode::TokenExpired),
+            7 => Some(LoginErrorCode::TooManyAttempts),
+            _ => None,
+        }
+    }
+}
 
 /// 登录任务结果
 #[derive(Debug, Clone)]
 pub struct LoginResult {
     pub success: bool,
-    pub player_id: Option<u64>,
-    pub player_name: Option<String>,
+    pub error_code: LoginErrorCode,
+    /// 从数据库加载的玩家信息，仅成功时有效
+    pub db_player: Option<DBPlayer>,
     pub error_message: Option<String>,
 }
 
 impl LoginResult {
     /// 创建成功结果
-    pub fn success(player_id: u64, player_name: String) -> Self {
+    pub fn success(db_player: DBPlayer) -> Self {
         Self {
             success: true,
-            player_id: Some(player_id),
-            player_name: Some(player_name),
+            error_code: LoginErrorCode::Success,
+            db_player: Some(db_player),
             error_message: None,
         }
     }
 
     /// 创建失败结果
-    pub fn failure(error_message: String) -> Self {
+    pub fn failure(error_code: LoginErrorCode, error_message: String) -> Self {
         Self {
             success: false,
-            player_id: None,
-            player_name: None,
+            error_code,
+            db_player: None,
             error_message: Some(error_message),
         }
     }
@@ -38,112 +82,99 @@ impl LoginResult {
 pub type LoginCallback = Box<dyn FnOnce(bool, LoginResult) + Send>;
 
 /// 登录任务
-/// 处理token解析、数据库查询等异步登录操作
+/// 处理token解析、玩家数据加载等异步登录操作
+///
+/// 通过TaskManager::spawn_task执行run_async返回的Future，完成后在主线程调用回调
 pub struct LoginTask {
+    /// 任务ID，由TaskManager分配
+    task_id: u64,
     /// 会话ID
     session_id: u64,
     /// 登录令牌
     token: String,
+    /// 令牌校验器
+    verifier: Arc<dyn TokenVerifier>,
+    /// 玩家数据加载器
+    player_loader: Arc<dyn PlayerLoader>,
     /// 任务创建时间
     created_time: std::time::Instant,
+    /// 登录结果，由工作线程写入
+    result: Arc<Mutex<Option<LoginResult>>>,
+    /// 完成回调
+    callback: Option<LoginCallback>,
 }
 
 impl LoginTask {
     /// 创建新的登录任务
-    pub fn new(session_id: u64, token: String) -> Self {
+    pub fn new(session_id: u64, token: String, verifier: Arc<dyn TokenVerifier>, player_loader: Arc<dyn PlayerLoader>, callback: LoginCallback) -> Self {
         Self {
+            task_id: 0,
             session_id,
             token,
+            verifier,
+            player_loader,
             created_time: std::time::Instant::now(),
+            result: Arc::new(Mutex::new(None)),
+            callback: Some(callback),
         }
     }
 
-    /// 运行登录任务
-    /// 执行token解析、数据库查询等操作，完成后调用回调函数
-    pub async fn run<F>(self, callback: F) 
-    where
-        F: FnOnce(bool, LoginResult) + Send + 'static,
-    {
-        debug!("Starting login task for session {}", self.session_id);
-        let start_time = std::time::Instant::now();
-
-        // TODO: 解析token
-        // 这里应该实现实际的token解析逻辑
-        // 例如：JWT解析、验证签名、检查过期时间等
-        debug!("Parsing token for session {}: {}", self.session_id, self.token);
-        
-        // TODO: 查询数据库获取玩家信息
-        // 这里应该实现实际的数据库查询
-        // 例如：根据token中的用户ID查询玩家信息
-        debug!("Querying database for player info, session {}", self.session_id);
-        
-        // 模拟异步操作，等待100毫秒
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        
-        // 模拟登录结果
-        // 实际应用中，这里应该根据token解析和数据库查询的结果来决定
-        let result = if self.token.len() > 5 {
-            // 模拟成功情况：token长度大于5就认为有效
-            let player_id = 10000 + self.session_id;
-            let player_name = format!("Player_{}", player_id);
-            
-            info!("Login task succeeded for session {}: player_id={}, name={}", 
-                  self.session_id, player_id, player_name);
-            
-            LoginResult::success(player_id, player_name)
-        } else {
-            // 模拟失败情况：token太短
-            let error_msg = format!("Invalid token for session {}", self.session_id);
-            warn!("Login task failed: {}", error_msg);
-            
-            LoginResult::failure(error_msg)
-        };
-
-        let elapsed = start_time.elapsed();
-        debug!("Login task completed for session {} in {:?}", self.session_id, elapsed);
-        
-        // 调用回调函数
-        callback(result.success, result);
-    }
+    /// 获取执行登录的Future
+    ///
+    /// Future在工作线程执行token解析、玩家数据加载等操作，结果保存在任务中，
+    /// 不能访问游戏状态
+    pub fn run_async(&self) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
+        let session_id = self.session_id;
+        let token = self.token.clone();
+        let verifier = Arc::clone(&self.verifier);
+        let player_loader = Arc::clone(&self.player_loader);
+        let result_slot = Arc::clone(&self.result);
 
-    /// 异步运行登录任务（返回Future）
-    pub fn run_async(self) -> Pin<Box<dyn Future<Output = LoginResult> + Send + 'static>> {
         Box::pin(async move {
-            debug!("Starting async login task for session {}", self.session_id);
+            debug!("Starting async login task for session {}", session_id);
             let start_time = std::time::Instant::now();
 
-            // TODO: 解析token
-            debug!("Parsing token for session {}: {}", self.session_id, self.token);
-            
-            // TODO: 查询数据库获取玩家信息
-            debug!("Querying database for player info, session {}", self.session_id);
-            
-            // 模拟异步操作
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            
-            // 模拟登录结果
-            let result = if self.token.len() > 5 {
-                let player_id = 10000 + self.session_id;
-                let player_name = format!("Player_{}", player_id);
-                
-                info!("Async login task succeeded for session {}: player_id={}, name={}", 
-                      self.session_id, player_id, player_name);
-                
-                LoginResult::success(player_id, player_name)
-            } else {
-                let error_msg = format!("Invalid token for session {}", self.session_id);
-                warn!("Async login task failed: {}", error_msg);
-                
-                LoginResult::failure(error_msg)
-            };
+            let result = Self::login(session_id, &token, verifier.as_ref(), player_loader.as_ref()).await;
 
             let elapsed = start_time.elapsed();
-            debug!("Async login task completed for session {} in {:?}", self.session_id, elapsed);
-            
-            result
+            debug!("Async login task completed for session {} in {:?}", session_id, elapsed);
+
+            *result_slot.lock().unwrap() = Some(result);
         })
     }
 
+    /// 解析token并加载玩家数据
+    async fn login(session_id: u64, token: &str, verifier: &dyn TokenVerifier, player_loader: &dyn PlayerLoader) -> LoginResult {
+        debug!("Verifying token for session {}", session_id);
+        let claims = match verifier.verify(token) {
+            Ok(claims) => claims,
+            Err(e) => {
+                let error_code = match e {
+                    TokenError::Expired => LoginErrorCode::TokenExpired,
+                    _ => LoginErrorCode::InvalidToken,
+                };
+                let error_msg = format!("Token rejected for session {}: {:?}", session_id, e);
+                warn!("Login task failed: {}", error_msg);
+                return LoginResult::failure(error_code, error_msg);
+            }
+        };
+        let player_id = claims.user_id;
+
+        debug!("Loading player {} for session {}", player_id, session_id);
+        let db_player = match player_loader.load(player_id).await {
+            Ok(db_player) => db_player,
+            Err(e) => {
+                let error_msg = format!("Failed to load player {} for session {}: {}", player_id, session_id, e);
+                error!("Login task failed: {}", error_msg);
+                return LoginResult::failure(LoginErrorCode::InternalError, error_msg);
+            }
+        };
+        info!("Login task succeeded for session {}: player_id={}, name={}",
+              session_id, db_player.player_id, db_player.nick_name);
+
+        LoginResult::success(db_player)
+    }
+
     /// 获取会话ID
     pub fn get_session_id(&self) -> u64 {
         self.session_id
@@ -160,10 +191,40 @@ impl LoginTask {
     }
 }
 
+impl Task for LoginTask {
+    fn task_id(&self) -> u64 {
+        self.task_id
+    }
+
+    fn set_task_id(&mut self, id: u64) {
+        self.task_id = id;
+    }
+
+    fn is_done(&self) -> bool {
+        self.result.lock().unwrap().is_some()
+    }
+
+    fn done(&mut self) {
+        let result = match self.result.lock().unwrap().take() {
+            Some(result) => result,
+            None => return,
+        };
+        debug!("Login task {} for session {} done after {:?}",
+               self.task_id, self.session_id, self.get_elapsed_time());
+
+        if let Some(callback) = self.callback.take() {
+            callback(result.success, result);
+        }
+    }
+}
+
 /// 登录任务构建器
 pub struct LoginTaskBuilder {
     session_id: Option<u64>,
     token: Option<String>,
+    verifier: Option<Arc<dyn TokenVerifier>>,
+    player_loader: Option<Arc<dyn PlayerLoader>>,
+    callback: Option<LoginCallback>,
 }
 
 impl LoginTaskBuilder {
@@ -172,6 +233,9 @@ impl LoginTaskBuilder {
         Self {
             session_id: None,
             token: None,
+            verifier: None,
+            player_loader: None,
+            callback: None,
         }
     }
 
@@ -187,11 +251,35 @@ impl LoginTaskBuilder {
         self
     }
 
+    /// 设置令牌校验器
+    pub fn verifier(mut self, verifier: Arc<dyn TokenVerifier>) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
+    /// 设置玩家数据加载器
+    pub fn player_loader(mut self, player_loader: Arc<dyn PlayerLoader>) -> Self {
+        self.player_loader = Some(player_loader);
+        self
+    }
+
+    /// 设置完成回调
+    pub fn callback<F>(mut self, callback: F) -> Self
+    where
+        F: FnOnce(bool, LoginResult) + Send + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
     /// 构建登录任务
     pub fn build(self) -> Result<LoginTask, String> {
         let session_id = self.session_id.ok_or("Session ID is required")?;
         let token = self.token.ok_or("Token is required")?;
-        
-        Ok(LoginTask::new(session_id, token))
+        let verifier = self.verifier.ok_or("Token verifier is required")?;
+        let player_loader = self.player_loader.ok_or("Player loader is required")?;
+        let callback = self.callback.ok_or("Callback is required")?;
+
+        Ok(LoginTask::new(session_id, token, verifier, player_loader, callback))
     }
-}
\ No newline at end of file
+}
@@ -1,3 +1,5 @@
+use crate::framework::timer::TimerId;
+
 /// 未登录玩家信息
 pub struct UnloginPlayer {
     /// 会话ID
@@ -6,6 +8,12 @@ pub struct UnloginPlayer {
     pub token: String,
     /// 登录时间
     pub login_time: std::time::Instant,
+    /// 登录超时定时器ID，0表示没有定时器
+    pub timeout_timer_id: TimerId,
+    /// 是否有正在执行的登录任务
+    pub logging_in: bool,
+    /// 已失败的登录次数
+    pub failed_attempts: u32,
 }
 
 impl UnloginPlayer {
@@ -15,6 +23,9 @@ impl UnloginPlayer {
             session_id,
             token,
             login_time: std::time::Instant::now(),
+            timeout_timer_id: 0,
+            logging_in: false,
+            failed_attempts: 0,
         }
     }
 
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use tracing::{debug, info, warn, error};
+use crate::framework::config::config::LoginConfig;
 use super::unlogin_player::UnloginPlayer;
 
 /// 未登录玩家管理器
@@ -8,8 +9,10 @@ use super::unlogin_player::UnloginPlayer;
 pub struct UnloginPlayerManager {
     /// 未登录玩家映射表 (session_id -> UnloginPlayer)
     players: HashMap<u64, UnloginPlayer>,
-    /// 登录超时时间（秒）
+    /// 登录超时时间（毫秒）
     login_timeout: u64,
+    /// 每个会话最多允许失败的登录次数，0表示不限制
+    max_attempts: u32,
 }
 
 impl UnloginPlayerManager {
@@ -17,27 +20,49 @@ impl UnloginPlayerManager {
     pub fn new() -> Self {
         Self {
             players: HashMap::new(),
-            login_timeout: 60, // 默认60秒超时
+            login_timeout: 60000, // 默认60秒超时
+            max_attempts: 0,
         }
     }
 
     /// 初始化管理器
-    pub fn init(&mut self) -> bool {
-        
+    pub fn init(&mut self, config: &LoginConfig) -> bool {
+        self.login_timeout = config.timeout_ms;
+        self.max_attempts = config.max_attempts;
         true
     }
 
-    /// 添加未登录玩家
-    pub fn add_player(&mut self, session_id: u64, token: String) -> bool {
+    /// 获取登录超时时间（毫秒）
+    pub fn get_login_timeout(&self) -> u64 {
+        self.login_timeout
+    }
+
+    /// 记录一次登录失败
+    ///
+    /// # 返回值
+    /// 失败次数达到上限时返回true，调用方应关闭会话
+    pub fn record_failed_attempt(&mut self, session_id: u64) -> bool {
+        let max_attempts = self.max_attempts;
+        match self.players.get_mut(&session_id) {
+            Some(player) => {
+                player.failed_attempts += 1;
+                max_attempts > 0 && player.failed_attempts >= max_attempts
+            }
+            None => false,
+        }
+    }
+
+    /// 添加未登录玩家，连接建立时调用，登录请求到达后再设置令牌
+    pub fn add_player(&mut self, session_id: u64) -> bool {
         if self.players.contains_key(&session_id) {
             warn!("UnloginPlayer with session_id {} already exists", session_id);
             return false;
         }
 
-        let player = UnloginPlayer::new(session_id, token.clone());
+        let player = UnloginPlayer::new(session_id, String::new());
 
         self.players.insert(session_id, player);
-        debug!("Added unlogin player: session_id={}, token={}", session_id, token);
+        debug!("Added unlogin player: session_id={}", session_id);
         true
     }
 
@@ -50,6 +75,11 @@ impl UnloginPlayerManager {
         player
     }
 
+    /// 获取未登录玩家
+    pub fn get_player(&self, session_id: u64) -> Option<&UnloginPlayer> {
+        self.players.get(&session_id)
+    }
+
     /// 获取未登录玩家（可变引用）
     pub fn get_player_mut(&mut self, session_id: u64) -> Option<&mut UnloginPlayer> {
         self.players.get_mut(&session_id)
@@ -67,8 +97,7 @@ impl UnloginPlayerManager {
 
     /// 清理超时的未登录玩家
     pub fn cleanup_timeout_players(&mut self) -> Vec<u64> {
-        let now = std::time::Instant::now();
-        let timeout_duration = std::time::Duration::from_secs(self.login_timeout);
+        let timeout_duration = std::time::Duration::from_millis(self.login_timeout);
         
         let mut timeout_sessions = Vec::new();
         
@@ -124,4 +153,33 @@ impl UnloginPlayerManager {
         
         info!("UnloginPlayerManager disposed, cleared {} unlogin players", player_count);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(max_attempts: u32) -> UnloginPlayerManager {
+        let mut manager = UnloginPlayerManager::new();
+        manager.init(&LoginConfig { max_attempts, ..LoginConfig::default() });
+        manager.add_player(1);
+        manager
+    }
+
+    #[test]
+    fn failed_attempts_reach_limit() {
+        let mut manager = manager(3);
+        assert!(!manager.record_failed_attempt(1));
+        assert!(!manager.record_failed_attempt(1));
+        assert!(manager.record_failed_attempt(1));
+        assert!(!manager.record_failed_attempt(2));
+    }
+
+    #[test]
+    fn zero_max_attempts_is_unlimited() {
+        let mut manager = manager(0);
+        for _ in 0..100 {
+            assert!(!manager.record_failed_attempt(1));
+        }
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Serialize, Deserialize};
 use super::tcp_client::TcpClient;
 use super::websocket_client::WebSocketClient;
 use super::event_emitter::ClientEventEmitter;
+use super::tls::ClientTlsOptions;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ClientType {
@@ -30,6 +31,7 @@ pub struct Client {
     pub host: String,
     pub tcp_port: Option<u16>,
     pub ws_port: Option<u16>,
+    pub tls: Option<ClientTlsOptions>,
     pub client_type: Arc<RwLock<ClientType>>,
     pub connected: Arc<RwLock<bool>>,
     tcp_client: Arc<RwLock<Option<Arc<TcpClient>>>>,
@@ -46,6 +48,7 @@ impl Client {
         host: String,
         tcp_port: Option<u16>,
         ws_port: Option<u16>,
+        tls: Option<ClientTlsOptions>,
         initial_type: ClientType,
     ) -> Self {
         Self {
@@ -55,6 +58,7 @@ impl Client {
             host,
             tcp_port,
             ws_port,
+            tls,
             client_type: Arc::new(RwLock::new(initial_type)),
             connected: Arc::new(RwLock::new(false)),
             tcp_client: Arc::new(RwLock::new(None)),
@@ -97,6 +101,7 @@ impl Client {
                             port,
                             auto_reconnect: false,
                             reconnect_interval: 5,
+                            tls: self.tls.as_ref().and_then(|tls| tls.tcp_config()),
                         },
                     ));
                     
@@ -184,6 +189,7 @@ impl Client {
                             port,
                             auto_reconnect: false,
                             reconnect_interval: 5,
+                            tls: self.tls.as_ref().and_then(|tls| tls.ws_config()),
                         },
                     ));
                     
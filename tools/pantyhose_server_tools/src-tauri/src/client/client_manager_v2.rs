@@ -5,6 +5,7 @@ use tokio::sync::RwLock;
 use once_cell::sync::Lazy;
 use serde::{Serialize, Deserialize};
 use super::client::{Client, ClientInfo, ClientType};
+use super::tls::ClientTlsOptions;
 
 // 全局客户端管理器
 pub static CLIENT_MANAGER: Lazy<ClientManager> = Lazy::new(|| ClientManager::new());
@@ -41,6 +42,7 @@ impl ClientManager {
         host: String,
         tcp_port: Option<u16>,
         ws_port: Option<u16>,
+        tls: Option<ClientTlsOptions>,
     ) -> Result<i32, String> {
         let client_id = self.next_id.fetch_add(1, Ordering::SeqCst);
         
@@ -61,6 +63,7 @@ impl ClientManager {
             host,
             tcp_port,
             ws_port,
+            tls.or_else(load_client_tls_options),
             initial_type,
         ));
         
@@ -337,6 +340,19 @@ impl ClientManager {
     }
 }
 
+/// 从app_config.json的client_tls字段读取TLS选项，用于连接启用了front_tls的服务器
+fn load_client_tls_options() -> Option<ClientTlsOptions> {
+    let config = crate::server_config::get_app_config().ok()?;
+    let tls = config.get("client_tls")?;
+    match serde_json::from_value::<ClientTlsOptions>(tls.clone()) {
+        Ok(options) => Some(options),
+        Err(e) => {
+            println!("client_tls配置无效: {}", e);
+            None
+        }
+    }
+}
+
 // ============ Tauri 命令 ============
 
 #[tauri::command]
@@ -346,8 +362,9 @@ pub async fn create_client(
     host: String,
     tcp_port: Option<u16>,
     ws_port: Option<u16>,
+    tls: Option<ClientTlsOptions>,
 ) -> Result<i32, String> {
-    CLIENT_MANAGER.create_client(server_id, server_name, host, tcp_port, ws_port).await
+    CLIENT_MANAGER.create_client(server_id, server_name, host, tcp_port, ws_port, tls).await
 }
 
 #[tauri::command]
@@ -7,6 +7,7 @@ pub mod dynamic_buffer;
 // 支持模块
 pub mod tcp_client;
 pub mod websocket_client;
+pub mod tls;
 
 // 导出新的客户端管理命令
 pub use client_manager_v2::{
@@ -1,12 +1,11 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tokio::net::TcpStream;
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
 use serde::{Serialize, Deserialize};
 use std::time::Duration;
 use tokio::time::timeout;
 use super::dynamic_buffer::DynamicBuffer;
+use super::tls::{connect_stream, ClientStream, ClientTlsConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TcpClientConfig {
@@ -14,6 +13,8 @@ pub struct TcpClientConfig {
     pub port: u16,
     pub auto_reconnect: bool,
     pub reconnect_interval: u64, // 秒
+    #[serde(default)]
+    pub tls: Option<ClientTlsConfig>, // 不为空时使用TLS连接
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -28,8 +29,8 @@ pub struct TcpClient {
     pub id: String,
     pub config: TcpClientConfig,
     pub status: Arc<Mutex<ClientStatus>>,
-    read_half: Arc<Mutex<Option<OwnedReadHalf>>>,
-    write_half: Arc<Mutex<Option<OwnedWriteHalf>>>,
+    read_half: Arc<Mutex<Option<ReadHalf<ClientStream>>>>,
+    write_half: Arc<Mutex<Option<WriteHalf<ClientStream>>>>,
     receive_callback: Arc<Mutex<Option<Box<dyn Fn(Vec<u8>) + Send + Sync>>>>,
     disconnect_callback: Arc<Mutex<Option<Box<dyn Fn() + Send + Sync>>>>,
 }
@@ -53,12 +54,12 @@ impl TcpClient {
             *status = ClientStatus::Connecting;
         }
 
-        let addr = format!("{}:{}", self.config.host, self.config.port);
+        let connect = connect_stream(&self.config.host, self.config.port, self.config.tls.as_ref());
         
-        match timeout(Duration::from_secs(10), TcpStream::connect(&addr)).await {
+        match timeout(Duration::from_secs(10), connect).await {
             Ok(Ok(stream)) => {
                 // 分离读写通道
-                let (read, write) = stream.into_split();
+                let (read, write) = tokio::io::split(stream);
                 
                 {
                     let mut read_lock = self.read_half.lock().await;
@@ -81,7 +82,7 @@ impl TcpClient {
                 Ok(())
             }
             Ok(Err(e)) => {
-                let error_msg = format!("连接失败: {}", e);
+                let error_msg = e;
                 {
                     let mut status = self.status.lock().await;
                     *status = ClientStatus::Error(error_msg.clone());
@@ -161,6 +162,7 @@ impl TcpClient {
         let reconnect_interval = self.config.reconnect_interval;
         let host = self.config.host.clone();
         let port = self.config.port;
+        let tls = self.config.tls.clone();
         let client_id = self.id.clone();
         
         tokio::spawn(async move {
@@ -278,11 +280,10 @@ impl TcpClient {
                     buffer.clear();
                     
                     // 尝试重新连接
-                    let addr = format!("{}:{}", host, port);
-                    match TcpStream::connect(&addr).await {
+                    match connect_stream(&host, port, tls.as_ref()).await {
                         Ok(stream) => {
                             // 分离读写通道
-                            let (read, write) = stream.into_split();
+                            let (read, write) = tokio::io::split(stream);
                             
                             {
                                 let mut read_lock = read_clone.lock().await;
@@ -0,0 +1,162 @@
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use serde::{Serialize, Deserialize};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+use tokio_rustls::rustls::pki_types::ServerName;
+
+fn default_true() -> bool {
+    true
+}
+
+/// 客户端TLS选项，与服务器config.xml中的front_tls对应
+///
+/// 可以在创建客户端时传入，也可以写在app_config.json的client_tls字段中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientTlsOptions {
+    /// TCP连接是否使用TLS
+    #[serde(default = "default_true")]
+    pub tcp: bool,
+    /// WebSocket连接是否使用TLS（wss://）
+    #[serde(default = "default_true")]
+    pub ws: bool,
+    /// 信任的CA证书（PEM），用于本地自签名CA；不配置时使用内置的公共根证书
+    #[serde(default)]
+    pub ca_cert: Option<String>,
+    /// 校验证书使用的服务器名，不配置时使用连接的host
+    #[serde(default)]
+    pub server_name: Option<String>,
+    /// TCP连接的ALPN协议，逗号分隔
+    #[serde(default)]
+    pub tcp_alpn: Option<String>,
+    /// WebSocket连接的ALPN协议，逗号分隔
+    #[serde(default)]
+    pub ws_alpn: Option<String>,
+}
+
+/// 单个连接的TLS配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientTlsConfig {
+    pub ca_cert: Option<String>,
+    pub server_name: Option<String>,
+    pub alpn: Option<String>,
+}
+
+impl ClientTlsOptions {
+    /// TCP连接使用的TLS配置，未启用时返回None
+    pub fn tcp_config(&self) -> Option<ClientTlsConfig> {
+        self.tcp.then(|| ClientTlsConfig {
+            ca_cert: self.ca_cert.clone(),
+            server_name: self.server_name.clone(),
+            alpn: self.tcp_alpn.clone(),
+        })
+    }
+
+    /// WebSocket连接使用的TLS配置，未启用时返回None
+    pub fn ws_config(&self) -> Option<ClientTlsConfig> {
+        self.ws.then(|| ClientTlsConfig {
+            ca_cert: self.ca_cert.clone(),
+            server_name: self.server_name.clone(),
+            alpn: self.ws_alpn.clone(),
+        })
+    }
+}
+
+/// 客户端连接流：明文TCP或TLS
+pub enum ClientStream {
+    Tcp(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl AsyncRead for ClientStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ClientStream::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            ClientStream::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for ClientStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            ClientStream::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            ClientStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ClientStream::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            ClientStream::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ClientStream::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            ClientStream::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
+
+/// 创建TLS连接器
+fn tls_connector(config: &ClientTlsConfig) -> Result<TlsConnector, String> {
+    let mut roots = RootCertStore::empty();
+    match config.ca_cert {
+        Some(ref ca_cert) => {
+            let file = File::open(ca_cert).map_err(|e| format!("无法打开CA证书 {}: {}", ca_cert, e))?;
+            for cert in rustls_pemfile::certs(&mut BufReader::new(file)) {
+                let cert = cert.map_err(|e| format!("CA证书解析失败 {}: {}", ca_cert, e))?;
+                roots.add(cert).map_err(|e| format!("CA证书无效 {}: {}", ca_cert, e))?;
+            }
+            if roots.is_empty() {
+                return Err(format!("CA证书文件中没有证书: {}", ca_cert));
+            }
+        }
+        None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+    }
+
+    let mut client_config = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("TLS配置失败: {}", e))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    client_config.alpn_protocols = config.alpn.as_deref().unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|protocol| !protocol.is_empty())
+        .map(|protocol| protocol.as_bytes().to_vec())
+        .collect();
+
+    Ok(TlsConnector::from(Arc::new(client_config)))
+}
+
+/// 连接服务器，配置了TLS时完成TLS握手
+pub async fn connect_stream(host: &str, port: u16, tls: Option<&ClientTlsConfig>) -> Result<ClientStream, String> {
+    let addr = if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    let stream = TcpStream::connect(&addr).await.map_err(|e| format!("连接失败: {}", e))?;
+
+    let Some(tls) = tls else {
+        return Ok(ClientStream::Tcp(stream));
+    };
+
+    let connector = tls_connector(tls)?;
+    let name = tls.server_name.clone()
+        .unwrap_or_else(|| host.trim_start_matches('[').trim_end_matches(']').to_string());
+    let server_name = ServerName::try_from(name.clone())
+        .map_err(|e| format!("无效的服务器名 {}: {}", name, e))?;
+    let tls_stream = connector.connect(server_name, stream).await
+        .map_err(|e| format!("TLS握手失败: {}", e))?;
+    Ok(ClientStream::Tls(Box::new(tls_stream)))
+}
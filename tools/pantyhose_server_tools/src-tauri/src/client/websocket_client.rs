@@ -1,10 +1,11 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::{client_async, tungstenite::Message};
 use futures_util::{StreamExt, SinkExt};
 use serde::{Serialize, Deserialize};
 use std::time::Duration;
 use tokio::time::timeout;
+use super::tls::{connect_stream, ClientStream, ClientTlsConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebSocketClientConfig {
@@ -12,6 +13,8 @@ pub struct WebSocketClientConfig {
     pub port: u16,
     pub auto_reconnect: bool,
     pub reconnect_interval: u64, // 秒
+    #[serde(default)]
+    pub tls: Option<ClientTlsConfig>, // 不为空时使用wss://
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -22,7 +25,21 @@ pub enum ClientStatus {
     Error(String),
 }
 
-type WsStream = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+type WsStream = tokio_tungstenite::WebSocketStream<ClientStream>;
+
+/// 建立TCP（和TLS）连接后进行WebSocket握手
+async fn connect_websocket(host: &str, port: u16, tls: Option<&ClientTlsConfig>) -> Result<WsStream, String> {
+    let scheme = if tls.is_some() { "wss" } else { "ws" };
+    let url = if host.contains(':') && !host.starts_with('[') {
+        format!("{}://[{}]:{}", scheme, host, port)
+    } else {
+        format!("{}://{}:{}", scheme, host, port)
+    };
+    let stream = connect_stream(host, port, tls).await?;
+    let (ws_stream, _) = client_async(url.as_str(), stream).await
+        .map_err(|e| format!("WebSocket握手失败: {}", e))?;
+    Ok(ws_stream)
+}
 
 pub struct WebSocketClient {
     pub id: String,
@@ -53,13 +70,11 @@ impl WebSocketClient {
             *status = ClientStatus::Connecting;
         }
 
-        let url = format!("ws://{}:{}", self.config.host, self.config.port);
-        
         match timeout(
             Duration::from_secs(10),
-            connect_async(&url)
+            connect_websocket(&self.config.host, self.config.port, self.config.tls.as_ref())
         ).await {
-            Ok(Ok((ws_stream, _))) => {
+            Ok(Ok(ws_stream)) => {
                 {
                     let mut stream_lock = self.stream.lock().await;
                     *stream_lock = Some(ws_stream);
@@ -168,6 +183,7 @@ impl WebSocketClient {
         let reconnect_interval = self.config.reconnect_interval;
         let host = self.config.host.clone();
         let port = self.config.port;
+        let tls = self.config.tls.clone();
         let client_id = self.id.clone();
         
         tokio::spawn(async move {
@@ -263,9 +279,8 @@ impl WebSocketClient {
                     tokio::time::sleep(Duration::from_secs(reconnect_interval)).await;
                     
                     // 尝试重新连接
-                    let url = format!("ws://{}:{}", host, port);
-                    match connect_async(&url).await {
-                        Ok((new_stream, _)) => {
+                    match connect_websocket(&host, port, tls.as_ref()).await {
+                        Ok(new_stream) => {
                             let mut stream = stream_clone.lock().await;
                             *stream = Some(new_stream);
                             
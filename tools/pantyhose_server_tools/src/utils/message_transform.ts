@@ -174,13 +174,14 @@ class MessageTransform {
       const message = MessageType.decode(bytes)
       const decodedData = MessageType.toObject(message)
       
-      // 如果是RpcMessageFResponse，则继续解析内部message并直接返回
-      if (messageType === 'cluster.RpcMessageFResponse' && decodedData.message && decodedData.msg_id) {
+      // 如果是RpcMessageFResponse或服务器推送的RpcPushMessageFNotify，则继续解析内部message并直接返回
+      const isWrappedMessage = messageType === 'cluster.RpcMessageFResponse' || messageType === 'cluster.RpcPushMessageFNotify'
+      if (isWrappedMessage && decodedData.message && decodedData.msg_id) {
         try {
           const innerMessageBytes = new Uint8Array(decodedData.message)
           return await this.decodeMessage(decodedData.msg_id, innerMessageBytes)
         } catch (error) {
-          console.warn('解析RpcMessageFResponse内部消息失败:', error)
+          console.warn(`解析${messageType}内部消息失败:`, error)
           // 即使内部消息解析失败，也返回外层消息
         }
       }
@@ -223,7 +224,9 @@ class MessageTransform {
            messageType === 'cluster.RpcMessageFResponse' ||
            messageType === 'cluster.RpcForwardMessageBRequest' ||
            messageType === 'cluster.RpcForwardMessageBResponse' ||
-           messageType === 'cluster.RpcForwardMessageBNotify'
+           messageType === 'cluster.RpcForwardMessageBNotify' ||
+           messageType === 'cluster.RpcPushMessageFNotify' ||
+           messageType === 'cluster.RpcPushMessageBNotify'
   }
 }
 
@@ -221,8 +221,9 @@ fn generate_message_id_file(output_dir: &Path, messages: &[ProtoMessage], length
         let (const_name, _) = &message_constants[&message.name];
         
         content.push_str(&format!("impl MessageId for super::{}::{} {{\n", module_name, message.name));
+        content.push_str(&format!("    const MSG_ID: u16 = {};\n\n", const_name));
         content.push_str(&format!("    fn msg_id(&self) -> u16 {{\n"));
-        content.push_str(&format!("        {}\n", const_name));
+        content.push_str(&format!("        Self::MSG_ID\n"));
         content.push_str(&format!("    }}\n"));
         content.push_str(&format!("}}\n\n"));
     }
@@ -312,6 +313,20 @@ fn generate_message_id_file(output_dir: &Path, messages: &[ProtoMessage], length
             const_name, method_name));
     }
     
+    content.push_str("            _ => None,\n");
+    content.push_str("        }\n");
+    content.push_str("    }\n\n");
+    
+    // 生成消息ID到消息名的映射表
+    content.push_str("    /// Get message name by ID\n");
+    content.push_str("    pub fn message_name(msg_id: u16) -> Option<&'static str> {\n");
+    content.push_str("        match msg_id {\n");
+    
+    for message in messages {
+        let (const_name, _) = &message_constants[&message.name];
+        content.push_str(&format!("            {} => Some(\"{}\"),\n", const_name, message.name));
+    }
+    
     content.push_str("            _ => None,\n");
     content.push_str("        }\n");
     content.push_str("    }\n");